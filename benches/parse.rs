@@ -0,0 +1,77 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use json_minimal::Json;
+
+// An array of `count` records like those of a typical API response.
+fn records(count: usize) -> Vec<u8> {
+    let records: Vec<String> = (0..count)
+        .map(|n| {
+            format!(
+                "{{\"id\":{},\"name\":\"user {}\",\"email\":\"user{}@example.com\",\"active\":{},\"score\":{}.5,\"tags\":[\"a\",\"b\",\"c\"],\"parent\":null}}",
+                n,
+                n,
+                n,
+                n % 2 == 0,
+                n % 100
+            )
+        })
+        .collect();
+
+    format!("[{}]", records.join(",")).into_bytes()
+}
+
+fn numbers(count: usize) -> Vec<u8> {
+    let numbers: Vec<String> = (0..count)
+        .map(|n| format!("{}.{}", n * 7919 % 100_000, n % 1000))
+        .collect();
+
+    format!("[{}]", numbers.join(",")).into_bytes()
+}
+
+fn strings(count: usize) -> Vec<u8> {
+    let strings: Vec<String> = (0..count)
+        .map(|n| {
+            format!(
+                "\"{} Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor.{}\"",
+                n,
+                if n % 10 == 0 { "\\n\\u00e9" } else { "" }
+            )
+        })
+        .collect();
+
+    format!("[{}]", strings.join(",")).into_bytes()
+}
+
+fn nested(depth: usize) -> Vec<u8> {
+    let mut input = Vec::new();
+
+    for n in 0..depth {
+        input.extend(format!("{{\"level\":{},\"next\":[", n).bytes());
+    }
+
+    for _ in 0..depth {
+        input.extend(b"]}");
+    }
+
+    input
+}
+
+fn parse(c: &mut Criterion) {
+    let inputs = [
+        ("records", records(10_000)),
+        ("numbers", numbers(100_000)),
+        ("strings", strings(10_000)),
+        ("nested", nested(500)),
+    ];
+
+    let mut group = c.benchmark_group("parse");
+
+    for (name, input) in &inputs {
+        group.throughput(Throughput::Bytes(input.len() as u64));
+        group.bench_function(*name, |b| b.iter(|| Json::parse(input).unwrap()));
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
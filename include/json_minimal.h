@@ -0,0 +1,35 @@
+/* C interface of json_minimal (built with the `ffi` feature), see src/ffi.rs. */
+
+#ifndef JSON_MINIMAL_H
+#define JSON_MINIMAL_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque parsed value. */
+typedef struct Json Json;
+
+/* Returns NULL on invalid input and stores a message in *error (if error isn't NULL), which must be
+ * released with json_minimal_string_free. The result must be released with json_minimal_free. */
+Json *json_minimal_parse(const uint8_t *input, size_t len, char **error);
+
+/* Looks up a JSON Pointer like "/users/0/name". Returns NULL if there is no such value. The result
+ * is borrowed from json and must not be freed. */
+const Json *json_minimal_pointer(const Json *json, const char *pointer);
+
+/* The printed value, to be released with json_minimal_string_free. */
+char *json_minimal_print(const Json *json);
+
+void json_minimal_free(Json *json);
+
+void json_minimal_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
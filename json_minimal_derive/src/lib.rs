@@ -0,0 +1,487 @@
+//! `#[derive(ToJson, FromJson)]` for `json_minimal`. Enable the `derive` feature of `json_minimal`
+//! instead of depending on this crate directly.
+//!
+//! A struct with named fields maps to a `Json::JSON` with one member per field, a newtype struct to
+//! the value it wraps, any other tuple struct to a `Json::ARRAY` and a unit struct to `Json::NULL`.
+//!
+//! Named fields accept these attributes:
+//! * `#[json(rename = "name")]` uses `name` for the member instead of the field's name.
+//! * `#[json(skip)]` leaves the field out of the `Json` and fills it with `Default::default()`.
+//! * `#[json(default)]` fills the field with `Default::default()` if the member is missing.
+//!
+//! Enums are externally tagged by default: a unit variant becomes a `Json::STRING` holding its name
+//! (so an enum of unit variants maps to plain strings) and any other variant a `Json::JSON` with a
+//! single member named after the variant, holding what a struct with the same fields would map to.
+//! With `#[json(tag = "type")]` on the enum, variants are internally tagged instead: a `Json::JSON`
+//! whose `type` member holds the variant's name, followed by the variant's fields. This only works
+//! for unit variants and variants with named fields. Variants accept `#[json(rename = "name")]`.
+
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::{
+    parse_macro_input, parse_quote, Attribute, Data, DataEnum, DeriveInput, Field, Fields,
+    FieldsNamed, Generics, Index, LitStr, Variant,
+};
+
+#[proc_macro_derive(ToJson, attributes(json))]
+pub fn derive_to_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    to_json(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(FromJson, attributes(json))]
+pub fn derive_from_json(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    from_json(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn to_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            container_options(&input.attrs, false)?;
+            check_unnamed(&data.fields)?;
+
+            fields_to_json(&data.fields, |field, index| match &field.ident {
+                Some(ident) => quote!(&self.#ident),
+                None => {
+                    let index = Index::from(index);
+
+                    quote!(&self.#index)
+                }
+            })?
+        }
+        Data::Enum(data) => enum_to_json(data, container_options(&input.attrs, true)?)?,
+        Data::Union(_) => return Err(unsupported(input)),
+    };
+
+    let generics = bound(&input.generics, quote!(::json_minimal::ToJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::json_minimal::ToJson for #ident #ty_generics #where_clause {
+            fn to_json(&self) -> ::json_minimal::Json {
+                #body
+            }
+        }
+    })
+}
+
+fn from_json(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let target = ident.unraw().to_string();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            container_options(&input.attrs, false)?;
+            check_unnamed(&data.fields)?;
+
+            fields_from_json(&data.fields, quote!(Self), &target)?
+        }
+        Data::Enum(data) => enum_from_json(data, container_options(&input.attrs, true)?, &target)?,
+        Data::Union(_) => return Err(unsupported(input)),
+    };
+
+    let generics = bound(&input.generics, quote!(::json_minimal::FromJson));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::json_minimal::FromJson for #ident #ty_generics #where_clause {
+            fn from_json(json: &::json_minimal::Json) -> ::std::result::Result<Self, ::json_minimal::FromJsonError> {
+                #body
+            }
+        }
+    })
+}
+
+// Builds the `Json` for `fields`, where `value` gives a reference to the field's value.
+fn fields_to_json<F>(fields: &Fields, value: F) -> syn::Result<TokenStream2>
+where
+    F: Fn(&Field, usize) -> TokenStream2,
+{
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let members = named_to_json(fields, value)?;
+
+            quote!(::json_minimal::Json::JSON(vec![#(#members),*]))
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            let value = value(&fields.unnamed[0], 0);
+
+            quote!(::json_minimal::ToJson::to_json(#value))
+        }
+        Fields::Unnamed(fields) => {
+            let values = fields.unnamed.iter().enumerate().map(|(index, field)| {
+                let value = value(field, index);
+
+                quote!(::json_minimal::ToJson::to_json(#value))
+            });
+
+            quote!(::json_minimal::Json::ARRAY(vec![#(#values),*]))
+        }
+        Fields::Unit => quote!(::json_minimal::Json::NULL),
+    })
+}
+
+fn named_to_json<F>(fields: &FieldsNamed, value: F) -> syn::Result<Vec<TokenStream2>>
+where
+    F: Fn(&Field, usize) -> TokenStream2,
+{
+    let mut members = Vec::new();
+
+    for (index, field) in fields.named.iter().enumerate() {
+        let options = FieldOptions::parse(field)?;
+        let name = options.name;
+        let value = value(field, index);
+
+        if !options.skip {
+            members.push(
+                quote!(::json_minimal::Json::member(#name, ::json_minimal::ToJson::to_json(#value))),
+            );
+        }
+    }
+
+    Ok(members)
+}
+
+// Builds the statements reading `fields` from `json` and returning `Ok(#path ...)`.
+fn fields_from_json(
+    fields: &Fields,
+    path: TokenStream2,
+    target: &str,
+) -> syn::Result<TokenStream2> {
+    Ok(match fields {
+        Fields::Named(fields) => {
+            let members = named_from_json(fields, target)?;
+
+            quote! {
+                let json = ::json_minimal::__private::object(json, #target)?;
+
+                Ok(#path { #(#members),* })
+            }
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            quote!(Ok(#path(::json_minimal::FromJson::from_json(json)?)))
+        }
+        Fields::Unnamed(fields) => {
+            let len = fields.unnamed.len();
+            let values =
+                (0..len).map(|index| quote!(::json_minimal::FromJson::from_json(&values[#index])?));
+
+            quote! {
+                let values = ::json_minimal::__private::elements(json, #target, #len)?;
+
+                Ok(#path(#(#values),*))
+            }
+        }
+        Fields::Unit => quote! {
+            ::json_minimal::__private::unit(json, #target)?;
+
+            Ok(#path)
+        },
+    })
+}
+
+fn named_from_json(fields: &FieldsNamed, target: &str) -> syn::Result<Vec<TokenStream2>> {
+    let mut members = Vec::new();
+
+    for field in &fields.named {
+        let options = FieldOptions::parse(field)?;
+        let ident = &field.ident;
+        let name = options.name;
+
+        members.push(if options.skip {
+            quote!(#ident: ::std::default::Default::default())
+        } else if options.default {
+            quote! {
+                #ident: ::json_minimal::__private::optional_field(json, #name)?
+                    .unwrap_or_default()
+            }
+        } else {
+            quote!(#ident: ::json_minimal::__private::field(json, #target, #name)?)
+        });
+    }
+
+    Ok(members)
+}
+
+fn enum_to_json(data: &DataEnum, tag: Option<String>) -> syn::Result<TokenStream2> {
+    let mut arms = Vec::new();
+
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let name = variant_name(variant)?;
+        let pattern = variant_pattern(variant)?;
+        let binding = |field: &Field, index: usize| match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let ident = format_ident!("__{}", index);
+
+                quote!(#ident)
+            }
+        };
+
+        let body = match (&tag, &variant.fields) {
+            (None, Fields::Unit) => quote!(::json_minimal::Json::string(#name)),
+            (None, fields) => {
+                let value = fields_to_json(fields, binding)?;
+
+                quote!(::json_minimal::Json::JSON(
+                    vec![::json_minimal::Json::member(#name, #value)]
+                ))
+            }
+            (Some(tag), Fields::Unit) => quote! {
+                ::json_minimal::Json::JSON(vec![
+                    ::json_minimal::Json::member(#tag, ::json_minimal::Json::string(#name))
+                ])
+            },
+            (Some(tag), Fields::Named(fields)) => {
+                let members = named_to_json(fields, binding)?;
+
+                quote! {
+                    ::json_minimal::Json::JSON(vec![
+                        ::json_minimal::Json::member(#tag, ::json_minimal::Json::string(#name)),
+                        #(#members),*
+                    ])
+                }
+            }
+            (Some(_), Fields::Unnamed(_)) => return Err(internally_tagged(variant)),
+        };
+
+        arms.push(quote!(Self::#ident #pattern => #body));
+    }
+
+    Ok(quote! {
+        match self {
+            #(#arms,)*
+        }
+    })
+}
+
+fn enum_from_json(data: &DataEnum, tag: Option<String>, target: &str) -> syn::Result<TokenStream2> {
+    let mut arms = Vec::new();
+
+    for variant in &data.variants {
+        let ident = &variant.ident;
+        let name = variant_name(variant)?;
+
+        arms.push(match (&tag, &variant.fields) {
+            (None, Fields::Unit) => quote! {
+                (#name, value) => {
+                    if let Some(json) = value {
+                        ::json_minimal::__private::unit(json, #target)?;
+                    }
+
+                    Ok(Self::#ident)
+                }
+            },
+            (None, fields) => {
+                let body = fields_from_json(fields, quote!(Self::#ident), target)?;
+
+                quote! {
+                    (#name, value) => {
+                        let json = ::json_minimal::__private::variant_value(value, #target)?;
+
+                        #body
+                    }
+                }
+            }
+            (Some(_), Fields::Unit) => quote!(#name => Ok(Self::#ident)),
+            (Some(_), Fields::Named(fields)) => {
+                let members = named_from_json(fields, target)?;
+
+                quote!(#name => Ok(Self::#ident { #(#members),* }))
+            }
+            (Some(_), Fields::Unnamed(_)) => return Err(internally_tagged(variant)),
+        });
+    }
+
+    let unknown = quote! {
+        Err(::json_minimal::FromJsonError::UnknownVariant {
+            target: #target,
+            variant: ::std::string::String::from(name),
+        })
+    };
+
+    Ok(match tag {
+        None => quote! {
+            match ::json_minimal::__private::variant(json, #target)? {
+                #(#arms,)*
+                (name, _) => #unknown,
+            }
+        },
+        Some(tag) => quote! {
+            let json = ::json_minimal::__private::object(json, #target)?;
+            let name: ::std::string::String = ::json_minimal::__private::field(json, #target, #tag)?;
+
+            match name.as_str() {
+                #(#arms,)*
+                name => #unknown,
+            }
+        },
+    })
+}
+
+// Binds the fields of `variant` in a `match self`, leaving out skipped ones.
+fn variant_pattern(variant: &Variant) -> syn::Result<TokenStream2> {
+    Ok(match &variant.fields {
+        Fields::Named(fields) => {
+            let mut idents = Vec::new();
+
+            for field in &fields.named {
+                if !FieldOptions::parse(field)?.skip {
+                    idents.push(&field.ident);
+                }
+            }
+
+            quote!({ #(#idents,)* .. })
+        }
+        Fields::Unnamed(fields) => {
+            check_unnamed(&variant.fields)?;
+
+            let idents = (0..fields.unnamed.len()).map(|index| format_ident!("__{}", index));
+
+            quote!((#(#idents),*))
+        }
+        Fields::Unit => quote!(),
+    })
+}
+
+fn internally_tagged(variant: &Variant) -> syn::Error {
+    syn::Error::new_spanned(
+        &variant.ident,
+        "internally tagged enums only support unit variants and variants with named fields",
+    )
+}
+
+fn unsupported(input: &DeriveInput) -> syn::Error {
+    syn::Error::new_spanned(&input.ident, "unions are not supported")
+}
+
+// The `#[json(...)]` attributes of a named field.
+struct FieldOptions {
+    name: String,
+    skip: bool,
+    default: bool,
+}
+
+impl FieldOptions {
+    fn parse(field: &Field) -> syn::Result<FieldOptions> {
+        let mut options = FieldOptions {
+            name: field
+                .ident
+                .as_ref()
+                .map(|ident| ident.unraw().to_string())
+                .unwrap_or_default(),
+            skip: false,
+            default: false,
+        };
+
+        for attr in json_attrs(&field.attrs) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    options.name = meta.value()?.parse::<LitStr>()?.value();
+                } else if meta.path.is_ident("skip") {
+                    options.skip = true;
+                } else if meta.path.is_ident("default") {
+                    options.default = true;
+                } else {
+                    return Err(meta.error("expected `rename`, `skip` or `default`"));
+                }
+
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+// The `#[json(tag = "...")]` attribute, which is only allowed on enums.
+fn container_options(attrs: &[Attribute], is_enum: bool) -> syn::Result<Option<String>> {
+    let mut tag = None;
+
+    for attr in json_attrs(attrs) {
+        attr.parse_nested_meta(|meta| {
+            if is_enum && meta.path.is_ident("tag") {
+                tag = Some(meta.value()?.parse::<LitStr>()?.value());
+
+                Ok(())
+            } else if is_enum {
+                Err(meta.error("expected `tag`"))
+            } else {
+                Err(meta.error("structs don't accept any `#[json(...)]` attributes"))
+            }
+        })?;
+    }
+
+    Ok(tag)
+}
+
+// The `#[json(rename = "...")]` attribute of a variant.
+fn variant_name(variant: &Variant) -> syn::Result<String> {
+    let mut name = variant.ident.unraw().to_string();
+
+    for attr in json_attrs(&variant.attrs) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                name = meta.value()?.parse::<LitStr>()?.value();
+
+                Ok(())
+            } else {
+                Err(meta.error("expected `rename`"))
+            }
+        })?;
+    }
+
+    Ok(name)
+}
+
+fn json_attrs(attrs: &[Attribute]) -> impl Iterator<Item = &Attribute> {
+    attrs.iter().filter(|attr| attr.path().is_ident("json"))
+}
+
+fn check_unnamed(fields: &Fields) -> syn::Result<()> {
+    if let Fields::Unnamed(fields) = fields {
+        if let Some(attr) = fields
+            .unnamed
+            .iter()
+            .flat_map(|field| json_attrs(&field.attrs))
+            .next()
+        {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "`#[json(...)]` is only supported on named fields",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+// Requires every type parameter to implement `bound` as well.
+fn bound(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    let params: Vec<_> = generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = generics.make_where_clause();
+
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+
+    generics
+}
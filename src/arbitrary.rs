@@ -0,0 +1,205 @@
+//! Generating random `Json` values for property tests (requires the `proptest` or `quickcheck` feature).
+//!
+//! The values are restricted to what `Json::parse` reads back from `Json::print` unchanged: numbers
+//! are finite and not negative, and strings (including the names of members) contain neither `"`
+//! nor `\`, as `print` doesn't escape them. Only `Json::JSON` holds `Json::OBJECT`s.
+
+use crate::Json;
+
+/// Limits the values generated by the `Arbitrary` impls of `Json`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArbitraryBounds {
+    /// How deeply objects and arrays may be nested, `0` allowing only plain values.
+    pub depth: u32,
+    /// The number of values (at all levels) to aim for. Only used by `proptest`, `quickcheck` uses the
+    /// size of its `Gen` instead.
+    pub size: u32,
+    /// The maximum number of members of an object or elements of an array.
+    pub width: u32,
+}
+
+impl Default for ArbitraryBounds {
+    fn default() -> ArbitraryBounds {
+        ArbitraryBounds {
+            depth: 4,
+            size: 64,
+            width: 8,
+        }
+    }
+}
+
+// Keeps a generated character out of the ones `print` would have to escape.
+fn printable(c: char) -> bool {
+    c != '"' && c != '\\'
+}
+
+fn members(members: Vec<(String, Json)>) -> Json {
+    Json::JSON(
+        members
+            .into_iter()
+            .map(|(name, value)| Json::OBJECT {
+                name,
+                value: Box::new(value),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(feature = "proptest")]
+mod proptest_impl {
+    use super::{members, printable, ArbitraryBounds};
+    use crate::Json;
+    use proptest::arbitrary::{any, Arbitrary};
+    use proptest::collection::vec;
+    use proptest::prelude::*;
+    use proptest::strategy::BoxedStrategy;
+    use std::iter::FromIterator;
+
+    fn string() -> impl Strategy<Value = String> {
+        vec(
+            any::<char>().prop_filter("must be printable", |c| printable(*c)),
+            0..8,
+        )
+        .prop_map(String::from_iter)
+    }
+
+    fn number() -> impl Strategy<Value = f64> {
+        prop_oneof![
+            (0u32..1000).prop_map(f64::from),
+            proptest::num::f64::POSITIVE
+                | proptest::num::f64::NORMAL
+                | proptest::num::f64::SUBNORMAL
+                | proptest::num::f64::ZERO,
+        ]
+    }
+
+    impl Arbitrary for Json {
+        type Parameters = ArbitraryBounds;
+        type Strategy = BoxedStrategy<Json>;
+
+        fn arbitrary_with(bounds: ArbitraryBounds) -> BoxedStrategy<Json> {
+            let width = bounds.width as usize;
+
+            let leaf = prop_oneof![
+                Just(Json::NULL),
+                any::<bool>().prop_map(Json::BOOL),
+                number().prop_map(Json::NUMBER),
+                string().prop_map(Json::STRING),
+            ];
+
+            leaf.prop_recursive(bounds.depth, bounds.size, bounds.width, move |inner| {
+                prop_oneof![
+                    vec(inner.clone(), 0..=width).prop_map(Json::ARRAY),
+                    vec((string(), inner), 0..=width).prop_map(members),
+                ]
+            })
+            .boxed()
+        }
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+mod quickcheck_impl {
+    use super::{members, printable, ArbitraryBounds};
+    use crate::Json;
+    use quickcheck::{Arbitrary, Gen};
+
+    fn string(g: &mut Gen) -> String {
+        String::arbitrary(g)
+            .chars()
+            .filter(|c| printable(*c))
+            .collect()
+    }
+
+    fn number(g: &mut Gen) -> f64 {
+        if bool::arbitrary(g) {
+            f64::from(u32::arbitrary(g) % 1000)
+        } else {
+            match f64::arbitrary(g).abs() {
+                val if val.is_finite() => val,
+                _ => 0.0,
+            }
+        }
+    }
+
+    // At most `depth` levels of objects and arrays below this one, each with at most as many members
+    // or elements as both the size of `g` and `width` allow.
+    fn arbitrary(g: &mut Gen, depth: u32, width: usize) -> Json {
+        let kinds = if depth == 0 { 4 } else { 6 };
+        let width = width.min(g.size());
+
+        match u32::arbitrary(g) % kinds {
+            0 => Json::NULL,
+            1 => Json::BOOL(bool::arbitrary(g)),
+            2 => Json::NUMBER(number(g)),
+            3 => Json::STRING(string(g)),
+            kind => {
+                let len = usize::arbitrary(g) % (width + 1);
+
+                if kind == 4 {
+                    Json::ARRAY((0..len).map(|_| arbitrary(g, depth - 1, width)).collect())
+                } else {
+                    members(
+                        (0..len)
+                            .map(|_| (string(g), arbitrary(g, depth - 1, width)))
+                            .collect(),
+                    )
+                }
+            }
+        }
+    }
+
+    impl Arbitrary for Json {
+        fn arbitrary(g: &mut Gen) -> Json {
+            let bounds = ArbitraryBounds::default();
+
+            arbitrary(g, bounds.depth, bounds.width as usize)
+        }
+
+        // Tries `null`, then each of the values held by an object or array and then the object or array
+        // without one of them.
+        fn shrink(&self) -> Box<dyn Iterator<Item = Json>> {
+            match self {
+                Json::JSON(values) | Json::ARRAY(values) => {
+                    let json = self.clone();
+                    let children: Vec<Json> =
+                        values.iter().map(|value| value.inner().clone()).collect();
+                    let len = values.len();
+
+                    Box::new(
+                        std::iter::once(Json::NULL)
+                            .chain(children)
+                            .chain((0..len).map(move |n| {
+                                let mut json = json.clone();
+
+                                if let Json::JSON(values) | Json::ARRAY(values) = &mut json {
+                                    values.remove(n);
+                                }
+
+                                json
+                            })),
+                    )
+                }
+                Json::STRING(val) => Box::new(
+                    std::iter::once(Json::NULL).chain(
+                        val.shrink()
+                            .filter(|val| val.chars().all(printable))
+                            .map(Json::STRING),
+                    ),
+                ),
+                Json::NUMBER(val) => Box::new(
+                    std::iter::once(Json::NULL).chain(
+                        val.shrink()
+                            .filter(|val| val.is_finite() && *val >= 0.0)
+                            .map(|val| Json::NUMBER(val + 0.0)),
+                    ),
+                ),
+                Json::BOOL(val) => {
+                    Box::new(std::iter::once(Json::NULL).chain(val.shrink().map(Json::BOOL)))
+                }
+                Json::OBJECT { name: _, value } => Json::shrink(value),
+                Json::NULL => quickcheck::empty_shrinker(),
+            }
+        }
+    }
+}
@@ -0,0 +1,520 @@
+//! Helpers for working with `Json::ARRAY`s, in particular arrays of objects, and conversions between arrays and objects.
+
+use crate::{Json, NotANumber};
+use std::cmp::Ordering;
+
+/// What `Json::stats` does with elements that aren't a `Json::NUMBER`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonNumeric {
+    /// Leave them out.
+    Skip,
+    /// Return a `NotANumber` error for the first one.
+    Fail,
+}
+
+/// Aggregated values of the numbers in an array, see `Json::stats`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Stats {
+    /// The arithmetic mean.
+    pub fn avg(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+impl Json {
+    /// Sorts the elements of a `Json::ARRAY` (or a `Json::OBJECT` holding one) with `compare`. The sort
+    /// is stable. Does nothing if called on any other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"[\"ccc\",\"a\",\"bb\"]").unwrap();
+    ///
+    /// json.sort_array_by(|a, b| {
+    ///     let a = a.as_str().map(str::len);
+    ///     let b = b.as_str().map(str::len);
+    ///
+    ///     a.cmp(&b)
+    /// });
+    ///
+    /// assert_eq!(json.print(), "[\"a\",\"bb\",\"ccc\"]");
+    /// ```
+    pub fn sort_array_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&Json, &Json) -> Ordering,
+    {
+        if let Some(values) = self.array_mut() {
+            values.sort_by(|a, b| compare(a, b));
+        }
+    }
+
+    /// Sorts an array of objects by the value of their member `key` (see `Json::compare` for the order).
+    /// Elements without that member come last. Does nothing if not called on a `Json::ARRAY`
+    /// (or a `Json::OBJECT` holding one).
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"[{\"Name\":\"b\",\"Age\":3},{\"Name\":\"c\"},{\"Name\":\"a\",\"Age\":1}]").unwrap();
+    ///
+    /// json.sort_array_by_key("Age");
+    ///
+    /// assert_eq!(
+    ///     json.print(),
+    ///     "[{\"Name\":\"a\",\"Age\":1},{\"Name\":\"b\",\"Age\":3},{\"Name\":\"c\"}]"
+    /// );
+    /// ```
+    pub fn sort_array_by_key(&mut self, key: &str) {
+        self.sort_array_by(|a, b| match (field(a, key), field(b, key)) {
+            (Some(a), Some(b)) => Json::compare(a, b),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+    }
+
+    /// A total order over `Json` values, as used by `sort_array_by_key`. Values of different variants
+    /// are ordered `Json::NULL` < `Json::BOOL` < `Json::NUMBER` < `Json::STRING` < `Json::ARRAY` <
+    /// `Json::JSON` < `Json::OBJECT`. Numbers compare as `f64::total_cmp` does, i.e. numerically but
+    /// with `-0.0` before `0.0` and `NaN` after every other number. Strings compare by their bytes,
+    /// arrays, objects and members element by element.
+    pub fn compare(a: &Json, b: &Json) -> Ordering {
+        match (a, b) {
+            (Json::BOOL(a), Json::BOOL(b)) => a.cmp(b),
+            (Json::NUMBER(a), Json::NUMBER(b)) => a.total_cmp(b),
+            (Json::STRING(a), Json::STRING(b)) => a.cmp(b),
+            (Json::ARRAY(a), Json::ARRAY(b)) | (Json::JSON(a), Json::JSON(b)) => a
+                .iter()
+                .zip(b)
+                .map(|(a, b)| Json::compare(a, b))
+                .find(|ordering| *ordering != Ordering::Equal)
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (
+                Json::OBJECT {
+                    name: a_name,
+                    value: a_value,
+                },
+                Json::OBJECT {
+                    name: b_name,
+                    value: b_value,
+                },
+            ) => a_name
+                .cmp(b_name)
+                .then_with(|| Json::compare(a_value, b_value)),
+            (a, b) => rank(a).cmp(&rank(b)),
+        }
+    }
+
+    /// Removes every element of a `Json::ARRAY` (or a `Json::OBJECT` holding one) which is (deeply)
+    /// equal to an element before it, so the first occurrence is kept and the order is preserved.
+    /// Does nothing if called on any other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"[1,[2],1,\"1\",[2]]").unwrap();
+    ///
+    /// json.dedup();
+    ///
+    /// assert_eq!(json.print(), "[1,[2],\"1\"]");
+    /// ```
+    pub fn dedup(&mut self) {
+        if let Some(values) = self.array_mut() {
+            let mut n = 0;
+
+            while n < values.len() {
+                if values[..n].contains(&values[n]) {
+                    values.remove(n);
+                } else {
+                    n += 1;
+                }
+            }
+        }
+    }
+
+    /// Same as `dedup` above, but for arrays of objects: an element is removed if an element before it
+    /// has a member `key` with an equal value. Elements without that member are always kept.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"[{\"id\":1,\"v\":\"a\"},{\"id\":2},{\"id\":1,\"v\":\"b\"}]").unwrap();
+    ///
+    /// json.dedup_by_key("id");
+    ///
+    /// assert_eq!(json.print(), "[{\"id\":1,\"v\":\"a\"},{\"id\":2}]");
+    /// ```
+    pub fn dedup_by_key(&mut self, key: &str) {
+        if let Some(values) = self.array_mut() {
+            let mut n = 0;
+
+            while n < values.len() {
+                let duplicate = match field(&values[n], key) {
+                    Some(value) => values[..n]
+                        .iter()
+                        .any(|json| field(json, key) == Some(value)),
+                    None => false,
+                };
+
+                if duplicate {
+                    values.remove(n);
+                } else {
+                    n += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns a new `Json::ARRAY` holding copies of the elements of a `Json::ARRAY` (or a `Json::OBJECT`
+    /// holding one) for which `keep` returns `true`. Returns an empty `Json::ARRAY` if called on any
+    /// other variant. `self` is left untouched; use `retain` to filter in place.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[{\"Name\":\"a\",\"Active\":true},{\"Name\":\"b\",\"Active\":false}]").unwrap();
+    ///
+    /// let active = json.filter_array(|user| user.get_bool("Active") == Some(true));
+    ///
+    /// assert_eq!(active.print(), "[{\"Name\":\"a\",\"Active\":true}]");
+    /// ```
+    pub fn filter_array<F>(&self, mut keep: F) -> Json
+    where
+        F: FnMut(&Json) -> bool,
+    {
+        Json::ARRAY(
+            self.as_array()
+                .unwrap_or_default()
+                .iter()
+                .filter(|json| keep(json))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Returns the first element of a `Json::ARRAY` (or a `Json::OBJECT` holding one) for which
+    /// `predicate` returns `true`. Returns `None` if there is none or if called on any other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[{\"id\":1,\"Name\":\"a\"},{\"id\":2,\"Name\":\"b\"}]").unwrap();
+    ///
+    /// let user = json.find(|user| user.get_f64("id") == Some(2.0));
+    ///
+    /// assert_eq!(user.and_then(|user| user.get_str("Name")), Some("b"));
+    /// assert_eq!(json.position(|user| user.get_f64("id") == Some(2.0)), Some(1));
+    /// ```
+    pub fn find<F>(&self, mut predicate: F) -> Option<&Json>
+    where
+        F: FnMut(&Json) -> bool,
+    {
+        self.as_array()?.iter().find(|json| predicate(json))
+    }
+
+    /// Same as `find` above, but the reference is mutable.
+    pub fn find_mut<F>(&mut self, mut predicate: F) -> Option<&mut Json>
+    where
+        F: FnMut(&Json) -> bool,
+    {
+        self.array_mut()?.iter_mut().find(|json| predicate(json))
+    }
+
+    /// Same as `find` above, but returns the index of the element.
+    pub fn position<F>(&self, predicate: F) -> Option<usize>
+    where
+        F: FnMut(&Json) -> bool,
+    {
+        self.as_array()?.iter().position(predicate)
+    }
+
+    /// Returns a new `Json::ARRAY` holding a copy of the value of the member `key` of every element of
+    /// an array of objects. Elements without that member are left out. Returns an empty `Json::ARRAY`
+    /// if not called on a `Json::ARRAY` (or a `Json::OBJECT` holding one).
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[{\"Name\":\"a\",\"Age\":1},{\"Age\":2},{\"Name\":\"c\"}]").unwrap();
+    ///
+    /// assert_eq!(json.pluck("Name").print(), "[\"a\",\"c\"]");
+    /// ```
+    pub fn pluck(&self, key: &str) -> Json {
+        Json::ARRAY(
+            self.as_array()
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|json| field(json, key))
+                .cloned()
+                .collect(),
+        )
+    }
+
+    /// Groups the elements of an array of objects by the value of their member `key`: returns a
+    /// `Json::JSON` with one member per distinct value (in the order of first appearance) holding a
+    /// `Json::ARRAY` of copies of the elements with that value. Strings are used as names as they are,
+    /// every other value in its printed form (e.g. `42` or `null`). Elements without that member are
+    /// left out. Returns an empty `Json::JSON` if not called on a `Json::ARRAY` (or a `Json::OBJECT` holding one).
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[{\"id\":1,\"Status\":\"open\"},{\"id\":2,\"Status\":\"done\"},{\"id\":3,\"Status\":\"open\"}]").unwrap();
+    ///
+    /// assert_eq!(
+    ///     json.group_by("Status").print(),
+    ///     "{\"open\":[{\"id\":1,\"Status\":\"open\"},{\"id\":3,\"Status\":\"open\"}],\"done\":[{\"id\":2,\"Status\":\"done\"}]}"
+    /// );
+    /// ```
+    pub fn group_by(&self, key: &str) -> Json {
+        let mut groups = Json::object();
+
+        for json in self.as_array().unwrap_or_default() {
+            let name = match field(json, key) {
+                Some(Json::STRING(val)) => val.clone(),
+                Some(value) => value.print(),
+                None => {
+                    continue;
+                }
+            };
+
+            match groups.value_of_mut(&name) {
+                Some(group) => {
+                    group.add(json.clone());
+                }
+                None => {
+                    groups.add(Json::member(name, Json::ARRAY(vec![json.clone()])));
+                }
+            }
+        }
+
+        groups
+    }
+
+    /// Aggregates the numbers in a `Json::ARRAY` (or a `Json::OBJECT` holding one). Elements which aren't
+    /// a `Json::NUMBER` are handled according to `non_numeric`. Returns `Ok(None)` if there are no numbers
+    /// (or if called on any other variant). Combine with `pluck` to aggregate a member of an array of objects.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[{\"Price\":2.5},{\"Price\":\"free\"},{\"Price\":4.5}]").unwrap();
+    ///
+    /// let prices = json.pluck("Price");
+    ///
+    /// match prices.stats(NonNumeric::Skip) {
+    ///     Ok(Some(stats)) => {
+    ///         assert_eq!(stats.count, 2);
+    ///         assert_eq!(stats.avg(), 3.5);
+    ///     },
+    ///     result => {
+    ///         panic!("Expected stats but found {:?}!!!",result);
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     prices.stats(NonNumeric::Fail),
+    ///     Err(NotANumber { index: 1, found: "Json::STRING" })
+    /// );
+    /// ```
+    pub fn stats(&self, non_numeric: NonNumeric) -> Result<Option<Stats>, NotANumber> {
+        let mut stats: Option<Stats> = None;
+
+        for (index, json) in self.as_array().unwrap_or_default().iter().enumerate() {
+            let n = match (json, non_numeric) {
+                (Json::NUMBER(n), _) => *n,
+                (_, NonNumeric::Skip) => {
+                    continue;
+                }
+                (json, NonNumeric::Fail) => {
+                    return Err(NotANumber {
+                        index,
+                        found: json.variant_name(),
+                    });
+                }
+            };
+
+            stats = Some(match stats {
+                Some(stats) => Stats {
+                    count: stats.count + 1,
+                    sum: stats.sum + n,
+                    min: stats.min.min(n),
+                    max: stats.max.max(n),
+                },
+                None => Stats {
+                    count: 1,
+                    sum: n,
+                    min: n,
+                    max: n,
+                },
+            });
+        }
+
+        Ok(stats)
+    }
+
+    /// The sum of the numbers in an array, skipping everything else (see `stats`).
+    pub fn sum(&self) -> Option<f64> {
+        self.skipping_stats().map(|stats| stats.sum)
+    }
+
+    /// The arithmetic mean of the numbers in an array, skipping everything else (see `stats`).
+    pub fn avg(&self) -> Option<f64> {
+        self.skipping_stats().map(|stats| stats.avg())
+    }
+
+    /// The smallest number in an array, skipping everything else (see `stats`).
+    pub fn min(&self) -> Option<f64> {
+        self.skipping_stats().map(|stats| stats.min)
+    }
+
+    /// The largest number in an array, skipping everything else (see `stats`).
+    pub fn max(&self) -> Option<f64> {
+        self.skipping_stats().map(|stats| stats.max)
+    }
+
+    fn skipping_stats(&self) -> Option<Stats> {
+        self.stats(NonNumeric::Skip).ok().flatten()
+    }
+
+    /// Transposes an array of objects (rows) into an object of arrays (columns):
+    /// `[{"a":1,"b":2},{"a":3,"b":4}]` becomes `{"a":[1,3],"b":[2,4]}`. The columns appear in the order
+    /// in which their names are first seen. Rows without a certain member get a `Json::NULL` in that column,
+    /// so all columns have the same length. Returns an empty `Json::JSON` if not called on a `Json::ARRAY`
+    /// (or a `Json::OBJECT` holding one). See `from_columns` for the reverse.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let rows = Json::parse(b"[{\"x\":1,\"y\":2},{\"x\":3}]").unwrap();
+    ///
+    /// let columns = rows.to_columns();
+    ///
+    /// assert_eq!(columns.print(), "{\"x\":[1,3],\"y\":[2,null]}");
+    /// assert_eq!(columns.from_columns().print(), "[{\"x\":1,\"y\":2},{\"x\":3,\"y\":null}]");
+    /// ```
+    pub fn to_columns(&self) -> Json {
+        let rows = self.as_array().unwrap_or_default();
+
+        let mut names: Vec<&str> = Vec::new();
+
+        for name in rows.iter().flat_map(Json::keys) {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        let mut columns = Json::object();
+
+        for name in names {
+            let cells = rows
+                .iter()
+                .map(|row| field(row, name).cloned().unwrap_or(Json::NULL))
+                .collect();
+
+            columns.add(Json::member(name, Json::ARRAY(cells)));
+        }
+
+        columns
+    }
+
+    /// Reverses `to_columns`: turns an object of arrays into an array of objects, where the n-th object
+    /// has a member for every column with an n-th element. Members which aren't arrays are treated as
+    /// columns holding a single element. Returns an empty `Json::ARRAY` if not called on a `Json::JSON`
+    /// (or a `Json::OBJECT` holding one).
+    pub fn from_columns(&self) -> Json {
+        let mut rows: Vec<Json> = Vec::new();
+
+        for (name, column) in self.keys().zip(self.values()) {
+            let cells = match column {
+                Json::ARRAY(cells) => cells.as_slice(),
+                cell => std::slice::from_ref(cell),
+            };
+
+            for (n, cell) in cells.iter().enumerate() {
+                if rows.len() <= n {
+                    rows.push(Json::object());
+                }
+
+                rows[n].add(Json::member(name, cell.clone()));
+            }
+        }
+
+        Json::ARRAY(rows)
+    }
+
+    /// Turns the members of a `Json::JSON` (or a `Json::OBJECT` holding one) into a `Json::ARRAY` of
+    /// `[name, value]` pairs, like JavaScript's `Object.entries`. Returns an empty `Json::ARRAY` for
+    /// every other variant. See `from_entries` for the reverse.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"a\":1,\"b\":[true]}").unwrap();
+    ///
+    /// let entries = json.to_entries();
+    ///
+    /// assert_eq!(entries.print(), "[[\"a\",1],[\"b\",[true]]]");
+    /// assert_eq!(entries.from_entries(), json);
+    /// ```
+    pub fn to_entries(&self) -> Json {
+        Json::ARRAY(
+            self.keys()
+                .zip(self.values())
+                .map(|(name, value)| Json::ARRAY(vec![Json::string(name), value.clone()]))
+                .collect(),
+        )
+    }
+
+    /// Turns a `Json::ARRAY` of `[name, value]` pairs into a `Json::JSON`, like JavaScript's
+    /// `Object.fromEntries`. Names which aren't a `Json::STRING` are used in their printed form,
+    /// elements which aren't a pair are left out. Returns an empty `Json::JSON` if not called on a
+    /// `Json::ARRAY` (or a `Json::OBJECT` holding one).
+    pub fn from_entries(&self) -> Json {
+        let mut json = Json::object();
+
+        for entry in self.as_array().unwrap_or_default() {
+            if let Some([name, value]) = entry.as_array() {
+                let name = match name {
+                    Json::STRING(name) => name.clone(),
+                    name => name.print(),
+                };
+
+                json.add(Json::member(name, value.clone()));
+            }
+        }
+
+        json
+    }
+
+    fn array_mut(&mut self) -> Option<&mut Vec<Json>> {
+        match self.inner_mut() {
+            Json::ARRAY(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+fn rank(json: &Json) -> u8 {
+    match json {
+        Json::NULL => 0,
+        Json::BOOL(_) => 1,
+        Json::NUMBER(_) => 2,
+        Json::STRING(_) => 3,
+        Json::ARRAY(_) => 4,
+        Json::JSON(_) => 5,
+        Json::OBJECT { name: _, value: _ } => 6,
+    }
+}
+
+// The value of the member `key` of an element of an array of objects.
+fn field<'a>(json: &'a Json, key: &str) -> Option<&'a Json> {
+    json.try_value_of(key).ok().flatten()
+}
@@ -0,0 +1,154 @@
+use crate::{AddError, Json};
+
+/// A fluent way of putting a `Json` together without panicking. Members are added with the named
+/// methods (`string`, `number`, `object`, ...), unnamed values and array elements with the `push` methods.
+/// The first error (see `Json::try_add`) is remembered and returned by `build`; everything added after it is ignored.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let json = JsonBuilder::new()
+///     .string("Greeting", "Hello, world!")
+///     .object("Days in the week", |days| {
+///         days.number("Total number of days", 7.0)
+///             .array("They are called", |names| {
+///                 names
+///                     .push(Json::string("Monday"))
+///                     .push(Json::string("Tuesday"))
+///             })
+///     })
+///     .bool("Minimal in my opinion", true)
+///     .null("How much I care about your opinion")
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(
+///     json.print(),
+///     "{\"Greeting\":\"Hello, world!\",\"Days in the week\":{\"Total number of days\":7,\"They are called\":[\"Monday\",\"Tuesday\"]},\"Minimal in my opinion\":true,\"How much I care about your opinion\":null}"
+/// );
+/// ```
+#[derive(Debug)]
+pub struct JsonBuilder {
+    json: Json,
+    error: Option<AddError>,
+}
+
+impl JsonBuilder {
+    /// Start building a `Json::JSON`.
+    pub fn new() -> JsonBuilder {
+        JsonBuilder::from(Json::object())
+    }
+
+    /// Start building a `Json::ARRAY`.
+    pub fn new_array() -> JsonBuilder {
+        JsonBuilder::from(Json::array())
+    }
+
+    /// Add a member holding a `Json::JSON` which is built by `build`.
+    pub fn object<F>(self, name: &str, build: F) -> JsonBuilder
+    where
+        F: FnOnce(JsonBuilder) -> JsonBuilder,
+    {
+        self.nested(Some(name), JsonBuilder::new(), build)
+    }
+
+    /// Add a member holding a `Json::ARRAY` which is built by `build`.
+    pub fn array<F>(self, name: &str, build: F) -> JsonBuilder
+    where
+        F: FnOnce(JsonBuilder) -> JsonBuilder,
+    {
+        self.nested(Some(name), JsonBuilder::new_array(), build)
+    }
+
+    /// Add a member holding a `Json::STRING`.
+    pub fn string<S: Into<String>>(self, name: &str, value: S) -> JsonBuilder {
+        self.value(name, Json::string(value))
+    }
+
+    /// Add a member holding a `Json::NUMBER`.
+    pub fn number(self, name: &str, value: f64) -> JsonBuilder {
+        self.value(name, Json::number(value))
+    }
+
+    /// Add a member holding a `Json::BOOL`.
+    pub fn bool(self, name: &str, value: bool) -> JsonBuilder {
+        self.value(name, Json::BOOL(value))
+    }
+
+    /// Add a member holding `Json::NULL`.
+    pub fn null(self, name: &str) -> JsonBuilder {
+        self.value(name, Json::NULL)
+    }
+
+    /// Add a member holding any `Json`.
+    pub fn value(self, name: &str, value: Json) -> JsonBuilder {
+        self.push(Json::member(name, value))
+    }
+
+    /// Add an unnamed value, e.g. an element of an array.
+    pub fn push(mut self, value: Json) -> JsonBuilder {
+        if self.error.is_none() {
+            if let Err(error) = self.json.try_add(value) {
+                self.error = Some(error);
+            }
+        }
+
+        self
+    }
+
+    /// Add an unnamed `Json::JSON` (e.g. an element of an array) which is built by `build`.
+    pub fn push_object<F>(self, build: F) -> JsonBuilder
+    where
+        F: FnOnce(JsonBuilder) -> JsonBuilder,
+    {
+        self.nested(None, JsonBuilder::new(), build)
+    }
+
+    /// Add an unnamed `Json::ARRAY` (e.g. an element of an array) which is built by `build`.
+    pub fn push_array<F>(self, build: F) -> JsonBuilder
+    where
+        F: FnOnce(JsonBuilder) -> JsonBuilder,
+    {
+        self.nested(None, JsonBuilder::new_array(), build)
+    }
+
+    /// Returns the finished `Json`, or the first error that occurred while building it.
+    pub fn build(self) -> Result<Json, AddError> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.json),
+        }
+    }
+
+    fn nested<F>(mut self, name: Option<&str>, builder: JsonBuilder, build: F) -> JsonBuilder
+    where
+        F: FnOnce(JsonBuilder) -> JsonBuilder,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+
+        match (build(builder).build(), name) {
+            (Ok(value), Some(name)) => self.value(name, value),
+            (Ok(value), None) => self.push(value),
+            (Err(error), _) => {
+                self.error = Some(error);
+
+                self
+            }
+        }
+    }
+}
+
+impl Default for JsonBuilder {
+    fn default() -> JsonBuilder {
+        JsonBuilder::new()
+    }
+}
+
+impl From<Json> for JsonBuilder {
+    /// Continue building an existing `Json`.
+    fn from(json: Json) -> JsonBuilder {
+        JsonBuilder { json, error: None }
+    }
+}
@@ -0,0 +1,348 @@
+//! Zero-copy parsing from a `bytes::Bytes` buffer (requires the `bytes` feature).
+
+use crate::{ErrorKind, Json, ParseError};
+use bytes::Bytes;
+use std::fmt;
+use std::ops::Deref;
+
+/// A string slice of the buffer handed to `Json::parse_bytes`. It is checked to be valid UTF-8 and
+/// dereferences to a `str`, while cloning it only bumps the reference count of the underlying `Bytes`.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BytesStr(Bytes);
+
+impl BytesStr {
+    // Every `BytesStr` goes through this check, which is what makes `as_str` sound.
+    fn from_utf8(bytes: Bytes) -> Result<BytesStr, std::str::Utf8Error> {
+        std::str::from_utf8(&bytes)?;
+
+        Ok(BytesStr(bytes))
+    }
+
+    /// Returns the string slice, without copying it.
+    pub fn as_str(&self) -> &str {
+        // SAFETY: the bytes were validated by `BytesStr::from_utf8` and are never mutated.
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Returns the underlying `Bytes`, which still share the buffer they were sliced from.
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+
+impl Deref for BytesStr {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for BytesStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for BytesStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for BytesStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl fmt::Debug for BytesStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for BytesStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<BytesStr> for String {
+    fn from(value: BytesStr) -> String {
+        String::from(value.as_str())
+    }
+}
+
+/// The result of `Json::parse_bytes`. It has the same shape as `Json`, but names and strings are
+/// `BytesStr` slices of the parsed buffer instead of owned `String`s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BytesJson {
+    OBJECT {
+        name: BytesStr,
+        value: Box<BytesJson>,
+    },
+    JSON(Vec<BytesJson>),
+    ARRAY(Vec<BytesJson>),
+    STRING(BytesStr),
+    NUMBER(f64),
+    BOOL(bool),
+    NULL,
+}
+
+impl BytesJson {
+    /// Returns the first `BytesJson::OBJECT` named `search` inside a `BytesJson::JSON` (or inside the
+    /// `BytesJson::JSON` held by a `BytesJson::OBJECT`). Every other variant yields `None`.
+    pub fn get(&self, search: &str) -> Option<&BytesJson> {
+        let members = match self {
+            BytesJson::JSON(members) => members,
+            BytesJson::OBJECT { name: _, value } => match &**value {
+                BytesJson::JSON(members) => members,
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        members.iter().find(|member| match member {
+            BytesJson::OBJECT { name, value: _ } => name == search,
+            _ => false,
+        })
+    }
+
+    /// Returns the string held by a `BytesJson::STRING` (or by a `BytesJson::OBJECT` holding one).
+    pub fn as_str(&self) -> Option<&BytesStr> {
+        match self {
+            BytesJson::STRING(val) => Some(val),
+            BytesJson::OBJECT { name: _, value } => match &**value {
+                BytesJson::STRING(val) => Some(val),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl From<BytesJson> for Json {
+    fn from(json: BytesJson) -> Json {
+        match json {
+            BytesJson::OBJECT { name, value } => Json::OBJECT {
+                name: String::from(name),
+                value: Box::new(Json::from(*value)),
+            },
+            BytesJson::JSON(values) => Json::JSON(values.into_iter().map(Json::from).collect()),
+            BytesJson::ARRAY(values) => Json::ARRAY(values.into_iter().map(Json::from).collect()),
+            BytesJson::STRING(val) => Json::STRING(String::from(val)),
+            BytesJson::NUMBER(val) => Json::NUMBER(val),
+            BytesJson::BOOL(val) => Json::BOOL(val),
+            BytesJson::NULL => Json::NULL,
+        }
+    }
+}
+
+impl Json {
+    /// Parses `input` just like `parse`, but keeps names and strings as `BytesStr` slices of `input`
+    /// rather than copying them. Only strings containing escape sequences need a buffer of their own.
+    /// This suits request bodies handed over as `Bytes` by `hyper`, `axum` and the like.
+    /// ## Example
+    /// ```
+    /// use bytes::Bytes;
+    /// use json_minimal::*;
+    ///
+    /// let body = Bytes::from_static(b"{\"greeting\":\"Hello\",\"escaped\":\"\\\"Hi\\\"\"}");
+    ///
+    /// let json = Json::parse_bytes(body.clone()).unwrap();
+    ///
+    /// let greeting = json.get("greeting").unwrap().as_str().unwrap();
+    ///
+    /// assert_eq!(greeting, "Hello");
+    /// assert_eq!(greeting.as_ptr(), body[13..].as_ptr());
+    /// assert_eq!(json.get("escaped").unwrap().as_str().unwrap(), "\"Hi\"");
+    ///
+    /// assert_eq!(
+    ///     Json::from(json),
+    ///     Json::parse(&body).unwrap()
+    /// );
+    /// ```
+    /// ## Errors
+    /// Fails with the same error as `parse`, also if `input` is empty.
+    pub fn parse_bytes(input: Bytes) -> Result<BytesJson, ParseError> {
+        let mut incr: usize = 0;
+
+        if input.is_empty() {
+            return Err(ParseError::at(&input, incr, ErrorKind::UnexpectedEof));
+        }
+
+        let result = match input[incr] as char {
+            '{' => parse_json(&input, &mut incr),
+            '\"' => parse_string(&input, &mut incr),
+            '[' => parse_array(&input, &mut incr),
+            't' | 'f' | 'n' | '0'..='9' => parse_scalar(&input, &mut incr),
+            _ => Err((incr, ErrorKind::InvalidValue)),
+        };
+
+        result.map_err(|(index, kind)| ParseError::at(&input, index, kind))
+    }
+}
+
+// Called by `parse_string` on a `:` after the name.
+fn parse_object(
+    input: &Bytes,
+    incr: &mut usize,
+    name: BytesStr,
+) -> Result<BytesJson, (usize, ErrorKind)> {
+    *incr += 1;
+
+    if *incr >= input.len() {
+        return Err((*incr, ErrorKind::UnexpectedEof));
+    }
+
+    while let '\r' | '\n' | '\t' | ' ' = input[*incr] as char {
+        *incr += 1;
+
+        if *incr >= input.len() {
+            return Err((*incr, ErrorKind::UnexpectedEof));
+        }
+    }
+
+    let value = match input[*incr] as char {
+        '{' => parse_json(input, incr)?,
+        '[' => parse_array(input, incr)?,
+        '\"' => parse_string(input, incr)?,
+        't' | 'f' | 'n' | '0'..='9' => parse_scalar(input, incr)?,
+        _ => {
+            return Err((*incr, ErrorKind::InvalidMember));
+        }
+    };
+
+    Ok(BytesJson::OBJECT {
+        name,
+        value: Box::new(value),
+    })
+}
+
+fn parse_json(input: &Bytes, incr: &mut usize) -> Result<BytesJson, (usize, ErrorKind)> {
+    parse_members(input, incr, b'}', ErrorKind::InvalidObject).map(BytesJson::JSON)
+}
+
+fn parse_array(input: &Bytes, incr: &mut usize) -> Result<BytesJson, (usize, ErrorKind)> {
+    parse_members(input, incr, b']', ErrorKind::InvalidArray).map(BytesJson::ARRAY)
+}
+
+// The shared loop of objects and arrays, which only differ in their closing bracket and the kind of
+// error.
+fn parse_members(
+    input: &Bytes,
+    incr: &mut usize,
+    close: u8,
+    kind: ErrorKind,
+) -> Result<Vec<BytesJson>, (usize, ErrorKind)> {
+    let mut result: Vec<BytesJson> = Vec::new();
+
+    *incr += 1;
+
+    if *incr >= input.len() {
+        return Err((*incr, ErrorKind::UnexpectedEof));
+    }
+
+    loop {
+        let json = match input[*incr] {
+            b',' => {
+                *incr += 1;
+
+                if *incr >= input.len() {
+                    return Err((*incr, ErrorKind::UnexpectedEof));
+                }
+
+                continue;
+            }
+            b'\r' | b'\n' | b'\t' | b' ' => {
+                *incr += 1;
+
+                if *incr >= input.len() {
+                    return Err((*incr, ErrorKind::UnexpectedEof));
+                }
+
+                continue;
+            }
+            c if c == close => {
+                *incr += 1;
+
+                return Ok(result);
+            }
+            b'\"' => parse_string(input, incr)?,
+            b'[' => parse_array(input, incr)?,
+            b'{' => parse_json(input, incr)?,
+            b't' | b'f' | b'n' | b'0'..=b'9' => parse_scalar(input, incr)?,
+            _ => {
+                return Err((*incr, kind));
+            }
+        };
+
+        if *incr >= input.len() {
+            return Err((*incr, ErrorKind::UnexpectedEof));
+        }
+
+        result.push(json);
+    }
+}
+
+// Slices the string out of `input` unless it contains an escape sequence, in which case it is decoded
+// into a buffer of its own by `Json::parse_string_escape_sequence`.
+fn parse_string(input: &Bytes, incr: &mut usize) -> Result<BytesJson, (usize, ErrorKind)> {
+    *incr += 1;
+
+    let start = *incr;
+    let mut escaped: Option<Vec<u8>> = None;
+
+    loop {
+        if *incr >= input.len() {
+            return Err((*incr, ErrorKind::UnexpectedEof));
+        }
+
+        match input[*incr] {
+            b'\"' => break,
+            b'\\' => {
+                let buffer = escaped.get_or_insert_with(|| input[start..*incr].to_vec());
+
+                Json::parse_string_escape_sequence(input, incr, buffer)?;
+            }
+            c => {
+                if let Some(buffer) = &mut escaped {
+                    buffer.push(c);
+                }
+
+                *incr += 1;
+            }
+        }
+    }
+
+    let bytes = match escaped {
+        Some(buffer) => Bytes::from(buffer),
+        None => input.slice(start..*incr),
+    };
+
+    *incr += 1;
+
+    let result = BytesStr::from_utf8(bytes).map_err(|_| (*incr, ErrorKind::InvalidUtf8))?;
+
+    if *incr < input.len() && input[*incr] == b':' {
+        parse_object(input, incr, result)
+    } else {
+        Ok(BytesJson::STRING(result))
+    }
+}
+
+// Numbers, bools and null don't hold any strings, so these are left to the regular parser.
+fn parse_scalar(input: &Bytes, incr: &mut usize) -> Result<BytesJson, (usize, ErrorKind)> {
+    let json = match input[*incr] {
+        b't' | b'f' => Json::parse_bool(input, incr)?,
+        b'n' => Json::parse_null(input, incr)?,
+        _ => Json::parse_number(input, incr)?,
+    };
+
+    match json {
+        Json::NUMBER(val) => Ok(BytesJson::NUMBER(val)),
+        Json::BOOL(val) => Ok(BytesJson::BOOL(val)),
+        _ => Ok(BytesJson::NULL),
+    }
+}
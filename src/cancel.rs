@@ -0,0 +1,76 @@
+//! Parsing and printing which can be cancelled, e.g. when the request they are for times out.
+
+use crate::parser::{self, Context, CANCEL_INTERVAL};
+use crate::printer::Printer;
+use crate::{Cancelled, Json, ParseError, ParseOptions};
+
+impl Json {
+    /// Parses `input` like `parse_with`, calling `cancelled` every few thousand values and failing
+    /// with `ErrorKind::Cancelled` as soon as it returns `true`, so that parsing a huge document
+    /// can be given up on, e.g. once a deadline has passed.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    /// use std::time::{Duration, Instant};
+    ///
+    /// let input = format!("[{}1]", "1,".repeat(100_000));
+    /// let options = ParseOptions::default();
+    /// let deadline = Instant::now() + Duration::from_secs(60);
+    ///
+    /// let json = Json::parse_cancellable(input.as_bytes(), &options, &mut || {
+    ///     Instant::now() > deadline
+    /// });
+    ///
+    /// assert!(json.is_ok());
+    ///
+    /// let json = Json::parse_cancellable(input.as_bytes(), &options, &mut || true);
+    ///
+    /// assert_eq!(json.unwrap_err().kind, ErrorKind::Cancelled);
+    /// ```
+    pub fn parse_cancellable(
+        input: &[u8],
+        options: &ParseOptions,
+        cancelled: &mut dyn FnMut() -> bool,
+    ) -> Result<Json, ParseError> {
+        let mut context = Context {
+            options: *options,
+            cancelled: Some(cancelled),
+            ..Context::default()
+        };
+        let mut incr = 0;
+
+        parser::parse_with(input, &mut incr, &mut context)
+            .map_err(|error| context.error(input, error))
+    }
+
+    /// Returns the same text as `print`, but with quotes, backslashes and control characters in names
+    /// and strings escaped, so that it can be parsed back. Calls `cancelled` every few thousand values
+    /// and gives up as soon as it returns `true`. Doesn't recurse, however deeply `self` is nested.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::ARRAY(vec![Json::NULL; 100_000]);
+    ///
+    /// assert_eq!(json.print_cancellable(&mut || false), Ok(json.print()));
+    /// assert_eq!(json.print_cancellable(&mut || true), Err(Cancelled));
+    /// ```
+    pub fn print_cancellable(
+        &self,
+        cancelled: &mut dyn FnMut() -> bool,
+    ) -> Result<String, Cancelled> {
+        let mut result = String::new();
+        let mut printer = Printer::new(self);
+        let mut count: usize = 0;
+
+        while printer.step(&mut result) {
+            count += 1;
+
+            if count % CANCEL_INTERVAL == 0 && cancelled() {
+                return Err(Cancelled);
+            }
+        }
+
+        Ok(result)
+    }
+}
@@ -0,0 +1,359 @@
+use crate::{CborError, Json};
+
+// Major types.
+const UNSIGNED: u8 = 0;
+const NEGATIVE: u8 = 1;
+const BYTES: u8 = 2;
+const TEXT: u8 = 3;
+const ARRAY: u8 = 4;
+const MAP: u8 = 5;
+const TAG: u8 = 6;
+const SIMPLE: u8 = 7;
+
+// The additional information denoting an indefinite length (or "break" for major type 7).
+const INDEFINITE: u8 = 31;
+
+// How deeply maps, arrays and tags may be nested, which bounds the recursion of decoding.
+const MAX_DEPTH: usize = 128;
+
+impl Json {
+    /// Encodes `self` as CBOR (RFC 8949). Requires the `cbor` feature.
+    ///
+    /// A `Json::JSON` becomes a map with text keys and a standalone `Json::OBJECT` a map with a single
+    /// entry. Unnamed values within a `Json::JSON` can't be keyed and are left out. Whole numbers are
+    /// encoded as integers, all others as double precision floats.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"a\":[1,true]}").unwrap();
+    ///
+    /// assert_eq!(json.to_cbor(), vec![0xa1, 0x61, b'a', 0x82, 0x01, 0xf5]);
+    /// assert_eq!(Json::from_cbor(&json.to_cbor()), Ok(json));
+    /// ```
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        encode(self, &mut output);
+
+        output
+    }
+
+    /// Decodes CBOR produced by `to_cbor` or any other encoder. Requires the `cbor` feature.
+    ///
+    /// Maps become a `Json::JSON` (integer keys are turned into names), byte strings a `Json::ARRAY`
+    /// of numbers and `undefined` `Json::NULL`. Tags are skipped and only their content is kept.
+    /// ## Errors
+    /// Fails on malformed or truncated input, map keys which are neither text nor integers, maps,
+    /// arrays and tags nested more than 128 deep, and bytes left over after the first data item.
+    pub fn from_cbor(input: &[u8]) -> Result<Json, CborError> {
+        let mut decoder = Decoder {
+            input,
+            position: 0,
+            depth: 0,
+        };
+
+        let json = decoder.decode()?;
+
+        if decoder.position < input.len() {
+            return Err(decoder.error("Unexpected bytes after the data item."));
+        }
+
+        Ok(json)
+    }
+}
+
+fn head(major: u8, val: u64, output: &mut Vec<u8>) {
+    let major = major << 5;
+
+    if val < 24 {
+        output.push(major | val as u8);
+    } else if val <= u8::MAX as u64 {
+        output.push(major | 24);
+        output.push(val as u8);
+    } else if val <= u16::MAX as u64 {
+        output.push(major | 25);
+        output.extend_from_slice(&(val as u16).to_be_bytes());
+    } else if val <= u32::MAX as u64 {
+        output.push(major | 26);
+        output.extend_from_slice(&(val as u32).to_be_bytes());
+    } else {
+        output.push(major | 27);
+        output.extend_from_slice(&val.to_be_bytes());
+    }
+}
+
+fn text(val: &str, output: &mut Vec<u8>) {
+    head(TEXT, val.len() as u64, output);
+    output.extend_from_slice(val.as_bytes());
+}
+
+fn encode(json: &Json, output: &mut Vec<u8>) {
+    match json {
+        Json::OBJECT { name, value } => {
+            head(MAP, 1, output);
+            text(name, output);
+            encode(value, output);
+        }
+        Json::JSON(values) => {
+            let members = values.iter().filter_map(|json| match json {
+                Json::OBJECT { name, value } => Some((name, value)),
+                _ => None,
+            });
+
+            head(MAP, members.clone().count() as u64, output);
+
+            for (name, value) in members {
+                text(name, output);
+                encode(value, output);
+            }
+        }
+        Json::ARRAY(values) => {
+            head(ARRAY, values.len() as u64, output);
+
+            for value in values {
+                encode(value, output);
+            }
+        }
+        Json::STRING(val) => text(val, output),
+        Json::NUMBER(val) if val.fract() == 0.0 && (0.0..18446744073709551616.0).contains(val) => {
+            head(UNSIGNED, *val as u64, output);
+        }
+        Json::NUMBER(val) if val.fract() == 0.0 && (-18446744073709551616.0..0.0).contains(val) => {
+            head(NEGATIVE, (-1.0 - val) as u64, output);
+        }
+        Json::NUMBER(val) => {
+            output.push(SIMPLE << 5 | 27);
+            output.extend_from_slice(&val.to_be_bytes());
+        }
+        Json::BOOL(false) => output.push(0xf4),
+        Json::BOOL(true) => output.push(0xf5),
+        Json::NULL => output.push(0xf6),
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    position: usize,
+    // How many maps, arrays and tags enclose the value being decoded.
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &'static str) -> CborError {
+        CborError {
+            position: self.position,
+            message,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CborError> {
+        match self
+            .input
+            .get(self.position..self.position.saturating_add(len))
+        {
+            Some(bytes) => {
+                self.position += len;
+                Ok(bytes)
+            }
+            None => Err(self.error("Unexpected end of input.")),
+        }
+    }
+
+    fn byte(&mut self) -> Result<u8, CborError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], CborError> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    // Reads the argument following an initial byte. `None` stands for an indefinite length.
+    fn argument(&mut self, info: u8) -> Result<Option<u64>, CborError> {
+        let val = match info {
+            0..=23 => info as u64,
+            24 => self.byte()? as u64,
+            25 => u16::from_be_bytes(self.bytes()?) as u64,
+            26 => u32::from_be_bytes(self.bytes()?) as u64,
+            27 => u64::from_be_bytes(self.bytes()?),
+            INDEFINITE => return Ok(None),
+            _ => return Err(self.error("Reserved additional information.")),
+        };
+
+        Ok(Some(val))
+    }
+
+    fn length(&mut self, info: u8) -> Result<Option<usize>, CborError> {
+        match self.argument(info)? {
+            // Every item takes at least one byte, which bounds any valid length.
+            Some(len) if len > (self.input.len() - self.position) as u64 => {
+                Err(self.error("Length exceeds the remaining input."))
+            }
+            len => Ok(len.map(|len| len as usize)),
+        }
+    }
+
+    // Consumes a "break" if it comes next.
+    fn at_break(&mut self) -> Result<bool, CborError> {
+        match self.input.get(self.position) {
+            Some(0xff) => {
+                self.position += 1;
+                Ok(true)
+            }
+            Some(_) => Ok(false),
+            None => Err(self.error("Unexpected end of input.")),
+        }
+    }
+
+    // Byte and text strings, including indefinite length ones made up of chunks.
+    fn string(&mut self, major: u8, info: u8) -> Result<Vec<u8>, CborError> {
+        match self.length(info)? {
+            Some(len) => Ok(self.take(len)?.to_vec()),
+            None => {
+                let mut bytes = Vec::new();
+
+                while !self.at_break()? {
+                    let initial = self.byte()?;
+
+                    if initial >> 5 != major || initial & 0x1f == INDEFINITE {
+                        return Err(self.error("Invalid chunk in an indefinite length string."));
+                    }
+
+                    bytes.extend(self.string(major, initial & 0x1f)?);
+                }
+
+                Ok(bytes)
+            }
+        }
+    }
+
+    fn decode(&mut self) -> Result<Json, CborError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Too deeply nested."));
+        }
+
+        self.depth += 1;
+
+        let json = self.item();
+
+        self.depth -= 1;
+
+        json
+    }
+
+    fn item(&mut self) -> Result<Json, CborError> {
+        let initial = self.byte()?;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+
+        match major {
+            UNSIGNED | NEGATIVE => match self.argument(info)? {
+                Some(val) if major == UNSIGNED => Ok(Json::NUMBER(val as f64)),
+                Some(val) => Ok(Json::NUMBER(-1.0 - val as f64)),
+                None => Err(self.error("Integers may not have an indefinite length.")),
+            },
+            BYTES => Ok(Json::ARRAY(
+                self.string(BYTES, info)?
+                    .into_iter()
+                    .map(|byte| Json::NUMBER(byte as f64))
+                    .collect(),
+            )),
+            TEXT => match String::from_utf8(self.string(TEXT, info)?) {
+                Ok(val) => Ok(Json::STRING(val)),
+                Err(_) => Err(self.error("Text string is not valid UTF-8.")),
+            },
+            ARRAY => {
+                let mut values = Vec::new();
+
+                match self.length(info)? {
+                    Some(len) => {
+                        for _ in 0..len {
+                            values.push(self.decode()?);
+                        }
+                    }
+                    None => {
+                        while !self.at_break()? {
+                            values.push(self.decode()?);
+                        }
+                    }
+                }
+
+                Ok(Json::ARRAY(values))
+            }
+            MAP => {
+                let mut values = Vec::new();
+
+                match self.length(info)? {
+                    Some(len) => {
+                        for _ in 0..len {
+                            values.push(self.member()?);
+                        }
+                    }
+                    None => {
+                        while !self.at_break()? {
+                            values.push(self.member()?);
+                        }
+                    }
+                }
+
+                Ok(Json::JSON(values))
+            }
+            TAG => match self.argument(info)? {
+                Some(_) => self.decode(),
+                None => Err(self.error("Tags may not have an indefinite length.")),
+            },
+            _ => self.simple(info),
+        }
+    }
+
+    fn member(&mut self) -> Result<Json, CborError> {
+        let position = self.position;
+
+        let name = match self.decode()? {
+            Json::STRING(name) => name,
+            Json::NUMBER(val) => val.to_string(),
+            _ => {
+                return Err(CborError {
+                    position,
+                    message: "Map keys must be text or integers.",
+                })
+            }
+        };
+
+        Ok(Json::member(name, self.decode()?))
+    }
+
+    fn simple(&mut self, info: u8) -> Result<Json, CborError> {
+        match info {
+            20 => Ok(Json::BOOL(false)),
+            21 => Ok(Json::BOOL(true)),
+            22 | 23 => Ok(Json::NULL),
+            25 => Ok(Json::NUMBER(half(u16::from_be_bytes(self.bytes()?)))),
+            26 => Ok(Json::NUMBER(f32::from_be_bytes(self.bytes()?) as f64)),
+            27 => Ok(Json::NUMBER(f64::from_be_bytes(self.bytes()?))),
+            INDEFINITE => Err(self.error("Unexpected break.")),
+            _ => Err(self.error("Unsupported simple value.")),
+        }
+    }
+}
+
+// Decodes an IEEE 754 half precision float.
+fn half(bits: u16) -> f64 {
+    let exponent = (bits >> 10) & 0x1f;
+    let mantissa = (bits & 0x3ff) as f64;
+
+    let val = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent as i32 - 15),
+    };
+
+    if bits & 0x8000 != 0 {
+        -val
+    } else {
+        val
+    }
+}
@@ -0,0 +1,385 @@
+//! Generating Rust structs (with `FromJson` impls) from a sample `Json`.
+
+use crate::Json;
+use std::fmt::Write;
+
+// The type inferred for a value, merged over every sample of it (e.g. all elements of an array).
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    // Nothing to infer from, like the elements of an empty array.
+    Unknown,
+    Null,
+    Bool,
+    Int,
+    Float,
+    Str,
+    Array(Box<Shape>),
+    Object(Vec<Field>),
+    Nullable(Box<Shape>),
+    // Samples of different kinds, which are left as a `Json`.
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    key: String,
+    shape: Shape,
+    // Missing from some of the samples.
+    optional: bool,
+}
+
+const RESERVED: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "self", "Self", "static", "struct", "super", "trait", "true", "type",
+    "unsafe", "use", "where", "while", "abstract", "become", "box", "do", "final", "macro",
+    "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+];
+
+impl Json {
+    /// Generates Rust source for the structs needed to read values shaped like `self` with
+    /// `FromJson::from_json`, the outermost one being called `name`. Objects become structs (nested
+    /// ones are named after their member), arrays `Vec`s, whole numbers `i64` and other numbers `f64`.
+    /// The elements of an array are merged, so members missing from some of them become `Option`s,
+    /// just like values which are `null` in some samples. Values which can't be given a single type
+    /// (including `null` alone and empty arrays) are kept as a `Json`. If `self` isn't an object, a
+    /// type alias called `name` is generated as well.
+    ///
+    /// Member names are turned into snake case field names (and the struct names into camel case), the
+    /// `FromJson` impls still look up the original names. A missing member is read as `Json::NULL`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let sample = Json::parse(b"{\"userId\":1,\"tags\":[\"a\"],\"home\":{\"lat\":52.5,\"lon\":13}}").unwrap();
+    ///
+    /// let code = sample.to_rust_structs("User");
+    ///
+    /// assert!(code.contains("pub struct User {\n    pub user_id: i64,\n    pub tags: Vec<String>,\n    pub home: Home,\n}"));
+    /// assert!(code.contains("pub struct Home {\n    pub lat: f64,\n    pub lon: i64,\n}"));
+    /// assert!(code.contains("impl FromJson for Home {"));
+    /// ```
+    pub fn to_rust_structs(&self, name: &str) -> String {
+        let mut generator = Generator {
+            structs: Vec::new(),
+            names: Vec::new(),
+        };
+
+        let shape = shape_of(self);
+        let alias = match shape {
+            Shape::Object(_) => None,
+            _ => {
+                generator.names.push(camel_case(name));
+
+                Some(generator.type_name(&shape, name))
+            }
+        };
+
+        if let Shape::Object(fields) = &shape {
+            generator.structure(fields, name);
+        }
+
+        let mut output = String::from("use json_minimal::{FromJson, FromJsonError, Json};\n");
+
+        if let Some(ty) = alias {
+            let _ = write!(output, "\npub type {} = {};\n", camel_case(name), ty);
+        }
+
+        for code in generator.structs {
+            output.push('\n');
+            output.push_str(&code);
+        }
+
+        output
+    }
+}
+
+fn shape_of(json: &Json) -> Shape {
+    match json.inner() {
+        Json::JSON(values) => {
+            let mut fields: Vec<Field> = Vec::new();
+
+            for value in values {
+                if let Json::OBJECT { name, value } = value {
+                    let shape = shape_of(value);
+
+                    match fields.iter_mut().find(|field| field.key == *name) {
+                        Some(field) => field.shape = merge(field.shape.clone(), shape),
+                        None => fields.push(Field {
+                            key: name.clone(),
+                            shape,
+                            optional: false,
+                        }),
+                    }
+                }
+            }
+
+            Shape::Object(fields)
+        }
+        Json::ARRAY(values) => Shape::Array(Box::new(
+            values.iter().map(shape_of).fold(Shape::Unknown, merge),
+        )),
+        Json::STRING(_) => Shape::Str,
+        Json::NUMBER(val) if val.fract() == 0.0 && val.abs() <= 9007199254740992.0 => Shape::Int,
+        Json::NUMBER(_) => Shape::Float,
+        Json::BOOL(_) => Shape::Bool,
+        Json::NULL | Json::OBJECT { name: _, value: _ } => Shape::Null,
+    }
+}
+
+fn merge(a: Shape, b: Shape) -> Shape {
+    match (a, b) {
+        (Shape::Unknown, shape) | (shape, Shape::Unknown) => shape,
+        (Shape::Null, shape) | (shape, Shape::Null) => nullable(shape),
+        (Shape::Nullable(a), b) | (b, Shape::Nullable(a)) => nullable(merge(*a, b)),
+        (Shape::Int, Shape::Float) | (Shape::Float, Shape::Int) => Shape::Float,
+        (Shape::Array(a), Shape::Array(b)) => Shape::Array(Box::new(merge(*a, *b))),
+        (Shape::Object(a), Shape::Object(b)) => Shape::Object(merge_fields(a, b)),
+        (a, b) if a == b => a,
+        _ => Shape::Any,
+    }
+}
+
+fn nullable(shape: Shape) -> Shape {
+    match shape {
+        Shape::Null | Shape::Nullable(_) | Shape::Any => shape,
+        shape => Shape::Nullable(Box::new(shape)),
+    }
+}
+
+// Fields which only one side has are optional.
+fn merge_fields(a: Vec<Field>, mut b: Vec<Field>) -> Vec<Field> {
+    let mut fields = Vec::new();
+
+    for field in a {
+        match b.iter().position(|other| other.key == field.key) {
+            Some(index) => {
+                let other = b.remove(index);
+
+                fields.push(Field {
+                    key: field.key,
+                    shape: merge(field.shape, other.shape),
+                    optional: field.optional || other.optional,
+                });
+            }
+            None => fields.push(Field {
+                optional: true,
+                ..field
+            }),
+        }
+    }
+
+    fields.extend(b.into_iter().map(|field| Field {
+        optional: true,
+        ..field
+    }));
+
+    fields
+}
+
+struct Generator {
+    // The generated structs, outermost first.
+    structs: Vec<String>,
+    names: Vec<String>,
+}
+
+impl Generator {
+    fn type_name(&mut self, shape: &Shape, hint: &str) -> String {
+        match shape {
+            Shape::Unknown | Shape::Null | Shape::Any => String::from("Json"),
+            Shape::Bool => String::from("bool"),
+            Shape::Int => String::from("i64"),
+            Shape::Float => String::from("f64"),
+            Shape::Str => String::from("String"),
+            Shape::Array(shape) => {
+                format!("Vec<{}>", self.type_name(shape, &format!("{}Item", hint)))
+            }
+            Shape::Nullable(shape) => format!("Option<{}>", self.type_name(shape, hint)),
+            Shape::Object(fields) => self.structure(fields, hint),
+        }
+    }
+
+    // Generates the struct and its `FromJson` impl, returning its name.
+    fn structure(&mut self, fields: &[Field], hint: &str) -> String {
+        let name = unique(camel_case(hint), &self.names);
+        self.names.push(name.clone());
+
+        // Keeps the outer struct ahead of the ones generated for its fields.
+        let index = self.structs.len();
+        self.structs.push(String::new());
+
+        let mut idents: Vec<String> = Vec::new();
+        let mut members = String::new();
+        let mut reads = String::new();
+
+        for field in fields {
+            let ident = unique(snake_case(&field.key), &idents);
+            idents.push(ident.clone());
+
+            let ident = if RESERVED.contains(&ident.as_str()) {
+                format!("r#{}", ident)
+            } else {
+                ident
+            };
+
+            let mut ty = self.type_name(&field.shape, &field.key);
+
+            if field.optional && !ty.starts_with("Option<") && ty != "Json" {
+                ty = format!("Option<{}>", ty);
+            }
+
+            let _ = writeln!(members, "    pub {}: {},", ident, ty);
+            let _ = writeln!(
+                reads,
+                "            {}: FromJson::from_json(member({:?})?)?,",
+                ident, field.key
+            );
+        }
+
+        let mut code = String::new();
+
+        let _ = writeln!(code, "#[derive(Debug, Clone, PartialEq)]");
+        if members.is_empty() {
+            let _ = writeln!(code, "pub struct {} {{}}\n", name);
+        } else {
+            let _ = writeln!(code, "pub struct {} {{\n{}}}\n", name, members);
+        }
+        let _ = writeln!(code, "impl FromJson for {} {{", name);
+        let _ = writeln!(
+            code,
+            "    fn from_json(json: &Json) -> Result<{}, FromJsonError> {{",
+            name
+        );
+
+        if fields.is_empty() {
+            let _ = writeln!(
+                code,
+                "        json.try_get(\"\").map(|_| {} {{}}).map_err(|error| {{",
+                name
+            );
+            let _ = writeln!(code, "            FromJsonError::InvalidType {{");
+            let _ = writeln!(code, "                target: {:?},", name);
+            let _ = writeln!(code, "                found: error.found,");
+            let _ = writeln!(code, "            }}");
+            let _ = writeln!(code, "        }})");
+        } else {
+            let _ = writeln!(
+                code,
+                "        let member = |name: &str| match json.try_value_of(name) {{"
+            );
+            let _ = writeln!(
+                code,
+                "            Ok(value) => Ok(value.unwrap_or(&Json::NULL)),"
+            );
+            let _ = writeln!(
+                code,
+                "            Err(error) => Err(FromJsonError::InvalidType {{"
+            );
+            let _ = writeln!(code, "                target: {:?},", name);
+            let _ = writeln!(code, "                found: error.found,");
+            let _ = writeln!(code, "            }}),");
+            let _ = writeln!(code, "        }};\n");
+            let _ = writeln!(code, "        Ok({} {{\n{}        }})", name, reads);
+        }
+
+        let _ = writeln!(code, "    }}\n}}");
+
+        self.structs[index] = code;
+
+        name
+    }
+}
+
+// Splits `userId`, `user_id`, `user-id` or `UserID` into lowercase words.
+fn words(input: &str) -> Vec<String> {
+    let mut words: Vec<String> = Vec::new();
+    let mut word = String::new();
+    let chars: Vec<char> = input.chars().collect();
+
+    for (n, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+
+            continue;
+        }
+
+        let boundary = c.is_uppercase()
+            && n > 0
+            && (chars[n - 1].is_lowercase()
+                || chars[n - 1].is_numeric()
+                || (chars[n - 1].is_uppercase()
+                    && chars.get(n + 1).is_some_and(|c| c.is_lowercase())));
+
+        if boundary && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+
+        word.extend(c.to_lowercase());
+    }
+
+    if !word.is_empty() {
+        words.push(word);
+    }
+
+    words
+}
+
+fn snake_case(input: &str) -> String {
+    let ident = words(input).join("_");
+
+    match ident.chars().next() {
+        None => String::from("field"),
+        Some(c) if c.is_numeric() => format!("_{}", ident),
+        _ if ident == "self" || ident == "crate" || ident == "super" => format!("{}_", ident),
+        _ => ident,
+    }
+}
+
+fn camel_case(input: &str) -> String {
+    let ident: String = words(input)
+        .iter()
+        .map(|word| {
+            let mut chars = word.chars();
+
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    match ident.chars().next() {
+        None => String::from("Value"),
+        Some(c) if c.is_numeric() => format!("_{}", ident),
+        // Avoids the keyword as well as shadowing the types used by the generated code.
+        _ if [
+            "Self",
+            "Json",
+            "FromJson",
+            "FromJsonError",
+            "Option",
+            "Vec",
+            "String",
+            "Result",
+        ]
+        .contains(&ident.as_str()) =>
+        {
+            format!("{}_", ident)
+        }
+        _ => ident,
+    }
+}
+
+// Appends a number to `name` if it is taken already.
+fn unique(name: String, taken: &[String]) -> String {
+    if !taken.contains(&name) {
+        return name;
+    }
+
+    (2..)
+        .map(|n| format!("{}{}", name, n))
+        .find(|name| !taken.contains(name))
+        .unwrap_or(name)
+}
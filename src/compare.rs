@@ -0,0 +1,262 @@
+//! Comparisons of two `Json`s looser than `==`, e.g. for tests against what a service returned.
+
+use crate::Json;
+
+/// What `Json::eq_with` overlooks when comparing two `Json`s. By default nothing is, which makes
+/// it the same as `==`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct EqOptions {
+    /// Compares the members of objects regardless of their order, as long as each one on one side
+    /// matches a different one on the other.
+    pub ignore_key_order: bool,
+    /// Compares the elements of arrays regardless of their order, as if they were (multi)sets.
+    pub ignore_array_order: bool,
+    /// How far apart two numbers may be and still match, see `Json::approx_eq`.
+    pub epsilon: f64,
+}
+
+impl Json {
+    /// Same as `==`, but numbers only need to be within `epsilon` of each other, so that a value
+    /// computed with floating point arithmetic somewhere else still matches the expected one.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let expected = Json::parse(b"{\"total\":0.3,\"items\":[0.1,0.2]}").unwrap();
+    /// let actual = Json::parse(b"{\"total\":0.30000000000000004,\"items\":[0.1,0.2]}").unwrap();
+    ///
+    /// assert_ne!(actual, expected);
+    /// assert!(actual.approx_eq(&expected, 1e-9));
+    /// assert!(!actual.approx_eq(&Json::parse(b"{\"total\":0.3}").unwrap(), 1e-9));
+    /// ```
+    pub fn approx_eq(&self, other: &Json, epsilon: f64) -> bool {
+        let options = EqOptions {
+            epsilon,
+            ..EqOptions::default()
+        };
+
+        Json::eq_with(self, other, &options)
+    }
+
+    /// Compares `a` with `b` like `==`, but overlooking what `options` says, e.g. the order of the
+    /// elements of arrays when the producer doesn't guarantee one. Matching members or elements
+    /// regardless of their order takes time quadratic in how many there are, or up to cubic with an
+    /// `epsilon`, as a number may then match others which don't match each other.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let a = Json::parse(b"{\"id\":7,\"tags\":[\"x\",\"y\",\"y\"]}").unwrap();
+    /// let b = Json::parse(b"{\"tags\":[\"y\",\"x\",\"y\"],\"id\":7}").unwrap();
+    ///
+    /// let options = EqOptions {
+    ///     ignore_key_order: true,
+    ///     ..EqOptions::default()
+    /// };
+    ///
+    /// assert!(!Json::eq_with(&a, &b, &options));
+    ///
+    /// let options = EqOptions {
+    ///     ignore_array_order: true,
+    ///     ..options
+    /// };
+    ///
+    /// assert!(Json::eq_with(&a, &b, &options));
+    /// ```
+    pub fn eq_with(a: &Json, b: &Json, options: &EqOptions) -> bool {
+        match (a, b) {
+            (Json::NUMBER(a), Json::NUMBER(b)) => a == b || (a - b).abs() <= options.epsilon,
+            (
+                Json::OBJECT { name, value },
+                Json::OBJECT {
+                    name: b_name,
+                    value: b_value,
+                },
+            ) => name == b_name && Json::eq_with(value, b_value, options),
+            (Json::JSON(a_values), Json::JSON(b_values)) => {
+                eq_values(a_values, b_values, options.ignore_key_order, options)
+            }
+            (Json::ARRAY(a_values), Json::ARRAY(b_values)) => {
+                eq_values(a_values, b_values, options.ignore_array_order, options)
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Whether `self` has everything `expected` has, e.g. in a contract test which only cares
+    /// about some of the fields of a response: every member of an object in `expected` needs one
+    /// with the same name in `self` which in turn has everything its value has, but `self` may
+    /// have more. Arrays need as many elements as in `expected`, each of which has everything the
+    /// one at the same index there has. Anything else needs to be equal.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let response = Json::parse(b"{\"id\":7,\"user\":{\"legs\":10},\"tags\":[{}]}").unwrap();
+    ///
+    /// assert!(response.contains_subset(&Json::parse(b"{\"user\":{\"legs\":10}}").unwrap()));
+    /// assert!(response.contains_subset(&Json::parse(b"{\"tags\":[{}]}").unwrap()));
+    /// assert!(!response.contains_subset(&Json::parse(b"{\"user\":{\"legs\":8}}").unwrap()));
+    /// assert!(!response.contains_subset(&Json::parse(b"{\"email\":null}").unwrap()));
+    /// ```
+    pub fn contains_subset(&self, expected: &Json) -> bool {
+        match (self, expected) {
+            (
+                Json::OBJECT { name, value },
+                Json::OBJECT {
+                    name: expected_name,
+                    value: expected_value,
+                },
+            ) => name == expected_name && value.contains_subset(expected_value),
+            (Json::JSON(values), Json::JSON(expected_values)) => expected_values
+                .iter()
+                .all(|expected| values.iter().any(|value| value.contains_subset(expected))),
+            (Json::ARRAY(values), Json::ARRAY(expected_values)) => {
+                values.len() == expected_values.len()
+                    && values
+                        .iter()
+                        .zip(expected_values)
+                        .all(|(value, expected)| value.contains_subset(expected))
+            }
+            (a, b) => a == b,
+        }
+    }
+
+    /// Whether `self` is equal to `pattern`, in which some strings stand for any value of a kind,
+    /// e.g. to assert that a response has a timestamp or a generated id without knowing which:
+    /// `"<<any>>"` matches anything, `"<<any_string>>"`, `"<<any_number>>"`, `"<<any_bool>>"`,
+    /// `"<<any_object>>"` and `"<<any_array>>"` any value of that kind. Everything else in
+    /// `pattern` needs to be equal, members of objects in the same order.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let response = Json::parse(b"{\"id\":\"7f3a\",\"at\":1700000000,\"ok\":true}").unwrap();
+    /// let pattern = b"{\"id\":\"<<any_string>>\",\"at\":\"<<any_number>>\",\"ok\":true}";
+    ///
+    /// assert!(response.matches_pattern(&Json::parse(pattern).unwrap()));
+    /// assert!(!response.matches_pattern(&Json::parse(b"{\"id\":\"<<any_number>>\"}").unwrap()));
+    /// ```
+    pub fn matches_pattern(&self, pattern: &Json) -> bool {
+        match (self, pattern) {
+            (json, Json::STRING(token)) if token.starts_with("<<") => match token.as_str() {
+                "<<any>>" => true,
+                "<<any_string>>" => matches!(json, Json::STRING(_)),
+                "<<any_number>>" => matches!(json, Json::NUMBER(_)),
+                "<<any_bool>>" => matches!(json, Json::BOOL(_)),
+                "<<any_object>>" => matches!(json, Json::JSON(_)),
+                "<<any_array>>" => matches!(json, Json::ARRAY(_)),
+                _ => json == pattern,
+            },
+            (
+                Json::OBJECT { name, value },
+                Json::OBJECT {
+                    name: pattern_name,
+                    value: pattern_value,
+                },
+            ) => name == pattern_name && value.matches_pattern(pattern_value),
+            (Json::JSON(values), Json::JSON(pattern_values))
+            | (Json::ARRAY(values), Json::ARRAY(pattern_values)) => {
+                values.len() == pattern_values.len()
+                    && values
+                        .iter()
+                        .zip(pattern_values)
+                        .all(|(value, pattern)| value.matches_pattern(pattern))
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+// Whether `a_values` and `b_values` match one by one, in any order if `unordered`.
+fn eq_values(a_values: &[Json], b_values: &[Json], unordered: bool, options: &EqOptions) -> bool {
+    if a_values.len() != b_values.len() {
+        return false;
+    }
+
+    if !unordered {
+        return a_values
+            .iter()
+            .zip(b_values)
+            .all(|(a, b)| Json::eq_with(a, b, options));
+    }
+
+    // Without an `epsilon` matching is an equivalence, so any value on the right which matches one
+    // on the left is as good as another. Those left are kept in order, so that values in the same
+    // order are found right away.
+    if options.epsilon <= 0.0 || options.epsilon.is_nan() {
+        let mut rest: Vec<&Json> = b_values.iter().collect();
+
+        return a_values.iter().all(|a| {
+            match rest.iter().position(|b| Json::eq_with(a, b, options)) {
+                Some(n) => {
+                    rest.remove(n);
+
+                    true
+                }
+                None => false,
+            }
+        });
+    }
+
+    // Which values on both sides match, then whether each one on the left can be paired with a
+    // different one on the right. Trying the first free match isn't enough once numbers match
+    // within `epsilon`, so the pairs made so far are rearranged where needed.
+    let matches: Vec<Vec<usize>> = a_values
+        .iter()
+        .map(|a| {
+            (0..b_values.len())
+                .filter(|n| Json::eq_with(a, &b_values[*n], options))
+                .collect()
+        })
+        .collect();
+    let mut paired = vec![None; b_values.len()];
+
+    (0..a_values.len()).all(|n| pair(n, &matches, &mut paired, &mut vec![false; b_values.len()]))
+}
+
+// Pairs the value `n` on the left with one on the right it matches, pairing the one which had it
+// with another one if necessary, and so on. `paired` is which value on the left each one on the
+// right is paired with, `visited` the ones on the right already tried.
+fn pair(
+    n: usize,
+    matches: &[Vec<usize>],
+    paired: &mut [Option<usize>],
+    visited: &mut [bool],
+) -> bool {
+    // The values on the left being paired, each with how many of its matches were tried. The last
+    // one tried is the one it takes if a free one turns up further on.
+    let mut path = vec![(n, 0)];
+
+    while let Some((left, tried)) = path.last_mut() {
+        *tried += matches[*left][*tried..]
+            .iter()
+            .take_while(|m| visited[**m])
+            .count()
+            + 1;
+
+        let m = match matches[*left].get(*tried - 1) {
+            Some(m) => *m,
+            None => {
+                path.pop();
+
+                continue;
+            }
+        };
+
+        visited[m] = true;
+
+        match paired[m] {
+            Some(other) => path.push((other, 0)),
+            None => {
+                for (left, tried) in path {
+                    paired[matches[left][tried - 1]] = Some(left);
+                }
+
+                return true;
+            }
+        }
+    }
+
+    false
+}
@@ -0,0 +1,528 @@
+use crate::{FromJsonError, Json};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::hash::BuildHasher;
+use std::iter::FromIterator;
+
+/// Converts a value into a `Json`.
+///
+/// Implemented for `Json` itself, `bool`, the number types, `char`, `str`, `String`, `Option`,
+/// slices, `Vec` and maps with `String` keys. A `HashMap` produces its members in iteration order.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// struct Point {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// impl ToJson for Point {
+///     fn to_json(&self) -> Json {
+///         Json::JSON(vec![
+///             Json::member("x", self.x.to_json()),
+///             Json::member("y", self.y.to_json()),
+///         ])
+///     }
+/// }
+///
+/// assert_eq!(Point { x: 1, y: 2 }.to_json().print(), "{\"x\":1,\"y\":2}");
+/// ```
+pub trait ToJson {
+    fn to_json(&self) -> Json;
+}
+
+/// Converts a `Json` into a value, the counterpart of `ToJson`.
+///
+/// Like `as_str` and friends, the implementations in this crate also accept a `Json::OBJECT`
+/// holding the expected value, so the result of `get` can be passed directly.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let json = Json::parse(b"{\"Days\":[\"Monday\",\"Tuesday\"],\"Year\":2020}").unwrap();
+///
+/// let days = Vec::<String>::from_json(json.get("Days").unwrap()).unwrap();
+///
+/// assert_eq!(days, vec!["Monday", "Tuesday"]);
+/// assert_eq!(u16::from_json(json.get("Year").unwrap()), Ok(2020));
+/// assert!(u8::from_json(json.get("Year").unwrap()).is_err());
+/// ```
+pub trait FromJson: Sized {
+    fn from_json(json: &Json) -> Result<Self, FromJsonError>;
+}
+
+impl<T: ToJson + ?Sized> ToJson for &T {
+    fn to_json(&self) -> Json {
+        (**self).to_json()
+    }
+}
+
+impl<T: ToJson + ?Sized> ToJson for Box<T> {
+    fn to_json(&self) -> Json {
+        (**self).to_json()
+    }
+}
+
+impl ToJson for Json {
+    fn to_json(&self) -> Json {
+        self.clone()
+    }
+}
+
+impl FromJson for Json {
+    fn from_json(json: &Json) -> Result<Json, FromJsonError> {
+        Ok(json.clone())
+    }
+}
+
+impl ToJson for bool {
+    fn to_json(&self) -> Json {
+        Json::BOOL(*self)
+    }
+}
+
+impl FromJson for bool {
+    fn from_json(json: &Json) -> Result<bool, FromJsonError> {
+        json.as_bool()
+            .ok_or_else(|| FromJsonError::invalid_type::<bool>(json))
+    }
+}
+
+macro_rules! float {
+    ($($t:ty)*) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Json {
+                    Json::NUMBER(*self as f64)
+                }
+            }
+
+            impl FromJson for $t {
+                fn from_json(json: &Json) -> Result<$t, FromJsonError> {
+                    json.as_f64()
+                        .map(|val| val as $t)
+                        .ok_or_else(|| FromJsonError::invalid_type::<$t>(json))
+                }
+            }
+        )*
+    };
+}
+
+float!(f32 f64);
+
+// Integers only accept whole numbers within their range.
+macro_rules! integer {
+    ($($t:ty)*) => {
+        $(
+            impl ToJson for $t {
+                fn to_json(&self) -> Json {
+                    Json::NUMBER(*self as f64)
+                }
+            }
+
+            impl FromJson for $t {
+                fn from_json(json: &Json) -> Result<$t, FromJsonError> {
+                    match json.as_f64() {
+                        // `MAX as f64` rounds up to the next power of two for 64 bits, which is
+                        // why that power of two is the (exclusive) bound instead.
+                        Some(val)
+                            if val.fract() == 0.0
+                                && val >= <$t>::MIN as f64
+                                && val < (<$t>::MAX / 2 + 1) as f64 * 2.0 =>
+                        {
+                            Ok(val as $t)
+                        }
+                        _ => Err(FromJsonError::invalid_type::<$t>(json)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+integer!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+impl ToJson for char {
+    fn to_json(&self) -> Json {
+        Json::STRING(self.to_string())
+    }
+}
+
+impl FromJson for char {
+    fn from_json(json: &Json) -> Result<char, FromJsonError> {
+        let mut chars = json.as_str().unwrap_or_default().chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(val), None) => Ok(val),
+            _ => Err(FromJsonError::invalid_type::<char>(json)),
+        }
+    }
+}
+
+impl ToJson for str {
+    fn to_json(&self) -> Json {
+        Json::string(self)
+    }
+}
+
+impl ToJson for String {
+    fn to_json(&self) -> Json {
+        Json::string(self.as_str())
+    }
+}
+
+impl FromJson for String {
+    fn from_json(json: &Json) -> Result<String, FromJsonError> {
+        json.as_str()
+            .map(String::from)
+            .ok_or_else(|| FromJsonError::invalid_type::<String>(json))
+    }
+}
+
+/// `None` becomes `Json::NULL`.
+impl<T: ToJson> ToJson for Option<T> {
+    fn to_json(&self) -> Json {
+        match self {
+            Some(val) => val.to_json(),
+            None => Json::NULL,
+        }
+    }
+}
+
+/// `Json::NULL` becomes `None`.
+impl<T: FromJson> FromJson for Option<T> {
+    fn from_json(json: &Json) -> Result<Option<T>, FromJsonError> {
+        match json.inner() {
+            Json::NULL => Ok(None),
+            _ => T::from_json(json).map(Some),
+        }
+    }
+}
+
+impl<T: ToJson> ToJson for [T] {
+    fn to_json(&self) -> Json {
+        Json::ARRAY(self.iter().map(ToJson::to_json).collect())
+    }
+}
+
+impl<T: ToJson> ToJson for Vec<T> {
+    fn to_json(&self) -> Json {
+        self.as_slice().to_json()
+    }
+}
+
+impl<T: FromJson> FromJson for Vec<T> {
+    fn from_json(json: &Json) -> Result<Vec<T>, FromJsonError> {
+        match json.inner() {
+            Json::ARRAY(values) => values
+                .iter()
+                .enumerate()
+                .map(|(index, json)| T::from_json(json).map_err(|error| error.at(index)))
+                .collect(),
+            _ => Err(FromJsonError::invalid_type::<Vec<T>>(json)),
+        }
+    }
+}
+
+impl<V: ToJson, S> ToJson for HashMap<String, V, S> {
+    fn to_json(&self) -> Json {
+        Json::JSON(
+            self.iter()
+                .map(|(name, value)| Json::member(name.as_str(), value.to_json()))
+                .collect(),
+        )
+    }
+}
+
+impl<V: FromJson, S: BuildHasher + Default> FromJson for HashMap<String, V, S> {
+    fn from_json(json: &Json) -> Result<HashMap<String, V, S>, FromJsonError> {
+        map_from_json::<V, HashMap<String, V, S>>(json)
+    }
+}
+
+impl<V: ToJson> ToJson for BTreeMap<String, V> {
+    fn to_json(&self) -> Json {
+        Json::JSON(
+            self.iter()
+                .map(|(name, value)| Json::member(name.as_str(), value.to_json()))
+                .collect(),
+        )
+    }
+}
+
+impl<V: FromJson> FromJson for BTreeMap<String, V> {
+    fn from_json(json: &Json) -> Result<BTreeMap<String, V>, FromJsonError> {
+        map_from_json::<V, BTreeMap<String, V>>(json)
+    }
+}
+
+// The owned counterparts of `FromJson` for the types `to_vec_of` is most useful with.
+macro_rules! try_from {
+    ($($t:ty => $variant:ident,)*) => {
+        $(
+            /// Also accepts a `Json::OBJECT` holding the value.
+            impl TryFrom<Json> for $t {
+                type Error = FromJsonError;
+
+                fn try_from(json: Json) -> Result<$t, FromJsonError> {
+                    match json.into_inner() {
+                        Json::$variant(val) => Ok(val),
+                        json => Err(FromJsonError::invalid_type::<$t>(&json)),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+try_from! {
+    String => STRING,
+    f64 => NUMBER,
+    bool => BOOL,
+}
+
+/// Fails with `FromJsonError::InvalidElement` for the first element that can't be converted.
+impl<T> TryFrom<Json> for Vec<T>
+where
+    T: TryFrom<Json>,
+    T::Error: Into<FromJsonError>,
+{
+    type Error = FromJsonError;
+
+    fn try_from(json: Json) -> Result<Vec<T>, FromJsonError> {
+        match json.into_inner() {
+            Json::ARRAY(values) => values
+                .into_iter()
+                .enumerate()
+                .map(|(index, json)| T::try_from(json).map_err(|error| error.into().at(index)))
+                .collect(),
+            json => Err(FromJsonError::invalid_type::<Vec<T>>(&json)),
+        }
+    }
+}
+
+impl Json {
+    /// Converts the elements of a `Json::ARRAY` (or a `Json::OBJECT` holding one) to `T`, which
+    /// may be any type implementing `TryFrom<Json>` with an error convertible into `FromJsonError`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Days\":[\"Monday\",\"Tuesday\"],\"Hours\":[8,7.5,null]}").unwrap();
+    ///
+    /// let days = json.get("Days").unwrap().to_vec_of::<String>().unwrap();
+    ///
+    /// assert_eq!(days, vec!["Monday", "Tuesday"]);
+    ///
+    /// let error = json.get("Hours").unwrap().to_vec_of::<f64>().unwrap_err();
+    ///
+    /// assert_eq!(error.to_string(), "Element 2: A `Json::NULL` can't be converted to a `f64`.");
+    /// ```
+    /// ## Errors
+    /// Fails with `FromJsonError::InvalidType` if `self` isn't an array and with
+    /// `FromJsonError::InvalidElement` for the first element that can't be converted.
+    pub fn to_vec_of<T>(&self) -> Result<Vec<T>, FromJsonError>
+    where
+        T: TryFrom<Json>,
+        T::Error: Into<FromJsonError>,
+    {
+        match self.inner() {
+            Json::ARRAY(values) => values
+                .iter()
+                .enumerate()
+                .map(|(index, json)| {
+                    T::try_from(json.clone()).map_err(|error| error.into().at(index))
+                })
+                .collect(),
+            json => Err(FromJsonError::invalid_type::<Vec<T>>(json)),
+        }
+    }
+
+    // Unwraps a `Json::OBJECT`, like `inner` but by value.
+    pub(crate) fn into_inner(self) -> Json {
+        match self {
+            Json::OBJECT { value, .. } => *value,
+            json => json,
+        }
+    }
+}
+
+/// Builds a `Json::JSON` with one member per entry, in iteration order.
+impl<S: BuildHasher> From<HashMap<String, Json, S>> for Json {
+    fn from(map: HashMap<String, Json, S>) -> Json {
+        Json::JSON(
+            map.into_iter()
+                .map(|(name, value)| Json::member(name, value))
+                .collect(),
+        )
+    }
+}
+
+/// Builds a `Json::JSON` with one member per entry, sorted by name.
+impl From<BTreeMap<String, Json>> for Json {
+    fn from(map: BTreeMap<String, Json>) -> Json {
+        Json::JSON(
+            map.into_iter()
+                .map(|(name, value)| Json::member(name, value))
+                .collect(),
+        )
+    }
+}
+
+/// Takes the members of a `Json::JSON` (or a `Json::OBJECT` holding one) apart without cloning.
+/// Fails on any other variant and on unnamed values. Of members with the same name, the last one wins.
+impl<S: BuildHasher + Default> TryFrom<Json> for HashMap<String, Json, S> {
+    type Error = FromJsonError;
+
+    fn try_from(json: Json) -> Result<HashMap<String, Json, S>, FromJsonError> {
+        map_from_owned(json)
+    }
+}
+
+/// Same as the `HashMap` conversion above.
+impl TryFrom<Json> for BTreeMap<String, Json> {
+    type Error = FromJsonError;
+
+    fn try_from(json: Json) -> Result<BTreeMap<String, Json>, FromJsonError> {
+        map_from_owned(json)
+    }
+}
+
+fn map_from_owned<M: FromIterator<(String, Json)>>(json: Json) -> Result<M, FromJsonError> {
+    let values = match json {
+        Json::OBJECT { value, .. } => match *value {
+            Json::JSON(values) => values,
+            json => return Err(FromJsonError::invalid_type::<M>(&json)),
+        },
+        Json::JSON(values) => values,
+        json => return Err(FromJsonError::invalid_type::<M>(&json)),
+    };
+
+    values
+        .into_iter()
+        .map(|json| match json {
+            Json::OBJECT { name, value } => Ok((name, *value)),
+            json => Err(FromJsonError::invalid_type::<M>(&json)),
+        })
+        .collect()
+}
+
+// A map can only be read from a `Json::JSON` whose values are all named.
+fn map_from_json<V: FromJson, M: FromIterator<(String, V)>>(
+    json: &Json,
+) -> Result<M, FromJsonError> {
+    match json.inner() {
+        Json::JSON(values) => values
+            .iter()
+            .map(|json| match json {
+                Json::OBJECT { name, value } => Ok((name.clone(), V::from_json(value)?)),
+                json => Err(FromJsonError::invalid_type::<M>(json)),
+            })
+            .collect(),
+        _ => Err(FromJsonError::invalid_type::<M>(json)),
+    }
+}
+
+// Used by the code `json_minimal_derive` generates. Not part of the public API.
+#[doc(hidden)]
+pub mod private {
+    use crate::{FromJson, FromJsonError, Json};
+
+    pub fn object<'a>(json: &'a Json, target: &'static str) -> Result<&'a Json, FromJsonError> {
+        match json.inner() {
+            json @ Json::JSON(_) => Ok(json),
+            json => Err(FromJsonError::InvalidType {
+                target,
+                found: json.variant_name(),
+            }),
+        }
+    }
+
+    // A missing member is read as `Json::NULL` so that `Option` fields may be left out.
+    pub fn field<T: FromJson>(
+        json: &Json,
+        target: &'static str,
+        field: &'static str,
+    ) -> Result<T, FromJsonError> {
+        match json.try_value_of(field) {
+            Ok(Some(value)) => T::from_json(value),
+            _ => {
+                T::from_json(&Json::NULL).map_err(|_| FromJsonError::MissingField { target, field })
+            }
+        }
+    }
+
+    // Used for `#[json(default)]`, where a missing member is `None`.
+    pub fn optional_field<T: FromJson>(
+        json: &Json,
+        field: &'static str,
+    ) -> Result<Option<T>, FromJsonError> {
+        match json.try_value_of(field) {
+            Ok(Some(value)) => T::from_json(value).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn elements<'a>(
+        json: &'a Json,
+        target: &'static str,
+        expected: usize,
+    ) -> Result<&'a [Json], FromJsonError> {
+        match json.inner() {
+            Json::ARRAY(values) if values.len() == expected => Ok(values),
+            Json::ARRAY(values) => Err(FromJsonError::InvalidLength {
+                target,
+                expected,
+                found: values.len(),
+            }),
+            json => Err(FromJsonError::InvalidType {
+                target,
+                found: json.variant_name(),
+            }),
+        }
+    }
+
+    // An externally tagged variant, i.e. a `Json::STRING` holding its name or a `Json::JSON` with a
+    // single member named after it.
+    pub fn variant<'a>(
+        json: &'a Json,
+        target: &'static str,
+    ) -> Result<(&'a str, Option<&'a Json>), FromJsonError> {
+        match json.inner() {
+            Json::STRING(name) => Ok((name, None)),
+            Json::JSON(values) if values.len() == 1 => match &values[0] {
+                Json::OBJECT { name, value } => Ok((name, Some(value))),
+                json => Err(FromJsonError::InvalidType {
+                    target,
+                    found: json.variant_name(),
+                }),
+            },
+            json => Err(FromJsonError::InvalidType {
+                target,
+                found: json.variant_name(),
+            }),
+        }
+    }
+
+    // The value of a variant with fields, which can't be given as just its name.
+    pub fn variant_value<'a>(
+        value: Option<&'a Json>,
+        target: &'static str,
+    ) -> Result<&'a Json, FromJsonError> {
+        value.ok_or(FromJsonError::InvalidType {
+            target,
+            found: "Json::STRING",
+        })
+    }
+
+    pub fn unit(json: &Json, target: &'static str) -> Result<(), FromJsonError> {
+        match json.inner() {
+            Json::NULL => Ok(()),
+            json => Err(FromJsonError::InvalidType {
+                target,
+                found: json.variant_name(),
+            }),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+//! Moving through a `Json` a step at a time, e.g. in a viewer or an editor.
+
+use crate::pointer;
+use crate::Json;
+
+/// A position in a `Json`, which can move down into a value, up out of it and on to the values
+/// beside it. It keeps the objects and arrays it is in, so each step takes constant time. See
+/// `Json::cursor`.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let json = Json::parse(b"{\"name\":\"app\",\"servers\":[{\"port\":80},{\"port\":443}]}").unwrap();
+///
+/// let mut cursor = json.cursor();
+///
+/// assert!(cursor.down());
+/// assert_eq!(cursor.name(), Some("name"));
+/// assert!(cursor.next_sibling());
+/// assert!(cursor.down());
+/// assert!(cursor.next_sibling());
+/// assert!(cursor.down());
+/// assert_eq!(cursor.current(), &Json::NUMBER(443.0));
+/// assert_eq!(cursor.pointer().as_deref(), Some("/servers/1/port"));
+///
+/// assert!(!cursor.down());
+/// assert!(cursor.up());
+/// assert!(cursor.prev_sibling());
+/// assert!(!cursor.prev_sibling());
+/// assert_eq!(cursor.pointer().as_deref(), Some("/servers/0"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonCursor<'a> {
+    root: &'a Json,
+    // The objects and arrays the cursor is in, outermost first.
+    levels: Vec<Level<'a>>,
+}
+
+#[derive(Debug, Clone)]
+struct Level<'a> {
+    values: &'a [Json],
+    // Which of `values` the cursor is at, or the value inside which it is.
+    index: usize,
+    object: bool,
+}
+
+impl<'a> JsonCursor<'a> {
+    /// A cursor at `root`.
+    pub fn new(root: &'a Json) -> JsonCursor<'a> {
+        JsonCursor {
+            root,
+            levels: Vec::new(),
+        }
+    }
+
+    /// The value the cursor is at. A member of an object is resolved to its value.
+    pub fn current(&self) -> &'a Json {
+        match self.levels.last() {
+            Some(level) => level.values[level.index].inner(),
+            None => self.root.inner(),
+        }
+    }
+
+    /// The name of the member the cursor is at, or `None` if it is at an element of an array, an
+    /// unnamed value or the root.
+    pub fn name(&self) -> Option<&'a str> {
+        match self.levels.last() {
+            Some(Level {
+                values,
+                index,
+                object: true,
+            }) => match &values[*index] {
+                Json::OBJECT { name, value: _ } => Some(name),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Where the value the cursor is at is among the members or elements beside it, or `None` at
+    /// the root.
+    pub fn index(&self) -> Option<usize> {
+        self.levels.last().map(|level| level.index)
+    }
+
+    /// The number of objects and arrays the cursor is in.
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Moves to the first member or element of the value the cursor is at. Returns `false`, without
+    /// moving, if there is none.
+    pub fn down(&mut self) -> bool {
+        let current = self.current();
+        let values = match current {
+            Json::JSON(values) | Json::ARRAY(values) if !values.is_empty() => values,
+            _ => return false,
+        };
+
+        self.levels.push(Level {
+            values,
+            index: 0,
+            object: matches!(current, Json::JSON(_)),
+        });
+
+        true
+    }
+
+    /// Moves to the object or array the cursor is in. Returns `false` at the root.
+    pub fn up(&mut self) -> bool {
+        self.levels.pop().is_some()
+    }
+
+    /// Moves to the next member or element. Returns `false`, without moving, if there is none.
+    pub fn next_sibling(&mut self) -> bool {
+        match self.levels.last_mut() {
+            Some(level) if level.index + 1 < level.values.len() => {
+                level.index += 1;
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Moves to the previous member or element. Returns `false`, without moving, if there is none.
+    pub fn prev_sibling(&mut self) -> bool {
+        match self.levels.last_mut() {
+            Some(level) if level.index > 0 => {
+                level.index -= 1;
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// A JSON Pointer (see `Json::pointer`) to the value the cursor is at, or `None` if an unnamed
+    /// value in an object is on the way, which can't be addressed.
+    pub fn pointer(&self) -> Option<String> {
+        let mut path = String::new();
+
+        for level in &self.levels {
+            match &level.values[level.index] {
+                _ if !level.object => path.push_str(&format!("/{}", level.index)),
+                Json::OBJECT { name, value: _ } => path = pointer::push(&path, name),
+                _ => return None,
+            }
+        }
+
+        Some(path)
+    }
+}
+
+impl Json {
+    /// Returns a `JsonCursor` at `self`.
+    pub fn cursor(&self) -> JsonCursor<'_> {
+        JsonCursor::new(self)
+    }
+}
@@ -0,0 +1,394 @@
+//! RFC 3339 timestamps (requires the `datetime` feature, which the `chrono` and `time` features
+//! enable as well).
+
+use crate::{DateTimeError, Json};
+use std::fmt;
+
+/// A validated RFC 3339 timestamp like `2020-02-29T12:30:00.25+01:00`.
+///
+/// With the `chrono` feature it converts to and from `chrono::DateTime`, with the `time` feature to
+/// and from `time::OffsetDateTime` (both through `TryFrom`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DateTime {
+    /// `0` to `9999`.
+    pub year: u16,
+    /// `1` to `12`.
+    pub month: u8,
+    /// `1` to `31`, depending on the month.
+    pub day: u8,
+    /// `0` to `23`.
+    pub hour: u8,
+    /// `0` to `59`.
+    pub minute: u8,
+    /// `0` to `60`, where `60` is a leap second.
+    pub second: u8,
+    /// The fractional second, `0` to `999_999_999`. Digits beyond nanoseconds are dropped.
+    pub nanosecond: u32,
+    /// The offset from UTC in minutes, e.g. `60` for `+01:00`. `Z` is `0`.
+    pub offset_minutes: i16,
+}
+
+impl DateTime {
+    /// Parses an RFC 3339 timestamp. Lower case `t` and `z` and a space instead of the `T` are accepted
+    /// as well.
+    /// ## Errors
+    /// Fails if `input` doesn't have the form above or a field is out of range (including days that
+    /// don't exist, like February 30th).
+    pub fn parse(input: &str) -> Result<DateTime, DateTimeError> {
+        let mut parser = Parser {
+            input: input.as_bytes(),
+            position: 0,
+            value: input,
+        };
+
+        let year = parser.number(4, 0, 9999)? as u16;
+        parser.expect(b"-")?;
+        let month = parser.number(2, 1, 12)? as u8;
+        parser.expect(b"-")?;
+        let day = parser.number(2, 1, days_in_month(year, month) as u32)? as u8;
+        parser.expect(b"Tt ")?;
+        let hour = parser.number(2, 0, 23)? as u8;
+        parser.expect(b":")?;
+        let minute = parser.number(2, 0, 59)? as u8;
+        parser.expect(b":")?;
+        let second = parser.number(2, 0, 60)? as u8;
+
+        let mut nanosecond = 0;
+
+        if parser.peek() == Some(b'.') {
+            parser.position += 1;
+
+            let start = parser.position;
+
+            while let Some(digit @ b'0'..=b'9') = parser.peek() {
+                if parser.position - start < 9 {
+                    nanosecond = nanosecond * 10 + (digit - b'0') as u32;
+                }
+
+                parser.position += 1;
+            }
+
+            match parser.position - start {
+                0 => return Err(parser.error("Expected a digit.")),
+                digits => nanosecond *= 10u32.pow(9u32.saturating_sub(digits as u32)),
+            }
+        }
+
+        let offset_minutes = match parser.peek() {
+            Some(b'Z') | Some(b'z') => {
+                parser.position += 1;
+                0
+            }
+            Some(sign @ b'+') | Some(sign @ b'-') => {
+                parser.position += 1;
+
+                let hours = parser.number(2, 0, 23)? as i16;
+                parser.expect(b":")?;
+                let minutes = parser.number(2, 0, 59)? as i16;
+
+                if sign == b'-' {
+                    -(hours * 60 + minutes)
+                } else {
+                    hours * 60 + minutes
+                }
+            }
+            _ => return Err(parser.error("Expected `Z` or an offset like `+01:00`.")),
+        };
+
+        if parser.position < input.len() {
+            return Err(parser.error("Unexpected characters after the timestamp."));
+        }
+
+        Ok(DateTime {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+            nanosecond,
+            offset_minutes,
+        })
+    }
+}
+
+/// Writes the timestamp in RFC 3339 form, with as many fractional digits as needed and `Z` for UTC.
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+
+        if self.nanosecond > 0 {
+            let fraction = format!("{:09}", self.nanosecond);
+
+            write!(f, ".{}", fraction.trim_end_matches('0'))?;
+        }
+
+        match self.offset_minutes {
+            0 => write!(f, "Z"),
+            offset => write!(
+                f,
+                "{}{:02}:{:02}",
+                if offset < 0 { '-' } else { '+' },
+                offset.abs() / 60,
+                offset.abs() % 60
+            ),
+        }
+    }
+}
+
+impl Json {
+    /// Parses the string held by a `Json::STRING` (or a `Json::OBJECT` holding one) as an RFC 3339
+    /// timestamp.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Created\":\"2020-02-29T12:30:00.25+01:00\",\"Updated\":\"2020-02-30T00:00:00Z\"}").unwrap();
+    ///
+    /// let created = json.get("Created").unwrap().as_datetime().unwrap();
+    ///
+    /// assert_eq!((created.year, created.month, created.day), (2020, 2, 29));
+    /// assert_eq!(created.offset_minutes, 60);
+    /// assert_eq!(Json::from_datetime(&created), Json::string("2020-02-29T12:30:00.25+01:00"));
+    ///
+    /// let error = json.get("Updated").unwrap().as_datetime().unwrap_err();
+    ///
+    /// assert_eq!(error.value, "2020-02-30T00:00:00Z");
+    /// assert_eq!(error.position, 8);
+    /// ```
+    /// ## Errors
+    /// Fails if `self` isn't a string (with the printed value and position `0`) or if the string
+    /// isn't a valid timestamp (see `DateTime::parse`).
+    pub fn as_datetime(&self) -> Result<DateTime, DateTimeError> {
+        match self.inner() {
+            Json::STRING(val) => DateTime::parse(val),
+            json => Err(DateTimeError {
+                value: json.print(),
+                position: 0,
+                message: "Expected a `Json::STRING`.",
+            }),
+        }
+    }
+
+    /// Creates a `Json::STRING` holding `datetime` in RFC 3339 form.
+    pub fn from_datetime(datetime: &DateTime) -> Json {
+        Json::STRING(datetime.to_string())
+    }
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    position: usize,
+    value: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &'static str) -> DateTimeError {
+        DateTimeError {
+            value: String::from(self.value),
+            position: self.position,
+            message,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    // Reads exactly `digits` digits. A value out of `min..=max` is reported at its first digit.
+    fn number(&mut self, digits: usize, min: u32, max: u32) -> Result<u32, DateTimeError> {
+        let start = self.position;
+        let mut val = 0;
+
+        for _ in 0..digits {
+            match self.peek() {
+                Some(digit @ b'0'..=b'9') => {
+                    val = val * 10 + (digit - b'0') as u32;
+                    self.position += 1;
+                }
+                _ => return Err(self.error("Expected a digit.")),
+            }
+        }
+
+        if val < min || val > max {
+            self.position = start;
+
+            return Err(self.error("Value out of range."));
+        }
+
+        Ok(val)
+    }
+
+    fn expect(&mut self, any_of: &[u8]) -> Result<(), DateTimeError> {
+        match self.peek() {
+            Some(byte) if any_of.contains(&byte) => {
+                self.position += 1;
+                Ok(())
+            }
+            _ => Err(self.error(match any_of[0] {
+                b'-' => "Expected `-`.",
+                b':' => "Expected `:`.",
+                _ => "Expected `T`.",
+            })),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+mod chrono_impl {
+    use super::DateTime;
+    use crate::DateTimeError;
+    use chrono::{Datelike, FixedOffset, NaiveDate, NaiveTime, TimeZone, Timelike};
+    use std::convert::TryFrom;
+
+    /// A leap second becomes a nanosecond value of `1_000_000_000` or more, as chrono represents it.
+    impl TryFrom<DateTime> for chrono::DateTime<FixedOffset> {
+        type Error = DateTimeError;
+
+        fn try_from(datetime: DateTime) -> Result<chrono::DateTime<FixedOffset>, DateTimeError> {
+            let (second, nanosecond) = match datetime.second {
+                60 => (59, datetime.nanosecond + 1_000_000_000),
+                second => (second, datetime.nanosecond),
+            };
+
+            let date = NaiveDate::from_ymd_opt(
+                datetime.year as i32,
+                datetime.month as u32,
+                datetime.day as u32,
+            );
+            let time = NaiveTime::from_hms_nano_opt(
+                datetime.hour as u32,
+                datetime.minute as u32,
+                second as u32,
+                nanosecond,
+            );
+            let offset = FixedOffset::east_opt(datetime.offset_minutes as i32 * 60);
+
+            match (date, time, offset) {
+                (Some(date), Some(time), Some(offset)) => offset
+                    .from_local_datetime(&date.and_time(time))
+                    .single()
+                    .ok_or_else(|| unrepresentable(&datetime)),
+                _ => Err(unrepresentable(&datetime)),
+            }
+        }
+    }
+
+    /// Fails for years outside of `0..=9999` and offsets that aren't whole minutes.
+    impl<Tz: TimeZone> TryFrom<chrono::DateTime<Tz>> for DateTime {
+        type Error = DateTimeError;
+
+        fn try_from(datetime: chrono::DateTime<Tz>) -> Result<DateTime, DateTimeError> {
+            let offset = datetime.fixed_offset().offset().local_minus_utc();
+
+            if !(0..=9999).contains(&datetime.year()) || offset % 60 != 0 {
+                return Err(DateTimeError {
+                    value: datetime.to_rfc3339(),
+                    position: 0,
+                    message: "Not representable as an RFC 3339 timestamp.",
+                });
+            }
+
+            let (second, nanosecond) = match datetime.nanosecond() {
+                nanosecond if nanosecond >= 1_000_000_000 => (60, nanosecond - 1_000_000_000),
+                nanosecond => (datetime.second() as u8, nanosecond),
+            };
+
+            Ok(DateTime {
+                year: datetime.year() as u16,
+                month: datetime.month() as u8,
+                day: datetime.day() as u8,
+                hour: datetime.hour() as u8,
+                minute: datetime.minute() as u8,
+                second,
+                nanosecond,
+                offset_minutes: (offset / 60) as i16,
+            })
+        }
+    }
+
+    fn unrepresentable(datetime: &DateTime) -> DateTimeError {
+        DateTimeError {
+            value: datetime.to_string(),
+            position: 0,
+            message: "Not representable as a `chrono::DateTime`.",
+        }
+    }
+}
+
+#[cfg(feature = "time")]
+mod time_impl {
+    use super::DateTime;
+    use crate::DateTimeError;
+    use std::convert::TryFrom;
+    use time::{Date, Month, OffsetDateTime, Time, UtcOffset};
+
+    /// Fails for leap seconds, which `time` doesn't support.
+    impl TryFrom<DateTime> for OffsetDateTime {
+        type Error = DateTimeError;
+
+        fn try_from(datetime: DateTime) -> Result<OffsetDateTime, DateTimeError> {
+            let unrepresentable = || DateTimeError {
+                value: datetime.to_string(),
+                position: 0,
+                message: "Not representable as a `time::OffsetDateTime`.",
+            };
+
+            let month = Month::try_from(datetime.month).map_err(|_| unrepresentable())?;
+            let date = Date::from_calendar_date(datetime.year as i32, month, datetime.day)
+                .map_err(|_| unrepresentable())?;
+            let time = Time::from_hms_nano(
+                datetime.hour,
+                datetime.minute,
+                datetime.second,
+                datetime.nanosecond,
+            )
+            .map_err(|_| unrepresentable())?;
+            let offset = UtcOffset::from_whole_seconds(datetime.offset_minutes as i32 * 60)
+                .map_err(|_| unrepresentable())?;
+
+            Ok(date.with_time(time).assume_offset(offset))
+        }
+    }
+
+    /// Fails for years outside of `0..=9999` and offsets that aren't whole minutes.
+    impl TryFrom<OffsetDateTime> for DateTime {
+        type Error = DateTimeError;
+
+        fn try_from(datetime: OffsetDateTime) -> Result<DateTime, DateTimeError> {
+            let offset = datetime.offset().whole_seconds();
+
+            if !(0..=9999).contains(&datetime.year()) || offset % 60 != 0 {
+                return Err(DateTimeError {
+                    value: datetime.to_string(),
+                    position: 0,
+                    message: "Not representable as an RFC 3339 timestamp.",
+                });
+            }
+
+            Ok(DateTime {
+                year: datetime.year() as u16,
+                month: datetime.month() as u8,
+                day: datetime.day(),
+                hour: datetime.hour(),
+                minute: datetime.minute(),
+                second: datetime.second(),
+                nanosecond: datetime.nanosecond(),
+                offset_minutes: (offset / 60) as i16,
+            })
+        }
+    }
+}
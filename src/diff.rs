@@ -0,0 +1,227 @@
+//! Structural comparison of two `Json`s.
+
+use crate::pointer;
+use crate::{HashTree, Json};
+use std::fmt;
+
+/// A single difference found by `Json::diff`. `path` is a JSON Pointer (RFC 6901) to the place where
+/// the two `Json`s differ, `expected` is what was found in the first one and `actual` what was found in
+/// the second one. Either is `None` if the member or element doesn't exist on that side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference<'a> {
+    pub path: String,
+    pub expected: Option<&'a Json>,
+    pub actual: Option<&'a Json>,
+}
+
+impl<'a> fmt::Display for Difference<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.expected, self.actual) {
+            (Some(expected), Some(actual)) => write!(
+                f,
+                "`{}`: expected {} but found {}",
+                self.path,
+                expected.print(),
+                actual.print()
+            ),
+            (Some(expected), None) => write!(
+                f,
+                "`{}`: expected {} but found nothing",
+                self.path,
+                expected.print()
+            ),
+            (None, Some(actual)) => {
+                write!(f, "`{}`: unexpected {}", self.path, actual.print())
+            }
+            (None, None) => write!(f, "`{}`: no difference", self.path),
+        }
+    }
+}
+
+impl Json {
+    /// Compares `expected` with `actual` and returns every place where they differ. Members of objects
+    /// are compared by name and arrays element by element. Objects holding unnamed values or the same
+    /// name more than once are compared as a whole. An empty `Vec` means the two are equal.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let expected = Json::parse(b"{\"Name\":\"Ferris\",\"Legs\":10}").unwrap();
+    /// let actual = Json::parse(b"{\"Name\":\"Ferris\",\"Legs\":8,\"Claws\":2}").unwrap();
+    ///
+    /// let differences = Json::diff(&expected, &actual);
+    ///
+    /// assert_eq!(differences.len(), 2);
+    /// assert_eq!(differences[0].to_string(), "`/Legs`: expected 10 but found 8");
+    /// assert_eq!(differences[1].to_string(), "`/Claws`: unexpected 2");
+    /// ```
+    pub fn diff<'a>(expected: &'a Json, actual: &'a Json) -> Vec<Difference<'a>> {
+        let mut differences = Vec::new();
+
+        diff(
+            String::new(),
+            expected.inner(),
+            actual.inner(),
+            None,
+            &mut differences,
+        );
+
+        differences
+    }
+
+    /// Same as `diff` above, but skips the values whose digests in `expected_hashes` and
+    /// `actual_hashes` (as `hash_tree` returns them for `expected` and `actual`) are the same, so
+    /// that comparing two large documents which only differ in a few places takes time in
+    /// proportion to those. As with `hash_canonical`, values which only differ in the order of
+    /// their members or in how numbers were written count as the same.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let expected = Json::parse(b"{\"Legs\":10,\"Log\":[1,2,3]}").unwrap();
+    /// let expected_hashes = expected.hash_tree();
+    ///
+    /// let actual = Json::parse(b"{\"Legs\":8,\"Log\":[1,2,3]}").unwrap();
+    /// let differences =
+    ///     Json::diff_hashed(&expected, &expected_hashes, &actual, &actual.hash_tree());
+    ///
+    /// assert_eq!(differences.len(), 1);
+    /// assert_eq!(differences[0].to_string(), "`/Legs`: expected 10 but found 8");
+    /// ```
+    pub fn diff_hashed<'a>(
+        expected: &'a Json,
+        expected_hashes: &HashTree,
+        actual: &'a Json,
+        actual_hashes: &HashTree,
+    ) -> Vec<Difference<'a>> {
+        let mut differences = Vec::new();
+
+        diff(
+            String::new(),
+            expected.inner(),
+            actual.inner(),
+            inner(expected, expected_hashes).zip(inner(actual, actual_hashes)),
+            &mut differences,
+        );
+
+        differences
+    }
+}
+
+// The digests of `json` and of the values inside it, followed by those of `other`, if known.
+type Hashes<'h> = Option<(&'h HashTree, &'h HashTree)>;
+
+fn diff<'a>(
+    path: String,
+    a: &'a Json,
+    b: &'a Json,
+    hashes: Hashes<'_>,
+    differences: &mut Vec<Difference<'a>>,
+) {
+    if hashes.is_some_and(|(a_hashes, b_hashes)| a_hashes.hash == b_hashes.hash) {
+        return;
+    }
+
+    match (a, b) {
+        (Json::JSON(a_values), Json::JSON(b_values))
+            if addressable(a_values) && addressable(b_values) =>
+        {
+            for (name, a_value) in a.iter() {
+                let name = name.unwrap_or_default();
+
+                let member_hashes = hashes.and_then(|(a_hashes, b_hashes)| {
+                    member(a_values, a_hashes, name).zip(member(b_values, b_hashes, name))
+                });
+
+                diff_or_missing(
+                    pointer::push(&path, name),
+                    Some(a_value),
+                    b.value_of(name),
+                    member_hashes,
+                    differences,
+                );
+            }
+
+            for (name, b_value) in b.iter() {
+                let name = name.unwrap_or_default();
+
+                if !a.contains_key(name) {
+                    diff_or_missing(
+                        pointer::push(&path, name),
+                        None,
+                        Some(b_value),
+                        None,
+                        differences,
+                    );
+                }
+            }
+        }
+        (Json::ARRAY(a_values), Json::ARRAY(b_values)) => {
+            for n in 0..a_values.len().max(b_values.len()) {
+                diff_or_missing(
+                    format!("{}/{}", path, n),
+                    a_values.get(n),
+                    b_values.get(n),
+                    hashes.and_then(|(a_hashes, b_hashes)| {
+                        a_hashes.children.get(n).zip(b_hashes.children.get(n))
+                    }),
+                    differences,
+                );
+            }
+        }
+        (a, b) => {
+            if a != b {
+                differences.push(Difference {
+                    path,
+                    expected: Some(a),
+                    actual: Some(b),
+                });
+            }
+        }
+    }
+}
+
+fn diff_or_missing<'a>(
+    path: String,
+    a: Option<&'a Json>,
+    b: Option<&'a Json>,
+    hashes: Hashes<'_>,
+    differences: &mut Vec<Difference<'a>>,
+) {
+    match (a, b) {
+        (Some(a), Some(b)) => diff(path, a, b, hashes, differences),
+        (expected, actual) => differences.push(Difference {
+            path,
+            expected,
+            actual,
+        }),
+    }
+}
+
+// Whether every member of an object can be addressed by a pointer, i.e. is named and unique.
+pub(crate) fn addressable(values: &[Json]) -> bool {
+    values.iter().enumerate().all(|(n, json)| match json {
+        Json::OBJECT { name, value: _ } => !values[..n]
+            .iter()
+            .any(|json| matches!(json, Json::OBJECT { name: other, value: _ } if other == name)),
+        _ => false,
+    })
+}
+
+// The digests of what `Json::inner` returns for `json`, given those of `json`.
+fn inner<'h>(json: &Json, hashes: &'h HashTree) -> Option<&'h HashTree> {
+    match json {
+        Json::OBJECT { name: _, value: _ } => hashes.children.first(),
+        _ => Some(hashes),
+    }
+}
+
+// The digests of the value of the (only) member named `name` in `values`, given those of the
+// object holding them.
+fn member<'h>(values: &[Json], hashes: &'h HashTree, name: &str) -> Option<&'h HashTree> {
+    let n = values
+        .iter()
+        .position(|json| matches!(json, Json::OBJECT { name: other, value: _ } if other == name))?;
+
+    hashes.children.get(n)?.children.first()
+}
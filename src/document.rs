@@ -0,0 +1,268 @@
+//! Editing the text of a value in place, keeping everything that isn't changed as it was written.
+
+use crate::pointer::unescape;
+use crate::spanned;
+use crate::{is_whitespace, Json, JsonEvent, JsonLexer, ParseError, Spans};
+use std::fmt;
+use std::ops::Range;
+
+/// The text of a value together with the value, which can be changed without reformatting the rest
+/// of the text, e.g. to change a setting in a configuration file written by hand.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let input = b"{\n    \"host\": \"localhost\",\n    \"port\": 80\n}\n";
+///
+/// let mut document = JsonDocument::parse(input).unwrap();
+///
+/// assert!(document.set("/port", &Json::NUMBER(8080.0)));
+/// assert!(document.set("/debug", &Json::BOOL(true)));
+///
+/// assert_eq!(
+///     document.to_string(),
+///     "{\n    \"host\": \"localhost\",\n    \"port\": 8080,\n    \"debug\": true\n}\n"
+/// );
+/// assert_eq!(document.json().pointer("/debug"), Some(&Json::BOOL(true)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonDocument {
+    text: Vec<u8>,
+    json: Json,
+    spans: Spans,
+    comments: bool,
+}
+
+impl JsonDocument {
+    /// Parses `input` like `Json::parse_spanned`, keeping it as it is.
+    pub fn parse(input: &[u8]) -> Result<JsonDocument, ParseError> {
+        let (json, spans) = Json::parse_spanned(input)?;
+
+        Ok(JsonDocument {
+            text: input.to_vec(),
+            json,
+            spans,
+            comments: false,
+        })
+    }
+
+    /// Parses `input` like `parse`, allowing comments (see `JsonLexer::allow_comments`), e.g. in a
+    /// JSONC configuration file. They are kept in the text like everything else.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\n  // Where to listen.\n  \"port\": 80, /* privileged */\n  \"host\": \"::1\" // IPv6\n}";
+    ///
+    /// let mut document = JsonDocument::parse_with_comments(input).unwrap();
+    ///
+    /// assert_eq!(document.comments("/port"), vec!["// Where to listen.", "/* privileged */"]);
+    /// assert!(document.set("/port", &Json::NUMBER(8080.0)));
+    /// assert!(document.set("/tls", &Json::BOOL(false)));
+    ///
+    /// assert_eq!(
+    ///     document.to_string(),
+    ///     "{\n  // Where to listen.\n  \"port\": 8080, /* privileged */\n  \"host\": \"::1\", // IPv6\n  \"tls\": false\n}"
+    /// );
+    /// ```
+    pub fn parse_with_comments(input: &[u8]) -> Result<JsonDocument, ParseError> {
+        let (json, spans) = spanned::parse(input, JsonLexer::new(input).allow_comments())?;
+
+        Ok(JsonDocument {
+            text: input.to_vec(),
+            json,
+            spans,
+            comments: true,
+        })
+    }
+
+    /// The value, as it currently is.
+    pub fn json(&self) -> &Json {
+        &self.json
+    }
+
+    /// Where the values are in the current text, see `Json::parse_spanned`.
+    pub fn spans(&self) -> &Spans {
+        &self.spans
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.text
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.text
+    }
+
+    /// Replaces the text of the value `pointer` refers to by `value` as printed by `Json::print`. If
+    /// the pointer names a member missing from an object, the member is added after the last one,
+    /// indented like the first. Everything else in the text stays the same, including the comments
+    /// around the value and behind the last member. Returns whether `value` was set, which it isn't
+    /// if there is nowhere to put it or if its text can't be parsed back (see `Json::print`).
+    pub fn set(&mut self, pointer: &str, value: &Json) -> bool {
+        let text = match self.spans.get(pointer) {
+            Some(span) => splice(&self.text, span, value.print().as_bytes()),
+            None => match self.add_member(pointer, value) {
+                Some(text) => text,
+                None => return false,
+            },
+        };
+
+        // The text of a value may not be parsed back the same, see `Json::print`.
+        match spanned::parse(&text, self.lexer(&text)) {
+            Ok((json, spans)) if json.pointer(pointer) == Some(value) => {
+                self.text = text;
+                self.json = json;
+                self.spans = spans;
+
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// The comments attached to the value `pointer` refers to (or its member), i.e. those on the lines
+    /// right before it and the one behind it on the same line. See `parse_with_comments`.
+    pub fn comments(&self, pointer: &str) -> Vec<String> {
+        let span = match self.spans.member(pointer) {
+            Some(span) => span,
+            None => return Vec::new(),
+        };
+
+        let mut comments = Vec::new();
+        let mut start = span.start;
+
+        for comment in self.spans.comments.iter().rev() {
+            if comment.end > start {
+                continue;
+            }
+
+            let line = &self.text[..comment.start];
+            let line = &line[line.iter().rposition(|c| *c == b'\n').map_or(0, |n| n + 1)..];
+
+            if !self.text[comment.end..start]
+                .iter()
+                .all(|c| is_whitespace(*c))
+                || !line.iter().all(|c| is_whitespace(*c))
+            {
+                break;
+            }
+
+            comments.insert(0, comment.clone());
+            start = comment.start;
+        }
+
+        let behind = self.spans.comments.iter().find(|comment| {
+            comment.start >= span.end
+                && self.text[span.end..comment.start]
+                    .iter()
+                    .all(|c| matches!(c, b' ' | b'\t' | b','))
+        });
+
+        comments
+            .into_iter()
+            .chain(behind.cloned())
+            .map(|comment| String::from_utf8_lossy(&self.text[comment]).into_owned())
+            .collect()
+    }
+
+    fn lexer<'a>(&self, input: &'a [u8]) -> JsonLexer<'a> {
+        if self.comments {
+            JsonLexer::new(input).allow_comments()
+        } else {
+            JsonLexer::new(input)
+        }
+    }
+
+    // The text with the member `pointer` names added to the object it is in.
+    fn add_member(&self, pointer: &str, value: &Json) -> Option<Vec<u8>> {
+        let (parent, name) = pointer.rsplit_once('/')?;
+        let span = self.spans.get(parent)?;
+        let object = &self.text[span.clone()];
+
+        // Standalone objects holding the object come first.
+        let mut lexer = self.lexer(object);
+
+        loop {
+            match lexer.next()?.ok()? {
+                JsonEvent::Key(_) => {}
+                JsonEvent::ObjectStart => break,
+                _ => return None,
+            }
+        }
+
+        let depth = lexer.depth();
+        let member = format!("\"{}\":", unescape(name));
+        let value = value.print();
+
+        // Behind the last member goes a comma, the new one goes behind its comments (if any).
+        let (comma, insert) = match (lexer.next()?.ok()?, lexer.span()) {
+            (JsonEvent::Key(_), key) => {
+                let indent = object[..key.start]
+                    .iter()
+                    .rposition(|c| !is_whitespace(*c))?
+                    + 1;
+                let indent = &object[indent..key.start];
+
+                lexer.next()?.ok()?;
+
+                let separator = &object[key.end + 1..lexer.span().start];
+                let separator = &separator[..separator
+                    .iter()
+                    .position(|c| !is_whitespace(*c))
+                    .unwrap_or(separator.len())];
+                let mut last = lexer.span().end;
+
+                while lexer.depth() >= depth {
+                    lexer.next()?.ok()?;
+
+                    if lexer.depth() == depth {
+                        last = lexer.span().end;
+                    }
+                }
+
+                let insert = [indent, member.as_bytes(), separator, value.as_bytes()];
+
+                (Some(last), insert.concat())
+            }
+            (JsonEvent::ObjectEnd, _) => (None, [member.as_bytes(), value.as_bytes()].concat()),
+            // A value without a name.
+            _ => return None,
+        };
+
+        let end = object[..object.len() - 1]
+            .iter()
+            .rposition(|c| !is_whitespace(*c))?
+            + 1;
+
+        let at = span.start + end;
+
+        // A line comment behind the last member has to stay at the end of its line.
+        let line_comment = self.spans.comments.iter().any(|comment| {
+            comment.end == at && self.text[comment.start + 1] == b'/' && !insert.contains(&b'\n')
+        });
+
+        let insert = if line_comment {
+            [&b"\n"[..], &insert].concat()
+        } else {
+            insert
+        };
+
+        let text = splice(&self.text, at..at, &insert);
+
+        match comma {
+            Some(at) => Some(splice(&text, span.start + at..span.start + at, b",")),
+            None => Some(text),
+        }
+    }
+}
+
+impl fmt::Display for JsonDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", String::from_utf8_lossy(&self.text))
+    }
+}
+
+fn splice(text: &[u8], span: Range<usize>, with: &[u8]) -> Vec<u8> {
+    [&text[..span.start], with, &text[span.end..]].concat()
+}
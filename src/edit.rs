@@ -0,0 +1,445 @@
+//! Editing a `Json` with undo and redo.
+
+use crate::patch::operation;
+use crate::pointer::{self, resolve_mut};
+use crate::{EditError, Json};
+
+/// A `Json` which records every change made through it, so that changes can be undone and redone,
+/// e.g. in a configuration editor. Changes are made by JSON Pointer, as the operations of a JSON
+/// Patch (RFC 6902) are, and `to_patch` returns those made so far as one.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let mut json = EditableJson::new(Json::parse(b"{\"port\":80,\"hosts\":[\"a\"]}").unwrap());
+///
+/// json.replace("/port", Json::number(8080.0)).unwrap();
+/// json.add("/hosts/-", Json::string("b")).unwrap();
+///
+/// assert_eq!(json.json().print(), "{\"port\":8080,\"hosts\":[\"a\",\"b\"]}");
+///
+/// assert!(json.undo());
+/// assert_eq!(json.json().print(), "{\"port\":8080,\"hosts\":[\"a\"]}");
+/// assert!(json.redo());
+/// assert_eq!(
+///     json.to_patch().print(),
+///     "[{\"op\":\"replace\",\"path\":\"/port\",\"value\":8080},\
+///       {\"op\":\"add\",\"path\":\"/hosts/1\",\"value\":\"b\"}]"
+/// );
+///
+/// let error = json.remove("/missing").unwrap_err();
+///
+/// assert_eq!(error.to_string(), "`/missing`: nothing to remove");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditableJson {
+    json: Json,
+    // The steps which can be undone, the last made last, each made of the edits it took in order.
+    done: Vec<Vec<Edit>>,
+    // The steps which can be redone, the last undone last.
+    undone: Vec<Vec<Edit>>,
+}
+
+// A change to a `Json`, with what it takes to undo it.
+#[derive(Debug, Clone, PartialEq)]
+enum Edit {
+    // `value` was inserted as the value at `path`, at `index` of the values of the object or array
+    // `path` points into. Into an object, `value` is the member.
+    Insert {
+        path: String,
+        index: usize,
+        value: Json,
+    },
+    // Same as `Insert`, but removed.
+    Remove {
+        path: String,
+        index: usize,
+        value: Json,
+    },
+    // The value at `path` was `old` and became `new`.
+    Replace {
+        path: String,
+        old: Json,
+        new: Json,
+    },
+}
+
+impl Edit {
+    // Makes the edit, or undoes it if not `forward`, on `json`, which it is known to fit.
+    fn apply(&self, json: &mut Json, forward: bool) {
+        match (self, forward) {
+            (Edit::Insert { path, index, value }, true)
+            | (Edit::Remove { path, index, value }, false) => {
+                values(json, path).insert(*index, value.clone());
+            }
+            (Edit::Insert { path, index, .. }, false)
+            | (Edit::Remove { path, index, .. }, true) => {
+                values(json, path).remove(*index);
+            }
+            (Edit::Replace { path, old, new }, forward) => {
+                let value = resolve_mut(json, path).expect("the value replaced");
+
+                *value = if forward { new.clone() } else { old.clone() };
+            }
+        }
+    }
+
+    // The edit as an operation of a JSON Patch.
+    fn operation(&self) -> Json {
+        match self {
+            Edit::Insert { path, value, .. } => operation("add", path.clone(), Some(value)),
+            Edit::Remove { path, .. } => operation("remove", path.clone(), None),
+            Edit::Replace { path, new, .. } => operation("replace", path.clone(), Some(new)),
+        }
+    }
+}
+
+impl EditableJson {
+    /// Starts recording the changes made to `json`.
+    pub fn new(json: Json) -> EditableJson {
+        EditableJson {
+            json,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// The value as changed so far.
+    pub fn json(&self) -> &Json {
+        &self.json
+    }
+
+    /// Returns the value as changed so far, forgetting what was changed.
+    pub fn into_inner(self) -> Json {
+        self.json
+    }
+
+    /// Adds `value` at `path` as the `add` operation of a JSON Patch does: into an object as a new
+    /// member (or in place of the value of the member of that name), into an array before the
+    /// element at that index (or at its end for `-`), or in place of the whole value for `""`.
+    pub fn add(&mut self, path: &str, value: Json) -> Result<(), EditError> {
+        let edit = self.add_edit(path, value)?;
+
+        self.commit(vec![edit]);
+
+        Ok(())
+    }
+
+    /// Removes the value at `path` and returns it. Of several members of the same name the first is
+    /// removed.
+    pub fn remove(&mut self, path: &str) -> Result<Json, EditError> {
+        let edit = self.remove_edit(path)?;
+        let value = match &edit {
+            Edit::Remove { value, .. } => value.clone().into_inner(),
+            _ => unreachable!(),
+        };
+
+        self.commit(vec![edit]);
+
+        Ok(value)
+    }
+
+    /// Replaces the value at `path` with `value` and returns the one it replaced.
+    pub fn replace(&mut self, path: &str, value: Json) -> Result<Json, EditError> {
+        if self.json.pointer(path).is_none() {
+            return Err(error(path, "nothing to replace"));
+        }
+
+        let edit = self.replace_edit(path, value);
+        let old = match &edit {
+            Edit::Replace { old, .. } => old.clone(),
+            _ => unreachable!(),
+        };
+
+        self.commit(vec![edit]);
+
+        Ok(old)
+    }
+
+    /// Applies a JSON Patch, i.e. a `Json::ARRAY` of operations like
+    /// `{"op":"add","path":"/a","value":1}` (see `Json::diff_patch`), as a single change which is
+    /// undone at once. If an operation fails, including a `test` one, those before it are undone
+    /// and the error returned.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = EditableJson::new(Json::parse(b"{\"a\":1,\"b\":[]}").unwrap());
+    ///
+    /// let patch = Json::parse(
+    ///     b"[{\"op\":\"copy\",\"from\":\"/a\",\"path\":\"/b/0\"},\
+    ///       {\"op\":\"move\",\"from\":\"/a\",\"path\":\"/c\"}]",
+    /// )
+    /// .unwrap();
+    ///
+    /// json.apply_patch(&patch).unwrap();
+    /// assert_eq!(json.json().print(), "{\"b\":[1],\"c\":1}");
+    ///
+    /// let patch = Json::parse(
+    ///     b"[{\"op\":\"remove\",\"path\":\"/c\"},{\"op\":\"test\",\"path\":\"/b/0\",\"value\":2}]",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(json.apply_patch(&patch).unwrap_err().message, "test failed");
+    /// assert_eq!(json.json().print(), "{\"b\":[1],\"c\":1}");
+    ///
+    /// assert!(json.undo());
+    /// assert_eq!(json.json().print(), "{\"a\":1,\"b\":[]}");
+    /// ```
+    pub fn apply_patch(&mut self, patch: &Json) -> Result<(), EditError> {
+        let operations = match patch.inner() {
+            Json::ARRAY(operations) => operations,
+            _ => return Err(error("", "expected an array of operations")),
+        };
+        let mut step = Vec::new();
+
+        for operation in operations {
+            if let Err(error) = self.operation(operation, &mut step) {
+                for edit in step.iter().rev() {
+                    edit.apply(&mut self.json, false);
+                }
+
+                return Err(error);
+            }
+        }
+
+        self.commit(step);
+
+        Ok(())
+    }
+
+    /// Undoes the last change which wasn't undone. Returns `false` if there is none.
+    pub fn undo(&mut self) -> bool {
+        let step = match self.done.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        for edit in step.iter().rev() {
+            edit.apply(&mut self.json, false);
+        }
+
+        self.undone.push(step);
+
+        true
+    }
+
+    /// Makes the last change undone again. Returns `false` if there is none, which is also the case
+    /// once a change was made after undoing.
+    pub fn redo(&mut self) -> bool {
+        let step = match self.undone.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        for edit in &step {
+            edit.apply(&mut self.json, true);
+        }
+
+        self.done.push(step);
+
+        true
+    }
+
+    /// Whether `undo` would undo something.
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    /// Whether `redo` would redo something.
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// Returns the changes made and not undone as a JSON Patch, which turns the value this was
+    /// created with into the one it is now.
+    pub fn to_patch(&self) -> Json {
+        Json::ARRAY(self.done.iter().flatten().map(Edit::operation).collect())
+    }
+
+    // Records `step`, whose edits were made already.
+    fn commit(&mut self, step: Vec<Edit>) {
+        if !step.is_empty() {
+            self.done.push(step);
+            self.undone.clear();
+        }
+    }
+
+    // Applies one operation of a JSON Patch, adding the edits it took to `step`.
+    fn operation(&mut self, operation: &Json, step: &mut Vec<Edit>) -> Result<(), EditError> {
+        let member = |name| match operation.inner() {
+            Json::JSON(_) => operation.value_of(name),
+            _ => None,
+        };
+        let path = match member("path").and_then(Json::as_str) {
+            Some(path) => path,
+            None => return Err(error("", "expected an operation with a `path`")),
+        };
+        let value = || member("value").ok_or_else(|| error(path, "expected a `value`"));
+        let from = || {
+            member("from")
+                .and_then(Json::as_str)
+                .ok_or_else(|| error(path, "expected a `from`"))
+        };
+
+        let edits = match member("op").and_then(Json::as_str) {
+            Some("add") => vec![self.add_edit(path, value()?.clone())?],
+            Some("remove") => vec![self.remove_edit(path)?],
+            Some("replace") => {
+                if self.json.pointer(path).is_none() {
+                    return Err(error(path, "nothing to replace"));
+                }
+
+                vec![self.replace_edit(path, value()?.clone())]
+            }
+            Some("move") => {
+                let from = from()?;
+
+                if path.starts_with(from) && path[from.len()..].starts_with('/') {
+                    return Err(error(path, "can't move a value into itself"));
+                }
+
+                let removed = self.remove_edit(from)?;
+                let value = match &removed {
+                    Edit::Remove { value, .. } => value.clone().into_inner(),
+                    _ => unreachable!(),
+                };
+
+                step.push(removed);
+                vec![self.add_edit(path, value)?]
+            }
+            Some("copy") => {
+                let value = match self.json.pointer(from()?) {
+                    Some(value) => value.clone(),
+                    None => return Err(error(path, "nothing to copy")),
+                };
+
+                vec![self.add_edit(path, value)?]
+            }
+            Some("test") => {
+                if self.json.pointer(path) != Some(value()?) {
+                    return Err(error(path, "test failed"));
+                }
+
+                Vec::new()
+            }
+            _ => return Err(error(path, "expected an `op` of a JSON Patch")),
+        };
+
+        step.extend(edits);
+
+        Ok(())
+    }
+
+    // Adds `value` at `path`, returning the edit it took.
+    fn add_edit(&mut self, path: &str, value: Json) -> Result<Edit, EditError> {
+        let (parent, token) = match split(path) {
+            Some(split) => split,
+            None => return Ok(self.replace_edit(path, value)),
+        };
+
+        let values = match self.json.pointer(parent).map(Json::inner) {
+            Some(Json::JSON(values)) => values,
+            Some(Json::ARRAY(values)) => {
+                let index = match token.as_str() {
+                    "-" => values.len(),
+                    token => match pointer::index(token).filter(|n| *n <= values.len()) {
+                        Some(index) => index,
+                        None => return Err(error(path, "no such index to add at")),
+                    },
+                };
+                let edit = Edit::Insert {
+                    path: format!("{}/{}", parent, index),
+                    index,
+                    value,
+                };
+
+                edit.apply(&mut self.json, true);
+
+                return Ok(edit);
+            }
+            _ => return Err(error(path, "no object or array to add to")),
+        };
+
+        if values.iter().any(|json| is_named(json, &token)) {
+            return Ok(self.replace_edit(path, value));
+        }
+
+        let edit = Edit::Insert {
+            path: path.to_string(),
+            index: values.len(),
+            value: Json::member(token, value),
+        };
+
+        edit.apply(&mut self.json, true);
+
+        Ok(edit)
+    }
+
+    // Removes the value at `path`, returning the edit it took.
+    fn remove_edit(&mut self, path: &str) -> Result<Edit, EditError> {
+        let (parent, token) = match split(path) {
+            Some(split) => split,
+            None => return Err(error(path, "can't remove the whole value")),
+        };
+
+        let index = match self.json.pointer(parent).map(Json::inner) {
+            Some(Json::JSON(values)) => values.iter().position(|json| is_named(json, &token)),
+            Some(Json::ARRAY(values)) => pointer::index(&token).filter(|n| *n < values.len()),
+            _ => None,
+        };
+        let index = index.ok_or_else(|| error(path, "nothing to remove"))?;
+        let edit = Edit::Remove {
+            path: path.to_string(),
+            index,
+            value: values(&mut self.json, path)[index].clone(),
+        };
+
+        edit.apply(&mut self.json, true);
+
+        Ok(edit)
+    }
+
+    // Replaces the value at `path`, which is known to be there, returning the edit it took.
+    fn replace_edit(&mut self, path: &str, value: Json) -> Edit {
+        let old = resolve_mut(&mut self.json, path).expect("the value replaced");
+        let edit = Edit::Replace {
+            path: path.to_string(),
+            old: old.take(),
+            new: value,
+        };
+
+        edit.apply(&mut self.json, true);
+
+        edit
+    }
+}
+
+// Splits `path` into the pointer to the object or array it points into and the (unescaped) last
+// token, or returns `None` for the empty pointer.
+fn split(path: &str) -> Option<(&str, String)> {
+    let n = path.rfind('/')?;
+
+    Some((&path[..n], pointer::unescape(&path[n + 1..])))
+}
+
+// The values of the object or array which `path` points into.
+fn values<'a>(json: &'a mut Json, path: &str) -> &'a mut Vec<Json> {
+    let parent = split(path).map_or("", |(parent, _)| parent);
+
+    match resolve_mut(json, parent).map(Json::inner_mut) {
+        Some(Json::JSON(values)) | Some(Json::ARRAY(values)) => values,
+        _ => panic!("an object or array at `{}`", parent),
+    }
+}
+
+fn is_named(json: &Json, search: &str) -> bool {
+    matches!(json, Json::OBJECT { name, value: _ } if name == search)
+}
+
+fn error(path: &str, message: &str) -> EditError {
+    EditError {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
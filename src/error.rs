@@ -0,0 +1,601 @@
+use crate::Json;
+use std::convert::Infallible;
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+/// The error returned by `Json::try_add`. Both variants give the value that could not be added back.
+#[derive(Debug, PartialEq)]
+pub enum AddError {
+    /// A `Json::JSON` was added to a `Json::JSON` without wrapping it in a `Json::OBJECT` first.
+    UnnamedJson(Json),
+    /// The value was added to something other than a `Json::JSON`, `Json::ARRAY` or `Json::OBJECT`
+    /// holding a `Json::JSON` or `Json::ARRAY`.
+    NotAContainer(Json),
+}
+
+impl fmt::Display for AddError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddError::UnnamedJson(_) => write!(
+                f,
+                "A `Json::JSON` may not be added to a `Json::JSON` if it is not within a `Json::OBJECT`."
+            ),
+            AddError::NotAContainer(_) => write!(
+                f,
+                "Values may only be added to a `Json::JSON`, `Json::ARRAY` or `Json::OBJECT` holding a `Json::JSON` or `Json::ARRAY`."
+            ),
+        }
+    }
+}
+
+impl Error for AddError {}
+
+/// The error returned by the non-panicking counterparts (`try_get`, `try_retain`, ...) of functions
+/// that may only be called on certain `Json` variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariantError {
+    /// The name of the function that was called, e.g. `"get"`.
+    pub function: &'static str,
+    /// The variant it was called on, e.g. `"Json::ARRAY"`.
+    pub found: &'static str,
+}
+
+impl VariantError {
+    pub(crate) fn new(function: &'static str, found: &Json) -> VariantError {
+        VariantError {
+            function,
+            found: found.variant_name(),
+        }
+    }
+}
+
+impl fmt::Display for VariantError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The function `{}` may not be called on a `{}`.",
+            self.function, self.found
+        )
+    }
+}
+
+impl Error for VariantError {}
+
+/// The error returned by `Json::stats` (with `NonNumeric::Fail`) for the first element which isn't a `Json::NUMBER`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotANumber {
+    /// The index of the element.
+    pub index: usize,
+    /// Its variant, e.g. `"Json::STRING"`.
+    pub found: &'static str,
+}
+
+impl fmt::Display for NotANumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Expected a `Json::NUMBER` at index {} but found a `{}`.",
+            self.index, self.found
+        )
+    }
+}
+
+impl Error for NotANumber {}
+
+/// The error returned by `Json::print_cancellable` if printing was cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Printing was cancelled.")
+    }
+}
+
+impl Error for Cancelled {}
+
+/// The error returned by `FromJson::from_json`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FromJsonError {
+    /// The value has the wrong variant or doesn't fit the target type (e.g. `300` for a `u8`).
+    InvalidType {
+        /// The name of the target type, e.g. `"u8"`.
+        target: &'static str,
+        /// The variant that was found, e.g. `"Json::STRING"`.
+        found: &'static str,
+    },
+    /// A member of a derived struct wasn't found and its type doesn't accept `Json::NULL` either.
+    MissingField {
+        /// The name of the struct.
+        target: &'static str,
+        /// The name of the member.
+        field: &'static str,
+    },
+    /// The array for a derived tuple struct has the wrong number of elements.
+    InvalidLength {
+        /// The name of the struct.
+        target: &'static str,
+        /// The number of fields of the struct.
+        expected: usize,
+        /// The number of elements of the array.
+        found: usize,
+    },
+    /// The name found for a derived enum doesn't match any of its variants.
+    UnknownVariant {
+        /// The name of the enum.
+        target: &'static str,
+        /// The name that was found.
+        variant: String,
+    },
+    /// An element of an array couldn't be converted. Only the first such element is reported.
+    InvalidElement {
+        /// The index of the element.
+        index: usize,
+        /// Why it couldn't be converted.
+        error: Box<FromJsonError>,
+    },
+}
+
+impl FromJsonError {
+    pub(crate) fn invalid_type<T>(found: &Json) -> FromJsonError {
+        FromJsonError::InvalidType {
+            target: std::any::type_name::<T>(),
+            found: found.inner().variant_name(),
+        }
+    }
+
+    pub(crate) fn at(self, index: usize) -> FromJsonError {
+        FromJsonError::InvalidElement {
+            index,
+            error: Box::new(self),
+        }
+    }
+}
+
+impl fmt::Display for FromJsonError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FromJsonError::InvalidType { target, found } => {
+                write!(f, "A `{}` can't be converted to a `{}`.", found, target)
+            }
+            FromJsonError::MissingField { target, field } => write!(
+                f,
+                "The member `{}` required for a `{}` is missing.",
+                field, target
+            ),
+            FromJsonError::InvalidLength {
+                target,
+                expected,
+                found,
+            } => write!(
+                f,
+                "A `{}` needs an array of {} elements but found {}.",
+                target, expected, found
+            ),
+            FromJsonError::UnknownVariant { target, variant } => {
+                write!(f, "`{}` is not a variant of `{}`.", variant, target)
+            }
+            FromJsonError::InvalidElement { index, error } => {
+                write!(f, "Element {}: {}", index, error)
+            }
+        }
+    }
+}
+
+impl Error for FromJsonError {}
+
+// Lets infallible conversions (like the one from `Json` to itself) be used with `to_vec_of`.
+impl From<Infallible> for FromJsonError {
+    fn from(never: Infallible) -> FromJsonError {
+        match never {}
+    }
+}
+
+/// What kind of error a `ParseError` is, so that callers can tell them apart without comparing
+/// messages. More kinds may be added.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let error = Json::validate(b"{\"retries\":[1,2").unwrap_err();
+///
+/// // Only the end is missing, so wait for more.
+/// assert_eq!(error.kind, ErrorKind::UnexpectedEof);
+/// assert_eq!(error.message, "Unexpected end of input.");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The input ended where more was expected, e.g. within a container, a string (also right
+    /// behind a `\`) or `true`, as if it was cut short.
+    UnexpectedEof,
+    /// Something which can't start a value where one was expected.
+    InvalidValue,
+    /// Something which doesn't belong in an object (`Json::JSON`).
+    InvalidObject,
+    /// Something which doesn't belong in an array.
+    InvalidArray,
+    /// Something which can't be the value of a member behind its `:`.
+    InvalidMember,
+    /// A string which isn't valid UTF-8.
+    InvalidUtf8,
+    /// A `\` followed by something which isn't an escape sequence.
+    InvalidEscape,
+    /// A `\u` not followed by four hexadecimal digits of a valid character.
+    InvalidUnicodeEscape,
+    /// Something starting with a digit (or a `-`, with `ParseOptions::strict_numbers`) which isn't a
+    /// number, like `1.2.3` or `12abc`.
+    InvalidNumber,
+    /// A number with a `0` in front of its other digits, like `007`, with
+    /// `ParseOptions::strict_numbers`.
+    LeadingZero,
+    /// A number without a digit where one is needed, like `1.` or `1e+`, with
+    /// `ParseOptions::strict_numbers`.
+    MissingDigit,
+    /// Something starting with `t` or `f` which is neither `true` nor `false`, like `ture`.
+    InvalidBool,
+    /// Something starting with `n` which isn't `null`, like `nil`.
+    InvalidNull,
+    /// A closing bracket which doesn't match the opening one, or comes where a value is expected.
+    UnexpectedClose,
+    /// A string in an object without a `:` behind it, i.e. which isn't the name of a member, with
+    /// `ParseOptions::strict_syntax`.
+    ExpectedColon,
+    /// A comma before the closing bracket of an object or array, with
+    /// `ParseOptions::strict_syntax`.
+    TrailingComma,
+    /// A comma before the first value of an object or array, or one more between two values, with
+    /// `ParseOptions::strict_syntax`.
+    ExtraComma,
+    /// Two values of an object or array without a comma between them, with
+    /// `ParseOptions::strict_syntax`.
+    MissingComma,
+    /// Something other than whitespace after the value.
+    TrailingData,
+    /// Something other than an array where one was expected.
+    ExpectedArray,
+    /// Objects and arrays nested more deeply than allowed.
+    TooDeep,
+    /// A `/*` comment without its `*/`.
+    UnterminatedComment,
+    /// More values than `ParseOptions::max_nodes` allows.
+    TooManyValues,
+    /// More string bytes than `ParseOptions::max_string_bytes` allows.
+    TooManyStringBytes,
+    /// A string longer than `ParseOptions::max_string_len` allows.
+    StringTooLong,
+    /// Parsing was cancelled, see `Json::parse_cancellable`.
+    Cancelled,
+    /// A value which doesn't conform to the schema passed to `Json::parse_with_schema` (requires
+    /// the `schema` feature).
+    SchemaViolation,
+    /// Reading the input failed, see `ParseError::io_error`.
+    Io,
+}
+
+impl ErrorKind {
+    /// The message of `ParseError`s of this kind, e.g. `"Error parsing array."`.
+    pub fn message(self) -> &'static str {
+        match self {
+            ErrorKind::UnexpectedEof => "Unexpected end of input.",
+            ErrorKind::InvalidValue => "Not a valid json format",
+            ErrorKind::InvalidObject => "Error parsing json.",
+            ErrorKind::InvalidArray => "Error parsing array.",
+            ErrorKind::InvalidMember => "Error parsing object.",
+            ErrorKind::InvalidUtf8 => "Error parsing non-utf8 string.",
+            ErrorKind::InvalidEscape => "Error parsing invalid string escape sequence.",
+            ErrorKind::InvalidUnicodeEscape => "Error parsing unicode string escape sequence.",
+            ErrorKind::InvalidNumber => "Error parsing number.",
+            ErrorKind::LeadingZero => "Leading zero in number.",
+            ErrorKind::MissingDigit => "Missing digit in number.",
+            ErrorKind::InvalidBool => "Error parsing bool.",
+            ErrorKind::InvalidNull => "Error parsing null.",
+            ErrorKind::UnexpectedClose => "Unexpected end of a container.",
+            ErrorKind::ExpectedColon => "Expected a colon.",
+            ErrorKind::TrailingComma => "Trailing comma.",
+            ErrorKind::ExtraComma => "Extra comma.",
+            ErrorKind::MissingComma => "Missing comma.",
+            ErrorKind::TrailingData => "Unexpected data after the value.",
+            ErrorKind::ExpectedArray => "Expected an array.",
+            ErrorKind::TooDeep => "Too deeply nested.",
+            ErrorKind::UnterminatedComment => "Unterminated comment.",
+            ErrorKind::TooManyValues => "Too many values.",
+            ErrorKind::TooManyStringBytes => "Too many string bytes.",
+            ErrorKind::StringTooLong => "String too long.",
+            ErrorKind::Cancelled => "Cancelled.",
+            ErrorKind::SchemaViolation => "Value doesn't conform to the schema.",
+            ErrorKind::Io => "Error reading the input.",
+        }
+    }
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+/// Where and why parsing failed, returned by `Json::parse` and the other ways to parse or check
+/// input, e.g. yielded by the iterator of `Json::parse_ndjson` for a line which isn't valid.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The line on which parsing failed, counting from `1`.
+    pub line: usize,
+    /// The byte offset into that line.
+    pub position: usize,
+    /// What went wrong.
+    pub kind: ErrorKind,
+    /// What went wrong as text, the `message` of `kind`.
+    pub message: &'static str,
+    /// Where the value which failed is in the document, like `config.retries` or `servers[2].port`
+    /// (see `KeyStyle::Dotted`), if it is inside an object or array and the parse keeps track of
+    /// it, as `Json::parse_with` does.
+    pub path: Option<String>,
+    // Set if reading the input failed.
+    io: Option<Arc<io::Error>>,
+}
+
+impl ParseError {
+    pub(crate) fn new(line: usize, position: usize, kind: ErrorKind) -> ParseError {
+        ParseError {
+            line,
+            position,
+            kind,
+            message: kind.message(),
+            path: None,
+            io: None,
+        }
+    }
+
+    // The error at `input[index]`, with the line and the offset into it worked out.
+    pub(crate) fn at(input: &[u8], index: usize, kind: ErrorKind) -> ParseError {
+        let (line, position) = locate(input, index);
+
+        ParseError::new(line, position, kind)
+    }
+
+    pub(crate) fn io(line: usize, error: io::Error) -> ParseError {
+        ParseError {
+            io: Some(Arc::new(error)),
+            ..ParseError::new(line, 0, ErrorKind::Io)
+        }
+    }
+
+    /// The I/O error which caused this one, if reading the input failed.
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.io.as_deref()
+    }
+}
+
+// The line of `input[index]`, counting from `1`, and the byte offset into it.
+pub(crate) fn locate(input: &[u8], index: usize) -> (usize, usize) {
+    let before = &input[..index.min(input.len())];
+    let line_start = before
+        .iter()
+        .rposition(|c| *c == b'\n')
+        .map_or(0, |n| n + 1);
+    let line = before.iter().filter(|c| **c == b'\n').count() + 1;
+
+    (line, before.len() - line_start)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` at line `{}`, position `{}`",
+            self.message, self.line, self.position
+        )?;
+
+        if let Some(path) = &self.path {
+            write!(f, ", while parsing the value of `{}`", path)?;
+        }
+
+        write!(f, ".")?;
+
+        match &self.io {
+            Some(error) => write!(f, " ({})", error),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.io {
+            Some(error) => Some(&**error),
+            None => None,
+        }
+    }
+}
+
+/// The error yielded by the iterator of `Json::parse_seq`.
+#[derive(Debug)]
+pub enum SeqError {
+    /// Reading from the underlying reader failed.
+    Io(io::Error),
+    /// A record isn't valid JSON.
+    Parse {
+        /// The number of the record, counting from `0` and leaving out empty ones.
+        record: usize,
+        /// The offset into the record (after leading whitespace) at which parsing failed.
+        position: usize,
+        /// What went wrong, e.g. `"Error parsing array."`.
+        message: &'static str,
+    },
+}
+
+impl fmt::Display for SeqError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeqError::Io(error) => write!(f, "{}", error),
+            SeqError::Parse {
+                record,
+                position,
+                message,
+            } => write!(
+                f,
+                "`{}` at position `{}` of record `{}`.",
+                message, position, record
+            ),
+        }
+    }
+}
+
+impl Error for SeqError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SeqError::Io(error) => Some(error),
+            SeqError::Parse { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for SeqError {
+    fn from(error: io::Error) -> SeqError {
+        SeqError::Io(error)
+    }
+}
+
+/// The error returned when an `EditableJson` can't be edited as asked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EditError {
+    /// The JSON Pointer (RFC 6901) of the edit, e.g. the `path` of an operation of a JSON Patch.
+    pub path: String,
+    /// What is wrong, e.g. `"nothing to remove"`.
+    pub message: String,
+}
+
+impl fmt::Display for EditError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.path, self.message)
+    }
+}
+
+impl Error for EditError {}
+
+/// The error returned by `to_value` and `from_value` (requires the `serde` feature).
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SerdeError {
+    /// What went wrong, e.g. `"invalid type: string \"x\", expected u32"`.
+    pub message: String,
+}
+
+#[cfg(feature = "serde")]
+impl fmt::Display for SerdeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl Error for SerdeError {}
+
+/// The error returned by `Json::from_cbor` (requires the `cbor` feature).
+#[cfg(feature = "cbor")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CborError {
+    /// The offset into the input at which decoding failed.
+    pub position: usize,
+    /// What went wrong, e.g. `"Unexpected end of input."`.
+    pub message: &'static str,
+}
+
+#[cfg(feature = "cbor")]
+impl fmt::Display for CborError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` at position `{}`.", self.message, self.position)
+    }
+}
+
+#[cfg(feature = "cbor")]
+impl Error for CborError {}
+
+/// The error returned by `Json::from_msgpack` (requires the `msgpack` feature).
+#[cfg(feature = "msgpack")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MsgPackError {
+    /// The offset into the input at which decoding failed.
+    pub position: usize,
+    /// What went wrong, e.g. `"Unexpected end of input."`.
+    pub message: &'static str,
+}
+
+#[cfg(feature = "msgpack")]
+impl fmt::Display for MsgPackError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` at position `{}`.", self.message, self.position)
+    }
+}
+
+#[cfg(feature = "msgpack")]
+impl Error for MsgPackError {}
+
+/// The error returned by `Json::as_datetime` and `DateTime::parse` (requires the `datetime` feature).
+#[cfg(feature = "datetime")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateTimeError {
+    /// The offending value.
+    pub value: String,
+    /// The byte offset into `value` at which it stopped being valid.
+    pub position: usize,
+    /// What went wrong, e.g. `"Value out of range."`.
+    pub message: &'static str,
+}
+
+#[cfg(feature = "datetime")]
+impl fmt::Display for DateTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (at position `{}` of `{}`)",
+            self.message, self.position, self.value
+        )
+    }
+}
+
+#[cfg(feature = "datetime")]
+impl Error for DateTimeError {}
+
+/// The error returned by `Schema::compile` (requires the `schema` feature).
+#[cfg(feature = "schema")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// A JSON Pointer (RFC 6901) into the schema to the keyword with the offending value.
+    pub path: String,
+    /// What is wrong with it, e.g. `"expected a number"`.
+    pub message: String,
+}
+
+#[cfg(feature = "schema")]
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.path, self.message)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl Error for SchemaError {}
+
+/// The error returned by `Json::as_uuid` (requires the `uuid` feature).
+#[cfg(feature = "uuid")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UuidError {
+    /// The offending value.
+    pub value: String,
+    /// What went wrong.
+    pub message: String,
+}
+
+#[cfg(feature = "uuid")]
+impl fmt::Display for UuidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (found `{}`)", self.message, self.value)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Error for UuidError {}
@@ -0,0 +1,107 @@
+//! A C interface (requires the `ffi` feature). Parsed values are handed out as opaque `Json` pointers
+//! which must be released with `json_minimal_free`, strings returned by these functions with
+//! `json_minimal_string_free`. The declarations for C are in `include/json_minimal.h`.
+
+use crate::{parser, Json};
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+
+/// Parses the `len` bytes at `input`. Returns a handle to the result or a null pointer if the
+/// input isn't valid. In that case a description of the error is stored in `*error` (unless `error`
+/// is null), to be released with `json_minimal_string_free`.
+/// ## Safety
+/// `input` must point to `len` readable bytes (it may be null if `len` is `0`) and `error` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn json_minimal_parse(
+    input: *const u8,
+    len: usize,
+    error: *mut *mut c_char,
+) -> *mut Json {
+    let input = if len == 0 {
+        &[][..]
+    } else {
+        std::slice::from_raw_parts(input, len)
+    };
+
+    let result = parser::parse(input, &mut 0);
+
+    match result {
+        Ok(json) => Box::into_raw(Box::new(json)),
+        Err((position, kind)) => {
+            if !error.is_null() {
+                *error = into_c_string(format!("`{}` at position `{}`.", kind, position));
+            }
+
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Looks up `pointer` (a NUL-terminated JSON Pointer like `/users/0/name`) in `json`, see
+/// `Json::pointer`. Returns a null pointer if there is no such value. The result borrows from
+/// `json`: it must not be freed and is only valid as long as `json` is.
+/// ## Safety
+/// `json` must be a handle returned by `json_minimal_parse` (or one borrowed from it) that hasn't
+/// been freed and `pointer` a NUL-terminated string. Null pointers for either result in a null
+/// pointer.
+#[no_mangle]
+pub unsafe extern "C" fn json_minimal_pointer(
+    json: *const Json,
+    pointer: *const c_char,
+) -> *const Json {
+    if json.is_null() || pointer.is_null() {
+        return ptr::null();
+    }
+
+    let pointer = match CStr::from_ptr(pointer).to_str() {
+        Ok(pointer) => pointer,
+        Err(_) => return ptr::null(),
+    };
+
+    match (*json).pointer(pointer) {
+        Some(json) => json,
+        None => ptr::null(),
+    }
+}
+
+/// Prints `json` (see `Json::print`) into a NUL-terminated string, to be released with
+/// `json_minimal_string_free`. Returns a null pointer if `json` is null or if the text would contain a
+/// NUL byte.
+/// ## Safety
+/// `json` must be null or a handle that hasn't been freed.
+#[no_mangle]
+pub unsafe extern "C" fn json_minimal_print(json: *const Json) -> *mut c_char {
+    if json.is_null() {
+        return ptr::null_mut();
+    }
+
+    into_c_string((*json).print())
+}
+
+/// Releases a handle returned by `json_minimal_parse`, including everything borrowed from it. Null
+/// pointers are ignored.
+/// ## Safety
+/// `json` must be null or a handle returned by `json_minimal_parse` that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn json_minimal_free(json: *mut Json) {
+    if !json.is_null() {
+        drop(Box::from_raw(json));
+    }
+}
+
+/// Releases a string returned by `json_minimal_print` or stored by `json_minimal_parse`. Null
+/// pointers are ignored.
+/// ## Safety
+/// `string` must be null or a string returned by this interface that hasn't been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn json_minimal_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        drop(CString::from_raw(string));
+    }
+}
+
+fn into_c_string(string: String) -> *mut c_char {
+    CString::new(string).map_or(ptr::null_mut(), CString::into_raw)
+}
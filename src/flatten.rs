@@ -0,0 +1,292 @@
+//! Flattening nested objects into a single level and back.
+
+use crate::pointer;
+use crate::Json;
+use std::collections::HashMap;
+
+// How many `Json::NULL`s an index past the end of an array may fill in. An index further away, which
+// would let a short key allocate a huge array, makes the array an object instead.
+const MAX_GAP: usize = 256;
+
+/// The form of the keys produced by `Json::flatten` and understood by `Json::unflatten`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyStyle {
+    /// `a.b[0].c`. Easy to read, but ambiguous if names contain `.`, `[` or `]`.
+    Dotted,
+    /// A JSON Pointer (RFC 6901) like `/a/b/0/c`. Lossless, except that an object whose names are
+    /// `0`, `1`, `2` and so on, in that order, comes back as an array.
+    Pointer,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Token {
+    Name(String),
+    Index(usize),
+}
+
+impl Json {
+    /// Returns a `Json::JSON` holding one member per leaf of `self` (strings, numbers, bools, nulls and
+    /// empty objects or arrays), named after its path in the form given by `style`. Unnamed values in
+    /// objects can't be addressed and are left out. Use `unflatten` to get the nested structure back.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"a\":{\"b\":[{\"c\":1},true]},\"d\":\"x\"}").unwrap();
+    ///
+    /// let flat = json.flatten(KeyStyle::Dotted);
+    ///
+    /// assert_eq!(flat.print(), "{\"a.b[0].c\":1,\"a.b[1]\":true,\"d\":\"x\"}");
+    /// assert_eq!(flat.unflatten(KeyStyle::Dotted), json);
+    ///
+    /// assert_eq!(
+    ///     json.flatten(KeyStyle::Pointer).print(),
+    ///     "{\"/a/b/0/c\":1,\"/a/b/1\":true,\"/d\":\"x\"}"
+    /// );
+    /// ```
+    pub fn flatten(&self, style: KeyStyle) -> Json {
+        let mut flat = Vec::new();
+
+        leaves(self.inner(), &mut Vec::new(), &mut |path, leaf| {
+            flat.push(Json::member(key(path, style), leaf.clone()));
+        });
+
+        Json::JSON(flat)
+    }
+
+    /// Reverses `flatten`: every member of a `Json::JSON` is put at the place its name (in the form
+    /// given by `style`) points to, creating objects and arrays on the way. Whether a value becomes an
+    /// array or an object depends on the tokens of all the names below it: it's an array if they are
+    /// all indices, an object (with the indices as names) as soon as one of them isn't. With
+    /// `KeyStyle::Pointer`, the indices must also first appear counting up from 0, the way `flatten`
+    /// writes them, so `{"/a/1":true}` gives `{"a":{"1":true}}`. With `KeyStyle::Dotted`, gaps in arrays
+    /// are filled with `Json::NULL`. An index more than 256 past the end of an array turns it into an
+    /// object, with members named after the indices of its elements. A member named `""` stands for
+    /// the root itself. Anything but a `Json::JSON` is returned as it is.
+    pub fn unflatten(&self, style: KeyStyle) -> Json {
+        let values = match self.inner() {
+            Json::JSON(values) => values,
+            json => {
+                return json.clone();
+            }
+        };
+
+        let mut entries: Vec<(Vec<Token>, Json)> = Vec::new();
+
+        for (name, value) in values.iter().filter_map(|json| match json {
+            Json::OBJECT { name, value } => Some((name, value)),
+            _ => None,
+        }) {
+            let tokens = match style {
+                KeyStyle::Dotted => dotted_tokens(name),
+                KeyStyle::Pointer => pointer::tokens(name)
+                    .into_iter()
+                    .map(|token| match token.parse::<usize>() {
+                        Ok(n) => Token::Index(n),
+                        Err(_) => Token::Name(token),
+                    })
+                    .collect(),
+            };
+
+            entries.push((tokens, value.unbox().clone()));
+        }
+
+        resolve(&mut entries, style == KeyStyle::Pointer);
+
+        let mut root: Option<Json> = None;
+
+        for (tokens, value) in entries {
+            match tokens.first() {
+                None => {
+                    root = Some(value);
+                }
+                Some(first) => {
+                    let root = root.get_or_insert_with(|| container_for(first));
+
+                    insert(root, &tokens, value);
+                }
+            }
+        }
+
+        root.unwrap_or_else(Json::object)
+    }
+}
+
+// Calls `f` with the path to every leaf of `json` (including empty objects and arrays).
+pub(crate) fn leaves(json: &Json, path: &mut Vec<Token>, f: &mut dyn FnMut(&[Token], &Json)) {
+    match json {
+        Json::JSON(values) if !values.is_empty() => {
+            for value in values {
+                if let Json::OBJECT { name, value } = value {
+                    path.push(Token::Name(name.clone()));
+                    leaves(value, path, f);
+                    path.pop();
+                }
+            }
+        }
+        Json::ARRAY(values) if !values.is_empty() => {
+            for (n, value) in values.iter().enumerate() {
+                path.push(Token::Index(n));
+                leaves(value.inner(), path, f);
+                path.pop();
+            }
+        }
+        leaf => f(path, leaf),
+    }
+}
+
+fn key(path: &[Token], style: KeyStyle) -> String {
+    let mut key = String::new();
+
+    for token in path {
+        match (token, style) {
+            (Token::Name(name), KeyStyle::Dotted) => {
+                if !key.is_empty() {
+                    key.push('.');
+                }
+
+                key.push_str(name);
+            }
+            (Token::Index(n), KeyStyle::Dotted) => {
+                key.push_str(&format!("[{}]", n));
+            }
+            (Token::Name(name), KeyStyle::Pointer) => {
+                key = pointer::push(&key, name);
+            }
+            (Token::Index(n), KeyStyle::Pointer) => {
+                key = format!("{}/{}", key, n);
+            }
+        }
+    }
+
+    key
+}
+
+fn dotted_tokens(key: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+
+    for segment in key.split('.').filter(|segment| !segment.is_empty()) {
+        let (name, mut indices) = match segment.find('[') {
+            Some(n) => segment.split_at(n),
+            None => (segment, ""),
+        };
+
+        if !name.is_empty() {
+            tokens.push(Token::Name(name.to_string()));
+        }
+
+        while let (Some(start), Some(end)) = (indices.find('['), indices.find(']')) {
+            match indices[start + 1..end].parse::<usize>() {
+                Ok(n) => tokens.push(Token::Index(n)),
+                Err(_) => tokens.push(Token::Name(indices[start + 1..end].to_string())),
+            }
+
+            indices = &indices[end + 1..];
+        }
+    }
+
+    tokens
+}
+
+// Settles, for every value the paths of `entries` go through, whether it becomes an array or an object,
+// looking at the tokens of all its children rather than the first one: an array if they are all
+// indices (that first appear counting up from 0 if `dense`), otherwise an object, whose indices are
+// then turned into names.
+pub(crate) fn resolve(entries: &mut [(Vec<Token>, Json)], dense: bool) {
+    // For each path leading to a parent, the number of indices seen below it, or `None` once it's
+    // an object.
+    let mut arrays: HashMap<Vec<Token>, Option<usize>> = HashMap::new();
+
+    for (path, _) in entries.iter() {
+        for (n, token) in path.iter().enumerate() {
+            if !arrays.contains_key(&path[..n]) {
+                arrays.insert(path[..n].to_vec(), Some(0));
+            }
+
+            if let Some(seen) = arrays.get_mut(&path[..n]) {
+                *seen = match (*seen, token) {
+                    (Some(count), Token::Index(index)) if !dense || *index < count => Some(count),
+                    (Some(count), Token::Index(index)) if *index == count => Some(count + 1),
+                    _ => None,
+                };
+            }
+        }
+    }
+
+    for (path, _) in entries.iter_mut() {
+        // Back to front, so the path up to a token is still the one it was counted under.
+        for n in (0..path.len()).rev() {
+            let index = match (&path[n], arrays.get(&path[..n])) {
+                (Token::Index(index), Some(None)) => *index,
+                _ => continue,
+            };
+
+            path[n] = Token::Name(index.to_string());
+        }
+    }
+}
+
+fn container_for(token: &Token) -> Json {
+    match token {
+        Token::Name(_) => Json::object(),
+        Token::Index(_) => Json::array(),
+    }
+}
+
+pub(crate) fn insert(json: &mut Json, tokens: &[Token], value: Json) {
+    let (token, rest) = match tokens.split_first() {
+        Some(split) => split,
+        None => {
+            *json = value;
+
+            return;
+        }
+    };
+
+    let child = match (json, token) {
+        (Json::ARRAY(values), Token::Index(n)) if *n <= values.len() + MAX_GAP => {
+            while values.len() <= *n {
+                values.push(Json::NULL);
+            }
+
+            &mut values[*n]
+        }
+        (json, token) => {
+            let name = match token {
+                Token::Name(name) => name.clone(),
+                Token::Index(n) => n.to_string(),
+            };
+
+            if let Json::ARRAY(values) = json {
+                let members = values
+                    .drain(..)
+                    .enumerate()
+                    .map(|(n, value)| Json::member(n.to_string(), value))
+                    .collect();
+
+                *json = Json::JSON(members);
+            } else if !matches!(json, Json::JSON(_)) {
+                *json = Json::object();
+            }
+
+            if !json.contains_key(&name) {
+                json.add(Json::member(name.clone(), Json::NULL));
+            }
+
+            match json.value_of_mut(&name) {
+                Some(child) => child,
+                None => {
+                    return;
+                }
+            }
+        }
+    };
+
+    if let Some(next) = rest.first() {
+        if !matches!(child, Json::JSON(_) | Json::ARRAY(_)) {
+            *child = container_for(next);
+        }
+    }
+
+    insert(child, rest, value);
+}
@@ -0,0 +1,269 @@
+//! Selecting (and changing) values by paths with wildcards, like `servers.*.port`.
+
+use crate::Json;
+
+// A segment of a pattern.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    // `**`, any number of levels, including none.
+    Levels,
+    // A name or an index, made of these pieces.
+    Pattern(Vec<Piece>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Piece {
+    Char(char),
+    // `?`, any one character.
+    One,
+    // `*`, any characters, including none.
+    Any,
+}
+
+impl Json {
+    /// Returns every value in `self` which `pattern` matches, in document order. A pattern is made of
+    /// names of members and indexes of elements, separated by `.`, as in `servers.0.port` (or in
+    /// brackets, as in `servers[0].port`), in which `*` matches any characters and `?` any one
+    /// character, so that `servers.*.port` is the `port` of every server and `servers.db-*` every
+    /// server whose name starts with `db-`. A segment of just `**` matches any number of levels,
+    /// including none. `\` takes the character after it literally, e.g. in `a\.b`. A
+    /// `Json::OBJECT` on the way is resolved to its value, and the empty pattern matches `self`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(
+    ///     b"{\"servers\":{\"web\":{\"port\":80},\"db-1\":{\"port\":5432},\"db-2\":{\"port\":5433}},\
+    ///       \"proxy\":{\"upstreams\":[{\"port\":8080}]}}",
+    /// )
+    /// .unwrap();
+    ///
+    /// let ports = |pattern| -> Vec<f64> {
+    ///     json.get_glob(pattern).into_iter().filter_map(Json::as_f64).collect()
+    /// };
+    ///
+    /// assert_eq!(ports("servers.*.port"), vec![80.0, 5432.0, 5433.0]);
+    /// assert_eq!(ports("servers.db-?.port"), vec![5432.0, 5433.0]);
+    /// assert_eq!(ports("proxy.upstreams[0].port"), vec![8080.0]);
+    /// assert_eq!(ports("**.port"), vec![80.0, 5432.0, 5433.0, 8080.0]);
+    /// assert!(ports("servers.*.host").is_empty());
+    /// ```
+    pub fn get_glob(&self, pattern: &str) -> Vec<&Json> {
+        let segments = segments(pattern);
+        let mut matches = Vec::new();
+
+        select(self, &segments, &start(&segments), &mut matches);
+
+        matches
+    }
+
+    /// Calls `visit` for every value in `self` which `pattern` matches (see `get_glob`), in
+    /// document order, and returns how many there were, for edits of many values at once. Like
+    /// `walk_mut`, the values inside a value are matched after `visit` returned, i.e. those of the
+    /// changed value.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(
+    ///     b"{\"plugins\":{\"lint\":{\"enabled\":true},\"fmt\":{\"enabled\":true}},\
+    ///       \"hooks\":[{\"enabled\":true}]}",
+    /// )
+    /// .unwrap();
+    ///
+    /// let count = json.select_all_mut("**.enabled", |enabled| *enabled = Json::BOOL(false));
+    ///
+    /// assert_eq!(count, 3);
+    /// assert!(json.get_glob("**.enabled").iter().all(|enabled| enabled.as_bool() == Some(false)));
+    /// ```
+    pub fn select_all_mut<F>(&mut self, pattern: &str, mut visit: F) -> usize
+    where
+        F: FnMut(&mut Json),
+    {
+        let segments = segments(pattern);
+        let mut count = 0;
+
+        select_mut(self, &segments, &start(&segments), &mut visit, &mut count);
+
+        count
+    }
+}
+
+// Collects the values in `json` (including itself) which `segments` match, having got as far into
+// them as `states` says.
+fn select<'a>(json: &'a Json, segments: &[Segment], states: &[usize], matches: &mut Vec<&'a Json>) {
+    let json = json.inner();
+
+    if states.contains(&segments.len()) {
+        matches.push(json);
+    }
+
+    let is_array = matches!(json, Json::ARRAY(_));
+
+    for (n, (name, value)) in json.iter().enumerate() {
+        let states = match (name, is_array) {
+            (Some(name), false) => step(segments, states, name),
+            (None, true) => step(segments, states, &n.to_string()),
+            // Unnamed values in objects can't be matched.
+            _ => continue,
+        };
+
+        if !states.is_empty() {
+            select(value, segments, &states, matches);
+        }
+    }
+}
+
+// Same as `select`, but calls `visit` for the values matched, counting them.
+fn select_mut(
+    json: &mut Json,
+    segments: &[Segment],
+    states: &[usize],
+    visit: &mut dyn FnMut(&mut Json),
+    count: &mut usize,
+) {
+    let json = json.inner_mut();
+
+    if states.contains(&segments.len()) {
+        visit(json);
+        *count += 1;
+    }
+
+    let is_array = matches!(json, Json::ARRAY(_));
+
+    for (n, (name, value)) in json.iter_mut().enumerate() {
+        let states = match (name, is_array) {
+            (Some(name), false) => step(segments, states, name),
+            (None, true) => step(segments, states, &n.to_string()),
+            _ => continue,
+        };
+
+        if !states.is_empty() {
+            select_mut(value, segments, &states, visit, count);
+        }
+    }
+}
+
+// Splits `pattern` into its segments.
+fn segments(pattern: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut pieces = Vec::new();
+    // Whether the segment so far is `**`, unescaped.
+    let mut levels = true;
+    let mut chars = pattern.chars();
+
+    let mut end = |pieces: &mut Vec<Piece>, levels: &mut bool| {
+        if !pieces.is_empty() {
+            let segment = if *levels && pieces == &[Piece::Any, Piece::Any] {
+                Segment::Levels
+            } else {
+                Segment::Pattern(pieces.split_off(0))
+            };
+
+            // Two `**` in a row match what one does.
+            if !(segment == Segment::Levels && segments.last() == Some(&Segment::Levels)) {
+                segments.push(segment);
+            }
+        }
+
+        pieces.clear();
+        *levels = true;
+    };
+
+    while let Some(c) = chars.next() {
+        match c {
+            '.' | '[' | ']' => end(&mut pieces, &mut levels),
+            '*' => pieces.push(Piece::Any),
+            '?' => {
+                pieces.push(Piece::One);
+                levels = false;
+            }
+            '\\' => {
+                pieces.push(Piece::Char(chars.next().unwrap_or('\\')));
+                levels = false;
+            }
+            c => {
+                pieces.push(Piece::Char(c));
+                levels = false;
+            }
+        }
+    }
+
+    end(&mut pieces, &mut levels);
+
+    segments
+}
+
+// How far into `segments` the value at the root has got.
+fn start(segments: &[Segment]) -> Vec<usize> {
+    closure(segments, vec![0])
+}
+
+// How far into `segments` the member or element `name` of a value which got as far as `states` gets.
+fn step(segments: &[Segment], states: &[usize], name: &str) -> Vec<usize> {
+    let mut next = Vec::new();
+
+    for &state in states {
+        match segments.get(state) {
+            Some(Segment::Levels) => next.push(state),
+            Some(Segment::Pattern(pieces)) if wildcard(pieces, name) => next.push(state + 1),
+            _ => {}
+        }
+    }
+
+    closure(segments, next)
+}
+
+// Adds the states after `**`, which may match no level at all.
+fn closure(segments: &[Segment], mut states: Vec<usize>) -> Vec<usize> {
+    let mut n = 0;
+
+    while n < states.len() {
+        if segments.get(states[n]) == Some(&Segment::Levels) {
+            states.push(states[n] + 1);
+        }
+
+        n += 1;
+    }
+
+    states.sort_unstable();
+    states.dedup();
+
+    states
+}
+
+// Whether `pieces` match all of `name`.
+fn wildcard(pieces: &[Piece], name: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    // Where to go back to after a mismatch: just behind the last `*` and where it started matching.
+    let mut retry = None;
+    let (mut p, mut n) = (0, 0);
+
+    while n < name.len() {
+        match pieces.get(p) {
+            Some(Piece::Any) => {
+                retry = Some((p + 1, n));
+                p += 1;
+            }
+            Some(Piece::One) => {
+                p += 1;
+                n += 1;
+            }
+            Some(Piece::Char(c)) if *c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match retry {
+                Some((after, start)) => {
+                    // Let the `*` match one more character.
+                    retry = Some((after, start + 1));
+                    p = after;
+                    n = start + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pieces[p..].iter().all(|piece| *piece == Piece::Any)
+}
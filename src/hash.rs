@@ -0,0 +1,174 @@
+//! Hashes of values which don't depend on how they were written, e.g. for cache keys.
+
+use crate::Json;
+
+// FNV-1a with 128 bits, which is stable across platforms and versions, unlike `DefaultHasher`.
+const OFFSET_BASIS: u128 = 0x6c62_272e_07bb_0142_62b8_2175_6295_c58d;
+const PRIME: u128 = 0x0000_0000_0100_0000_0000_0000_0000_013b;
+
+struct Fnv(u128);
+
+impl Fnv {
+    fn new() -> Fnv {
+        Fnv(OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u128::from(*byte)).wrapping_mul(PRIME);
+        }
+    }
+
+    // Writes the length first, so that two strings next to each other can't be taken for others.
+    fn write_str(&mut self, string: &str) {
+        self.write(&(string.len() as u64).to_le_bytes());
+        self.write(string.as_bytes());
+    }
+}
+
+impl Json {
+    /// A digest of `self` in its canonical form, which is the same for values which only differ in
+    /// the order of the members of objects or in how numbers were written, e.g. `1.0` and `1e0`,
+    /// or `-0` and `0`. Computed without printing `self`, for cache keys or telling whether a
+    /// document changed. It stays the same across platforms and versions of this crate, but isn't
+    /// cryptographic, so it can't tell values apart which were crafted to collide.
+    ///
+    /// Members with the same name keep their order, as with `sort_keys_recursive`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let a = Json::parse(b"{\"b\":[1.0,true],\"a\":{\"d\":null,\"c\":\"x\"}}").unwrap();
+    /// let b = Json::parse(b"{\"a\":{\"c\":\"x\",\"d\":null},\"b\":[1e0,true]}").unwrap();
+    ///
+    /// assert_eq!(a.hash_canonical(), b.hash_canonical());
+    /// assert_ne!(a.hash_canonical(), Json::parse(b"{\"b\":[true,1]}").unwrap().hash_canonical());
+    /// ```
+    pub fn hash_canonical(&self) -> u128 {
+        hash(self)
+    }
+}
+
+/// The digests of a value and of every value inside it, as `Json::hash_tree` returns them, e.g. to
+/// keep along with a large document and later find out quickly what changed in it (see
+/// `Json::diff_hashed`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HashTree {
+    /// The digest of the value, as `Json::hash_canonical` returns it.
+    pub hash: u128,
+    /// The digests of the values directly inside it, in order: the members of an object, the
+    /// elements of an array or the value of a member. Empty for anything else.
+    pub children: Vec<HashTree>,
+}
+
+impl Json {
+    /// The digests of `self` and of every value inside it, computed bottom-up like
+    /// `hash_canonical`, so that the digest of each value is made of those of the values inside
+    /// it. Two values with the same digest can be taken to be the same without looking inside,
+    /// e.g. to skip the branches of two large documents which are the same when comparing them.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let a = Json::parse(b"{\"big\":[1,2,3],\"small\":1}").unwrap();
+    /// let b = Json::parse(b"{\"big\":[1,2,3],\"small\":2}").unwrap();
+    ///
+    /// let (a_tree, b_tree) = (a.hash_tree(), b.hash_tree());
+    ///
+    /// assert_eq!(a_tree.hash, a.hash_canonical());
+    /// assert_ne!(a_tree.hash, b_tree.hash);
+    /// assert_eq!(a_tree.children[0], b_tree.children[0]);
+    /// assert_ne!(a_tree.children[1], b_tree.children[1]);
+    /// ```
+    pub fn hash_tree(&self) -> HashTree {
+        let children: Vec<HashTree> = values(self).iter().map(Json::hash_tree).collect();
+        let digests: Vec<u128> = children.iter().map(|child| child.hash).collect();
+
+        HashTree {
+            hash: digest(self, &digests),
+            children,
+        }
+    }
+}
+
+// The digest of `json`, made of those of the values inside it.
+fn hash(json: &Json) -> u128 {
+    let digests: Vec<u128> = values(json).iter().map(hash).collect();
+
+    digest(json, &digests)
+}
+
+// The values directly inside `json`.
+fn values(json: &Json) -> &[Json] {
+    match json {
+        Json::OBJECT { name: _, value } => std::slice::from_ref(value),
+        Json::JSON(values) | Json::ARRAY(values) => values,
+        _ => &[],
+    }
+}
+
+// The digest of `json`, given those of the values directly inside it, in order.
+fn digest(json: &Json, digests: &[u128]) -> u128 {
+    let mut fnv = Fnv::new();
+
+    match json {
+        Json::NULL => fnv.write(b"n"),
+        Json::BOOL(true) => fnv.write(b"t"),
+        Json::BOOL(false) => fnv.write(b"f"),
+        Json::NUMBER(number) => {
+            // `-0` is `0`, and every NaN the same.
+            let number = if *number == 0.0 {
+                0.0
+            } else if number.is_nan() {
+                f64::NAN
+            } else {
+                *number
+            };
+
+            fnv.write(b"0");
+            fnv.write(&number.to_bits().to_le_bytes());
+        }
+        Json::STRING(string) => {
+            fnv.write(b"\"");
+            fnv.write_str(string);
+        }
+        Json::OBJECT { name, value: _ } => {
+            fnv.write(b":");
+            fnv.write_str(name);
+            fnv.write(&digests[0].to_le_bytes());
+        }
+        Json::ARRAY(_) => {
+            fnv.write(b"[");
+            fnv.write(&(digests.len() as u64).to_le_bytes());
+
+            for digest in digests {
+                fnv.write(&digest.to_le_bytes());
+            }
+        }
+        Json::JSON(values) => {
+            let mut members: Vec<(Option<&str>, u128)> = values
+                .iter()
+                .zip(digests)
+                .map(|(value, digest)| match value {
+                    Json::OBJECT { name, value: _ } => (Some(name.as_str()), *digest),
+                    _ => (None, *digest),
+                })
+                .collect();
+
+            // Named members first, like `sort_keys_recursive` does.
+            members.sort_by(|(a, _), (b, _)| match (a, b) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
+
+            fnv.write(b"{");
+            fnv.write(&(members.len() as u64).to_le_bytes());
+
+            for (_, digest) in members {
+                fnv.write(&digest.to_le_bytes());
+            }
+        }
+    }
+
+    fnv.0
+}
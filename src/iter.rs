@@ -0,0 +1,344 @@
+//! Iterators over the members of a `Json::JSON`, the elements of a `Json::ARRAY` and the leaves of a `Json`.
+
+use crate::pointer;
+use crate::Json;
+use std::slice;
+
+/// An iterator over the members of a `Json::JSON` or the elements of a `Json::ARRAY`. See `Json::iter()`.
+#[derive(Debug)]
+pub struct Iter<'a> {
+    members: slice::Iter<'a, Json>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (Option<&'a str>, &'a Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.members.next().map(|json| match json {
+            Json::OBJECT { name, value } => (Some(name.as_str()), value.unbox()),
+            json => (None, json),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.members.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for Iter<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.members.next_back().map(|json| match json {
+            Json::OBJECT { name, value } => (Some(name.as_str()), value.unbox()),
+            json => (None, json),
+        })
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+/// Same as `Iter` above, but the references to the values are mutable. See `Json::iter_mut()`.
+#[derive(Debug)]
+pub struct IterMut<'a> {
+    members: slice::IterMut<'a, Json>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (Option<&'a str>, &'a mut Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.members.next().map(|json| match json {
+            Json::OBJECT { name, value } => (Some(name.as_str()), value.unbox_mut()),
+            json => (None, json),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.members.size_hint()
+    }
+}
+
+impl<'a> DoubleEndedIterator for IterMut<'a> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.members.next_back().map(|json| match json {
+            Json::OBJECT { name, value } => (Some(name.as_str()), value.unbox_mut()),
+            json => (None, json),
+        })
+    }
+}
+
+impl<'a> ExactSizeIterator for IterMut<'a> {}
+
+/// An iterator moving the members out of a `Json::JSON` or the elements out of a `Json::ARRAY`.
+/// Created by the `into_iter` method on `Json` (provided by the `IntoIterator` trait).
+#[derive(Debug)]
+pub struct IntoIter {
+    members: std::vec::IntoIter<Json>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (Option<String>, Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.members.next().map(|json| match json {
+            Json::OBJECT { name, value } => (Some(name), *value),
+            json => (None, json),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.members.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for IntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.members.next_back().map(|json| match json {
+            Json::OBJECT { name, value } => (Some(name), *value),
+            json => (None, json),
+        })
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+/// Consumes a `Json::JSON` or `Json::ARRAY` (or a `Json::OBJECT` holding either of the two), yielding
+/// the owned members as `(Some(name), value)` and array elements as `(None, value)`. Every other variant
+/// yields nothing.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let json = Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Answer\":42}").unwrap();
+///
+/// let names: Vec<String> = json.into_iter().filter_map(|(name, _)| name).collect();
+///
+/// assert_eq!(names, vec!["Greeting", "Answer"]);
+/// ```
+impl IntoIterator for Json {
+    type Item = (Option<String>, Json);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        let members = match self {
+            Json::OBJECT { name: _, value } => match *value {
+                Json::JSON(values) | Json::ARRAY(values) => values,
+                _ => Vec::new(),
+            },
+            Json::JSON(values) | Json::ARRAY(values) => values,
+            _ => Vec::new(),
+        };
+
+        IntoIter {
+            members: members.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Json {
+    type Item = (Option<&'a str>, &'a Json);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Json {
+    type Item = (Option<&'a str>, &'a mut Json);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
+
+/// An iterator over the leaves of a `Json` and the JSON Pointers to them. See `Json::paths()`.
+#[derive(Debug)]
+pub struct Paths<'a> {
+    stack: Vec<(String, &'a Json)>,
+}
+
+impl<'a> Iterator for Paths<'a> {
+    type Item = (String, &'a Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((path, json)) = self.stack.pop() {
+            match json {
+                Json::JSON(values) if !values.is_empty() => {
+                    for value in values.iter().rev() {
+                        if let Json::OBJECT { name, value } = value {
+                            self.stack.push((pointer::push(&path, name), value));
+                        }
+                    }
+                }
+                Json::ARRAY(values) if !values.is_empty() => {
+                    for (n, value) in values.iter().enumerate().rev() {
+                        self.stack.push((format!("{}/{}", path, n), value.inner()));
+                    }
+                }
+                leaf => {
+                    return Some((path, leaf));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl Json {
+    /// Returns an iterator over the members of a `Json::JSON` or the elements of a `Json::ARRAY` (or a
+    /// `Json::OBJECT` holding either of the two). Members are yielded as `(Some(name), value)`, array elements
+    /// and unnamed values as `(None, value)`, so the same loop works for both. Yields nothing for every other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Days\":[\"Monday\",\"Tuesday\"]}").unwrap();
+    ///
+    /// for (name, value) in json.iter() {
+    ///     match name {
+    ///         Some("Greeting") => assert_eq!(value, &Json::STRING(String::from("Hello, world!"))),
+    ///         Some("Days") => assert_eq!(value.iter().count(), 2),
+    ///         name => panic!("Unexpected member {:?}!!!", name),
+    ///     }
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            members: self.members().iter(),
+        }
+    }
+
+    /// Same as `iter` above, but the references to the values are mutable.
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            members: self.members_mut().iter_mut(),
+        }
+    }
+
+    /// Returns an iterator over every leaf of `self` (strings, numbers, bools, nulls and empty objects or
+    /// arrays) together with the JSON Pointer (RFC 6901) leading to it, in document order. Unnamed values
+    /// in objects can't be addressed and are left out.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"a\":{\"b\":[1,true]},\"c/d\":null}").unwrap();
+    ///
+    /// let paths: Vec<String> = json.paths().map(|(path, _)| path).collect();
+    ///
+    /// assert_eq!(paths, vec!["/a/b/0", "/a/b/1", "/c~1d"]);
+    /// ```
+    pub fn paths(&self) -> Paths<'_> {
+        Paths {
+            stack: vec![(String::new(), self.inner())],
+        }
+    }
+
+    /// Returns an iterator over the names of the members of a `Json::JSON` (or a `Json::OBJECT` holding one),
+    /// in the order in which they appear. Yields nothing for every other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Answer\":42}").unwrap();
+    ///
+    /// assert_eq!(json.keys().collect::<Vec<&str>>(), vec!["Greeting", "Answer"]);
+    /// ```
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.object_members().iter().filter_map(|json| match json {
+            Json::OBJECT { name, value: _ } => Some(name.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the values of the members of a `Json::JSON` (or a `Json::OBJECT` holding one),
+    /// i.e. the `value` of every `Json::OBJECT` it holds. Yields nothing for every other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Answer\":42}").unwrap();
+    ///
+    /// assert_eq!(json.values().nth(1), Some(&Json::NUMBER(42.0)));
+    /// ```
+    pub fn values(&self) -> impl Iterator<Item = &Json> {
+        self.object_members().iter().filter_map(|json| match json {
+            Json::OBJECT { name: _, value } => Some(value.unbox()),
+            _ => None,
+        })
+    }
+
+    /// Same as `values` above, but the references are mutable.
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Json> {
+        self.object_members_mut()
+            .iter_mut()
+            .filter_map(|json| match json {
+                Json::OBJECT { name: _, value } => Some(value.unbox_mut()),
+                _ => None,
+            })
+    }
+
+    /// Returns the name and the value of the member at `index` of a `Json::JSON` (or a `Json::OBJECT`
+    /// holding one), counting members as `keys` yields them, for documents in which their order
+    /// matters. Returns `None` if there are fewer members and for every other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"name\":\"app\",\"version\":2,\"debug\":true}").unwrap();
+    ///
+    /// assert_eq!(json.get_index(1), Some(("version", &Json::NUMBER(2.0))));
+    /// assert_eq!(json.get_index(3), None);
+    /// assert_eq!(json.first(), Some(("name", &Json::STRING(String::from("app")))));
+    /// assert_eq!(json.last(), Some(("debug", &Json::BOOL(true))));
+    /// ```
+    pub fn get_index(&self, index: usize) -> Option<(&str, &Json)> {
+        self.named_members().nth(index)
+    }
+
+    /// Same as `get_index` above, but the reference to the value is mutable.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&str, &mut Json)> {
+        self.object_members_mut()
+            .iter_mut()
+            .filter_map(|json| match json {
+                Json::OBJECT { name, value } => Some((name.as_str(), value.unbox_mut())),
+                _ => None,
+            })
+            .nth(index)
+    }
+
+    /// Returns the first member of a `Json::JSON` (or a `Json::OBJECT` holding one), see
+    /// `get_index`.
+    pub fn first(&self) -> Option<(&str, &Json)> {
+        self.named_members().next()
+    }
+
+    /// Returns the last member of a `Json::JSON` (or a `Json::OBJECT` holding one), see
+    /// `get_index`.
+    pub fn last(&self) -> Option<(&str, &Json)> {
+        self.named_members().next_back()
+    }
+
+    fn named_members(&self) -> impl DoubleEndedIterator<Item = (&str, &Json)> {
+        self.object_members().iter().filter_map(|json| match json {
+            Json::OBJECT { name, value } => Some((name.as_str(), value.unbox())),
+            _ => None,
+        })
+    }
+
+    fn object_members(&self) -> &[Json] {
+        match self.inner() {
+            Json::JSON(_) => self.members(),
+            _ => &[],
+        }
+    }
+
+    fn object_members_mut(&mut self) -> &mut [Json] {
+        match self.inner() {
+            Json::JSON(_) => self.members_mut(),
+            _ => &mut [],
+        }
+    }
+}
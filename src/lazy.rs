@@ -0,0 +1,237 @@
+//! Deferred parsing, where values are only located up front and parsed when they are needed.
+
+use crate::pointer::unescape;
+use crate::stream::{scan_string, scan_value};
+use crate::{is_whitespace, ErrorKind, Json, ParseError};
+use std::cell::OnceCell;
+
+/// A value of which only the boundaries are known. Members and elements are located when first
+/// looked up, values are parsed when first asked for. Both are cached. See `Json::parse_lazy`.
+#[derive(Debug)]
+pub struct LazyJson<'a> {
+    // The whole input, so that errors can give the line.
+    input: &'a [u8],
+    start: usize,
+    end: usize,
+    children: OnceCell<Vec<(Option<String>, LazyJson<'a>)>>,
+    json: OnceCell<Result<Json, ParseError>>,
+}
+
+impl<'a> LazyJson<'a> {
+    fn new(input: &'a [u8], start: usize, end: usize) -> LazyJson<'a> {
+        LazyJson {
+            input,
+            start,
+            end,
+            children: OnceCell::new(),
+            json: OnceCell::new(),
+        }
+    }
+
+    /// The text of this value.
+    pub fn raw(&self) -> &'a [u8] {
+        &self.input[self.start..self.end]
+    }
+
+    /// Returns the first member named `search` of an object (or of the object held by a standalone
+    /// object), without parsing any of the members. Every other value has no members. Looking up a
+    /// member in a malformed object only finds the ones in front of the error, which `value` reports.
+    pub fn get(&self, search: &str) -> Option<&LazyJson<'a>> {
+        self.children()
+            .iter()
+            .find(|(name, _)| name.as_deref() == Some(search))
+            .map(|(_, value)| value)
+    }
+
+    /// Returns element `index` of an array (or of the array held by a standalone object), see `get`.
+    pub fn index(&self, index: usize) -> Option<&LazyJson<'a>> {
+        match self.container()? {
+            (start, _) if self.input[start] == b'[' => {
+                self.children().get(index).map(|(_, value)| value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses this value, on the first call. Positions in the error refer to the whole input.
+    pub fn value(&self) -> Result<&Json, ParseError> {
+        self.json
+            .get_or_init(|| self.parse())
+            .as_ref()
+            .map_err(Clone::clone)
+    }
+
+    fn parse(&self) -> Result<Json, ParseError> {
+        let mut incr = self.start;
+
+        Json::parse_value(&self.input[..self.end], &mut incr)
+            .map_err(|(position, message)| ParseError::at(self.input, position, message))
+    }
+
+    // The bounds of the object or array this is (or which this standalone object holds).
+    fn container(&self) -> Option<(usize, usize)> {
+        let (mut start, end) = (self.start, self.end);
+
+        while self.input[start] == b'\"' {
+            let name_end = scan_string(&self.input[..end], start)?;
+
+            if name_end == end || self.input[name_end] != b':' {
+                return None;
+            }
+
+            start = skip_whitespace(self.input, name_end + 1, end)?;
+        }
+
+        match self.input[start] {
+            b'{' | b'[' => Some((start, end)),
+            _ => None,
+        }
+    }
+
+    fn children(&self) -> &[(Option<String>, LazyJson<'a>)] {
+        self.children.get_or_init(|| match self.container() {
+            Some((start, end)) => scan_children(self.input, start, end),
+            None => Vec::new(),
+        })
+    }
+}
+
+// Locates the members of the object or the elements of the array at `input[start..end]`, stopping at
+// anything unexpected.
+fn scan_children<'a>(
+    input: &'a [u8],
+    start: usize,
+    end: usize,
+) -> Vec<(Option<String>, LazyJson<'a>)> {
+    let object = input[start] == b'{';
+    let body = &input[..end - 1];
+    let mut children = Vec::new();
+    let mut n = start + 1;
+
+    while let Some(value_start) =
+        (n..body.len()).find(|n| !is_whitespace(body[*n]) && body[*n] != b',')
+    {
+        let name_end = match body[value_start] {
+            b'\"' if object => scan_string(body, value_start),
+            _ => None,
+        };
+
+        let (name, value_start) = match name_end {
+            Some(name_end) if body.get(name_end) == Some(&b':') => {
+                let mut incr = value_start;
+
+                let name = match Json::parse_string(body, &mut incr) {
+                    Ok(name) => name,
+                    Err(_) => break,
+                };
+
+                match skip_whitespace(body, name_end + 1, body.len()) {
+                    Some(value_start) => (Some(name), value_start),
+                    None => break,
+                }
+            }
+            _ => (None, value_start),
+        };
+
+        let value_end = scan_value(body, value_start, true);
+
+        match value_end {
+            Some(value_end) if value_end > value_start => {
+                children.push((name, LazyJson::new(input, value_start, value_end)));
+                n = value_end;
+            }
+            _ => break,
+        }
+    }
+
+    children
+}
+
+fn skip_whitespace(input: &[u8], start: usize, end: usize) -> Option<usize> {
+    (start..end).find(|n| !is_whitespace(input[*n]))
+}
+
+impl Json {
+    /// Locates the value in `input` without parsing it, so that only the parts actually needed are
+    /// parsed later on (see `LazyJson`). This pays off when a few members are read from a large
+    /// document. Only the boundaries of the value are checked up front.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"id\":7,\"items\":[{\"name\":\"a\"},{\"name\":\"b\"}],\"huge\":[[1,2],[3]]}";
+    ///
+    /// let lazy = Json::parse_lazy(input).unwrap();
+    ///
+    /// assert_eq!(lazy.get("id").unwrap().value().unwrap(), &Json::NUMBER(7.0));
+    /// assert_eq!(
+    ///     lazy.get("items").unwrap().index(1).unwrap().get("name").unwrap().value().unwrap(),
+    ///     &Json::string("b")
+    /// );
+    /// assert_eq!(lazy.get("huge").unwrap().raw(), b"[[1,2],[3]]");
+    /// assert!(lazy.get("missing").is_none());
+    /// ```
+    /// ## Errors
+    /// Fails if `input` doesn't hold a value or holds anything but whitespace after it.
+    pub fn parse_lazy(input: &[u8]) -> Result<LazyJson<'_>, ParseError> {
+        let start = skip_whitespace(input, 0, input.len())
+            .ok_or_else(|| ParseError::at(input, input.len(), ErrorKind::UnexpectedEof))?;
+
+        let end = scan_value(input, start, true)
+            .ok_or_else(|| ParseError::at(input, input.len(), ErrorKind::UnexpectedEof))?;
+
+        match skip_whitespace(input, end, input.len()) {
+            Some(n) => Err(ParseError::at(input, n, ErrorKind::TrailingData)),
+            None => Ok(LazyJson::new(input, start, end)),
+        }
+    }
+    /// Parses only the value `pointer` refers to (see `pointer`), skipping over everything else in
+    /// `input`, e.g. to read a single member of a huge document. Apart from the value, only the
+    /// boundaries of what is on the way to it are checked.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"meta\":{\"count\":2},\"data\":{\"items\":[{\"id\":1},{\"id\":2}]}}";
+    ///
+    /// assert_eq!(
+    ///     Json::parse_pointer(input, "/data/items/1").unwrap(),
+    ///     Some(Json::parse(b"{\"id\":2}").unwrap())
+    /// );
+    /// assert_eq!(Json::parse_pointer(input, "/data/items/2").unwrap(), None);
+    /// ```
+    /// ## Errors
+    /// Fails like `parse_lazy`, or if the value can't be parsed.
+    pub fn parse_pointer(input: &[u8], pointer: &str) -> Result<Option<Json>, ParseError> {
+        let lazy = Json::parse_lazy(input)?;
+
+        if pointer.is_empty() {
+            return lazy.parse().map(Some);
+        }
+
+        if !pointer.starts_with('/') {
+            return Ok(None);
+        }
+
+        let mut value = &lazy;
+
+        for token in pointer[1..].split('/') {
+            let token = unescape(token);
+
+            let found = value.get(&token).or_else(|| {
+                // Indexes are plain decimal numbers without leading zeros.
+                let index = token.bytes().all(|c| c.is_ascii_digit())
+                    && (token == "0" || !token.starts_with('0'));
+
+                value.index(token.parse().ok().filter(|_| index)?)
+            });
+
+            value = match found {
+                Some(value) => value,
+                None => return Ok(None),
+            };
+        }
+
+        value.parse().map(Some)
+    }
+}
@@ -0,0 +1,276 @@
+//! A pull-based tokenizer, yielding the structure of the input as events instead of building a tree.
+
+use crate::stream::scan_string;
+use crate::{is_whitespace, ErrorKind, Json, ParseError};
+use std::ops::Range;
+
+/// An event of `JsonLexer`. Containers are bracketed by a start and an end event, a member of an
+/// object is a `Key` followed by the events of its value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    ObjectStart,
+    ObjectEnd,
+    ArrayStart,
+    ArrayEnd,
+    /// The name of the member whose value comes next.
+    Key(String),
+    String(String),
+    Number(f64),
+    Bool(bool),
+    Null,
+}
+
+/// An iterator over the events of a value, which lets custom data structures be built or the input
+/// be filtered without a `Json` tree. It accepts what `Json::parse` accepts, including a standalone
+/// object (`"name":value`) at the top, and stops after the first error.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let input = b"{\"name\":\"n\",\"tags\":[\"a\",\"b\"],\"ok\":true}";
+///
+/// let mut lexer = JsonLexer::new(input);
+///
+/// assert_eq!(lexer.next().unwrap().unwrap(), JsonEvent::ObjectStart);
+/// assert_eq!(lexer.next().unwrap().unwrap(), JsonEvent::Key(String::from("name")));
+/// assert_eq!(lexer.next().unwrap().unwrap(), JsonEvent::String(String::from("n")));
+///
+/// // Collect every string inside the `tags` array.
+/// let mut tags = Vec::new();
+///
+/// while let Some(event) = lexer.next() {
+///     match event.unwrap() {
+///         JsonEvent::String(tag) if lexer.depth() == 2 => tags.push(tag),
+///         _ => {}
+///     }
+/// }
+///
+/// assert_eq!(tags, vec!["a", "b"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonLexer<'a> {
+    input: &'a [u8],
+    index: usize,
+    // The containers entered, as their opening bracket.
+    stack: Vec<u8>,
+    span: Range<usize>,
+    // Set if comments are allowed, holding those skipped.
+    comments: Option<Vec<Range<usize>>>,
+    // Set after a `Key`, until its value has begun.
+    key: bool,
+    // Set once the top value is complete, after which only whitespace may follow.
+    complete: bool,
+    // Set after the first error.
+    done: bool,
+}
+
+impl<'a> JsonLexer<'a> {
+    pub fn new(input: &'a [u8]) -> JsonLexer<'a> {
+        JsonLexer {
+            input,
+            index: 0,
+            stack: Vec::new(),
+            span: 0..0,
+            comments: None,
+            key: false,
+            complete: false,
+            done: false,
+        }
+    }
+
+    /// The number of objects and arrays entered but not yet left.
+    pub fn depth(&self) -> usize {
+        self.stack.len()
+    }
+
+    /// The bytes of the input the last event was read from, e.g. for syntax highlighting. That of a
+    /// `Key` is the quoted name, without the `:`. The span is empty before the first event.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"id\": 36, \"tags\":[true]}";
+    ///
+    /// let mut lexer = JsonLexer::new(input);
+    /// let mut spans = Vec::new();
+    ///
+    /// while let Some(event) = lexer.next() {
+    ///     event.unwrap();
+    ///     spans.push(&input[lexer.span()]);
+    /// }
+    ///
+    /// assert_eq!(spans, vec![&b"{"[..], b"\"id\"", b"36", b"\"tags\"", b"[", b"true", b"]", b"}"]);
+    /// ```
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Allows comments wherever whitespace is allowed, both `// ...` up to the end of the line and
+    /// `/* ... */`, as in JSONC. They are skipped, see `comments`. A number, `true`, `false` or `null`
+    /// needs whitespace, `,`, `]` or `}` after it before a comment.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"[1, // one\n/* two */ 2]";
+    ///
+    /// let mut lexer = JsonLexer::new(input).allow_comments();
+    ///
+    /// assert_eq!(lexer.by_ref().filter_map(Result::ok).count(), 4);
+    /// assert_eq!(&input[lexer.comments()[1].clone()], b"/* two */");
+    /// ```
+    pub fn allow_comments(mut self) -> JsonLexer<'a> {
+        self.comments = Some(Vec::new());
+        self
+    }
+
+    /// The comments skipped so far (see `allow_comments`).
+    pub fn comments(&self) -> &[Range<usize>] {
+        self.comments.as_deref().unwrap_or(&[])
+    }
+
+    // Skips whitespace and comments, as well as commas inside a container as the parser does.
+    fn skip(&mut self) -> Result<(), ParseError> {
+        while self.index < self.input.len() {
+            match self.input[self.index] {
+                c if is_whitespace(c) => self.index += 1,
+                b',' if !self.stack.is_empty() => self.index += 1,
+                b'/' if self.comments.is_some() => {
+                    let start = self.index;
+
+                    let end = match self.input.get(start + 1) {
+                        Some(b'/') => (start..self.input.len())
+                            .find(|n| self.input[*n] == b'\n')
+                            .unwrap_or(self.input.len()),
+                        Some(b'*') => match (start + 2..self.input.len())
+                            .find(|n| self.input[*n..].starts_with(b"*/"))
+                        {
+                            Some(n) => n + 2,
+                            None => return Err(self.error(start, ErrorKind::UnterminatedComment)),
+                        },
+                        _ => return Ok(()),
+                    };
+
+                    if let Some(comments) = &mut self.comments {
+                        comments.push(start..end);
+                    }
+
+                    self.index = end;
+                }
+                _ => return Ok(()),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn error(&mut self, index: usize, message: ErrorKind) -> ParseError {
+        self.done = true;
+
+        ParseError::at(self.input, index, message)
+    }
+
+    fn close(&mut self, key: bool, open: u8, event: JsonEvent) -> Result<JsonEvent, ParseError> {
+        if key || self.stack.last() != Some(&open) {
+            return Err(self.error(self.index, ErrorKind::UnexpectedClose));
+        }
+
+        self.stack.pop();
+        self.index += 1;
+
+        Ok(event)
+    }
+
+    fn string(&mut self) -> Result<JsonEvent, ParseError> {
+        let end = match scan_string(self.input, self.index) {
+            Some(end) => end,
+            None => return Err(self.error(self.input.len(), ErrorKind::UnexpectedEof)),
+        };
+
+        let string = match Json::parse_string(self.input, &mut self.index) {
+            Ok(string) => string,
+            Err((position, message)) => return Err(self.error(position, message)),
+        };
+
+        if self.input.get(end) == Some(&b':') {
+            self.index = end + 1;
+            self.key = true;
+
+            return Ok(JsonEvent::Key(string));
+        }
+
+        Ok(JsonEvent::String(string))
+    }
+
+    fn scalar(&mut self) -> Result<JsonEvent, ParseError> {
+        match Json::parse_value(self.input, &mut self.index) {
+            Ok(Json::NUMBER(number)) => Ok(JsonEvent::Number(number)),
+            Ok(Json::BOOL(bool)) => Ok(JsonEvent::Bool(bool)),
+            Ok(Json::NULL) => Ok(JsonEvent::Null),
+            Ok(_) => unreachable!(),
+            Err((position, message)) => Err(self.error(position, message)),
+        }
+    }
+}
+
+impl<'a> Iterator for JsonLexer<'a> {
+    type Item = Result<JsonEvent, ParseError>;
+
+    fn next(&mut self) -> Option<Result<JsonEvent, ParseError>> {
+        if self.done {
+            return None;
+        }
+
+        if let Err(error) = self.skip() {
+            return Some(Err(error));
+        }
+
+        if self.complete {
+            self.done = true;
+
+            if self.index < self.input.len() {
+                return Some(Err(ParseError::at(
+                    self.input,
+                    self.index,
+                    ErrorKind::TrailingData,
+                )));
+            }
+
+            return None;
+        }
+
+        if self.index >= self.input.len() {
+            return Some(Err(self.error(self.index, ErrorKind::UnexpectedEof)));
+        }
+
+        let key = self.key;
+        let start = self.index;
+        self.key = false;
+
+        let event = match self.input[self.index] {
+            b'{' => {
+                self.stack.push(b'{');
+                self.index += 1;
+
+                Ok(JsonEvent::ObjectStart)
+            }
+            b'[' => {
+                self.stack.push(b'[');
+                self.index += 1;
+
+                Ok(JsonEvent::ArrayStart)
+            }
+            b'}' => self.close(key, b'{', JsonEvent::ObjectEnd),
+            b']' => self.close(key, b'[', JsonEvent::ArrayEnd),
+            b'\"' => self.string(),
+            b't' | b'f' | b'n' | b'0'..=b'9' => self.scalar(),
+            _ => Err(self.error(self.index, ErrorKind::InvalidValue)),
+        };
+
+        // The `:` behind a name was skipped already.
+        self.span = start..self.index - self.key as usize;
+        self.complete = event.is_ok() && self.stack.is_empty() && !self.key;
+
+        Some(event)
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Json {
     OBJECT { name: String, value: Box<Json> },
     JSON(Vec<Json>),
@@ -9,6 +9,12 @@ pub enum Json {
     NULL,
 }
 
+impl Default for Json {
+    fn default() -> Json {
+        Json::new()
+    }
+}
+
 impl Json {
     /// Construct a new `Json::JSON`
     /// ## Example
@@ -21,11 +27,66 @@ impl Json {
         Json::JSON(Vec::new())
     }
 
+    /// Construct an empty `Json::JSON`. Same as `new` above, but reads better next to `array`.
+    pub fn object() -> Json {
+        Json::JSON(Vec::new())
+    }
+
+    /// Construct an empty `Json::ARRAY`.
+    pub fn array() -> Json {
+        Json::ARRAY(Vec::new())
+    }
+
+    /// Construct a `Json::STRING`.
+    pub fn string<S: Into<String>>(value: S) -> Json {
+        Json::STRING(value.into())
+    }
+
+    /// Construct a `Json::NUMBER`.
+    pub fn number(value: f64) -> Json {
+        Json::NUMBER(value)
+    }
+
+    /// Construct a `Json::OBJECT`, i.e. a member named `name` holding `value`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::object();
+    ///
+    /// json
+    ///     .add(Json::member("Greeting", Json::string("Hello, world!")))
+    ///     .add(Json::member("Days", Json::array()));
+    ///
+    /// assert_eq!(
+    ///     json,
+    ///     Json::JSON(vec![
+    ///         Json::OBJECT {
+    ///             name: String::from("Greeting"),
+    ///
+    ///             value: Box::new(Json::STRING(String::from("Hello, world!")))
+    ///         },
+    ///         Json::OBJECT {
+    ///             name: String::from("Days"),
+    ///
+    ///             value: Box::new(Json::ARRAY(Vec::new()))
+    ///         }
+    ///     ])
+    /// );
+    /// ```
+    pub fn member<S: Into<String>>(name: S, value: Json) -> Json {
+        Json::OBJECT {
+            name: name.into(),
+
+            value: Box::new(value),
+        }
+    }
+
     /// Add any `Json` variant to a `Json` variant of type `Json::JSON`, `Json::ARRAY`
     /// or a `Json::OBJECT` (holding a `Json::JSON`,`Json::ARRAY`,`Json::OBJECT` (holding a `Json::JSON`,`Json::`...)).
     /// ## Panics!
     /// Will panic if the conditions stated above are not met OR if an attempt is made to add a `Json::JSON` to a `Json::JSON`
-    /// without wrapping it in a `Json::OBJECT` first.
+    /// without wrapping it in a `Json::OBJECT` first. Use `try_add` (see below) if this is not acceptable.
     /// ## Example
     /// ```
     ///     use json_minimal::*;
@@ -46,119 +107,64 @@ impl Json {
     /// ```
     /// See the <a href="https://github.com/36den/json_minimal-rs/">tutorial</a> on github for more.
     pub fn add(&mut self, value: Json) -> &mut Json {
-        match self {
-            Json::JSON(values) => match value {
-                Json::OBJECT { name, value } => {
-                    values.push(Json::OBJECT { name, value });
-                }
-                Json::JSON(_) => {
+        if let Err(error) = self.try_add(value) {
+            match error {
+                AddError::UnnamedJson(_) => {
                     panic!("A `Json::JSON` may not be added to a `Json::JSON` if it is not within a `Json::OBJECT`.");
                 }
-                Json::ARRAY(vals) => {
-                    values.push(Json::ARRAY(vals));
-                }
-                Json::STRING(val) => {
-                    values.push(Json::STRING(val));
-                }
-                Json::NUMBER(val) => {
-                    values.push(Json::NUMBER(val));
-                }
-                Json::BOOL(val) => {
-                    values.push(Json::BOOL(val));
-                }
-                Json::NULL => {
-                    values.push(Json::NULL);
-                }
-            },
-            Json::OBJECT {
-                name: _,
-                value: obj_val,
-            } => match obj_val.unbox_mut() {
-                Json::JSON(values) => match value {
-                    Json::OBJECT { name, value } => {
-                        values.push(Json::OBJECT { name, value });
-                    }
-                    Json::JSON(_) => {
-                        panic!("A `Json::JSON` may not be added to a `Json::JSON` if it is not within a `Json::OBJECT`.");
-                    }
-                    Json::ARRAY(vals) => {
-                        values.push(Json::ARRAY(vals));
-                    }
-                    Json::STRING(val) => {
-                        values.push(Json::STRING(val));
-                    }
-                    Json::NUMBER(val) => {
-                        values.push(Json::NUMBER(val));
-                    }
-                    Json::BOOL(val) => {
-                        values.push(Json::BOOL(val));
-                    }
-                    Json::NULL => {
-                        values.push(Json::NULL);
-                    }
-                },
-                Json::ARRAY(values) => match value {
-                    Json::OBJECT { name, value } => {
-                        values.push(Json::OBJECT { name, value });
-                    }
-                    Json::JSON(vals) => {
-                        values.push(Json::JSON(vals));
-                    }
-                    Json::ARRAY(vals) => {
-                        values.push(Json::ARRAY(vals));
-                    }
-                    Json::STRING(val) => {
-                        values.push(Json::STRING(val));
-                    }
-                    Json::NUMBER(val) => {
-                        values.push(Json::NUMBER(val));
-                    }
-                    Json::BOOL(val) => {
-                        values.push(Json::BOOL(val));
-                    }
-                    Json::NULL => {
-                        values.push(Json::NULL);
-                    }
-                },
-                json => {
-                    panic!("The function `add(`&mut self`,`name: String`,`value: Json`)` may only be called on a `Json::JSON`, `Json::ARRAY` or `Json::OBJECT` holding a `Json::JSON` or `Json::ARRAY`. It was called on: {:?}",json);
-                }
-            },
-            Json::ARRAY(values) => match value {
-                Json::OBJECT { name, value } => {
-                    values.push(Json::OBJECT { name, value });
+                AddError::NotAContainer(_) => {
+                    panic!("The function `add(`&mut self`,`name: String`,`value: Json`)` may only be called on a `Json::JSON`, `Json::ARRAY` or `Json::OBJECT` holding a `Json::JSON` or `Json::ARRAY`. It was called on: {:?}",self);
                 }
+            }
+        }
+
+        self
+    }
+
+    /// Same as `add` above, but returns an `AddError` (which gives the rejected value back) instead of panicking.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::new();
+    ///
+    /// assert!(json.try_add(Json::NUMBER(42.0)).is_ok());
+    ///
+    /// match Json::NULL.try_add(Json::NUMBER(42.0)) {
+    ///     Err(AddError::NotAContainer(value)) => {
+    ///         assert_eq!(value, Json::NUMBER(42.0));
+    ///     },
+    ///     result => {
+    ///         panic!("Expected AddError::NotAContainer but found {:?}!!!",result);
+    ///     }
+    /// }
+    /// ```
+    pub fn try_add(&mut self, value: Json) -> Result<&mut Json, AddError> {
+        match self.inner_mut() {
+            Json::JSON(values) => match value {
                 Json::JSON(vals) => {
-                    values.push(Json::JSON(vals));
-                }
-                Json::ARRAY(vals) => {
-                    values.push(Json::ARRAY(vals));
-                }
-                Json::STRING(val) => {
-                    values.push(Json::STRING(val));
-                }
-                Json::NUMBER(val) => {
-                    values.push(Json::NUMBER(val));
-                }
-                Json::BOOL(val) => {
-                    values.push(Json::BOOL(val));
+                    return Err(AddError::UnnamedJson(Json::JSON(vals)));
                 }
-                Json::NULL => {
-                    values.push(Json::NULL);
+                value => {
+                    values.push(value);
                 }
             },
-            json => {
-                panic!("The function `add(`&mut self`,`name: String`,`value: Json`)` may only be called on a `Json::JSON`, `Json::ARRAY` or `Json::OBJECT` holding a `Json::JSON` or `Json::ARRAY`. It was called on: {:?}",json);
+            Json::ARRAY(values) => {
+                values.push(value);
+            }
+            _ => {
+                return Err(AddError::NotAContainer(value));
             }
         }
 
-        self
+        Ok(self)
     }
 
     /// Get the `Json` with the requested name if it exists.
     /// ## Panics
     /// This function will panic if called on a `Json` variant other than `Json::JSON` or `Json::OBJECT`,
-    /// as only these two variants may hold `Json::OBJECT` (which has a `name` field).
+    /// as only these two variants may hold `Json::OBJECT` (which has a `name` field). Use `try_get` (see below)
+    /// if this is not acceptable.
     /// ## Example
     /// ```
     /// use json_minimal::*;
@@ -201,87 +207,213 @@ impl Json {
     /// }
     /// ```
     pub fn get(&self, search: &str) -> Option<&Json> {
-        match self {
-            Json::JSON(values) => {
-                for n in 0..values.len() {
-                    match &values[n] {
-                        Json::OBJECT { name, value: _ } => {
-                            if name == search {
-                                return Some(&values[n]);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-
-                return None;
-            }
-            Json::OBJECT { name: _, value } => match value.unbox() {
-                Json::JSON(values) => {
-                    for n in 0..values.len() {
-                        match &values[n] {
-                            Json::OBJECT { name, value: _ } => {
-                                if name == search {
-                                    return Some(&values[n]);
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    return None;
-                }
-                json => {
-                    panic!("The function `get(`&self`,`search: &str`)` may only be called on a `Json::JSON` or a `Json::OBJECT` holding a `Json::JSON`. I was called on: {:?}",json);
-                }
-            },
-            json => {
-                panic!("The function `get(`&self`,`search: &str`)` may only be called on a `Json::JSON`. I was called on: {:?}",json);
-            }
-        }
+        self.try_get(search)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
     /// Same as `get` above, but the references are mutable. Use `unbox_mut()` (see below) with this one.
     /// ## Panics
     /// This function will panic if called on a `Json` variant other than `Json::JSON` or `Json::OBJECT`,
-    /// as only these two variants may hold `Json::OBJECT` which has a `name` field.
+    /// as only these two variants may hold `Json::OBJECT` which has a `name` field. Use `try_get_mut` (see below)
+    /// if this is not acceptable.
     pub fn get_mut(&mut self, search: &str) -> Option<&mut Json> {
-        match self {
-            Json::JSON(values) => {
-                for n in 0..values.len() {
-                    match &values[n] {
-                        Json::OBJECT { name, value: _ } => {
-                            if name == search {
-                                return Some(&mut values[n]);
-                            }
-                        }
-                        _ => {}
-                    }
-                }
-            }
-            Json::OBJECT { name: _, value } => match value.unbox_mut() {
-                Json::JSON(values) => {
-                    for n in 0..values.len() {
-                        match &values[n] {
-                            Json::OBJECT { name, value: _ } => {
-                                if name == search {
-                                    return Some(&mut values[n]);
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                }
-                json => {
-                    panic!("The function `get_mut(`&self`,`search: &str`)` may only be called on a `Json::JSON` or a `Json::OBJECT` holding a `Json::JSON`. I was called on: {:?}",json);
-                }
-            },
-            json => {
-                panic!("The function `get_mut(`&self`,`search: &str`)` may only be called on a `Json::JSON` or a `Json::OBJECT` holding a `Json::JSON`. I was called on: {:?}",json);
-            }
+        self.try_get_mut(search)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `get` above, but returns a `VariantError` instead of panicking if called on a `Json` variant
+    /// other than `Json::JSON` or a `Json::OBJECT` holding one.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[\"Not\",\"an\",\"object\"]").unwrap();
+    ///
+    /// match json.try_get("Greeting") {
+    ///     Err(error) => {
+    ///         assert_eq!(error.found, "Json::ARRAY");
+    ///     },
+    ///     Ok(json) => {
+    ///         panic!("Expected an error but found {:?}!!!",json);
+    ///     }
+    /// }
+    /// ```
+    pub fn try_get(&self, search: &str) -> Result<Option<&Json>, VariantError> {
+        match self.inner() {
+            Json::JSON(values) => Ok(values
+                .iter()
+                .find(|json| matches!(json, Json::OBJECT { name, value: _ } if name == search))),
+            json => Err(VariantError::new("get", json)),
         }
+    }
+
+    /// Same as `try_get` above, but the references are mutable.
+    pub fn try_get_mut(&mut self, search: &str) -> Result<Option<&mut Json>, VariantError> {
+        match self.inner_mut() {
+            Json::JSON(values) => Ok(values
+                .iter_mut()
+                .find(|json| matches!(json, Json::OBJECT { name, value: _ } if name == search))),
+            json => Err(VariantError::new("get_mut", json)),
+        }
+    }
+
+    /// Same as `get` above, but the name only needs to match up to ASCII case, e.g. for headers
+    /// from producers which don't agree on how to spell `Content-Type`. A member whose name
+    /// matches exactly is preferred, otherwise the first one which matches is returned.
+    /// ## Panics
+    /// Same as `get`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"ETag\":\"a\",\"etag\":\"b\"}").unwrap();
+    ///
+    /// assert_eq!(
+    ///     json.get_ignore_case("ETAG"),
+    ///     Some(&Json::member("ETag", Json::STRING(String::from("a"))))
+    /// );
+    /// assert_eq!(
+    ///     json.get_ignore_case("etag"),
+    ///     Some(&Json::member("etag", Json::STRING(String::from("b"))))
+    /// );
+    /// assert_eq!(json.get_ignore_case("tag"), None);
+    /// ```
+    pub fn get_ignore_case(&self, search: &str) -> Option<&Json> {
+        self.try_get_ignore_case(search)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `get_ignore_case` above, but returns a `VariantError` instead of panicking (see
+    /// `try_get`).
+    pub fn try_get_ignore_case(&self, search: &str) -> Result<Option<&Json>, VariantError> {
+        let values = match self.inner() {
+            Json::JSON(values) => values,
+            json => return Err(VariantError::new("get_ignore_case", json)),
+        };
+
+        let matches = |exact: bool| {
+            values.iter().find(|json| match json {
+                Json::OBJECT { name, value: _ } if exact => name == search,
+                Json::OBJECT { name, value: _ } => name.eq_ignore_ascii_case(search),
+                _ => false,
+            })
+        };
+
+        Ok(matches(true).or_else(|| matches(false)))
+    }
+
+    /// Same as `get` above, but returns the value of the requested `Json::OBJECT` instead of the
+    /// `Json::OBJECT` itself, so no further matching or `unbox()`ing is necessary.
+    /// ## Panics
+    /// Same as `get`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Greeting\":\"Hello, world!\"}").unwrap();
+    ///
+    /// match json.value_of("Greeting") {
+    ///     Some(Json::STRING(val)) => {
+    ///         assert_eq!("Hello, world!",val);
+    ///     },
+    ///     json => {
+    ///         panic!("Expected Json::STRING but found {:?}!!!",json);
+    ///     }
+    /// }
+    /// ```
+    pub fn value_of(&self, search: &str) -> Option<&Json> {
+        self.get(search).map(|json| json.inner())
+    }
+
+    /// Same as `value_of` above, but the reference is mutable.
+    /// ## Panics
+    /// Same as `get_mut`.
+    pub fn value_of_mut(&mut self, search: &str) -> Option<&mut Json> {
+        self.get_mut(search).map(|json| json.inner_mut())
+    }
+
+    /// Same as `value_of` above, but returns a `VariantError` instead of panicking (see `try_get`).
+    pub fn try_value_of(&self, search: &str) -> Result<Option<&Json>, VariantError> {
+        self.try_get(search)
+            .map(|json| json.map(|json| json.inner()))
+    }
+
+    /// Same as `try_value_of` above, but the reference is mutable.
+    pub fn try_value_of_mut(&mut self, search: &str) -> Result<Option<&mut Json>, VariantError> {
+        self.try_get_mut(search)
+            .map(|json| json.map(|json| json.inner_mut()))
+    }
+
+    /// Returns the string held by a `Json::STRING` (or a `Json::OBJECT` holding one), `None` otherwise.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.inner() {
+            Json::STRING(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Returns the number held by a `Json::NUMBER` (or a `Json::OBJECT` holding one), `None` otherwise.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.inner() {
+            Json::NUMBER(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a `Json::BOOL` (or a `Json::OBJECT` holding one), `None` otherwise.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self.inner() {
+            Json::BOOL(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of a `Json::ARRAY` (or a `Json::OBJECT` holding one), `None` otherwise.
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self.inner() {
+            Json::ARRAY(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    /// Looks up the member `search` (see `value_of`) and returns its value if it is a `Json::STRING`.
+    /// Returns `None` if the member doesn't exist, holds a different variant or if called on something
+    /// other than a `Json::JSON` or a `Json::OBJECT` holding one. Never panics.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Name\":\"Ferris\",\"Legs\":10,\"Crab\":true,\"Friends\":[]}").unwrap();
+    ///
+    /// assert_eq!(json.get_str("Name"), Some("Ferris"));
+    /// assert_eq!(json.get_f64("Legs"), Some(10.0));
+    /// assert_eq!(json.get_bool("Crab"), Some(true));
+    /// assert_eq!(json.get_array("Friends").map(|friends| friends.len()), Some(0));
+    ///
+    /// assert_eq!(json.get_str("Legs"), None);
+    /// assert_eq!(json.get_f64("Age"), None);
+    /// ```
+    pub fn get_str(&self, search: &str) -> Option<&str> {
+        self.typed_value_of(search).and_then(Json::as_str)
+    }
+
+    /// Same as `get_str` above, but for a `Json::NUMBER`.
+    pub fn get_f64(&self, search: &str) -> Option<f64> {
+        self.typed_value_of(search).and_then(Json::as_f64)
+    }
+
+    /// Same as `get_str` above, but for a `Json::BOOL`.
+    pub fn get_bool(&self, search: &str) -> Option<bool> {
+        self.typed_value_of(search).and_then(Json::as_bool)
+    }
+
+    /// Same as `get_str` above, but for a `Json::ARRAY`.
+    pub fn get_array(&self, search: &str) -> Option<&[Json]> {
+        self.typed_value_of(search).and_then(Json::as_array)
+    }
 
-        None
+    fn typed_value_of(&self, search: &str) -> Option<&Json> {
+        self.try_value_of(search).ok().flatten()
     }
 
     /// Enables matching the contents of a `Box`.
@@ -294,6 +426,157 @@ impl Json {
         self
     }
 
+    /// Replaces `self` with `Json::NULL` and returns the previous value. Use this (together with
+    /// `get_mut`) to move a subtree out of a `Json` without cloning it.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"Days\":[\"Monday\",\"Tuesday\"]}").unwrap();
+    ///
+    /// let days = match json.get_mut("Days") {
+    ///     Some(Json::OBJECT { name: _, value }) => value.take(),
+    ///     _ => {
+    ///         panic!("Days not found!!!");
+    ///     }
+    /// };
+    ///
+    /// assert_eq!(days.print(), "[\"Monday\",\"Tuesday\"]");
+    /// assert_eq!(json.print(), "{\"Days\":null}");
+    /// ```
+    pub fn take(&mut self) -> Json {
+        std::mem::replace(self, Json::NULL)
+    }
+
+    /// Keeps only the members of a `Json::JSON` (or the elements of a `Json::ARRAY`) for which `keep`
+    /// returns `true`. The first argument passed to `keep` is the name of the member, or `None` for
+    /// array elements and unnamed values. Can also be called on a `Json::OBJECT` holding either of the two.
+    /// ## Panics
+    /// Will panic if called on any other variant. Use `try_retain` (see below) if this is not acceptable.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"Name\":\"Ferris\",\"Email\":null,\"Age\":null}").unwrap();
+    ///
+    /// json.retain(|_, value| match value {
+    ///     Json::NULL => false,
+    ///     _ => true,
+    /// });
+    ///
+    /// assert_eq!(json.print(), "{\"Name\":\"Ferris\"}");
+    /// ```
+    pub fn retain<F>(&mut self, keep: F)
+    where
+        F: FnMut(Option<&str>, &Json) -> bool,
+    {
+        self.try_retain(keep)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `retain` above, but returns a `VariantError` instead of panicking.
+    pub fn try_retain<F>(&mut self, mut keep: F) -> Result<(), VariantError>
+    where
+        F: FnMut(Option<&str>, &Json) -> bool,
+    {
+        match self.inner_mut() {
+            Json::JSON(values) | Json::ARRAY(values) => {
+                values.retain(|json| match json {
+                    Json::OBJECT { name, value } => keep(Some(name), value),
+                    json => keep(None, json),
+                });
+
+                Ok(())
+            }
+            json => Err(VariantError::new("retain", json)),
+        }
+    }
+
+    /// Returns `true` if a `Json::JSON` (or a `Json::OBJECT` holding one) has a member with the given name.
+    /// Returns `false` for every other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Greeting\":\"Hello, world!\"}").unwrap();
+    ///
+    /// assert!(json.contains_key("Greeting"));
+    /// assert!(!json.contains_key("Farewell"));
+    /// ```
+    pub fn contains_key(&self, search: &str) -> bool {
+        match self.inner() {
+            Json::JSON(values) => values
+                .iter()
+                .any(|json| matches!(json, Json::OBJECT { name, value: _ } if name == search)),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if a `Json::ARRAY` (or a `Json::OBJECT` holding one) has an element equal to `search`.
+    /// Elements are compared deeply, i.e. nested arrays and objects have to match completely.
+    /// Returns `false` for every other variant.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"[1,\"two\",[3]]").unwrap();
+    ///
+    /// assert!(json.contains(&Json::STRING(String::from("two"))));
+    /// assert!(json.contains(&Json::ARRAY(vec![Json::NUMBER(3.0)])));
+    /// assert!(!json.contains(&Json::NUMBER(2.0)));
+    /// ```
+    pub fn contains(&self, search: &Json) -> bool {
+        match self.inner() {
+            Json::ARRAY(values) => values.contains(search),
+            _ => false,
+        }
+    }
+
+    // The name of the variant, as used in error messages.
+    pub(crate) fn variant_name(&self) -> &'static str {
+        match self {
+            Json::OBJECT { name: _, value: _ } => "Json::OBJECT",
+            Json::JSON(_) => "Json::JSON",
+            Json::ARRAY(_) => "Json::ARRAY",
+            Json::STRING(_) => "Json::STRING",
+            Json::NUMBER(_) => "Json::NUMBER",
+            Json::BOOL(_) => "Json::BOOL",
+            Json::NULL => "Json::NULL",
+        }
+    }
+
+    // Resolves a `Json::OBJECT` to the value it holds so that methods can treat `Json::JSON`/`Json::ARRAY`
+    // and objects holding them alike.
+    fn inner(&self) -> &Json {
+        match self {
+            Json::OBJECT { name: _, value } => value,
+            json => json,
+        }
+    }
+
+    fn inner_mut(&mut self) -> &mut Json {
+        match self {
+            Json::OBJECT { name: _, value } => value,
+            json => json,
+        }
+    }
+
+    // The members of a `Json::JSON` or the elements of a `Json::ARRAY` (also when held by a `Json::OBJECT`).
+    // Every other variant has none.
+    fn members(&self) -> &[Json] {
+        match self.inner() {
+            Json::JSON(values) | Json::ARRAY(values) => values,
+            _ => &[],
+        }
+    }
+
+    fn members_mut(&mut self) -> &mut [Json] {
+        match self.inner_mut() {
+            Json::JSON(values) | Json::ARRAY(values) => values,
+            _ => &mut [],
+        }
+    }
+
     /// Returns a `String` of the form: `{"Json":"Value",...}` but can also be called on 'standalone objects'
     /// which could result in `"Object":{"Stuff":...}` or `"Json":true`.
     pub fn print(&self) -> String {
@@ -306,24 +589,28 @@ impl Json {
             Json::JSON(values) => {
                 result.push('{');
 
-                for n in 0..values.len() {
-                    result.push_str(&values[n].print());
+                for value in values {
+                    result.push_str(&value.print());
                     result.push(',');
                 }
 
-                result.pop();
+                if !values.is_empty() {
+                    result.pop();
+                }
 
                 result.push('}');
             }
             Json::ARRAY(values) => {
                 result.push('[');
 
-                for n in 0..values.len() {
-                    result.push_str(&values[n].print());
+                for value in values {
+                    result.push_str(&value.print());
                     result.push(',');
                 }
 
-                result.pop();
+                if !values.is_empty() {
+                    result.pop();
+                }
 
                 result.push(']');
             }
@@ -350,8 +637,12 @@ impl Json {
 
     /// Parses the given bytes if a json structure is found. It even works with `\"Hello\":\"World\"`
     /// (doesn't have to be like `{...}`), i.e. it can return any of the variants in the `Json` enum.
-    /// The error is returned in the form `(last position, what went wrong)`. Unfortunately the error
-    /// description are minimal (basically "Error parsing ...type...").
+    /// The error is a `ParseError` saying where parsing failed and what went wrong, as an `ErrorKind`
+    /// to match on and as a (minimal) message.
+    ///
+    /// Objects and arrays may be nested 1024 deep, deeper input fails with `"Too deeply nested."`.
+    /// Parsing itself doesn't recurse, but dropping, printing, cloning or comparing a `Json` does, so
+    /// a deeper value (also one built by hand) could overflow the stack.
     /// ## Example
     /// ```
     /// use json_minimal::*;
@@ -382,218 +673,80 @@ impl Json {
     ///             }
     ///         }
     ///     },
-    ///     Err( (pos,msg) ) => {
-    ///         panic!("`{}` at position `{}`!!!",msg,pos);
+    ///     Err(error) => {
+    ///         panic!("{}!!!",error);
     ///     }
     /// }
     /// ```
     /// See the <a href="https://github.com/36den/json_minimal-rs/">tutorial</a> on github for more.
-    pub fn parse(input: &[u8]) -> Result<Json, (usize, &'static str)> {
+    pub fn parse(input: &[u8]) -> Result<Json, ParseError> {
         let mut incr: usize = 0;
 
-        match input[incr] as char {
-            '{' => Self::parse_json(input, &mut incr),
-            '\"' => Self::parse_string(input, &mut incr),
-            '[' => Self::parse_array(input, &mut incr),
-            't' | 'f' => Self::parse_bool(input, &mut incr),
-            'n' => Self::parse_null(input, &mut incr),
-            '0'..='9' => Self::parse_number(input, &mut incr),
-            _ => Err((incr, "Not a valid json format")),
-        }
+        Self::parse_value(input, &mut incr).map_err(|(index, kind)| ParseError::at(input, index, kind))
     }
 
-    // This must exclusively be used by `parse_string` to make any sense.
-    fn parse_object(
-        input: &[u8],
-        incr: &mut usize,
-        name: String,
-    ) -> Result<Json, (usize, &'static str)> {
-//        if input[*incr] as char != ':' {
-//            return Err((*incr, "Error parsing object."));
-//        }
-
-        *incr += 1;
-
-        if *incr >= input.len() {
-            return Err((*incr, "Error parsing object."));
-        }
-
-        loop {
-            match input[*incr] as char {
-                '\r' | '\n' | '\t' | ' ' => {
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        return Err((*incr, "Error parsing object."));
-                    }
-                },
-                _ => {
-                 break;
-                }
-            }
-        }
-
-        let value = match input[*incr] as char {
-            '{' => Self::parse_json(input, incr)?,
-            '[' => Self::parse_array(input, incr)?,
-            '\"' => Self::parse_string(input, incr)?,
-            't' | 'f' => Self::parse_bool(input, incr)?,
-            'n' => Self::parse_null(input, incr)?,
-            '0'..='9' => Self::parse_number(input, incr)?,
-            _ => {
-                return Err((*incr, "Error parsing object."));
-            }
-        };
-
-        Ok(Json::OBJECT {
-            name,
-
-            value: Box::new(value),
-        })
+    // Parses the value starting at `input[*incr]`, leaving `incr` just behind it.
+    fn parse_value(input: &[u8], incr: &mut usize) -> Result<Json, (usize, ErrorKind)> {
+        parser::parse(input, incr)
     }
 
-    // Parse if you thik it's something like `{...}`
-    fn parse_json(input: &[u8], incr: &mut usize) -> Result<Json, (usize, &'static str)> {
-        let mut result: Vec<Json> = Vec::new();
-
-//        if input[*incr] as char != '{' {
-//            return Err((*incr, "Error parsing json."));
-//        }
-
-        *incr += 1;
-
-        if *incr >= input.len() {
-            return Err((*incr, "Error parsing json."));
-        }
-
-        loop {
-            let json = match input[*incr] as char {
-                ',' => {
-                    *incr += 1;
-                    continue;
-                }
-                '\"' => Self::parse_string(input, incr)?,
-                '[' => Self::parse_array(input, incr)?,
-                't' | 'f' => Self::parse_bool(input, incr)?,
-                'n' => Self::parse_null(input, incr)?,
-                '0'..='9' => Self::parse_number(input, incr)?,
-                '}' => {
-                    *incr += 1;
-
-                    return Ok(Json::JSON(result));
-                }
-                '{' => Self::parse_json(input, incr)?,
-                '\r' | '\n' | '\t' | ' ' => {                    
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        return Err((*incr, "Error parsing json."));
-                    }
-
-                    continue;
-                },
-                _ => {
-                    return Err((*incr, "Error parsing json."));
-                }
-            };
-
-            result.push(json);
-        }
+    // Parse a &str if you know that it corresponds to/starts with a json String. Whether it is the
+    // name of a member is up to the caller.
+    fn parse_string(input: &[u8], incr: &mut usize) -> Result<String, (usize, ErrorKind)> {
+        Self::parse_quoted(input, incr, b'\"', None)
     }
 
-    // Parse a &str if you're sure it resembles `[...`
-    fn parse_array(input: &[u8], incr: &mut usize) -> Result<Json, (usize, &'static str)> {
-        let mut result: Vec<Json> = Vec::new();
+    // Parse a string between `quote`s, which are `'` when repairing, inside which `\'` is a quote.
+    // Fails before copying or even looking at much more than `max_len` bytes.
+    fn parse_quoted(
+        input: &[u8],
+        incr: &mut usize,
+        quote: u8,
+        max_len: Option<usize>,
+    ) -> Result<String, (usize, ErrorKind)> {
+        let mut result: Vec<u8> = Vec::new();
+        let start = *incr;
 
-//        if input[*incr] as char != '[' {
-//            return Err((*incr, "Error parsing array."));
+//        if input[*incr] as char != '\"' {
+//            return Err((*incr, "Error parsing string."));
 //        }
 
         *incr += 1;
 
         if *incr >= input.len() {
-            return Err((*incr, "Error parsing array."));
+            return Err((*incr, ErrorKind::UnexpectedEof));
         }
 
         loop {
-            let json = match input[*incr] as char {
-                ',' => {
-                    *incr += 1;
-                    continue;
-                }
-                '\"' => Self::parse_string(input, incr)?,
-                '[' => Self::parse_array(input, incr)?,
-                '{' => Self::parse_json(input, incr)?,
-                't' | 'f' => Self::parse_bool(input, incr)?,
-                'n' => Self::parse_null(input, incr)?,
-                '0'..='9' => Self::parse_number(input, incr)?,
-                ']' => {
-                    *incr += 1;
-
-                    return Ok(Json::ARRAY(result));
-                },
-                '\r' | '\n' | '\t' | ' ' => {
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        return Err((*incr, "Error parsing array."));
-                    }
-
-                    continue;
-                },
-                _ => {
-                    return Err((*incr, "Error parsing array."));
-                }
+            // Everything up to the next quote or escape sequence is copied as it is, looking only as
+            // far as `max_len` still allows.
+            let rest = &input[*incr..];
+            let end = match max_len {
+                Some(max_len) if result.len() > max_len => {
+                    return Err((start, ErrorKind::StringTooLong));
+                }
+                Some(max_len) => (max_len - result.len()).saturating_add(1).min(rest.len()),
+                None => rest.len(),
             };
 
-            result.push(json);
-        }
-    }
-
-    // Parse a &str if you know that it corresponds to/starts with a json String.
-    fn parse_string(input: &[u8], incr: &mut usize) -> Result<Json, (usize, &'static str)> {
-        let mut result: Vec<u8> = Vec::new();
-
-//        if input[*incr] as char != '\"' {
-//            return Err((*incr, "Error parsing string."));
-//        }
+            let n = match scan::find(&rest[..end], &[quote, b'\\']) {
+                Some(n) => n,
+                None if end < rest.len() => return Err((start, ErrorKind::StringTooLong)),
+                None => return Err((input.len(), ErrorKind::UnexpectedEof)),
+            };
 
-        *incr += 1;
+            result.extend_from_slice(&rest[..n]);
+            *incr += n;
 
-        if *incr >= input.len() {
-            return Err((*incr, "Error parsing string."));
-        }
+            if rest[n] == b'\\' && quote == b'\'' && rest.get(n + 1) == Some(&b'\'') {
+                result.push(b'\'');
+                *incr += 2;
+            } else if rest[n] == b'\\' {
+                Self::parse_string_escape_sequence(input, incr, &mut result)?;
+            } else {
+                *incr += 1;
 
-        loop {
-            match input[*incr] {
-                b'\"' => {
-                    *incr += 1;
-
-                    let result = String::from_utf8(result)
-                        .map_err(|_| (*incr, "Error parsing non-utf8 string."))?;
-
-                    if *incr < input.len() {
-                        if input[*incr] as char == ':' {
-                            return Self::parse_object(input, incr, result);
-                        } else {
-                            return Ok(Json::STRING(result));
-                        }
-                    } else {
-                        return Ok(Json::STRING(result));
-                    }
-                },
-                b'\\' => {
-                    Self::parse_string_escape_sequence(input, incr, &mut result)?;
-                },
-                c => {
-                    result.push(c);
-
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        return Err((*incr, "Error parsing string."));
-                    }
-                }
+                return String::from_utf8(result).map_err(|_| (*incr, ErrorKind::InvalidUtf8));
             }
         }
     }
@@ -603,7 +756,7 @@ impl Json {
         input: &[u8],
         incr: &mut usize,
         result: &mut Vec<u8>,
-    ) -> Result<(), (usize, &'static str)> {
+    ) -> Result<(), (usize, ErrorKind)> {
 //        if input[*incr] as char != '\\' {
 //            return Err((*incr, "Error parsing string escape sequence."));
 //        }
@@ -611,7 +764,7 @@ impl Json {
         *incr += 1;
 
         if *incr >= input.len() {
-            return Err((*incr, "Error parsing string escape sequence."));
+            return Err((*incr, ErrorKind::UnexpectedEof));
         }
 
         match input[*incr] as char {
@@ -634,142 +787,229 @@ impl Json {
                 result.push(b'\t');
             }
             'u' => {
-                const BAD_UNICODE: &str = "Error parsing unicode string escape sequence.";
+                // The input may end within the four digits, but not before a wrong one.
+                if *incr + 5 > input.len() && input[*incr + 1..].iter().all(u8::is_ascii_hexdigit) {
+                    return Err((input.len(), ErrorKind::UnexpectedEof));
+                }
 
-                if *incr + 4 >= input.len() {
-                    return Err((*incr, BAD_UNICODE));
+                if *incr + 5 > input.len() {
+                    return Err((*incr, ErrorKind::InvalidUnicodeEscape));
                 }
 
-                let hex = (&input[*incr + 1..*incr + 5]).to_vec();
-                let hex = String::from_utf8(hex).map_err(|_| (*incr, BAD_UNICODE))?;
-                let value = u16::from_str_radix(&hex, 16).map_err(|_| (*incr, BAD_UNICODE))?;
-                let value = std::char::from_u32(value as u32).ok_or((*incr, BAD_UNICODE))?;
+                let hex = input[*incr + 1..*incr + 5].to_vec();
+                let hex =
+                    String::from_utf8(hex).map_err(|_| (*incr, ErrorKind::InvalidUnicodeEscape))?;
+                let value = u16::from_str_radix(&hex, 16)
+                    .map_err(|_| (*incr, ErrorKind::InvalidUnicodeEscape))?;
+                let value = std::char::from_u32(value as u32)
+                    .ok_or((*incr, ErrorKind::InvalidUnicodeEscape))?;
 
                 let mut buffer = [0; 4];
                 result.extend(value.encode_utf8(&mut buffer).as_bytes());
                 *incr += 4;
             }
             _ => {
-                return Err((*incr, "Error parsing invalid string escape sequence."));
+                return Err((*incr, ErrorKind::InvalidEscape));
             }
         }
 
         *incr += 1;
 
         if *incr >= input.len() {
-            return Err((*incr, "Error parsing string escape sequence."));
+            return Err((*incr, ErrorKind::UnexpectedEof));
         }
 
         Ok(())
     }
 
-    fn parse_number(input: &[u8], incr: &mut usize) -> Result<Json, (usize, &'static str)> {
-        let mut result = String::new();
-
-        loop {
-            match input[*incr] as char {
-                ',' | ']' | '}' | '\r' | '\n' | '\t' | ' ' => {
-                    break;
-                },
-                c => {
-                    result.push(c);
-
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        match result.parse::<f64>() {
-                            Ok(num) => {
-                                return Ok(Json::NUMBER(num));
-                            }
-                            Err(_) => {
-                                return Err((*incr, "Error parsing number."));
-                            }
-                        }
-                    }
-                }
-            }
+    fn parse_number(input: &[u8], incr: &mut usize) -> Result<Json, (usize, ErrorKind)> {
+        let token = Self::parse_token(input, incr);
+
+        // Anything but ASCII isn't part of a number anyway.
+        match std::str::from_utf8(token)
+            .ok()
+            .and_then(|token| token.parse::<f64>().ok())
+        {
+            Some(value) => Ok(Json::NUMBER(value)),
+            None if truncated(input, *incr, token) => Err((*incr, ErrorKind::UnexpectedEof)),
+            None => Err((*incr, ErrorKind::InvalidNumber)),
         }
+    }
 
-        match result.parse::<f64>() {
-            Ok(num) => {
-                return Ok(Json::NUMBER(num));
-            }
-            Err(_) => {
-                return Err((*incr, "Error parsing number."));
-            }
+    fn parse_bool(input: &[u8], incr: &mut usize) -> Result<Json, (usize, ErrorKind)> {
+        match Self::parse_token(input, incr) {
+            b"true" => Ok(Json::BOOL(true)),
+            b"false" => Ok(Json::BOOL(false)),
+            token if truncated(input, *incr, token) => Err((*incr, ErrorKind::UnexpectedEof)),
+            _ => Err((*incr, ErrorKind::InvalidBool)),
         }
     }
 
-    fn parse_bool(input: &[u8], incr: &mut usize) -> Result<Json, (usize, &'static str)> {
-        let mut result = String::new();
-
-        loop {
-            match input[*incr] as char {
-                ',' | ']' | '}' | '\r' | '\n' | '\t' | ' ' => {
-                    break;
-                },
-                c => {
-                    result.push(c);
-
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        if result == "true" {
-                            return Ok(Json::BOOL(true));
-                        }
-
-                        if result == "false" {
-                            return Ok(Json::BOOL(false));
-                        }
-
-                        return Err((*incr, "Error parsing bool."));
-                    }
-                }
-            }
+    fn parse_null(input: &[u8], incr: &mut usize) -> Result<Json, (usize, ErrorKind)> {
+        match Self::parse_token(input, incr) {
+            b"null" => Ok(Json::NULL),
+            token if truncated(input, *incr, token) => Err((*incr, ErrorKind::UnexpectedEof)),
+            _ => Err((*incr, ErrorKind::InvalidNull)),
         }
+    }
 
-        if result == "true" {
-            return Ok(Json::BOOL(true));
-        }
+    // The bytes of a number, `true`, `false` or `null` up to whatever ends it, leaving `incr` there.
+    fn parse_token<'a>(input: &'a [u8], incr: &mut usize) -> &'a [u8] {
+        let start = *incr;
 
-        if result == "false" {
-            return Ok(Json::BOOL(false));
-        }
+        *incr = input[start..]
+            .iter()
+            .position(|c| matches!(c, b',' | b']' | b'}' | b'\r' | b'\n' | b'\t' | b' '))
+            .map_or(input.len(), |n| start + n);
 
-        return Err((*incr, "Error parsing bool."));
+        &input[start..*incr]
     }
+}
 
-    fn parse_null(input: &[u8], incr: &mut usize) -> Result<Json, (usize, &'static str)> {
-        let mut result = String::new();
-
-        loop {
-            match input[*incr] as char {
-                ',' | ']' | '}' | '\r' | '\n' | '\t' | ' ' => {
-                    break;
-                },
-                c => {
-                    result.push(c);
-
-                    *incr += 1;
-
-                    if *incr >= input.len() {
-                        if result == "null" {
-                            return Ok(Json::NULL);
-                        } else {
-                            return Err((*incr, "Error parsing null."));
-                        }
-                    }
-                }
-            }
+// Whether `token`, a number, `true`, `false` or `null` (or Python's `True`, `False` or `None` when
+// repairing) ending at `end`, fails only because the end of the input cut it short. Only an exponent
+// can be cut short so that what is left isn't a number.
+fn truncated(input: &[u8], end: usize, token: &[u8]) -> bool {
+    const LITERALS: [&[u8]; 6] = [b"true", b"false", b"null", b"True", b"False", b"None"];
+
+    end == input.len()
+        && match token.first() {
+            Some(b'0'..=b'9') => matches!(token.last(), Some(b'e' | b'E' | b'+' | b'-')),
+            Some(_) => LITERALS.iter().any(|literal| literal.starts_with(token)),
+            None => false,
         }
+}
 
-        if result == "null" {
-            return Ok(Json::NULL);
-        } else {
-            return Err((*incr, "Error parsing null."));
-        }
-    }
+// JSON's insignificant whitespace.
+fn is_whitespace(c: u8) -> bool {
+    matches!(c, b' ' | b'\t' | b'\r' | b'\n')
 }
 
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+mod arbitrary;
+mod array;
+mod builder;
+#[cfg(feature = "bytes")]
+mod bytes_impl;
+mod cancel;
+#[cfg(feature = "cbor")]
+mod cbor;
+mod codegen;
+mod compare;
+mod convert;
+mod cursor;
+#[cfg(feature = "datetime")]
+mod datetime;
+mod diff;
+mod document;
+mod edit;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod flatten;
+mod glob;
+mod hash;
+pub mod iter;
+mod lazy;
+mod lexer;
+mod merge;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+mod ndjson;
+mod normalize;
+mod options;
+mod order;
+mod parser;
+mod patch;
+pub mod pointer;
+mod printer;
+mod projection;
+mod query;
+#[cfg(feature = "rayon")]
+mod rayon_impl;
+mod recover;
+mod repair;
+mod report;
+mod scan;
+#[cfg(feature = "schema")]
+mod schema;
+mod seq;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "serde_json")]
+mod serde_json_impl;
+#[cfg(feature = "serde")]
+mod serde_value;
+mod spanned;
+mod stream;
+#[cfg(feature = "tokio")]
+mod tokio_impl;
+#[cfg(feature = "uuid")]
+mod uuid_impl;
+mod validate;
+mod walk;
+mod warning;
+
+#[cfg(any(feature = "proptest", feature = "quickcheck"))]
+pub use arbitrary::ArbitraryBounds;
+pub use array::{NonNumeric, Stats};
+pub use builder::JsonBuilder;
+pub use compare::EqOptions;
+#[cfg(feature = "bytes")]
+pub use bytes_impl::{BytesJson, BytesStr};
+#[doc(hidden)]
+pub use convert::private as __private;
+pub use convert::{FromJson, ToJson};
+pub use cursor::JsonCursor;
+#[cfg(feature = "datetime")]
+pub use datetime::DateTime;
+pub use diff::Difference;
+pub use document::JsonDocument;
+pub use edit::EditableJson;
+#[cfg(feature = "cbor")]
+pub use error::CborError;
+#[cfg(feature = "datetime")]
+pub use error::DateTimeError;
+#[cfg(feature = "msgpack")]
+pub use error::MsgPackError;
+#[cfg(feature = "schema")]
+pub use error::SchemaError;
+#[cfg(feature = "serde")]
+pub use error::SerdeError;
+#[cfg(feature = "uuid")]
+pub use error::UuidError;
+pub use error::{
+    AddError, Cancelled, EditError, ErrorKind, FromJsonError, NotANumber, ParseError, SeqError,
+    VariantError,
+};
+pub use flatten::KeyStyle;
+pub use hash::HashTree;
+#[cfg(feature = "derive")]
+pub use json_minimal_derive::{FromJson, ToJson};
+pub use lazy::LazyJson;
+pub use lexer::{JsonEvent, JsonLexer};
+pub use merge::{ArrayMerge, Conflict, MergeStrategy};
+pub use ndjson::NdjsonReader;
+pub use normalize::{DuplicateKeys, NormalizeOptions};
+pub use options::ParseOptions;
+pub use query::QueryStyle;
+#[cfg(feature = "rayon")]
+pub use rayon_impl::ParNdjsonReader;
+pub use repair::{Repair, RepairKind};
+pub use report::ParseReport;
+#[cfg(feature = "schema")]
+pub use schema::{Schema, SchemaValidator, Violation};
+pub use seq::{SeqReader, SeqWriter};
+#[cfg(feature = "serde")]
+pub use serde_value::{from_value, to_value};
+pub use spanned::Spans;
+pub use stream::{ArrayReader, StreamReader};
+pub use walk::Walk;
+pub use warning::{Warning, WarningKind};
+
+// Lets the derived code refer to `::json_minimal` within this crate's own tests.
+#[cfg(all(test, feature = "derive"))]
+extern crate self as json_minimal;
+
 #[cfg(test)]
 mod tests;
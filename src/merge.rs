@@ -0,0 +1,115 @@
+//! Deep merging of two `Json`s.
+
+use crate::Json;
+
+/// How `Json::merge` handles two arrays found at the same place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayMerge {
+    /// The array of `other` replaces the array of `self` (subject to `Conflict`).
+    Replace,
+    /// The elements of `other` are appended to the elements of `self`.
+    Concat,
+    /// Like `Concat`, but elements (deeply) equal to one already present are left out.
+    Union,
+}
+
+/// How `Json::merge` resolves two values found at the same place which can't be merged,
+/// e.g. two different strings or a number and an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conflict {
+    /// The value of `other` wins.
+    Overwrite,
+    /// The value of `self` wins.
+    Keep,
+}
+
+/// Controls `Json::merge`. The default replaces arrays and lets `other` win, which is what you want
+/// when layering configuration files (defaults, then user, then environment).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergeStrategy {
+    pub arrays: ArrayMerge,
+    pub conflicts: Conflict,
+}
+
+impl Default for MergeStrategy {
+    fn default() -> MergeStrategy {
+        MergeStrategy {
+            arrays: ArrayMerge::Replace,
+            conflicts: Conflict::Overwrite,
+        }
+    }
+}
+
+impl Json {
+    /// Deeply merges `other` into `self`. Objects are merged member by member (members only found in
+    /// `other` are appended), arrays according to `strategy.arrays` and everything else according to
+    /// `strategy.conflicts`. Can also be called on a `Json::OBJECT`, in which case its value is merged.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut config = Json::parse(b"{\"Server\":{\"Port\":80,\"Hosts\":[\"a\"]},\"Debug\":false}").unwrap();
+    /// let user = Json::parse(b"{\"Server\":{\"Port\":8080,\"Hosts\":[\"a\",\"b\"]},\"Theme\":\"dark\"}").unwrap();
+    ///
+    /// config.merge(
+    ///     user,
+    ///     MergeStrategy {
+    ///         arrays: ArrayMerge::Union,
+    ///         ..MergeStrategy::default()
+    ///     },
+    /// );
+    ///
+    /// assert_eq!(
+    ///     config.print(),
+    ///     "{\"Server\":{\"Port\":8080,\"Hosts\":[\"a\",\"b\"]},\"Debug\":false,\"Theme\":\"dark\"}"
+    /// );
+    /// ```
+    pub fn merge(&mut self, other: Json, strategy: MergeStrategy) {
+        let other = match other {
+            Json::OBJECT { name: _, value } => *value,
+            other => other,
+        };
+
+        match (self.inner_mut(), other) {
+            (Json::JSON(values), Json::JSON(others)) => {
+                for other in others {
+                    match other {
+                        Json::OBJECT { name, value } => {
+                            let existing = values.iter_mut().find(
+                                |json| matches!(json, Json::OBJECT { name: own, value: _ } if *own == name),
+                            );
+
+                            match existing {
+                                Some(existing) => existing.merge(*value, strategy),
+                                None => values.push(Json::OBJECT { name, value }),
+                            }
+                        }
+                        other => values.push(other),
+                    }
+                }
+            }
+            (Json::ARRAY(values), Json::ARRAY(others)) => match strategy.arrays {
+                ArrayMerge::Replace => {
+                    if strategy.conflicts == Conflict::Overwrite {
+                        *values = others;
+                    }
+                }
+                ArrayMerge::Concat => {
+                    values.extend(others);
+                }
+                ArrayMerge::Union => {
+                    for other in others {
+                        if !values.contains(&other) {
+                            values.push(other);
+                        }
+                    }
+                }
+            },
+            (own, other) => {
+                if strategy.conflicts == Conflict::Overwrite {
+                    *own = other;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{Json, MsgPackError};
+
+// How deeply maps and arrays may be nested, which bounds the recursion of decoding.
+const MAX_DEPTH: usize = 128;
+
+impl Json {
+    /// Encodes `self` as MessagePack. Requires the `msgpack` feature.
+    ///
+    /// A `Json::JSON` becomes a map with string keys and a standalone `Json::OBJECT` a map with a
+    /// single entry. Unnamed values within a `Json::JSON` can't be keyed and are left out. Whole
+    /// numbers use the smallest integer format that fits, all others are written as float 64.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"a\":[1,true]}").unwrap();
+    ///
+    /// assert_eq!(json.to_msgpack(), vec![0x81, 0xa1, b'a', 0x92, 0x01, 0xc3]);
+    /// assert_eq!(Json::from_msgpack(&json.to_msgpack()), Ok(json));
+    /// ```
+    pub fn to_msgpack(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        encode(self, &mut output);
+
+        output
+    }
+
+    /// Decodes MessagePack produced by `to_msgpack` or any other encoder. Requires the `msgpack`
+    /// feature.
+    ///
+    /// Maps become a `Json::JSON` (integer keys are turned into names) and binary data a
+    /// `Json::ARRAY` of numbers.
+    /// ## Errors
+    /// Fails on malformed or truncated input, extension types, map keys which are neither strings
+    /// nor integers, maps and arrays nested more than 128 deep, and bytes left over after the first
+    /// value.
+    pub fn from_msgpack(input: &[u8]) -> Result<Json, MsgPackError> {
+        let mut decoder = Decoder {
+            input,
+            position: 0,
+            depth: 0,
+        };
+
+        let json = decoder.decode()?;
+
+        if decoder.position < input.len() {
+            return Err(decoder.error("Unexpected bytes after the value."));
+        }
+
+        Ok(json)
+    }
+}
+
+// Writes the header of a string, array or map. `fix` is the first byte of the format's "fix"
+// variant holding up to `fix_max` elements, `wide` the first of its 8 (strings only), 16 and 32 bit
+// variants.
+fn header(len: usize, fix: u8, fix_max: usize, wide: &[u8], output: &mut Vec<u8>) {
+    if len <= fix_max {
+        output.push(fix | len as u8);
+    } else if wide.len() == 3 && len <= u8::MAX as usize {
+        output.push(wide[0]);
+        output.push(len as u8);
+    } else if len <= u16::MAX as usize {
+        output.push(wide[wide.len() - 2]);
+        output.extend_from_slice(&(len as u16).to_be_bytes());
+    } else {
+        output.push(wide[wide.len() - 1]);
+        output.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+}
+
+fn string(val: &str, output: &mut Vec<u8>) {
+    header(val.len(), 0xa0, 31, &[0xd9, 0xda, 0xdb], output);
+    output.extend_from_slice(val.as_bytes());
+}
+
+fn integer(val: f64, output: &mut Vec<u8>) {
+    if val >= 0.0 {
+        let val = val as u64;
+
+        if val <= 0x7f {
+            output.push(val as u8);
+        } else if val <= u8::MAX as u64 {
+            output.push(0xcc);
+            output.push(val as u8);
+        } else if val <= u16::MAX as u64 {
+            output.push(0xcd);
+            output.extend_from_slice(&(val as u16).to_be_bytes());
+        } else if val <= u32::MAX as u64 {
+            output.push(0xce);
+            output.extend_from_slice(&(val as u32).to_be_bytes());
+        } else {
+            output.push(0xcf);
+            output.extend_from_slice(&val.to_be_bytes());
+        }
+    } else {
+        let val = val as i64;
+
+        if val >= -32 {
+            output.push(val as u8);
+        } else if val >= i8::MIN as i64 {
+            output.push(0xd0);
+            output.push(val as u8);
+        } else if val >= i16::MIN as i64 {
+            output.push(0xd1);
+            output.extend_from_slice(&(val as i16).to_be_bytes());
+        } else if val >= i32::MIN as i64 {
+            output.push(0xd2);
+            output.extend_from_slice(&(val as i32).to_be_bytes());
+        } else {
+            output.push(0xd3);
+            output.extend_from_slice(&val.to_be_bytes());
+        }
+    }
+}
+
+fn encode(json: &Json, output: &mut Vec<u8>) {
+    match json {
+        Json::OBJECT { name, value } => {
+            output.push(0x81);
+            string(name, output);
+            encode(value, output);
+        }
+        Json::JSON(values) => {
+            let members = values.iter().filter_map(|json| match json {
+                Json::OBJECT { name, value } => Some((name, value)),
+                _ => None,
+            });
+
+            header(members.clone().count(), 0x80, 15, &[0xde, 0xdf], output);
+
+            for (name, value) in members {
+                string(name, output);
+                encode(value, output);
+            }
+        }
+        Json::ARRAY(values) => {
+            header(values.len(), 0x90, 15, &[0xdc, 0xdd], output);
+
+            for value in values {
+                encode(value, output);
+            }
+        }
+        Json::STRING(val) => string(val, output),
+        Json::NUMBER(val)
+            if val.fract() == 0.0
+                && (-9223372036854775808.0..18446744073709551616.0).contains(val) =>
+        {
+            integer(*val, output);
+        }
+        Json::NUMBER(val) => {
+            output.push(0xcb);
+            output.extend_from_slice(&val.to_be_bytes());
+        }
+        Json::BOOL(false) => output.push(0xc2),
+        Json::BOOL(true) => output.push(0xc3),
+        Json::NULL => output.push(0xc0),
+    }
+}
+
+struct Decoder<'a> {
+    input: &'a [u8],
+    position: usize,
+    // How many maps and arrays enclose the value being decoded.
+    depth: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn error(&self, message: &'static str) -> MsgPackError {
+        MsgPackError {
+            position: self.position,
+            message,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MsgPackError> {
+        match self
+            .input
+            .get(self.position..self.position.saturating_add(len))
+        {
+            Some(bytes) => {
+                self.position += len;
+                Ok(bytes)
+            }
+            None => Err(self.error("Unexpected end of input.")),
+        }
+    }
+
+    fn bytes<const N: usize>(&mut self) -> Result<[u8; N], MsgPackError> {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+
+    // Reads an 8, 16 or 32 bit length.
+    fn length(&mut self, size: usize) -> Result<usize, MsgPackError> {
+        Ok(match size {
+            1 => self.bytes::<1>()?[0] as usize,
+            2 => u16::from_be_bytes(self.bytes()?) as usize,
+            _ => u32::from_be_bytes(self.bytes()?) as usize,
+        })
+    }
+
+    fn string(&mut self, len: usize) -> Result<Json, MsgPackError> {
+        let position = self.position;
+
+        match String::from_utf8(self.take(len)?.to_vec()) {
+            Ok(val) => Ok(Json::STRING(val)),
+            Err(_) => Err(MsgPackError {
+                position,
+                message: "String is not valid UTF-8.",
+            }),
+        }
+    }
+
+    fn binary(&mut self, len: usize) -> Result<Json, MsgPackError> {
+        Ok(Json::ARRAY(
+            self.take(len)?
+                .iter()
+                .map(|byte| Json::NUMBER(*byte as f64))
+                .collect(),
+        ))
+    }
+
+    fn array(&mut self, len: usize) -> Result<Json, MsgPackError> {
+        // Every value takes at least one byte, which bounds any valid length.
+        if len > self.input.len() - self.position {
+            return Err(self.error("Length exceeds the remaining input."));
+        }
+
+        let mut values = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            values.push(self.decode()?);
+        }
+
+        Ok(Json::ARRAY(values))
+    }
+
+    fn map(&mut self, len: usize) -> Result<Json, MsgPackError> {
+        if len > self.input.len() - self.position {
+            return Err(self.error("Length exceeds the remaining input."));
+        }
+
+        let mut values = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            let position = self.position;
+
+            let name = match self.decode()? {
+                Json::STRING(name) => name,
+                Json::NUMBER(val) if val.fract() == 0.0 => val.to_string(),
+                _ => {
+                    return Err(MsgPackError {
+                        position,
+                        message: "Map keys must be strings or integers.",
+                    })
+                }
+            };
+
+            values.push(Json::member(name, self.decode()?));
+        }
+
+        Ok(Json::JSON(values))
+    }
+
+    fn decode(&mut self) -> Result<Json, MsgPackError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Too deeply nested."));
+        }
+
+        self.depth += 1;
+
+        let json = self.item();
+
+        self.depth -= 1;
+
+        json
+    }
+
+    fn item(&mut self) -> Result<Json, MsgPackError> {
+        let marker = self.bytes::<1>()?[0];
+
+        match marker {
+            0x00..=0x7f => Ok(Json::NUMBER(marker as f64)),
+            0x80..=0x8f => self.map((marker & 0x0f) as usize),
+            0x90..=0x9f => self.array((marker & 0x0f) as usize),
+            0xa0..=0xbf => self.string((marker & 0x1f) as usize),
+            0xc0 => Ok(Json::NULL),
+            0xc2 => Ok(Json::BOOL(false)),
+            0xc3 => Ok(Json::BOOL(true)),
+            0xc4..=0xc6 => {
+                let len = self.length(1 << (marker - 0xc4))?;
+                self.binary(len)
+            }
+            0xca => Ok(Json::NUMBER(f32::from_be_bytes(self.bytes()?) as f64)),
+            0xcb => Ok(Json::NUMBER(f64::from_be_bytes(self.bytes()?))),
+            0xcc => Ok(Json::NUMBER(self.bytes::<1>()?[0] as f64)),
+            0xcd => Ok(Json::NUMBER(u16::from_be_bytes(self.bytes()?) as f64)),
+            0xce => Ok(Json::NUMBER(u32::from_be_bytes(self.bytes()?) as f64)),
+            0xcf => Ok(Json::NUMBER(u64::from_be_bytes(self.bytes()?) as f64)),
+            0xd0 => Ok(Json::NUMBER(i8::from_be_bytes(self.bytes()?) as f64)),
+            0xd1 => Ok(Json::NUMBER(i16::from_be_bytes(self.bytes()?) as f64)),
+            0xd2 => Ok(Json::NUMBER(i32::from_be_bytes(self.bytes()?) as f64)),
+            0xd3 => Ok(Json::NUMBER(i64::from_be_bytes(self.bytes()?) as f64)),
+            0xd9..=0xdb => {
+                let len = self.length(1 << (marker - 0xd9))?;
+                self.string(len)
+            }
+            0xdc | 0xdd => {
+                let len = self.length(2 << (marker - 0xdc))?;
+                self.array(len)
+            }
+            0xde | 0xdf => {
+                let len = self.length(2 << (marker - 0xde))?;
+                self.map(len)
+            }
+            0xe0..=0xff => Ok(Json::NUMBER(marker as i8 as f64)),
+            0xc7..=0xc9 | 0xd4..=0xd8 => {
+                self.position -= 1;
+                Err(self.error("Extension types are not supported."))
+            }
+            _ => {
+                self.position -= 1;
+                Err(self.error("Invalid marker."))
+            }
+        }
+    }
+}
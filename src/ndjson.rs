@@ -0,0 +1,178 @@
+//! Newline-delimited JSON (NDJSON, also known as JSON Lines), i.e. one value per line.
+
+use crate::{is_whitespace, ErrorKind, Json, LazyJson, ParseError};
+use std::io::{self, BufRead, Write};
+
+/// An iterator over the values of an NDJSON input. See `Json::parse_ndjson`.
+#[derive(Debug)]
+pub struct NdjsonReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    line: usize,
+    // Set after an I/O error, which would most likely just repeat.
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for NdjsonReader<R> {
+    type Item = Result<Json, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Json, ParseError>> {
+        while !self.done {
+            self.buffer.clear();
+
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    self.line += 1;
+
+                    if let Some(result) = parse_line(&self.buffer, self.line) {
+                        return Some(result);
+                    }
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    self.done = true;
+
+                    return Some(Err(ParseError::io(self.line + 1, error)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<R> NdjsonReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl Json {
+    /// Reads newline-delimited JSON from `reader`, yielding one value per line. Blank lines are
+    /// skipped, as is whitespace around the values (including the `\r` of `\r\n`). A line that isn't a
+    /// single valid value yields an error with its line number and reading continues with the next
+    /// one. Reading stops after an I/O error (see `ParseError::io_error`).
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"id\":1}\n\n[1,2]\r\n{\"id\":}\n\"a\" \"b\"\n";
+    ///
+    /// let mut values = Json::parse_ndjson(&input[..]);
+    ///
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"{\"id\":1}").unwrap());
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"[1,2]").unwrap());
+    ///
+    /// let error = values.next().unwrap().unwrap_err();
+    ///
+    /// assert_eq!((error.line, error.position), (4, 6));
+    /// assert_eq!(values.next().unwrap().unwrap_err().to_string(), "`Unexpected data after the value.` at line `5`, position `4`.");
+    /// assert!(values.next().is_none());
+    /// ```
+    pub fn parse_ndjson<R: BufRead>(reader: R) -> NdjsonReader<R> {
+        NdjsonReader {
+            reader,
+            buffer: Vec::new(),
+            line: 0,
+            done: false,
+        }
+    }
+
+    /// Copies the records of the NDJSON read from `reader` for which `keep` returns `true` to
+    /// `writer`, one per line, returning how many there were. Each record is only located up front
+    /// (see `parse_lazy`), `keep` parses as much of it as it needs to. Blank lines are dropped, as is
+    /// whitespace around the records. Only a line at a time is held in memory, so that files of any
+    /// size can be filtered.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"level\":\"info\",\"msg\":\"a\"}\n\n{\"level\":\"error\",\"msg\":\"b\"}\r\n";
+    /// let mut output = Vec::new();
+    ///
+    /// let count = Json::filter_ndjson(&input[..], &mut output, |record| {
+    ///     record.get("level").and_then(|level| level.value().ok()) == Some(&Json::string("error"))
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(count, 1);
+    /// assert_eq!(output, b"{\"level\":\"error\",\"msg\":\"b\"}\n");
+    /// ```
+    /// ## Errors
+    /// Fails with the first line that doesn't hold a single value (see `parse_lazy`), or with the
+    /// first I/O error (see `ParseError::io_error`).
+    pub fn filter_ndjson<R, W, F>(
+        mut reader: R,
+        mut writer: W,
+        mut keep: F,
+    ) -> Result<usize, ParseError>
+    where
+        R: BufRead,
+        W: Write,
+        F: FnMut(&LazyJson<'_>) -> bool,
+    {
+        let mut buffer = Vec::new();
+        let mut line = 0;
+        let mut count = 0;
+
+        loop {
+            buffer.clear();
+
+            match reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => break,
+                Ok(_) => line += 1,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                Err(error) => return Err(ParseError::io(line + 1, error)),
+            }
+
+            if buffer.iter().all(|c| is_whitespace(*c)) {
+                continue;
+            }
+
+            // Positions are relative to the line.
+            let record = Json::parse_lazy(&buffer)
+                .map_err(|error| ParseError::new(line, error.position, error.kind))?;
+
+            if keep(&record) {
+                writer
+                    .write_all(record.raw())
+                    .and_then(|_| writer.write_all(b"\n"))
+                    .map_err(|error| ParseError::io(line, error))?;
+
+                count += 1;
+            }
+        }
+
+        writer
+            .flush()
+            .map_err(|error| ParseError::io(line, error))?;
+
+        Ok(count)
+    }
+}
+
+// Parses a line unless it is blank.
+pub(crate) fn parse_line(line: &[u8], number: usize) -> Option<Result<Json, ParseError>> {
+    let start = line.iter().position(|c| !is_whitespace(*c))?;
+    let end = line.iter().rposition(|c| !is_whitespace(*c))? + 1;
+
+    // Positions are relative to the line, so the parser gets all of it and starts at the value.
+    let line = &line[..end];
+    let mut incr = start;
+
+    let json = match Json::parse_value(line, &mut incr) {
+        Ok(json) => json,
+        Err((position, message)) => return Some(Err(ParseError::new(number, position, message))),
+    };
+
+    match line[incr..].iter().position(|c| !is_whitespace(*c)) {
+        Some(offset) => Some(Err(ParseError::new(
+            number,
+            incr + offset,
+            ErrorKind::TrailingData,
+        ))),
+        None => Some(Ok(json)),
+    }
+}
@@ -0,0 +1,126 @@
+//! Normalizing documents, so that the same content from different producers compares equal.
+
+use crate::order::sort_members;
+use crate::Json;
+use std::collections::HashMap;
+#[cfg(feature = "unicode-normalization")]
+use unicode_normalization::{is_nfc, UnicodeNormalization};
+
+/// Which of the members with the same name in an object `Json::normalize` keeps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// All of them.
+    Keep,
+    /// The first one, as most streaming parsers do.
+    First,
+    /// The last one, as JavaScript's `JSON.parse` does.
+    Last,
+}
+
+/// Controls `Json::normalize`. The default does everything it can: it keeps the last of the
+/// members with the same name and sorts them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NormalizeOptions {
+    pub duplicate_keys: DuplicateKeys,
+    /// Sorts the members of every object by name, like `Json::sort_keys_recursive`.
+    pub sort_keys: bool,
+    /// Brings every string and name of a member into Unicode Normalization Form C, so that e.g.
+    /// `"é"` and `"é"` become the same. Only with the `unicode-normalization` feature, without it
+    /// strings are left as they are whatever this says.
+    pub nfc: bool,
+}
+
+impl Default for NormalizeOptions {
+    fn default() -> NormalizeOptions {
+        NormalizeOptions {
+            duplicate_keys: DuplicateKeys::Last,
+            sort_keys: true,
+            nfc: true,
+        }
+    }
+}
+
+impl Json {
+    /// Normalizes `self` (and everything inside it) according to `options`, so that documents
+    /// from producers which write the same content differently compare equal afterwards: `-0`
+    /// becomes `0`, strings and names of members can be brought into Unicode Normalization Form C
+    /// (before members with the same name are looked for), members with the same name are
+    /// resolved as `options.duplicate_keys` says and members can be sorted by name.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut a = Json::parse(b"{\"b\":[0,{\"d\":1,\"c\":2}],\"a\":1,\"a\":2}").unwrap();
+    /// let mut b = Json::parse(b"{\"a\":2,\"b\":[0,{\"c\":2,\"d\":1}]}").unwrap();
+    ///
+    /// a.normalize(NormalizeOptions::default());
+    /// b.normalize(NormalizeOptions::default());
+    ///
+    /// assert_eq!(a, b);
+    /// assert_eq!(a.print(), "{\"a\":2,\"b\":[0,{\"c\":2,\"d\":1}]}");
+    /// ```
+    pub fn normalize(&mut self, options: NormalizeOptions) {
+        match self {
+            Json::NUMBER(number) if *number == 0.0 => *number = 0.0,
+            Json::STRING(string) => normalize_string(string, &options),
+            Json::OBJECT { name, value } => {
+                normalize_string(name, &options);
+                value.normalize(options);
+            }
+            Json::ARRAY(values) => {
+                for value in values {
+                    value.normalize(options);
+                }
+            }
+            Json::JSON(values) => {
+                for value in values.iter_mut() {
+                    value.normalize(options);
+                }
+
+                if options.duplicate_keys != DuplicateKeys::Keep {
+                    remove_duplicates(values, options.duplicate_keys);
+                }
+
+                if options.sort_keys {
+                    sort_members(values);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "unicode-normalization")]
+fn normalize_string(string: &mut String, options: &NormalizeOptions) {
+    if options.nfc && !is_nfc(string) {
+        *string = string.nfc().collect();
+    }
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+fn normalize_string(_: &mut String, _: &NormalizeOptions) {}
+
+// Removes all but the first or the last (as `keep` says) of the members with the same name.
+fn remove_duplicates(values: &mut Vec<Json>, keep: DuplicateKeys) {
+    let mut kept: HashMap<&str, usize> = HashMap::new();
+
+    for (n, json) in values.iter().enumerate() {
+        if let Json::OBJECT { name, value: _ } = json {
+            if keep == DuplicateKeys::Last || !kept.contains_key(name.as_str()) {
+                kept.insert(name, n);
+            }
+        }
+    }
+
+    let retained: Vec<bool> = values
+        .iter()
+        .enumerate()
+        .map(|(n, json)| match json {
+            Json::OBJECT { name, value: _ } => kept[name.as_str()] == n,
+            _ => true,
+        })
+        .collect();
+    let mut retained = retained.into_iter();
+
+    values.retain(|_| retained.next().unwrap_or(true));
+}
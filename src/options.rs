@@ -0,0 +1,78 @@
+//! Parsing within limits, e.g. to turn away untrusted payloads which would cost too much.
+
+use crate::parser::{self, Context};
+use crate::{Json, ParseError};
+
+/// Limits for `Json::parse_with`, none of which are set by default. Parsing stops as soon as one
+/// is exceeded, so that a payload crafted to blow up in memory (like a decompression bomb) can't.
+/// Also what it accepts besides standard JSON, see `allow_comments`, `strict_numbers` and
+/// `strict_syntax`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// How many values may be parsed, counted like `ParseReport::nodes`. Fails with
+    /// `"Too many values."`.
+    pub max_nodes: Option<usize>,
+    /// How many bytes all strings and names of members may take together, counted like
+    /// `ParseReport::string_bytes`. Fails with `"Too many string bytes."` at the string which
+    /// exceeds it.
+    pub max_string_bytes: Option<usize>,
+    /// How many bytes a single string or name of a member may take, escape sequences decoded.
+    /// Unlike `max_string_bytes`, it is checked while the string is copied, so that a huge one
+    /// fails before it takes up any more memory. Fails with `"String too long."` at the string.
+    pub max_string_len: Option<usize>,
+    /// Allows comments wherever whitespace is allowed inside an object or array, both `// ...` up
+    /// to the end of the line and `/* ... */`, as in JSONC. A number, `true`, `false` or `null`
+    /// needs whitespace, `,`, `]` or `}` after it before a comment.
+    pub allow_comments: bool,
+    /// Checks numbers against the grammar of JSON instead of taking whatever `f64` parses, failing
+    /// at the first byte which doesn't fit in: `"Leading zero in number."` for `007`, `"Missing
+    /// digit in number."` for `1.` or `1e` and `"Error parsing number."` for `1.2.3`. Also allows
+    /// negative numbers, which `parse` doesn't.
+    pub strict_numbers: bool,
+    /// Checks the colons and commas of objects and arrays, which `parse` doesn't, so that it takes
+    /// `{"a" 1}` or `[1 2]`. Fails with `"Expected a colon."` at a string in an object without a
+    /// `:` behind it, `"Trailing comma."` for `[1,]`, `"Extra comma."` for `[,1]` or `[1,,2]`,
+    /// `"Missing comma."` for `[1 2]` and `"Error parsing json."` at anything but a member in an
+    /// object. Also allows whitespace before the `:` of a member.
+    pub strict_syntax: bool,
+}
+
+impl Json {
+    /// Parses `input` like `parse`, within the limits of `options`. Unlike with `parse`, an error
+    /// also says which member or element failed (see `ParseError::path`), which helps to find it in
+    /// large documents.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let options = ParseOptions {
+    ///     max_nodes: Some(1000),
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// assert!(Json::parse_with(b"[1,2,3]", &options).is_ok());
+    ///
+    /// let bomb = format!("[{}]", "[],".repeat(2000));
+    /// let error = Json::parse_with(bomb.as_bytes(), &options).unwrap_err();
+    ///
+    /// assert_eq!((error.position, error.message), (3003, "Too many values."));
+    ///
+    /// let input = b"{\"config\":{\"retries\":tree}}";
+    /// let error = Json::parse_with(input, &options).unwrap_err();
+    ///
+    /// assert_eq!(error.path.as_deref(), Some("config.retries"));
+    /// assert!(error
+    ///     .to_string()
+    ///     .ends_with("position `25`, while parsing the value of `config.retries`."));
+    /// ```
+    pub fn parse_with(input: &[u8], options: &ParseOptions) -> Result<Json, ParseError> {
+        let mut context = Context {
+            options: *options,
+            ..Context::default()
+        };
+        let mut incr = 0;
+
+        parser::parse_with(input, &mut incr, &mut context)
+            .map_err(|error| context.error(input, error))
+    }
+}
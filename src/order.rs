@@ -0,0 +1,261 @@
+//! Ordering of the members of objects.
+
+use crate::{Json, VariantError, Walk};
+use std::cmp::Ordering;
+use std::iter;
+
+impl Json {
+    /// Inserts a member named `name` into a `Json::JSON` (or a `Json::OBJECT` holding one) so that
+    /// it becomes the member at `index` (see `get_index`), e.g. to keep `"name"` at the top of a
+    /// generated config after adding to it. A member of the same name is taken out first, and its
+    /// value returned.
+    /// ## Panics
+    /// Will panic if called on any other variant, or if `index` is greater than the number of the
+    /// other members, in both cases before changing anything. Use `try_insert_at` (see below) if
+    /// this is not acceptable.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"version\":1,\"debug\":true}").unwrap();
+    ///
+    /// assert_eq!(json.insert_at(0, "name", Json::string("app")), None);
+    /// assert_eq!(json.insert_at(2, "version", Json::number(2.0)), Some(Json::number(1.0)));
+    ///
+    /// assert_eq!(json.print(), "{\"name\":\"app\",\"debug\":true,\"version\":2}");
+    /// ```
+    pub fn insert_at<S: Into<String>>(
+        &mut self,
+        index: usize,
+        name: S,
+        value: Json,
+    ) -> Option<Json> {
+        let name = name.into();
+
+        if let Json::JSON(values) = self.inner() {
+            let count = others(values, &name);
+
+            assert!(
+                index <= count,
+                "insertion index (is {}) should be <= number of members (is {})",
+                index,
+                count
+            );
+        }
+
+        self.try_insert_at(index, name, value)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `insert_at` above, but returns a `VariantError` instead of panicking if called on a
+    /// variant other than `Json::JSON` or a `Json::OBJECT` holding one. An `index` greater than the
+    /// number of the other members puts the member at the end instead of panicking.
+    pub fn try_insert_at<S: Into<String>>(
+        &mut self,
+        index: usize,
+        name: S,
+        value: Json,
+    ) -> Result<Option<Json>, VariantError> {
+        let values = match self.inner_mut() {
+            Json::JSON(values) => values,
+            json => return Err(VariantError::new("insert_at", json)),
+        };
+        let name = name.into();
+        let index = index.min(others(values, &name));
+
+        let previous = values
+            .iter()
+            .position(|json| is_named(json, &name))
+            .map(|n| values.remove(n).into_inner());
+
+        let at = position(values, index).unwrap_or(values.len());
+
+        values.insert(at, Json::member(name, value));
+
+        Ok(previous)
+    }
+
+    /// Moves the member named `name` of a `Json::JSON` (or a `Json::OBJECT` holding one) so that it
+    /// becomes the member at `index` (see `get_index`), e.g. when it was dragged in an editor.
+    /// Returns `false` if there is no such member. Of several members of the same name the first is
+    /// moved.
+    /// ## Panics
+    /// Will panic if called on any other variant, or if there is such a member and `index` isn't less
+    /// than the number of members. Use `try_move_key` (see below) if this is not acceptable.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"a\":1,\"b\":2,\"c\":3}").unwrap();
+    ///
+    /// assert!(json.move_key("c", 0));
+    /// assert!(json.move_key("a", 2));
+    /// assert!(!json.move_key("d", 0));
+    ///
+    /// assert_eq!(json.print(), "{\"c\":3,\"b\":2,\"a\":1}");
+    /// ```
+    pub fn move_key(&mut self, name: &str, index: usize) -> bool {
+        if let Json::JSON(values) = self.inner() {
+            let count = others(values, name);
+
+            assert!(
+                index <= count || !values.iter().any(|json| is_named(json, name)),
+                "index (is {}) should be < number of members (is {})",
+                index,
+                count + 1
+            );
+        }
+
+        self.try_move_key(name, index)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `move_key` above, but returns a `VariantError` instead of panicking if called on a
+    /// variant other than `Json::JSON` or a `Json::OBJECT` holding one. Also returns `false`, leaving
+    /// the member where it is, if `index` isn't less than the number of members.
+    pub fn try_move_key(&mut self, name: &str, index: usize) -> Result<bool, VariantError> {
+        let values = match self.inner_mut() {
+            Json::JSON(values) => values,
+            json => return Err(VariantError::new("move_key", json)),
+        };
+
+        let from = match values.iter().position(|json| is_named(json, name)) {
+            Some(from) if index <= others(values, name) => from,
+            _ => return Ok(false),
+        };
+        let member = values.remove(from);
+
+        let at = position(values, index).unwrap_or(values.len());
+
+        values.insert(at, member);
+
+        Ok(true)
+    }
+
+    /// Sorts the members of a `Json::JSON` (or a `Json::OBJECT` holding one) by comparing their
+    /// names with `compare`, leaving the objects inside as they are (see `sort_keys_recursive`).
+    /// The sort is stable, and unnamed values are moved behind the named members.
+    /// ## Panics
+    /// Will panic if called on any other variant. Use `try_sort_keys_by` (see below) if this is not
+    /// acceptable.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"b\":1,\"id\":2,\"a\":{\"d\":3,\"c\":4}}").unwrap();
+    ///
+    /// // `id` first, then the rest by name.
+    /// json.sort_keys_by(|a, b| (a != "id").cmp(&(b != "id")).then(a.cmp(b)));
+    ///
+    /// assert_eq!(json.print(), "{\"id\":2,\"a\":{\"d\":3,\"c\":4},\"b\":1}");
+    /// ```
+    pub fn sort_keys_by<F>(&mut self, compare: F)
+    where
+        F: FnMut(&str, &str) -> Ordering,
+    {
+        self.try_sort_keys_by(compare)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Same as `sort_keys_by` above, but returns a `VariantError` instead of panicking.
+    pub fn try_sort_keys_by<F>(&mut self, compare: F) -> Result<(), VariantError>
+    where
+        F: FnMut(&str, &str) -> Ordering,
+    {
+        match self.inner_mut() {
+            Json::JSON(values) => {
+                sort_members_by(values, compare);
+
+                Ok(())
+            }
+            json => Err(VariantError::new("sort_keys_by", json)),
+        }
+    }
+
+    /// Sorts the members of every object in `self` (including `self` and objects inside arrays) by
+    /// name. The sort is stable, so members with the same name keep their order. Unnamed values are
+    /// moved behind the named members. Useful for normalizing documents before comparing or committing them.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"b\":[{\"z\":1,\"y\":2}],\"a\":{\"d\":true,\"c\":false}}").unwrap();
+    ///
+    /// json.sort_keys_recursive();
+    ///
+    /// assert_eq!(json.print(), "{\"a\":{\"c\":false,\"d\":true},\"b\":[{\"y\":2,\"z\":1}]}");
+    /// ```
+    pub fn sort_keys_recursive(&mut self) {
+        self.walk_mut(|json| {
+            if let Json::JSON(values) = json {
+                sort_members(values);
+            }
+
+            Walk::Continue
+        });
+    }
+}
+
+// Sorts the members of an object by name, stably, moving unnamed values behind them.
+pub(crate) fn sort_members(values: &mut [Json]) {
+    sort_members_by(values, |a, b| a.cmp(b));
+}
+
+// Same as `sort_members`, comparing names with `compare`.
+fn sort_members_by<F>(values: &mut [Json], mut compare: F)
+where
+    F: FnMut(&str, &str) -> Ordering,
+{
+    values.sort_by(|a, b| match (a, b) {
+        (
+            Json::OBJECT {
+                name: a_name,
+                value: _,
+            },
+            Json::OBJECT {
+                name: b_name,
+                value: _,
+            },
+        ) => compare(a_name, b_name),
+        (a, b) => member_rank(a).cmp(&member_rank(b)),
+    });
+}
+
+// Named members go before unnamed values.
+fn member_rank(json: &Json) -> u8 {
+    match json {
+        Json::OBJECT { name: _, value: _ } => 0,
+        _ => 1,
+    }
+}
+
+fn is_member(json: &Json) -> bool {
+    matches!(json, Json::OBJECT { name: _, value: _ })
+}
+
+fn is_named(json: &Json, name: &str) -> bool {
+    matches!(json, Json::OBJECT { name: other, value: _ } if other == name)
+}
+
+// The number of members in `values` besides the first one named `name`, if there is one.
+fn others(values: &[Json], name: &str) -> usize {
+    let count = values.iter().filter(|json| is_member(json)).count();
+
+    if values.iter().any(|json| is_named(json, name)) {
+        count - 1
+    } else {
+        count
+    }
+}
+
+// Where the member at `index` (counting only members) is in `values`, or the end if `index` is the
+// number of members.
+fn position(values: &[Json], index: usize) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter(|(_, json)| is_member(json))
+        .map(|(n, _)| n)
+        .chain(iter::once(values.len()))
+        .nth(index)
+}
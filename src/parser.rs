@@ -0,0 +1,723 @@
+//! The parser behind `Json::parse`, a single loop over the input which keeps the containers and
+//! members it is in on a stack of its own instead of recursing into them.
+
+use crate::scan;
+#[cfg(feature = "schema")]
+use crate::schema::Node;
+use crate::stream::scan_string;
+use crate::{
+    truncated, ErrorKind, Json, ParseError, ParseOptions, ParseReport, Repair, RepairKind, Warning,
+    WarningKind,
+};
+
+// How many values or brackets are parsed between checks whether parsing was cancelled.
+pub(crate) const CANCEL_INTERVAL: usize = 4096;
+
+// How deeply objects and arrays may be nested. Parsing doesn't recurse, but dropping, printing,
+// cloning or comparing the `Json` returned does, so deeper input could overflow the stack later on.
+pub(crate) const MAX_DEPTH: usize = 1024;
+
+// What a parse is set up with, and what it collects besides the value.
+#[derive(Default)]
+pub(crate) struct Context<'k> {
+    pub(crate) options: ParseOptions,
+    // The values, strings and levels of nesting parsed.
+    pub(crate) report: ParseReport,
+    // If set, errors are collected here and parsing goes on behind them.
+    pub(crate) errors: Option<Vec<ParseError>>,
+    // If set, warnings are collected here.
+    pub(crate) warnings: Option<Vec<Warning>>,
+    // If set, common defects are repaired, collecting what was repaired here.
+    pub(crate) repairs: Option<Vec<Repair>>,
+    // The path to the value which failed, set along with the error returned.
+    pub(crate) path: Option<String>,
+    // If set, parsing fails once this returns `true`.
+    pub(crate) cancelled: Option<&'k mut dyn FnMut() -> bool>,
+    // If set, every value is checked against this schema once it is complete.
+    #[cfg(feature = "schema")]
+    pub(crate) schema: Option<&'k Node>,
+}
+
+impl Context<'_> {
+    // The error returned by `parse_with` as a `ParseError`, naming the path to the value which
+    // failed.
+    pub(crate) fn error(
+        &mut self,
+        input: &[u8],
+        (position, kind): (usize, ErrorKind),
+    ) -> ParseError {
+        error(input, position, kind, self.path.take())
+    }
+}
+
+// A container or member entered but not yet left.
+enum Frame {
+    // An object or an array, by its closing bracket, with the values parsed so far.
+    Container(u8, Vec<Json>),
+    // The name of a member whose value is being parsed, and where it starts.
+    Member(String, usize),
+}
+
+// Where the next value is expected.
+#[derive(Clone, Copy)]
+enum State {
+    // At the start, where nothing may come before the value.
+    Top,
+    // Behind the `:` of a member.
+    Member,
+    // Inside a container, where commas are skipped and its closing bracket may come instead.
+    Container,
+}
+
+struct Parser<'a, 'c, 'k> {
+    input: &'a [u8],
+    stack: Vec<Frame>,
+    state: State,
+    depth: usize,
+    // Where the value or bracket parsed last starts.
+    start: usize,
+    // Where the commas skipped since then are, if warnings are collected.
+    commas: Vec<usize>,
+    // The values and brackets parsed, to check whether parsing was cancelled every so often.
+    steps: usize,
+    context: &'c mut Context<'k>,
+}
+
+// Parses the value starting at `input[*incr]`, leaving `incr` just behind it.
+pub(crate) fn parse(input: &[u8], incr: &mut usize) -> Result<Json, (usize, ErrorKind)> {
+    parse_with(input, incr, &mut Context::default())
+}
+
+// Like `parse`, set up by `context`. If errors are to be collected, only exceeding a limit fails.
+pub(crate) fn parse_with(
+    input: &[u8],
+    incr: &mut usize,
+    context: &mut Context,
+) -> Result<Json, (usize, ErrorKind)> {
+    let mut parser = Parser {
+        input,
+        stack: Vec::new(),
+        state: State::Top,
+        depth: 0,
+        start: *incr,
+        commas: Vec::new(),
+        steps: 0,
+        context,
+    };
+
+    loop {
+        let (position, kind) = match parser.step(incr) {
+            Ok(Some(json)) => return Ok(json),
+            Ok(None) => continue,
+            Err(error) => error,
+        };
+
+        let path = parser.path();
+
+        match &mut parser.context.errors {
+            Some(errors) if !stops(kind) => errors.push(error(input, position, kind, path)),
+            _ => {
+                parser.context.path = path;
+
+                return Err((position, kind));
+            }
+        }
+
+        if let Some(json) = parser.recover(incr)? {
+            return Ok(json);
+        }
+    }
+}
+
+impl<'k> Parser<'_, '_, 'k> {
+    // Parses the next value or bracket, returning the value at the top once it is complete.
+    fn step(&mut self, incr: &mut usize) -> Result<Option<Json>, (usize, ErrorKind)> {
+        let input = self.input;
+
+        self.steps += 1;
+
+        if let Some(cancelled) = &mut self.context.cancelled {
+            if self.steps % CANCEL_INTERVAL == 0 && cancelled() {
+                return Err((*incr, ErrorKind::Cancelled));
+            }
+        }
+
+        let close = match (self.state, self.stack.last()) {
+            (State::Container, Some(Frame::Container(close, _))) => Some(*close),
+            _ => None,
+        };
+
+        let c = match self.state {
+            State::Top => Ok(input.get(*incr).copied()),
+            State::Member => self.skip(incr, false),
+            State::Container => self.skip(incr, true),
+        };
+
+        self.start = *incr;
+
+        let c = match c? {
+            Some(c) => c,
+            None => return Err((*incr, ErrorKind::UnexpectedEof)),
+        };
+
+        if let State::Container = self.state {
+            self.check_commas(Some(c) == close)?;
+        }
+
+        if let Some(end) = self.unquoted_key(c) {
+            if exceeds(end - *incr, self.context.options.max_string_len) {
+                return Err((*incr, ErrorKind::StringTooLong));
+            }
+
+            let name = input[*incr..end].iter().map(|c| *c as char).collect();
+
+            *incr = end;
+            self.repair(RepairKind::UnquotedKey);
+            self.string(name, incr)?;
+
+            return Ok(None);
+        }
+
+        // Anything but a member in an object, e.g. `{1}`.
+        if self.context.options.strict_syntax && close == Some(b'}') && !matches!(c, b'\"' | b'}') {
+            return Err((*incr, ErrorKind::InvalidObject));
+        }
+
+        let value = match c {
+            b'{' | b'[' => {
+                let close = if c == b'{' { b'}' } else { b']' };
+
+                #[cfg(feature = "schema")]
+                if let Some(node) = self.schema() {
+                    let empty = if c == b'{' { Json::JSON } else { Json::ARRAY };
+
+                    if !node.allows(&empty(Vec::new())) {
+                        return Err((*incr, ErrorKind::SchemaViolation));
+                    }
+                }
+
+                if self.depth == MAX_DEPTH {
+                    return Err((*incr, ErrorKind::TooDeep));
+                }
+
+                self.stack.push(Frame::Container(close, Vec::new()));
+                self.state = State::Container;
+                *incr += 1;
+
+                self.depth += 1;
+                self.context.report.depth = self.context.report.depth.max(self.depth);
+
+                return Ok(None);
+            }
+            b'\"' => {
+                let max_len = self.context.options.max_string_len;
+                let string = Json::parse_quoted(input, incr, b'\"', max_len)?;
+
+                match self.string(string, incr)? {
+                    Some(json) => json,
+                    None => return Ok(None),
+                }
+            }
+            b'\'' if self.context.repairs.is_some() => {
+                let max_len = self.context.options.max_string_len;
+                let string = Json::parse_quoted(input, incr, b'\'', max_len)?;
+
+                self.repair(RepairKind::SingleQuotes);
+
+                match self.string(string, incr)? {
+                    Some(json) => json,
+                    None => return Ok(None),
+                }
+            }
+            b't' | b'f' => Json::parse_bool(input, incr)?,
+            b'n' => Json::parse_null(input, incr)?,
+            b'0'..=b'9' => self.number(incr)?,
+            b'-' if self.context.options.strict_numbers => self.number(incr)?,
+            b'T' | b'F' | b'N' if self.context.repairs.is_some() => {
+                let value = match Json::parse_token(input, incr) {
+                    b"True" => Json::BOOL(true),
+                    b"False" => Json::BOOL(false),
+                    b"None" => Json::NULL,
+                    token if truncated(input, *incr, token) => {
+                        return Err((*incr, ErrorKind::UnexpectedEof));
+                    }
+                    _ => return Err((self.start, message(self.state, close))),
+                };
+
+                self.repair(RepairKind::PythonLiteral);
+
+                value
+            }
+            c if Some(c) == close => {
+                *incr += 1;
+
+                self.close()
+            }
+            _ => return Err((*incr, message(self.state, close))),
+        };
+
+        self.complete(value, *incr)
+    }
+
+    // Parses a number, checking it against the grammar of JSON first if `strict_numbers` is set.
+    fn number(&mut self, incr: &mut usize) -> Result<Json, (usize, ErrorKind)> {
+        if self.context.options.strict_numbers {
+            check_number(self.input, *incr)?;
+        }
+
+        Json::parse_number(self.input, incr)
+    }
+
+    // Counts `string`, which is the name of a member if a `:` follows, entering the member, or a
+    // string value, which is returned.
+    fn string(
+        &mut self,
+        string: String,
+        incr: &mut usize,
+    ) -> Result<Option<Json>, (usize, ErrorKind)> {
+        let report = &mut self.context.report;
+
+        report.string_bytes += string.len();
+
+        if exceeds(report.string_bytes, self.context.options.max_string_bytes) {
+            return Err((self.start, ErrorKind::TooManyStringBytes));
+        }
+
+        let strict = self.context.options.strict_syntax;
+        let mut colon = *incr;
+
+        if strict {
+            skip(self.input, &mut colon, false);
+        }
+
+        if self.input.get(colon) == Some(&b':') {
+            self.stack.push(Frame::Member(string, self.start));
+            self.state = State::Member;
+            *incr = colon + 1;
+
+            return Ok(None);
+        }
+
+        let object = matches!(
+            (self.state, self.stack.last()),
+            (State::Container, Some(Frame::Container(b'}', _)))
+        );
+
+        if strict && object {
+            return Err((colon, ErrorKind::ExpectedColon));
+        }
+
+        Ok(Some(Json::STRING(string)))
+    }
+
+    // The end of the name of a member starting with `c` which isn't quoted, if repairing and there
+    // is one, like `retries` in `retries:3`.
+    fn unquoted_key(&self, c: u8) -> Option<usize> {
+        let identifier = |c: &u8| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$');
+
+        if self.context.repairs.is_none() || c.is_ascii_digit() || !identifier(&c) {
+            return None;
+        }
+
+        let end = self.start
+            + self.input[self.start..]
+                .iter()
+                .take_while(|c| identifier(c))
+                .count();
+
+        (self.input.get(end) == Some(&b':')).then_some(end)
+    }
+
+    fn repair(&mut self, kind: RepairKind) {
+        if let Some(repairs) = &mut self.context.repairs {
+            repairs.push(Repair::at(self.input, self.start, kind));
+        }
+    }
+
+    // Skips whitespace (and commas, if `commas` is set) as well as comments if they are allowed,
+    // returning the byte behind them, if any.
+    fn skip(&mut self, incr: &mut usize, commas: bool) -> Result<Option<u8>, (usize, ErrorKind)> {
+        let input = self.input;
+        // The commas are only skipped one by one to be checked.
+        let count =
+            commas && (self.context.warnings.is_some() || self.context.options.strict_syntax);
+
+        loop {
+            match skip(input, incr, commas && !count) {
+                Some(b',') if count => {
+                    self.commas.push(*incr);
+                    *incr += 1;
+                }
+                Some(b'/') if self.context.options.allow_comments => {
+                    let start = *incr;
+
+                    *incr = match input.get(start + 1) {
+                        Some(b'/') => {
+                            scan::find(&input[start..], b"\n").map_or(input.len(), |n| start + n)
+                        }
+                        Some(b'*') => {
+                            match input[start + 2..].windows(2).position(|w| w == b"*/") {
+                                Some(n) => start + n + 4,
+                                None => return Err((start, ErrorKind::UnterminatedComment)),
+                            }
+                        }
+                        _ => return Ok(Some(b'/')),
+                    };
+
+                    self.warn(start, WarningKind::Comment);
+                }
+                c => return Ok(c),
+            }
+        }
+    }
+
+    // Warns about the commas skipped before a value or, if `closing`, before the closing bracket of
+    // the container at the top, or fails if checking the syntax.
+    fn check_commas(&mut self, closing: bool) -> Result<(), (usize, ErrorKind)> {
+        let strict = self.context.options.strict_syntax;
+
+        if self.context.warnings.is_none() && !strict {
+            return Ok(());
+        }
+
+        let empty = match self.stack.last() {
+            Some(Frame::Container(_, values)) => values.is_empty(),
+            _ => return Ok(()),
+        };
+
+        let warning = match (self.commas.as_slice(), closing, empty) {
+            ([first, ..], true, false) => Some((*first, WarningKind::TrailingComma)),
+            ([first, ..], _, true) => Some((*first, WarningKind::ExtraComma)),
+            ([_, second, ..], false, false) => Some((*second, WarningKind::ExtraComma)),
+            ([], false, false) => Some((self.start, WarningKind::MissingComma)),
+            _ => None,
+        };
+
+        self.commas.clear();
+
+        match warning {
+            Some((position, kind)) if strict => {
+                let kind = match kind {
+                    WarningKind::TrailingComma => ErrorKind::TrailingComma,
+                    WarningKind::ExtraComma => ErrorKind::ExtraComma,
+                    _ => ErrorKind::MissingComma,
+                };
+
+                Err((position, kind))
+            }
+            Some((position, kind)) => {
+                self.warn(position, kind);
+
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn warn(&mut self, index: usize, kind: WarningKind) {
+        if let Some(warnings) = &mut self.context.warnings {
+            warnings.push(Warning::at(self.input, index, kind));
+        }
+    }
+
+    // Where the value being parsed is, like `servers[2].port`, if inside an object or array.
+    fn path(&self) -> Option<String> {
+        let mut path = String::new();
+
+        for frame in &self.stack {
+            match frame {
+                Frame::Container(b']', values) => path.push_str(&format!("[{}]", values.len())),
+                Frame::Container(..) => {}
+                Frame::Member(name, _) => {
+                    if !path.is_empty() {
+                        path.push('.');
+                    }
+
+                    path.push_str(name);
+                }
+            }
+        }
+
+        Some(path).filter(|path| !path.is_empty())
+    }
+
+    // The schema of the value being parsed, if there is one for it. The schema of a member applies
+    // to its value, and names of members only matter inside objects.
+    #[cfg(feature = "schema")]
+    fn schema(&self) -> Option<&'k Node> {
+        let mut node = self.context.schema?;
+        // Whether `node` is the schema of the object the next member is in.
+        let mut object = false;
+
+        for frame in &self.stack {
+            match frame {
+                Frame::Container(b'}', _) => object = true,
+                Frame::Container(..) => node = node.items()?,
+                Frame::Member(name, _) if object => {
+                    node = node.property(name)?;
+                    object = false;
+                }
+                Frame::Member(..) => {}
+            }
+        }
+
+        // Unnamed values in objects have none.
+        (!object).then_some(node)
+    }
+
+    // Pops the container at the top of the stack as a value.
+    fn close(&mut self) -> Json {
+        self.depth -= 1;
+
+        match self.stack.pop() {
+            Some(Frame::Container(b'}', values)) => Json::JSON(values),
+            Some(Frame::Container(_, values)) => Json::ARRAY(values),
+            _ => unreachable!(),
+        }
+    }
+
+    // Completes the members `value` is the value of, then puts it into the container they are in,
+    // returning it instead if there is none.
+    fn complete(
+        &mut self,
+        mut value: Json,
+        incr: usize,
+    ) -> Result<Option<Json>, (usize, ErrorKind)> {
+        #[cfg(feature = "schema")]
+        if let Some(node) = self.schema() {
+            if !node.conforms(&value) {
+                return Err((self.start, ErrorKind::SchemaViolation));
+            }
+        }
+
+        self.state = State::Container;
+        // Where the outermost member completed starts.
+        let mut start = None;
+
+        loop {
+            self.context.report.nodes += 1;
+
+            if exceeds(self.context.report.nodes, self.context.options.max_nodes) {
+                return Err((incr, ErrorKind::TooManyValues));
+            }
+
+            match self.stack.last_mut() {
+                Some(Frame::Container(close, values)) => {
+                    let duplicate = match (&value, start) {
+                        (Json::OBJECT { name, .. }, Some(start))
+                            if *close == b'}' && self.context.warnings.is_some() =>
+                        {
+                            values
+                                .iter()
+                                .any(|json| match json {
+                                    Json::OBJECT { name: other, .. } => other == name,
+                                    _ => false,
+                                })
+                                .then_some(start)
+                        }
+                        _ => None,
+                    };
+
+                    values.push(value);
+
+                    if let Some(start) = duplicate {
+                        self.warn(start, WarningKind::DuplicateKey);
+                    }
+
+                    return Ok(None);
+                }
+                Some(Frame::Member(..)) => {
+                    if let Some(Frame::Member(name, position)) = self.stack.pop() {
+                        start = Some(position);
+                        value = Json::OBJECT {
+                            name,
+                            value: Box::new(value),
+                        };
+                    }
+                }
+                None => return Ok(Some(value)),
+            }
+        }
+    }
+
+    // Gets past the value which failed, dropping it along with the members it is the value of.
+    // Inside a container, the input is skipped up to the next comma or closing bracket. A closing
+    // bracket of a container further out closes the ones in between, any other one is skipped. The
+    // containers still open at the end of the input are closed. Returns the value at the top once
+    // it is complete.
+    fn recover(&mut self, incr: &mut usize) -> Result<Option<Json>, (usize, ErrorKind)> {
+        while let Some(Frame::Member(..)) = self.stack.last() {
+            self.stack.pop();
+        }
+
+        // Nothing is left of the value at the top.
+        if self.stack.is_empty() {
+            return Ok(Some(Json::NULL));
+        }
+
+        self.state = State::Container;
+        *incr = self.start;
+
+        match self.input.get(self.start).copied() {
+            Some(c @ (b'}' | b']')) => {
+                let outer = self.stack.iter().rposition(|frame| match frame {
+                    Frame::Container(close, _) => *close == c,
+                    Frame::Member(..) => false,
+                });
+
+                match outer {
+                    // The container it belongs to is further out.
+                    Some(outer) if outer + 1 < self.stack.len() => {
+                        let value = self.close();
+
+                        self.complete(value, *incr)
+                    }
+                    // It belongs to the container it is in, behind a member without a value.
+                    Some(_) => Ok(None),
+                    None => {
+                        *incr += 1;
+
+                        Ok(None)
+                    }
+                }
+            }
+            Some(_) => {
+                *incr = boundary(self.input, self.start);
+
+                if *incr < self.input.len() {
+                    return Ok(None);
+                }
+
+                self.close_all(*incr)
+            }
+            None => self.close_all(*incr),
+        }
+    }
+
+    // Closes all the containers still open, returning the value at the top.
+    fn close_all(&mut self, incr: usize) -> Result<Option<Json>, (usize, ErrorKind)> {
+        loop {
+            let value = self.close();
+
+            if let Some(json) = self.complete(value, incr)? {
+                return Ok(Some(json));
+            }
+        }
+    }
+}
+
+// Skips whitespace (and commas, if `commas` is set), returning the byte behind it, if any.
+fn skip(input: &[u8], incr: &mut usize, commas: bool) -> Option<u8> {
+    let rest = &input[*incr..];
+    let set: &[u8] = if commas { b" \t\r\n," } else { b" \t\r\n" };
+
+    match scan::skip(rest, set) {
+        Some(n) => {
+            *incr += n;
+
+            Some(rest[n])
+        }
+        None => {
+            *incr = input.len();
+
+            None
+        }
+    }
+}
+
+// The next comma or closing bracket from `start` on which isn't inside a string or a container, or
+// the end of the input.
+fn boundary(input: &[u8], start: usize) -> usize {
+    let mut depth = 0;
+    let mut n = start;
+
+    while n < input.len() {
+        match input[n] {
+            b'\"' => {
+                n = scan_string(input, n).unwrap_or(input.len());
+
+                continue;
+            }
+            b',' | b'}' | b']' if depth == 0 => return n,
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => depth -= 1,
+            _ => {}
+        }
+
+        n += 1;
+    }
+
+    input.len()
+}
+
+fn error(input: &[u8], position: usize, kind: ErrorKind, path: Option<String>) -> ParseError {
+    let mut error = ParseError::at(input, position, kind);
+
+    error.path = path;
+    error
+}
+
+// Checks the number at `start` against the grammar of JSON, failing at the first byte which
+// doesn't fit in: a digit behind a leading `0`, something other than a digit where one is needed,
+// as behind `-`, `.` or an exponent, or anything else before whatever ends the number.
+fn check_number(input: &[u8], start: usize) -> Result<(), (usize, ErrorKind)> {
+    let digits = |n: usize| {
+        let count = input[n..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+        match count {
+            0 if n == input.len() => Err((n, ErrorKind::UnexpectedEof)),
+            0 => Err((n, ErrorKind::MissingDigit)),
+            _ => Ok(n + count),
+        }
+    };
+
+    let mut n = start + usize::from(input[start] == b'-');
+
+    if input.get(n) == Some(&b'0') && input.get(n + 1).is_some_and(u8::is_ascii_digit) {
+        return Err((n, ErrorKind::LeadingZero));
+    }
+
+    n = digits(n)?;
+
+    if input.get(n) == Some(&b'.') {
+        n = digits(n + 1)?;
+    }
+
+    if matches!(input.get(n), Some(b'e' | b'E')) {
+        n += 1;
+        n += usize::from(matches!(input.get(n), Some(b'+' | b'-')));
+        n = digits(n)?;
+    }
+
+    match input.get(n) {
+        None | Some(b',' | b']' | b'}' | b'\r' | b'\n' | b'\t' | b' ') => Ok(()),
+        Some(_) => Err((n, ErrorKind::InvalidNumber)),
+    }
+}
+
+fn exceeds(count: usize, limit: Option<usize>) -> bool {
+    limit.is_some_and(|limit| count > limit)
+}
+
+// Exceeding a limit or being cancelled stops parsing even if errors are collected.
+fn stops(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::TooManyValues
+            | ErrorKind::TooManyStringBytes
+            | ErrorKind::StringTooLong
+            | ErrorKind::Cancelled
+            | ErrorKind::TooDeep
+    )
+}
+
+// What an error says where a value doesn't start as one should, given the closing bracket of the
+// container it is in.
+fn message(state: State, close: Option<u8>) -> ErrorKind {
+    match (state, close) {
+        (State::Top, _) => ErrorKind::InvalidValue,
+        (State::Member, _) => ErrorKind::InvalidMember,
+        (State::Container, Some(b'}')) => ErrorKind::InvalidObject,
+        (State::Container, _) => ErrorKind::InvalidArray,
+    }
+}
@@ -0,0 +1,90 @@
+//! JSON Patch (RFC 6902) generation.
+
+use crate::diff::addressable;
+use crate::pointer;
+use crate::Json;
+
+impl Json {
+    /// Returns a JSON Patch (RFC 6902), i.e. a `Json::ARRAY` of operations like
+    /// `{"op":"replace","path":"/Greeting","value":"Hi"}`, which transforms `a` into `b`.
+    /// Members of objects are compared by name and arrays element by element, so only what
+    /// actually changed ends up in the patch. Objects holding unnamed values or the same name
+    /// more than once can't be addressed member by member and are replaced as a whole.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let a = Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Days\":[\"Monday\",\"Tuesday\"],\"Old\":true}").unwrap();
+    /// let b = Json::parse(b"{\"Greeting\":\"Hi!\",\"Days\":[\"Monday\",\"Tuesday\",\"Wednesday\"],\"New\":null}").unwrap();
+    ///
+    /// assert_eq!(
+    ///     Json::diff_patch(&a, &b).print(),
+    ///     "[{\"op\":\"replace\",\"path\":\"/Greeting\",\"value\":\"Hi!\"},{\"op\":\"add\",\"path\":\"/Days/2\",\"value\":\"Wednesday\"},{\"op\":\"remove\",\"path\":\"/Old\"},{\"op\":\"add\",\"path\":\"/New\",\"value\":null}]"
+    /// );
+    /// ```
+    pub fn diff_patch(a: &Json, b: &Json) -> Json {
+        let mut operations = Vec::new();
+
+        diff(String::new(), a.inner(), b.inner(), &mut operations);
+
+        Json::ARRAY(operations)
+    }
+}
+
+fn diff(path: String, a: &Json, b: &Json, operations: &mut Vec<Json>) {
+    match (a, b) {
+        (Json::JSON(a_values), Json::JSON(b_values))
+            if addressable(a_values) && addressable(b_values) =>
+        {
+            for (name, a_value) in a.iter() {
+                let name = name.unwrap_or_default();
+
+                match b.value_of(name) {
+                    Some(b_value) => diff(pointer::push(&path, name), a_value, b_value, operations),
+                    None => operations.push(operation("remove", pointer::push(&path, name), None)),
+                }
+            }
+
+            for (name, b_value) in b.iter() {
+                let name = name.unwrap_or_default();
+
+                if !a.contains_key(name) {
+                    operations.push(operation("add", pointer::push(&path, name), Some(b_value)));
+                }
+            }
+        }
+        (Json::ARRAY(a_values), Json::ARRAY(b_values)) => {
+            for (n, (a_value, b_value)) in a_values.iter().zip(b_values).enumerate() {
+                diff(format!("{}/{}", path, n), a_value, b_value, operations);
+            }
+
+            // Remove from the back so that the indices of the remaining elements stay valid.
+            for n in (b_values.len()..a_values.len()).rev() {
+                operations.push(operation("remove", format!("{}/{}", path, n), None));
+            }
+
+            for (n, b_value) in b_values.iter().enumerate().skip(a_values.len()) {
+                operations.push(operation("add", format!("{}/{}", path, n), Some(b_value)));
+            }
+        }
+        (a, b) => {
+            if a != b {
+                operations.push(operation("replace", path, Some(b)));
+            }
+        }
+    }
+}
+
+pub(crate) fn operation(op: &str, path: String, value: Option<&Json>) -> Json {
+    let mut operation = Json::object();
+
+    operation
+        .add(Json::member("op", Json::string(op)))
+        .add(Json::member("path", Json::string(path)));
+
+    if let Some(value) = value {
+        operation.add(Json::member("value", value.inner().clone()));
+    }
+
+    operation
+}
@@ -0,0 +1,194 @@
+//! JSON Pointer (RFC 6901) helpers, for building pointers to pass to `Json::pointer` and the
+//! like from names which may contain `/` or `~`.
+
+use crate::Json;
+use std::fmt;
+
+/// Escapes a single reference token, i.e. `~` becomes `~0` and `/` becomes `~1`.
+/// ## Example
+/// ```
+/// use json_minimal::pointer;
+///
+/// assert_eq!(pointer::escape("a/b~c"), "a~1b~0c");
+/// assert_eq!(pointer::unescape("a~1b~0c"), "a/b~c");
+/// ```
+pub fn escape(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+/// Appends `token` (escaped) to the pointer `path`.
+pub(crate) fn push(path: &str, token: &str) -> String {
+    format!("{}/{}", path, escape(token))
+}
+
+/// Reverses `escape`, i.e. `~1` becomes `/` and `~0` becomes `~`.
+pub fn unescape(token: &str) -> String {
+    token.replace("~1", "/").replace("~0", "~")
+}
+
+/// Splits `path` into its (unescaped) reference tokens. The empty pointer has none.
+pub(crate) fn tokens(path: &str) -> Vec<String> {
+    if path.is_empty() {
+        return Vec::new();
+    }
+
+    path.trim_start_matches('/')
+        .split('/')
+        .map(unescape)
+        .collect()
+}
+
+/// A JSON Pointer built a reference token at a time, each escaped as it is pushed, so that names
+/// containing `/` or `~` can't end up referring to something else.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+/// use json_minimal::pointer::PointerBuf;
+///
+/// let json = Json::parse(b"{\"paths\":{\"/users/{id}\":[\"get\",\"put\"]}}").unwrap();
+///
+/// let mut pointer = PointerBuf::new();
+///
+/// pointer.push("paths");
+/// pointer.push("/users/{id}");
+/// pointer.push_index(1);
+///
+/// assert_eq!(pointer.as_str(), "/paths/~1users~1{id}/1");
+/// assert_eq!(json.pointer(pointer.as_str()), Some(&Json::string("put")));
+///
+/// assert_eq!(pointer.pop(), Some(String::from("1")));
+/// assert_eq!(pointer.tokens(), vec!["paths", "/users/{id}"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PointerBuf {
+    path: String,
+}
+
+impl PointerBuf {
+    /// The empty pointer, which refers to the whole document.
+    pub fn new() -> PointerBuf {
+        PointerBuf::default()
+    }
+
+    /// Appends the name of a member, escaping it.
+    pub fn push(&mut self, token: &str) {
+        self.path.push('/');
+        self.path.push_str(&escape(token));
+    }
+
+    /// Appends the index of an element of an array.
+    pub fn push_index(&mut self, index: usize) {
+        self.push(&index.to_string());
+    }
+
+    /// Removes the last reference token and returns it unescaped, or `None` if there is none.
+    pub fn pop(&mut self) -> Option<String> {
+        let start = self.path.rfind('/')?;
+        let token = unescape(&self.path[start + 1..]);
+
+        self.path.truncate(start);
+
+        Some(token)
+    }
+
+    /// The pointer, escaped.
+    pub fn as_str(&self) -> &str {
+        &self.path
+    }
+
+    /// The reference tokens, unescaped.
+    pub fn tokens(&self) -> Vec<String> {
+        tokens(&self.path)
+    }
+}
+
+impl fmt::Display for PointerBuf {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.path)
+    }
+}
+
+impl AsRef<str> for PointerBuf {
+    fn as_ref(&self) -> &str {
+        &self.path
+    }
+}
+
+impl From<PointerBuf> for String {
+    fn from(pointer: PointerBuf) -> String {
+        pointer.path
+    }
+}
+
+impl Json {
+    /// Looks up the value `path` (a JSON Pointer) refers to, e.g. `/users/0/name` for the `name`
+    /// member of the first element of `users`. The empty pointer refers to `self`. A `Json::OBJECT`
+    /// on the way is resolved to its value, and of several members of the same name the first wins.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"users\":[{\"name\":\"Ada\"}],\"a/b\":1}").unwrap();
+    ///
+    /// assert_eq!(json.pointer("/users/0/name"), Some(&Json::string("Ada")));
+    /// assert_eq!(json.pointer("/a~1b"), Some(&Json::NUMBER(1.0)));
+    /// assert_eq!(json.pointer("/users/1"), None);
+    /// assert_eq!(json.pointer("users"), None);
+    /// ```
+    pub fn pointer(&self, path: &str) -> Option<&Json> {
+        if path.is_empty() {
+            return Some(self);
+        }
+
+        if !path.starts_with('/') {
+            return None;
+        }
+
+        path[1..].split('/').try_fold(self, |json, token| {
+            let token = unescape(token);
+
+            match json.inner() {
+                Json::JSON(values) => values.iter().find_map(|value| match value {
+                    Json::OBJECT { name, value } if *name == token => Some(&**value),
+                    _ => None,
+                }),
+                Json::ARRAY(values) => values.get(index(&token)?),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Same as `Json::pointer`, but the reference is mutable.
+pub(crate) fn resolve_mut<'a>(json: &'a mut Json, path: &str) -> Option<&'a mut Json> {
+    if path.is_empty() {
+        return Some(json);
+    }
+
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    path[1..].split('/').try_fold(json, |json, token| {
+        let token = unescape(token);
+
+        match json.inner_mut() {
+            Json::JSON(values) => values.iter_mut().find_map(|value| match value {
+                Json::OBJECT { name, value } if *name == token => Some(&mut **value),
+                _ => None,
+            }),
+            Json::ARRAY(values) => values.get_mut(index(&token)?),
+            _ => None,
+        }
+    })
+}
+
+/// The index of an element of an array `token` stands for. Indexes are plain decimal numbers
+/// without leading zeros.
+pub(crate) fn index(token: &str) -> Option<usize> {
+    if token.bytes().all(|c| c.is_ascii_digit()) && (token == "0" || !token.starts_with('0')) {
+        token.parse().ok()
+    } else {
+        None
+    }
+}
@@ -0,0 +1,85 @@
+//! Printing a value a bit at a time over a stack of its own instead of recursing, with names and
+//! strings escaped. Whoever drives it can do something in between, like checking whether printing
+//! was cancelled or writing out what was printed so far.
+
+use crate::Json;
+
+// What is left to print, the top of the stack coming next.
+enum Step<'a> {
+    Value(&'a Json),
+    Text(&'static str),
+}
+
+pub(crate) struct Printer<'a> {
+    steps: Vec<Step<'a>>,
+}
+
+impl<'a> Printer<'a> {
+    pub(crate) fn new(json: &'a Json) -> Printer<'a> {
+        Printer {
+            steps: vec![Step::Value(json)],
+        }
+    }
+
+    // Appends the next value, name or bracket to `output`, returning `false` once everything has
+    // been printed.
+    pub(crate) fn step(&mut self, output: &mut String) -> bool {
+        let step = match self.steps.pop() {
+            Some(step) => step,
+            None => return false,
+        };
+
+        match step {
+            Step::Text(text) => output.push_str(text),
+            Step::Value(Json::OBJECT { name, value }) => {
+                escape(name, output);
+                output.push(':');
+                self.steps.push(Step::Value(value));
+            }
+            Step::Value(Json::JSON(values)) => {
+                output.push('{');
+                self.push_members(values, "}");
+            }
+            Step::Value(Json::ARRAY(values)) => {
+                output.push('[');
+                self.push_members(values, "]");
+            }
+            Step::Value(Json::STRING(val)) => escape(val, output),
+            Step::Value(json) => output.push_str(&json.print()),
+        }
+
+        true
+    }
+
+    // Schedules `values` separated by commas, followed by `close`.
+    fn push_members(&mut self, values: &'a [Json], close: &'static str) {
+        self.steps.push(Step::Text(close));
+
+        for (n, value) in values.iter().enumerate().rev() {
+            self.steps.push(Step::Value(value));
+
+            if n > 0 {
+                self.steps.push(Step::Text(","));
+            }
+        }
+    }
+}
+
+// Appends `string` quoted, escaping quotes, backslashes and control characters.
+fn escape(string: &str, output: &mut String) {
+    output.push('"');
+
+    for c in string.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c < ' ' => output.push_str(&format!("\\u{:04x}", c as u32)),
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+}
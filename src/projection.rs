@@ -0,0 +1,224 @@
+//! Parsing only some parts of the input, skipping over the rest.
+
+use crate::pointer::unescape;
+use crate::stream::{scan_string, scan_value};
+use crate::{is_whitespace, ErrorKind, Json, ParseError};
+use std::ops::Range;
+
+impl Json {
+    /// Parses only what `paths` (JSON Pointers, see `pointer`) refer to, together with the objects
+    /// and arrays on the way, as if all of `input` was parsed and everything else removed afterwards.
+    /// Everything else is only scanned for its end, without allocating anything, which keeps the
+    /// memory needed flat for wide records of which only a few members are of interest. The elements
+    /// kept of an array move up in place of those dropped.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"id\":7,\"name\":\"n\",\"tags\":[\"a\",\"b\"],\"blob\":\"...\",\"user\":{\"id\":3,\"bio\":\"...\"}}";
+    ///
+    /// let json = Json::parse_only(input, &["/id", "/tags/1", "/user/id"]).unwrap();
+    ///
+    /// assert_eq!(json, Json::parse(b"{\"id\":7,\"tags\":[\"b\"],\"user\":{\"id\":3}}").unwrap());
+    /// ```
+    /// ## Errors
+    /// Fails like `parse_lazy`, or if something kept or on the way to it can't be parsed.
+    pub fn parse_only(input: &[u8], paths: &[&str]) -> Result<Json, ParseError> {
+        let start = (0..input.len())
+            .find(|n| !is_whitespace(input[*n]))
+            .ok_or_else(|| ParseError::at(input, input.len(), ErrorKind::UnexpectedEof))?;
+
+        let end = scan_value(input, start, true)
+            .ok_or_else(|| ParseError::at(input, input.len(), ErrorKind::UnexpectedEof))?;
+
+        if let Some(n) = (end..input.len()).find(|n| !is_whitespace(input[*n])) {
+            return Err(ParseError::at(input, n, ErrorKind::TrailingData));
+        }
+
+        // Anything but a pointer refers to nothing.
+        let paths: Vec<Vec<String>> = paths
+            .iter()
+            .filter_map(|path| {
+                if path.is_empty() {
+                    Some(Vec::new())
+                } else {
+                    Some(path.strip_prefix('/')?.split('/').map(unescape).collect())
+                }
+            })
+            .collect();
+        let paths: Vec<&[String]> = paths.iter().map(Vec::as_slice).collect();
+
+        project(input, start..end, &paths)
+    }
+}
+
+// Parses what `paths` refer to in the value at `input[span]`.
+fn project(input: &[u8], span: Range<usize>, paths: &[&[String]]) -> Result<Json, ParseError> {
+    let parse = || {
+        let mut incr = span.start;
+
+        Json::parse_value(&input[..span.end], &mut incr)
+            .map_err(|(position, message)| ParseError::at(input, position, message))
+    };
+
+    if paths.iter().any(|path| path.is_empty()) {
+        return parse();
+    }
+
+    match input[span.start] {
+        b'{' | b'[' => {
+            let object = input[span.start] == b'{';
+            let mut values = Vec::new();
+
+            for (index, member) in Members::new(input, span).enumerate() {
+                let (name, value) = member?;
+
+                let rest: Vec<&[String]> = paths
+                    .iter()
+                    .filter(|path| match &name {
+                        Some(name) => is_named(input, name.clone(), &path[0]),
+                        None => !object && is_index(&path[0], index),
+                    })
+                    .map(|path| &path[1..])
+                    .collect();
+
+                if rest.is_empty() {
+                    continue;
+                }
+
+                let json = project(input, value, &rest)?;
+
+                values.push(match name {
+                    Some(name) => Json::OBJECT {
+                        name: parse_name(input, name)?,
+                        value: Box::new(json),
+                    },
+                    None => json,
+                });
+            }
+
+            Ok(if object {
+                Json::JSON(values)
+            } else {
+                Json::ARRAY(values)
+            })
+        }
+        // A standalone object, which pointers see through.
+        b'\"' => match standalone(input, span.clone()) {
+            Some((name, value)) => Ok(Json::OBJECT {
+                name: parse_name(input, name)?,
+                value: Box::new(project(input, value, paths)?),
+            }),
+            None => parse(),
+        },
+        _ => parse(),
+    }
+}
+
+// The members of an object (with their names, still quoted) or the elements of an array.
+struct Members<'a> {
+    input: &'a [u8],
+    object: bool,
+    // Where the next one is looked for.
+    n: usize,
+    // The closing bracket.
+    end: usize,
+    done: bool,
+}
+
+impl<'a> Members<'a> {
+    fn new(input: &'a [u8], span: Range<usize>) -> Members<'a> {
+        Members {
+            input,
+            object: input[span.start] == b'{',
+            n: span.start + 1,
+            end: span.end - 1,
+            done: false,
+        }
+    }
+
+    fn member(&self, start: usize) -> Result<(Option<Range<usize>>, Range<usize>), ParseError> {
+        let body = &self.input[..self.end];
+        let error = |n| ParseError::at(self.input, n, ErrorKind::InvalidObject);
+        let mut value = start;
+        let mut name = None;
+
+        let name_end = match body[start] {
+            b'\"' if self.object => scan_string(body, start),
+            _ => None,
+        };
+
+        if let Some(name_end) = name_end.filter(|end| body.get(*end) == Some(&b':')) {
+            name = Some(start..name_end);
+            value = (name_end + 1..body.len())
+                .find(|n| !is_whitespace(body[*n]))
+                .ok_or_else(|| error(self.end))?;
+        }
+
+        let end = scan_value(body, value, true)
+            .filter(|end| *end > value)
+            .ok_or_else(|| error(value))?;
+
+        Ok((name, value..end))
+    }
+}
+
+impl<'a> Iterator for Members<'a> {
+    type Item = Result<(Option<Range<usize>>, Range<usize>), ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let start = (self.n..self.end)
+            .find(|n| !is_whitespace(self.input[*n]) && self.input[*n] != b',')?;
+
+        let member = self.member(start);
+
+        match &member {
+            Ok((_, value)) => self.n = value.end,
+            Err(_) => self.done = true,
+        }
+
+        Some(member)
+    }
+}
+
+// The name and the value of the standalone object at `input[span]`, if it is one.
+fn standalone(input: &[u8], span: Range<usize>) -> Option<(Range<usize>, Range<usize>)> {
+    let name_end = scan_string(&input[..span.end], span.start)?;
+
+    if input.get(name_end) != Some(&b':') {
+        return None;
+    }
+
+    let value = (name_end + 1..span.end).find(|n| !is_whitespace(input[*n]))?;
+
+    Some((span.start..name_end, value..span.end))
+}
+
+fn parse_name(input: &[u8], name: Range<usize>) -> Result<String, ParseError> {
+    let mut incr = name.start;
+
+    Json::parse_string(input, &mut incr)
+        .map_err(|(position, message)| ParseError::at(input, position, message))
+}
+
+// Compares the (quoted) name at `input[name]` to `token`, only decoding it if it has to.
+fn is_named(input: &[u8], name: Range<usize>, token: &str) -> bool {
+    let raw = &input[name.start + 1..name.end - 1];
+
+    if raw.contains(&b'\\') {
+        parse_name(input, name).is_ok_and(|name| name == token)
+    } else {
+        raw == token.as_bytes()
+    }
+}
+
+// Indexes are plain decimal numbers without leading zeros.
+fn is_index(token: &str, index: usize) -> bool {
+    token.bytes().all(|c| c.is_ascii_digit())
+        && (token == "0" || !token.starts_with('0'))
+        && token.parse() == Ok(index)
+}
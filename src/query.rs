@@ -0,0 +1,242 @@
+//! Converting between objects and URL query strings (`a=1&b=hello%20world`).
+
+use crate::flatten::{insert, leaves, resolve, Token};
+use crate::Json;
+use std::collections::HashMap;
+
+/// How `Json::to_query` and `Json::from_query` handle nesting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryStyle {
+    /// Only the members of the object itself become parameters. Arrays repeat the parameter
+    /// (`a=1&a=2`), nested objects are written as JSON text.
+    Flat,
+    /// Nested members are written in bracket notation, like `a[b][0]=1`. `a[]=1&a[]=2` is read as an
+    /// array as well.
+    Brackets,
+}
+
+impl Json {
+    /// Encodes the members of a `Json::JSON` (or a `Json::OBJECT` holding one) as a query string,
+    /// without the leading `?`. Strings are written as they are, numbers and bools as printed and
+    /// `Json::NULL` as an empty value. Everything but ASCII letters, digits and `-._~` is
+    /// percent-encoded (except for the brackets of `QueryStyle::Brackets`). Unnamed values and empty
+    /// objects or arrays nested in `QueryStyle::Brackets` are left out, anything but an object results
+    /// in an empty string.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"q\":\"hello world\",\"page\":2,\"tags\":[\"a\",\"b\"]}").unwrap();
+    ///
+    /// assert_eq!(json.to_query(QueryStyle::Flat), "q=hello%20world&page=2&tags=a&tags=b");
+    /// assert_eq!(
+    ///     json.to_query(QueryStyle::Brackets),
+    ///     "q=hello%20world&page=2&tags[0]=a&tags[1]=b"
+    /// );
+    /// ```
+    pub fn to_query(&self, style: QueryStyle) -> String {
+        let mut pairs = Vec::new();
+
+        let values = match self.inner() {
+            Json::JSON(values) => values,
+            _ => {
+                return String::new();
+            }
+        };
+
+        match style {
+            QueryStyle::Flat => {
+                for (name, value) in values.iter().filter_map(|json| match json {
+                    Json::OBJECT { name, value } => Some((name, value.unbox())),
+                    _ => None,
+                }) {
+                    match value {
+                        Json::ARRAY(values) => {
+                            for value in values {
+                                pairs.push(pair(name, value, false));
+                            }
+                        }
+                        value => pairs.push(pair(name, value, false)),
+                    }
+                }
+            }
+            QueryStyle::Brackets => {
+                leaves(self.inner(), &mut Vec::new(), &mut |path, leaf| {
+                    if let (Some(Token::Name(first)), false) =
+                        (path.first(), matches!(leaf, Json::JSON(_) | Json::ARRAY(_)))
+                    {
+                        let mut key = first.clone();
+
+                        for token in &path[1..] {
+                            match token {
+                                Token::Name(name) => key.push_str(&format!("[{}]", name)),
+                                Token::Index(n) => key.push_str(&format!("[{}]", n)),
+                            }
+                        }
+
+                        pairs.push(pair(&key, leaf, true));
+                    }
+                });
+            }
+        }
+
+        pairs.join("&")
+    }
+
+    /// Decodes a query string (with or without the leading `?`) into a `Json::JSON`. All values are
+    /// `Json::STRING`s, since a query string doesn't say which of them are numbers. `+` is read as a
+    /// space and a parameter without `=` as one with an empty value.
+    ///
+    /// With `QueryStyle::Flat` a parameter given more than once becomes a `Json::ARRAY`. With
+    /// `QueryStyle::Brackets` nested keys create objects, while `[]` and numeric keys like `[0]`
+    /// create arrays, unless names are used under the same key too, as in `a[0]=x&a[b]=y`, which makes
+    /// it an object with the indices as names. Gaps in arrays are filled with `Json::NULL`, but an index more than 256 past the
+    /// end of an array turns it into an object, so that a key like `a[50000000]` can't allocate a huge
+    /// array.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::from_query("?a[b]=1&a[c][]=x&a[c][]=y+z", QueryStyle::Brackets);
+    ///
+    /// assert_eq!(json.print(), "{\"a\":{\"b\":\"1\",\"c\":[\"x\",\"y z\"]}}");
+    ///
+    /// let json = Json::from_query("a=1&b=%F0%9F%A6%80&a=2", QueryStyle::Flat);
+    ///
+    /// assert_eq!(json.print(), "{\"a\":[\"1\",\"2\"],\"b\":\"🦀\"}");
+    /// ```
+    pub fn from_query(query: &str, style: QueryStyle) -> Json {
+        let query = query.strip_prefix('?').unwrap_or(query);
+
+        let mut root = Json::object();
+        // The number of `[]` seen so far for each key prefix.
+        let mut appended: HashMap<String, usize> = HashMap::new();
+        let mut entries = Vec::new();
+
+        for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+            let (key, value) = match parameter.find('=') {
+                Some(n) => (&parameter[..n], &parameter[n + 1..]),
+                None => (parameter, ""),
+            };
+
+            let key = decode(key);
+            let value = Json::STRING(decode(value));
+
+            match style {
+                QueryStyle::Flat => match root.value_of_mut(&key) {
+                    Some(Json::ARRAY(values)) => values.push(value),
+                    Some(existing) => {
+                        let first = existing.take();
+
+                        *existing = Json::ARRAY(vec![first, value]);
+                    }
+                    None => {
+                        root.add(Json::member(key, value));
+                    }
+                },
+                QueryStyle::Brackets => {
+                    entries.push((bracket_tokens(&key, &mut appended), value));
+                }
+            }
+        }
+
+        resolve(&mut entries, false);
+
+        for (tokens, value) in entries {
+            insert(&mut root, &tokens, value);
+        }
+
+        root
+    }
+}
+
+fn pair(key: &str, value: &Json, brackets: bool) -> String {
+    let value = match value {
+        Json::STRING(val) => encode(val, false),
+        Json::NULL => String::new(),
+        value => encode(&value.print(), false),
+    };
+
+    format!("{}={}", encode(key, brackets), value)
+}
+
+fn encode(input: &str, brackets: bool) -> String {
+    let mut output = String::new();
+
+    for byte in input.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                output.push(byte as char);
+            }
+            b'[' | b']' if brackets => output.push(byte as char),
+            byte => output.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    output
+}
+
+// Percent-decodes `input`. Malformed escapes are kept as they are, invalid UTF-8 is replaced.
+fn decode(input: &str) -> String {
+    let input = input.as_bytes();
+    let mut output = Vec::with_capacity(input.len());
+    let mut n = 0;
+
+    while n < input.len() {
+        let escaped = input
+            .get(n + 1..n + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (input[n], escaped) {
+            (b'%', Some(byte)) => {
+                output.push(byte);
+                n += 3;
+            }
+            (b'+', _) => {
+                output.push(b' ');
+                n += 1;
+            }
+            (byte, _) => {
+                output.push(byte);
+                n += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&output).into_owned()
+}
+
+// Splits `a[b][0][]` into its tokens, turning `[]` into the next free index for that prefix.
+fn bracket_tokens(key: &str, appended: &mut HashMap<String, usize>) -> Vec<Token> {
+    let (name, mut rest) = match key.find('[') {
+        Some(n) if key.ends_with(']') => key.split_at(n),
+        _ => (key, ""),
+    };
+
+    let mut tokens = vec![Token::Name(String::from(name))];
+    let mut prefix = String::from(name);
+
+    while let (Some(0), Some(end)) = (rest.find('['), rest.find(']')) {
+        let segment = &rest[1..end];
+
+        if segment.is_empty() {
+            let next = appended.entry(prefix.clone()).or_insert(0);
+
+            tokens.push(Token::Index(*next));
+            prefix.push_str(&format!("[{}]", next));
+            *next += 1;
+        } else {
+            match segment.parse::<usize>() {
+                Ok(n) => tokens.push(Token::Index(n)),
+                Err(_) => tokens.push(Token::Name(String::from(segment))),
+            }
+
+            prefix.push_str(&rest[..=end]);
+        }
+
+        rest = &rest[end + 1..];
+    }
+
+    tokens
+}
@@ -0,0 +1,115 @@
+//! Parsing NDJSON on several threads (requires the `rayon` feature).
+
+use crate::ndjson::parse_line;
+use crate::{Json, ParseError};
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use std::io::{self, BufRead};
+
+// The lines are read in batches of about this size, each of which is parsed in parallel.
+const BATCH: usize = 4 * 1024 * 1024;
+
+/// An iterator over the values of an NDJSON input parsed on several threads. See
+/// `Json::par_parse_ndjson`.
+#[derive(Debug)]
+pub struct ParNdjsonReader<R> {
+    reader: R,
+    // The results of the current batch, in order.
+    values: VecDeque<Result<Json, ParseError>>,
+    line: usize,
+    // Set after an I/O error, which would most likely just repeat.
+    done: bool,
+}
+
+impl<R: BufRead> ParNdjsonReader<R> {
+    // Reads the next batch of lines and parses them.
+    fn read_batch(&mut self) {
+        let mut buffer = Vec::new();
+        // Where each line starts in the buffer, and its number.
+        let mut lines = Vec::new();
+        let mut failed = None;
+
+        while buffer.len() < BATCH {
+            let start = buffer.len();
+
+            match self.reader.read_until(b'\n', &mut buffer) {
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(_) => {
+                    self.line += 1;
+                    lines.push((start, self.line));
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    self.done = true;
+                    failed = Some(ParseError::io(self.line + 1, error));
+                    break;
+                }
+            }
+        }
+
+        let ends: Vec<usize> = lines
+            .iter()
+            .skip(1)
+            .map(|(start, _)| *start)
+            .chain(Some(buffer.len()))
+            .collect();
+
+        let values: Vec<Result<Json, ParseError>> = lines
+            .par_iter()
+            .zip(ends.par_iter())
+            .filter_map(|((start, number), end)| parse_line(&buffer[*start..*end], *number))
+            .collect();
+
+        self.values.extend(values);
+        self.values.extend(failed.map(Err));
+    }
+}
+
+impl<R: BufRead> Iterator for ParNdjsonReader<R> {
+    type Item = Result<Json, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Json, ParseError>> {
+        while self.values.is_empty() && !self.done {
+            self.read_batch();
+        }
+
+        self.values.pop_front()
+    }
+}
+
+impl<R> ParNdjsonReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl Json {
+    /// Reads newline-delimited JSON from `reader` like `parse_ndjson`, yielding the same values and
+    /// errors in the same order, but parses the lines on rayon's thread pool, e.g. to bulk-load
+    /// millions of records. The input is read in batches of about 4 MiB, each of which is parsed in
+    /// parallel before its values are yielded, so that memory stays bounded for inputs of any size.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input: String = (0..1000).map(|n| format!("{{\"id\":{}}}\n", n)).collect();
+    ///
+    /// let ids: Vec<f64> = Json::par_parse_ndjson(input.as_bytes())
+    ///     .map(|record| record.unwrap().get_f64("id").unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(ids, (0..1000).map(f64::from).collect::<Vec<f64>>());
+    /// ```
+    pub fn par_parse_ndjson<R: BufRead>(reader: R) -> ParNdjsonReader<R> {
+        ParNdjsonReader {
+            reader,
+            values: VecDeque::new(),
+            line: 0,
+            done: false,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Parsing which goes on behind errors, e.g. for an editor to point out all of them at once.
+
+use crate::parser::{self, Context};
+use crate::{is_whitespace, ErrorKind, Json, ParseError};
+
+impl Json {
+    /// Parses `input` like `parse`, but instead of stopping at the first error, collects it and
+    /// goes on behind it, returning as much of the value as could be made out along with all the
+    /// errors found, in order, e.g. for an editor or a linter. An empty list means `input` parsed
+    /// just like with `parse`, except that data after the value is an error too.
+    ///
+    /// A value which fails is dropped along with the members it is the value of, and the input is
+    /// skipped up to the next comma or closing bracket. A closing bracket which doesn't belong
+    /// where it is closes the containers in between if it belongs to one further out, or is
+    /// skipped otherwise. The containers still open at the end of the input are closed. If nothing
+    /// is left of the value, it is `Json::NULL`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let (json, errors) = Json::parse_recovering(b"{\"a\":[1,tru,3],\"b\":nul,\"c\":\"d\"}");
+    ///
+    /// assert_eq!(json.print(), "{\"a\":[1,3],\"c\":\"d\"}");
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!((errors[0].position, errors[0].kind), (11, ErrorKind::InvalidBool));
+    /// assert_eq!((errors[1].position, errors[1].kind), (22, ErrorKind::InvalidNull));
+    /// ```
+    pub fn parse_recovering(input: &[u8]) -> (Json, Vec<ParseError>) {
+        let mut context = Context {
+            errors: Some(Vec::new()),
+            ..Context::default()
+        };
+        let mut incr = 0;
+
+        // Without limits, nothing stops parsing.
+        let json = parser::parse_with(input, &mut incr, &mut context).unwrap_or(Json::NULL);
+        let mut errors = context.errors.unwrap_or_default();
+
+        if let Some(offset) = input[incr..].iter().position(|c| !is_whitespace(*c)) {
+            errors.push(ParseError::at(
+                input,
+                incr + offset,
+                ErrorKind::TrailingData,
+            ));
+        }
+
+        (json, errors)
+    }
+}
@@ -0,0 +1,113 @@
+//! Parsing almost-JSON, repairing the defects sloppy producers commonly leave in it.
+
+use crate::error::locate;
+use crate::parser::{self, Context};
+use crate::{Json, ParseError};
+use std::fmt;
+
+/// A defect `Json::parse_repair` repaired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Repair {
+    /// The line it is on, counting from `1`.
+    pub line: usize,
+    /// The byte offset into that line.
+    pub position: usize,
+    /// What was repaired.
+    pub kind: RepairKind,
+}
+
+impl Repair {
+    // The repair at `input[index]`, with the line and the offset into it worked out.
+    pub(crate) fn at(input: &[u8], index: usize, kind: RepairKind) -> Repair {
+        let (line, position) = locate(input, index);
+
+        Repair {
+            line,
+            position,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for Repair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` at line `{}`, position `{}`.",
+            self.kind, self.line, self.position
+        )
+    }
+}
+
+/// What kind of defect a `Repair` is about. More kinds may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RepairKind {
+    /// A string (or name of a member) between single quotes, inside which `\'` is a quote.
+    SingleQuotes,
+    /// The name of a member without quotes, made of ASCII letters, digits, `_` and `$`.
+    UnquotedKey,
+    /// Python's `True`, `False` or `None`.
+    PythonLiteral,
+}
+
+impl RepairKind {
+    /// What a repair of this kind says, e.g. `"Unquoted key."`.
+    pub fn message(self) -> &'static str {
+        match self {
+            RepairKind::SingleQuotes => "Single-quoted string.",
+            RepairKind::UnquotedKey => "Unquoted key.",
+            RepairKind::PythonLiteral => "Python literal.",
+        }
+    }
+}
+
+impl fmt::Display for RepairKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl Json {
+    /// Parses `input` like `parse`, but repairs common defects instead of failing on them, e.g. to
+    /// ingest data from sloppy producers: strings between single quotes, names of members without
+    /// quotes and Python's `True`, `False` and `None`. Also returns what was repaired, in order, at
+    /// where each defect starts.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{name:'O\\'Brien',\"admin\":True,'team':None}";
+    /// let (json, repairs) = Json::parse_repair(input).unwrap();
+    ///
+    /// assert_eq!(json.print(), "{\"name\":\"O'Brien\",\"admin\":true,\"team\":null}");
+    ///
+    /// let repairs: Vec<(usize, RepairKind)> = repairs
+    ///     .iter()
+    ///     .map(|repair| (repair.position, repair.kind))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     repairs,
+    ///     vec![
+    ///         (1, RepairKind::UnquotedKey),
+    ///         (6, RepairKind::SingleQuotes),
+    ///         (25, RepairKind::PythonLiteral),
+    ///         (30, RepairKind::SingleQuotes),
+    ///         (37, RepairKind::PythonLiteral),
+    ///     ]
+    /// );
+    /// ```
+    pub fn parse_repair(input: &[u8]) -> Result<(Json, Vec<Repair>), ParseError> {
+        let mut context = Context {
+            repairs: Some(Vec::new()),
+            ..Context::default()
+        };
+        let mut incr = 0;
+
+        let json = parser::parse_with(input, &mut incr, &mut context)
+            .map_err(|error| context.error(input, error))?;
+
+        Ok((json, context.repairs.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,61 @@
+//! Statistics about what a parse cost, e.g. to monitor untrusted payloads.
+
+use crate::parser::{self, Context};
+use crate::{Json, ParseError};
+use std::time::{Duration, Instant};
+
+/// What `Json::parse_with_report` found while parsing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseReport {
+    /// The bytes of the input read, up to the end of the value or to where parsing failed.
+    pub bytes: usize,
+    /// The number of values, counting a member (a `Json::OBJECT`) and its value separately.
+    pub nodes: usize,
+    /// How deeply objects and arrays were nested, `0` for a plain value.
+    pub depth: usize,
+    /// The length of all the strings and names of members, escape sequences decoded.
+    pub string_bytes: usize,
+    /// How long parsing took.
+    pub time: Duration,
+}
+
+impl Json {
+    /// Parses `input` like `parse`, also reporting what it cost, e.g. for a service to keep track of
+    /// the payloads it handles. The report is returned even if parsing fails, covering what was
+    /// parsed until then (the values completed, the strings read and the levels entered).
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let (json, report) = Json::parse_with_report(b"{\"tags\":[\"a\",\"bc\"],\"n\":1} ");
+    ///
+    /// assert!(json.is_ok());
+    /// assert_eq!(report.bytes, 25);
+    /// assert_eq!(report.nodes, 7);
+    /// assert_eq!(report.depth, 2);
+    /// assert_eq!(report.string_bytes, 8);
+    ///
+    /// let (json, report) = Json::parse_with_report(b"[[1,2],[3,");
+    ///
+    /// assert_eq!(json.unwrap_err().position, 10);
+    /// assert_eq!((report.bytes, report.nodes), (10, 4));
+    /// ```
+    pub fn parse_with_report(input: &[u8]) -> (Result<Json, ParseError>, ParseReport) {
+        let mut context = Context::default();
+        let mut incr = 0;
+        let start = Instant::now();
+
+        let result = parser::parse_with(input, &mut incr, &mut context);
+        let mut report = context.report;
+
+        report.time = start.elapsed();
+        report.bytes = match &result {
+            Ok(_) => incr,
+            Err((position, _)) => *position,
+        };
+
+        let result = result.map_err(|error| context.error(input, error));
+
+        (result, report)
+    }
+}
@@ -0,0 +1,59 @@
+//! Finding the bytes the parser looks for, such as the end of a string. With the `simd` feature,
+//! eight bytes are looked at a time within a `u64` (SWAR, "SIMD within a register"), which can pay
+//! off for long strings and deep indentation.
+
+// The first index of a byte in `needles`.
+pub(crate) fn find(bytes: &[u8], needles: &[u8]) -> Option<usize> {
+    search(bytes, needles, true)
+}
+
+// The first index of a byte not in `set`.
+pub(crate) fn skip(bytes: &[u8], set: &[u8]) -> Option<usize> {
+    search(bytes, set, false)
+}
+
+#[cfg(not(feature = "simd"))]
+fn search(bytes: &[u8], set: &[u8], member: bool) -> Option<usize> {
+    bytes.iter().position(|c| set.contains(c) == member)
+}
+
+#[cfg(feature = "simd")]
+fn search(bytes: &[u8], set: &[u8], member: bool) -> Option<usize> {
+    use std::convert::TryInto;
+
+    let mut chunks = bytes.chunks_exact(8);
+    let mut offset = 0;
+
+    for chunk in &mut chunks {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        let found = set.iter().fold(0, |found, c| found | equal(word, *c));
+        let found = if member { found } else { !found & HIGH };
+
+        // The lowest byte comes first, as the word was read little-endian.
+        if found != 0 {
+            return Some(offset + found.trailing_zeros() as usize / 8);
+        }
+
+        offset += 8;
+    }
+
+    chunks
+        .remainder()
+        .iter()
+        .position(|c| set.contains(c) == member)
+        .map(|n| offset + n)
+}
+
+#[cfg(feature = "simd")]
+const LOW: u64 = 0x0101_0101_0101_0101;
+#[cfg(feature = "simd")]
+const HIGH: u64 = 0x8080_8080_8080_8080;
+
+// The high bit of each byte of `word` equal to `c`. Adding to the low seven bits of each byte can't
+// carry over into the next, so unlike the usual trick there are no false positives.
+#[cfg(feature = "simd")]
+fn equal(word: u64, c: u8) -> u64 {
+    let x = word ^ (LOW * c as u64);
+
+    !(((x & !HIGH) + !HIGH) | x) & HIGH
+}
@@ -0,0 +1,839 @@
+//! Validating documents against a JSON Schema (requires the `schema` feature).
+
+use crate::parser::{self, Context};
+use crate::pointer;
+use crate::{EqOptions, Json, JsonEvent, ParseError, ParseOptions, SchemaError};
+use regex::Regex;
+use std::fmt::{self, Write};
+use std::mem;
+
+/// A JSON Schema compiled by `Schema::compile`, to validate documents against. Only the keywords
+/// of draft 2020-12 most schemas are made of are supported: `type`, `properties`, `required`,
+/// `items`, `enum`, `minimum`, `maximum`, `exclusiveMinimum`, `exclusiveMaximum`, `minLength`,
+/// `maxLength`, `minItems`, `maxItems` and `pattern`, as well as `true` and `false` as schemas.
+/// Every other keyword is ignored.
+#[derive(Debug, Clone)]
+pub struct Schema {
+    root: Node,
+}
+
+/// A place where a document doesn't conform to a `Schema`, as `Schema::validate` finds them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Violation {
+    /// A JSON Pointer (RFC 6901) to the value which doesn't conform.
+    pub path: String,
+    /// The keyword of the schema which it doesn't conform to, e.g. `"required"`.
+    pub keyword: &'static str,
+    /// What is wrong, e.g. `"expected a string but found a number"`.
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`: {}", self.path, self.message)
+    }
+}
+
+impl Schema {
+    /// Compiles `schema`, failing if a keyword it supports has a value which doesn't make sense,
+    /// e.g. a `pattern` which isn't a regular expression.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let schema = Json::parse(
+    ///     b"{\"type\":\"object\",\"required\":[\"name\"],\"properties\":{\
+    ///       \"name\":{\"type\":\"string\",\"minLength\":1},\
+    ///       \"tags\":{\"type\":\"array\",\"items\":{\"enum\":[\"a\",\"b\"]}}}}",
+    /// )
+    /// .unwrap();
+    /// let schema = Schema::compile(&schema).unwrap();
+    ///
+    /// assert!(schema.is_valid(&Json::parse(b"{\"name\":\"Ferris\",\"tags\":[\"a\"]}").unwrap()));
+    ///
+    /// let violations = schema.validate(&Json::parse(b"{\"tags\":[\"a\",\"c\"]}").unwrap());
+    ///
+    /// assert_eq!(violations.len(), 2);
+    /// assert_eq!(violations[0].to_string(), "``: missing member `name`");
+    /// assert_eq!(
+    ///     violations[1].to_string(),
+    ///     "`/tags/1`: expected one of [\"a\",\"b\"] but found \"c\""
+    /// );
+    ///
+    /// let error = Schema::compile(&Json::parse(b"{\"minimum\":\"1\"}").unwrap()).unwrap_err();
+    ///
+    /// assert_eq!(error.to_string(), "`/minimum`: expected a number");
+    /// ```
+    pub fn compile(schema: &Json) -> Result<Schema, SchemaError> {
+        Ok(Schema {
+            root: Node::compile(schema.inner(), "")?,
+        })
+    }
+
+    /// Returns every place where `json` doesn't conform to `self`, in document order. An empty
+    /// `Vec` means it does.
+    pub fn validate(&self, json: &Json) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        self.root.check(json.inner(), "", true, &mut violations);
+
+        violations
+    }
+
+    /// Whether `json` conforms to `self`.
+    pub fn is_valid(&self, json: &Json) -> bool {
+        self.validate(json).is_empty()
+    }
+
+    /// A `SchemaValidator` for a document whose events are yet to come.
+    pub fn validator(&self) -> SchemaValidator<'_> {
+        SchemaValidator {
+            root: &self.root,
+            levels: Vec::new(),
+            path: String::new(),
+            key: None,
+            building: 0,
+        }
+    }
+
+    /// Returns every place where the document `events` yields doesn't conform to `self`, like
+    /// `validate` but without building the document, see `SchemaValidator`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let schema = b"{\"items\":{\"type\":\"integer\",\"minimum\":0}}";
+    /// let schema = Schema::compile(&Json::parse(schema).unwrap()).unwrap();
+    ///
+    /// let input = format!("[{}0.5]", "1,".repeat(100_000));
+    /// let violations = schema.validate_events(JsonLexer::new(input.as_bytes())).unwrap();
+    ///
+    /// assert_eq!(violations.len(), 1);
+    /// assert_eq!(
+    ///     violations[0].to_string(),
+    ///     "`/100000`: expected an integer but found a number"
+    /// );
+    ///
+    /// assert!(schema.validate_events(JsonLexer::new(b"[1,2")).is_err());
+    /// ```
+    /// ## Errors
+    /// Fails with the first error `events` yields.
+    pub fn validate_events<I>(&self, events: I) -> Result<Vec<Violation>, ParseError>
+    where
+        I: IntoIterator<Item = Result<JsonEvent, ParseError>>,
+    {
+        let mut validator = self.validator();
+        let mut violations = Vec::new();
+
+        for event in events {
+            violations.append(&mut validator.feed(&event?));
+        }
+
+        Ok(violations)
+    }
+}
+
+/// Validates a document against a `Schema` event by event, as a `JsonLexer` yields them, so that
+/// documents too large to be built can be validated. It takes memory for the objects and arrays
+/// entered but not yet left rather than for the values in them, except that an object or array
+/// checked against `enum` is built to be compared. Violations are found in the order `validate`
+/// finds them, except that those of an object or array itself (but for its type) are found at its
+/// end, after those of the values in it.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let schema = b"{\"required\":[\"id\"],\"properties\":{\"id\":{\"type\":\"integer\"}}}";
+/// let schema = Schema::compile(&Json::parse(schema).unwrap()).unwrap();
+///
+/// let mut validator = schema.validator();
+///
+/// assert!(validator.feed(&JsonEvent::ObjectStart).is_empty());
+/// assert!(validator.feed(&JsonEvent::Key(String::from("id"))).is_empty());
+/// assert_eq!(validator.feed(&JsonEvent::Null)[0].path, "/id");
+/// assert!(validator.feed(&JsonEvent::ObjectEnd).is_empty());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SchemaValidator<'s> {
+    root: &'s Node,
+    levels: Vec<Level<'s>>,
+    // A JSON Pointer to the value being validated.
+    path: String,
+    // The name of the member whose value comes next.
+    key: Option<String>,
+    // The number of levels being built.
+    building: usize,
+}
+
+impl<'s> SchemaValidator<'s> {
+    /// Takes the next event of the document, returning the violations found with it. Events which
+    /// don't follow those before, e.g. an end without a start, are ignored.
+    pub fn feed(&mut self, event: &JsonEvent) -> Vec<Violation> {
+        let mut violations = Vec::new();
+
+        match event {
+            JsonEvent::Key(name) => {
+                self.build(event);
+
+                // Names of members only matter inside objects, and only the first of a chain.
+                if let Some(level) = self.levels.last_mut().filter(|level| level.object) {
+                    if self.key.is_none() {
+                        if let Some(node) = level.node {
+                            for (n, required) in node.required.iter().enumerate() {
+                                level.found[n] |= required == name;
+                            }
+                        }
+
+                        self.key = Some(name.clone());
+                    }
+                }
+            }
+            JsonEvent::ObjectStart | JsonEvent::ArrayStart => {
+                self.build(event);
+
+                let object = *event == JsonEvent::ObjectStart;
+                let empty = if object { Json::JSON } else { Json::ARRAY };
+                let (mut node, parent) = self.enter();
+
+                if let Some(wrong) = node.filter(|node| !node.allows(&empty(Vec::new()))) {
+                    wrong.check(&empty(Vec::new()), &self.path, false, &mut violations);
+                    node = None;
+                }
+
+                let value = node
+                    .filter(|node| node.values.is_some())
+                    .map(|_| Builder::new(object));
+
+                self.building += value.is_some() as usize;
+                self.levels.push(Level {
+                    node,
+                    object,
+                    parent,
+                    count: 0,
+                    found: vec![false; node.map_or(0, |node| node.required.len())],
+                    value,
+                });
+            }
+            JsonEvent::ObjectEnd | JsonEvent::ArrayEnd => {
+                let level = match self.levels.pop() {
+                    Some(level) => level,
+                    None => return violations,
+                };
+
+                if let Some(node) = level.node {
+                    let path = &self.path;
+                    let mut violation = |keyword, message| {
+                        violations.push(Violation {
+                            path: path.clone(),
+                            keyword,
+                            message,
+                        })
+                    };
+
+                    if let Some(value) = level.value {
+                        self.building -= 1;
+                        node.check_enum(&value.finish(), &mut violation);
+                    }
+
+                    if level.object {
+                        let found = &level.found;
+
+                        node.check_required(|n| found[n], &mut violation);
+                    } else {
+                        node.check_items(level.count, &mut violation);
+                    }
+                }
+
+                self.path.truncate(level.parent);
+                self.build(event);
+            }
+            _ => {
+                self.build(event);
+
+                let (node, parent) = self.enter();
+
+                if let Some(node) = node {
+                    node.check(&scalar(event), &self.path, false, &mut violations);
+                }
+
+                self.path.truncate(parent);
+            }
+        }
+
+        violations
+    }
+
+    // Appends where the value starting next is to `path`, returning its schema if it has one and
+    // the length of `path` before.
+    fn enter(&mut self) -> (Option<&'s Node>, usize) {
+        let parent = self.path.len();
+        let key = self.key.take();
+
+        let level = match self.levels.last_mut() {
+            Some(level) => level,
+            None => return (Some(self.root), parent),
+        };
+
+        level.count += 1;
+
+        let node = if !level.object {
+            let _ = write!(self.path, "/{}", level.count - 1);
+
+            level.node.and_then(Node::items)
+        } else if let Some(name) = key {
+            self.path.push('/');
+            self.path.push_str(&pointer::escape(&name));
+
+            level.node.and_then(|node| node.property(&name))
+        } else {
+            // Unnamed values in objects have none.
+            None
+        };
+
+        (node, parent)
+    }
+
+    // Passes `event` on to the levels being built.
+    fn build(&mut self, event: &JsonEvent) {
+        if self.building == 0 {
+            return;
+        }
+
+        for builder in self
+            .levels
+            .iter_mut()
+            .filter_map(|level| level.value.as_mut())
+        {
+            builder.push(event);
+        }
+    }
+}
+
+// An object or array entered, with what is needed to check it once it is left.
+#[derive(Debug, Clone)]
+struct Level<'s> {
+    // Its schema, unless it has none or is of the wrong type.
+    node: Option<&'s Node>,
+    object: bool,
+    // The length of the path to where it is in.
+    parent: usize,
+    // The number of values in it.
+    count: usize,
+    // Which members of `required` were found in it.
+    found: Vec<bool>,
+    // It, as far as it was built, if there is an `enum` to compare it against.
+    value: Option<Builder>,
+}
+
+// An object or array built from events.
+#[derive(Debug, Clone)]
+struct Builder {
+    // The containers entered, each with the names of the members it is the value of and the values
+    // in it so far. The first is the object or array being built.
+    stack: Vec<(bool, Vec<String>, Vec<Json>)>,
+    // The names of the members whose value comes next.
+    names: Vec<String>,
+}
+
+impl Builder {
+    fn new(object: bool) -> Builder {
+        Builder {
+            stack: vec![(object, Vec::new(), Vec::new())],
+            names: Vec::new(),
+        }
+    }
+
+    // Takes an event inside the object or array being built.
+    fn push(&mut self, event: &JsonEvent) {
+        match event {
+            JsonEvent::Key(name) => self.names.push(name.clone()),
+            JsonEvent::ObjectStart | JsonEvent::ArrayStart => {
+                let object = *event == JsonEvent::ObjectStart;
+
+                self.stack
+                    .push((object, mem::take(&mut self.names), Vec::new()));
+            }
+            JsonEvent::ObjectEnd | JsonEvent::ArrayEnd => {
+                let (object, names, values) = self.stack.pop().expect("a container to end");
+
+                self.add(names, container(object, values));
+            }
+            _ => {
+                let names = mem::take(&mut self.names);
+
+                self.add(names, scalar(event));
+            }
+        }
+    }
+
+    // Adds `value` to the container it is in, as the value of the members `names`.
+    fn add(&mut self, names: Vec<String>, mut value: Json) {
+        for name in names.into_iter().rev() {
+            value = Json::OBJECT {
+                name,
+                value: Box::new(value),
+            };
+        }
+
+        self.stack.last_mut().expect("a container").2.push(value);
+    }
+
+    fn finish(mut self) -> Json {
+        let (object, _, values) = self.stack.pop().expect("a container");
+
+        container(object, values)
+    }
+}
+
+fn container(object: bool, values: Vec<Json>) -> Json {
+    if object {
+        Json::JSON(values)
+    } else {
+        Json::ARRAY(values)
+    }
+}
+
+// The value of an event which is a value by itself.
+fn scalar(event: &JsonEvent) -> Json {
+    match event {
+        JsonEvent::String(string) => Json::STRING(string.clone()),
+        JsonEvent::Number(number) => Json::NUMBER(*number),
+        JsonEvent::Bool(bool) => Json::BOOL(*bool),
+        _ => Json::NULL,
+    }
+}
+
+impl Json {
+    /// Parses `input` like `parse_with`, checking each value against `schema` as soon as it is
+    /// complete (and the type of each object or array as soon as it starts), so that a document
+    /// which doesn't conform fails before the rest of it is parsed, with
+    /// `ErrorKind::SchemaViolation` at where the value starts, or at the closing bracket of an
+    /// object or array missing something. `Schema::validate` tells what is wrong in detail. The
+    /// schema is passed besides `options` rather than in them, so that `ParseOptions` stays `Copy`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let schema = b"{\"items\":{\"type\":\"object\",\"required\":[\"id\"]}}";
+    /// let schema = Schema::compile(&Json::parse(schema).unwrap()).unwrap();
+    /// let options = ParseOptions::default();
+    ///
+    /// assert!(Json::parse_with_schema(b"[{\"id\":1},{\"id\":2}]", &options, &schema).is_ok());
+    ///
+    /// let input = b"[{\"id\":1},{\"name\":\"x\"},{\"id\":3}]";
+    /// let error = Json::parse_with_schema(input, &options, &schema).unwrap_err();
+    ///
+    /// assert_eq!(error.kind, ErrorKind::SchemaViolation);
+    /// assert_eq!((error.position, error.path.as_deref()), (21, Some("[1]")));
+    /// ```
+    pub fn parse_with_schema(
+        input: &[u8],
+        options: &ParseOptions,
+        schema: &Schema,
+    ) -> Result<Json, ParseError> {
+        let mut context = Context {
+            options: *options,
+            schema: Some(&schema.root),
+            ..Context::default()
+        };
+        let mut incr = 0;
+
+        parser::parse_with(input, &mut incr, &mut context)
+            .map_err(|error| context.error(input, error))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Type {
+    Null,
+    Boolean,
+    Object,
+    Array,
+    Number,
+    Integer,
+    String,
+}
+
+impl Type {
+    fn parse(name: &str) -> Option<Type> {
+        match name {
+            "null" => Some(Type::Null),
+            "boolean" => Some(Type::Boolean),
+            "object" => Some(Type::Object),
+            "array" => Some(Type::Array),
+            "number" => Some(Type::Number),
+            "integer" => Some(Type::Integer),
+            "string" => Some(Type::String),
+            _ => None,
+        }
+    }
+
+    // What a value of this type is called in messages.
+    fn name(self) -> &'static str {
+        match self {
+            Type::Null => "null",
+            Type::Boolean => "a bool",
+            Type::Object => "an object",
+            Type::Array => "an array",
+            Type::Number => "a number",
+            Type::Integer => "an integer",
+            Type::String => "a string",
+        }
+    }
+
+    fn of(json: &Json) -> Type {
+        match json {
+            Json::NULL => Type::Null,
+            Json::BOOL(_) => Type::Boolean,
+            Json::JSON(_) | Json::OBJECT { name: _, value: _ } => Type::Object,
+            Json::ARRAY(_) => Type::Array,
+            Json::NUMBER(number) if number.fract() == 0.0 => Type::Integer,
+            Json::NUMBER(_) => Type::Number,
+            Json::STRING(_) => Type::String,
+        }
+    }
+
+    fn matches(self, json: &Json) -> bool {
+        let found = Type::of(json);
+
+        found == self || (self == Type::Number && found == Type::Integer)
+    }
+}
+
+// A compiled schema, or one of the schemas inside it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Node {
+    // Set for `false`, which nothing conforms to.
+    reject: bool,
+    types: Option<Vec<Type>>,
+    properties: Vec<(String, Node)>,
+    required: Vec<String>,
+    items: Option<Box<Node>>,
+    values: Option<Vec<Json>>,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    exclusive_minimum: Option<f64>,
+    exclusive_maximum: Option<f64>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    min_items: Option<usize>,
+    max_items: Option<usize>,
+    pattern: Option<Regex>,
+}
+
+impl Node {
+    // Compiles `schema`, which is at `path` in the schema being compiled.
+    fn compile(schema: &Json, path: &str) -> Result<Node, SchemaError> {
+        let members = match schema {
+            Json::BOOL(accept) => {
+                return Ok(Node {
+                    reject: !accept,
+                    ..Node::default()
+                });
+            }
+            Json::JSON(_) => schema.iter(),
+            _ => return Err(error(path, "expected an object or a bool")),
+        };
+
+        let mut node = Node::default();
+
+        for (keyword, value) in members {
+            let keyword = match keyword {
+                Some(keyword) => keyword,
+                None => continue,
+            };
+            let path = &pointer::push(path, keyword);
+
+            match keyword {
+                "type" => node.types = Some(types(value, path)?),
+                "properties" => {
+                    if !matches!(value, Json::JSON(_)) {
+                        return Err(error(path, "expected an object"));
+                    }
+
+                    for (name, schema) in value.iter() {
+                        if let Some(name) = name {
+                            let schema = Node::compile(schema, &pointer::push(path, name))?;
+
+                            node.properties.push((name.to_string(), schema));
+                        }
+                    }
+                }
+                "required" => node.required = strings(value, path)?,
+                "items" => node.items = Some(Box::new(Node::compile(value, path)?)),
+                "enum" => match value {
+                    Json::ARRAY(values) => node.values = Some(values.clone()),
+                    _ => return Err(error(path, "expected an array")),
+                },
+                "minimum" => node.minimum = Some(number(value, path)?),
+                "maximum" => node.maximum = Some(number(value, path)?),
+                "exclusiveMinimum" => node.exclusive_minimum = Some(number(value, path)?),
+                "exclusiveMaximum" => node.exclusive_maximum = Some(number(value, path)?),
+                "minLength" => node.min_length = Some(count(value, path)?),
+                "maxLength" => node.max_length = Some(count(value, path)?),
+                "minItems" => node.min_items = Some(count(value, path)?),
+                "maxItems" => node.max_items = Some(count(value, path)?),
+                "pattern" => match value {
+                    Json::STRING(pattern) => match Regex::new(pattern) {
+                        Ok(pattern) => node.pattern = Some(pattern),
+                        Err(_) => return Err(error(path, "expected a regular expression")),
+                    },
+                    _ => return Err(error(path, "expected a string")),
+                },
+                // Unknown keywords are ignored, as the specification asks.
+                _ => {}
+            }
+        }
+
+        Ok(node)
+    }
+
+    // Whether `json` itself conforms, taking the values inside it to do so.
+    pub(crate) fn conforms(&self, json: &Json) -> bool {
+        let mut violations = Vec::new();
+
+        self.check(json, "", false, &mut violations);
+
+        violations.is_empty()
+    }
+
+    // Whether a value of the same type as `json` may conform, before what is inside it is known.
+    pub(crate) fn allows(&self, json: &Json) -> bool {
+        match &self.types {
+            _ if self.reject => false,
+            Some(types) => types.iter().any(|kind| kind.matches(json)),
+            None => true,
+        }
+    }
+
+    // Checks `json`, which is at `path` in the document being validated, and everything inside it
+    // if `deep`.
+    fn check(&self, json: &Json, path: &str, deep: bool, violations: &mut Vec<Violation>) {
+        let mut violation = |keyword, message| {
+            violations.push(Violation {
+                path: path.to_string(),
+                keyword,
+                message,
+            })
+        };
+
+        if self.reject {
+            return violation("false", "expected nothing".to_string());
+        }
+
+        if let Some(types) = &self.types {
+            if !types.iter().any(|kind| kind.matches(json)) {
+                let expected: Vec<&str> = types.iter().map(|kind| kind.name()).collect();
+                let message = format!(
+                    "expected {} but found {}",
+                    expected.join(" or "),
+                    Type::of(json).name()
+                );
+
+                // Nothing else is worth checking for a value of the wrong type.
+                return violation("type", message);
+            }
+        }
+
+        self.check_enum(json, &mut violation);
+
+        match json {
+            Json::NUMBER(number) => {
+                if let Some(minimum) = self.minimum.filter(|minimum| number < minimum) {
+                    violation("minimum", bound("at least", minimum, *number));
+                }
+
+                if let Some(maximum) = self.maximum.filter(|maximum| number > maximum) {
+                    violation("maximum", bound("at most", maximum, *number));
+                }
+
+                if let Some(minimum) = self.exclusive_minimum.filter(|minimum| number <= minimum) {
+                    violation("exclusiveMinimum", bound("more than", minimum, *number));
+                }
+
+                if let Some(maximum) = self.exclusive_maximum.filter(|maximum| number >= maximum) {
+                    violation("exclusiveMaximum", bound("less than", maximum, *number));
+                }
+            }
+            Json::STRING(string) => {
+                let length = string.chars().count();
+
+                if let Some(min_length) = self.min_length.filter(|min| length < *min) {
+                    violation(
+                        "minLength",
+                        size("at least", min_length, "characters", length),
+                    );
+                }
+
+                if let Some(max_length) = self.max_length.filter(|max| length > *max) {
+                    violation(
+                        "maxLength",
+                        size("at most", max_length, "characters", length),
+                    );
+                }
+
+                if let Some(pattern) = self.pattern.as_ref().filter(|p| !p.is_match(string)) {
+                    let message = format!(
+                        "expected to match `{}` but found {}",
+                        pattern.as_str(),
+                        json.print()
+                    );
+
+                    violation("pattern", message);
+                }
+            }
+            Json::JSON(_) => {
+                self.check_required(|n| json.contains_key(&self.required[n]), &mut violation);
+
+                for (name, value) in json.iter().filter(|_| deep) {
+                    let name = match name {
+                        Some(name) => name,
+                        None => continue,
+                    };
+
+                    if let Some(node) = self.property(name) {
+                        node.check(value, &pointer::push(path, name), deep, violations);
+                    }
+                }
+            }
+            Json::ARRAY(values) => {
+                self.check_items(values.len(), &mut violation);
+
+                if let Some(items) = self.items.as_ref().filter(|_| deep) {
+                    for (n, value) in values.iter().enumerate() {
+                        let path = format!("{}/{}", path, n);
+
+                        items.check(value.inner(), &path, deep, violations);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // Checks `json` against `enum`.
+    fn check_enum(&self, json: &Json, violation: &mut impl FnMut(&'static str, String)) {
+        let values = match &self.values {
+            Some(values) => values,
+            None => return,
+        };
+        let options = EqOptions {
+            ignore_key_order: true,
+            ..EqOptions::default()
+        };
+
+        if !values
+            .iter()
+            .any(|value| Json::eq_with(value, json, &options))
+        {
+            let message = format!(
+                "expected one of {} but found {}",
+                Json::ARRAY(values.clone()).print(),
+                json.print()
+            );
+
+            violation("enum", message);
+        }
+    }
+
+    // Checks an object against `required`, `contains(n)` telling whether it has the `n`th member.
+    fn check_required(
+        &self,
+        contains: impl Fn(usize) -> bool,
+        violation: &mut impl FnMut(&'static str, String),
+    ) {
+        for (n, name) in self.required.iter().enumerate() {
+            if !contains(n) {
+                violation("required", format!("missing member `{}`", name));
+            }
+        }
+    }
+
+    // Checks the number of elements of an array.
+    fn check_items(&self, count: usize, violation: &mut impl FnMut(&'static str, String)) {
+        if let Some(min_items) = self.min_items.filter(|min| count < *min) {
+            violation("minItems", size("at least", min_items, "elements", count));
+        }
+
+        if let Some(max_items) = self.max_items.filter(|max| count > *max) {
+            violation("maxItems", size("at most", max_items, "elements", count));
+        }
+    }
+
+    // The schema of the elements of an array.
+    pub(crate) fn items(&self) -> Option<&Node> {
+        self.items.as_deref()
+    }
+
+    // The schema of the member named `name` of an object.
+    pub(crate) fn property(&self, name: &str) -> Option<&Node> {
+        self.properties
+            .iter()
+            .find(|(property, _)| property == name)
+            .map(|(_, node)| node)
+    }
+}
+
+fn error(path: &str, message: &str) -> SchemaError {
+    SchemaError {
+        path: path.to_string(),
+        message: message.to_string(),
+    }
+}
+
+fn types(value: &Json, path: &str) -> Result<Vec<Type>, SchemaError> {
+    let names = match value {
+        Json::STRING(name) => vec![name.clone()],
+        _ => strings(value, path)?,
+    };
+
+    names
+        .iter()
+        .map(|name| Type::parse(name).ok_or_else(|| error(path, "expected the name of a type")))
+        .collect()
+}
+
+fn strings(value: &Json, path: &str) -> Result<Vec<String>, SchemaError> {
+    let values = match value {
+        Json::ARRAY(values) => values,
+        _ => return Err(error(path, "expected an array of strings")),
+    };
+
+    values
+        .iter()
+        .map(|value| match value {
+            Json::STRING(string) => Ok(string.clone()),
+            _ => Err(error(path, "expected an array of strings")),
+        })
+        .collect()
+}
+
+fn number(value: &Json, path: &str) -> Result<f64, SchemaError> {
+    match value {
+        Json::NUMBER(number) => Ok(*number),
+        _ => Err(error(path, "expected a number")),
+    }
+}
+
+fn count(value: &Json, path: &str) -> Result<usize, SchemaError> {
+    match value {
+        Json::NUMBER(number) if *number >= 0.0 && number.fract() == 0.0 => Ok(*number as usize),
+        _ => Err(error(path, "expected a non-negative integer")),
+    }
+}
+
+fn bound(expected: &str, bound: f64, number: f64) -> String {
+    format!(
+        "expected {} {} but found {}",
+        expected,
+        Json::NUMBER(bound).print(),
+        Json::NUMBER(number).print()
+    )
+}
+
+fn size(expected: &str, bound: usize, unit: &str, found: usize) -> String {
+    format!(
+        "expected {} {} {} but found {}",
+        expected, bound, unit, found
+    )
+}
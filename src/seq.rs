@@ -0,0 +1,167 @@
+//! JSON text sequences (RFC 7464), i.e. values each preceded by a record separator (`0x1E`) and
+//! followed by a line feed.
+
+use crate::printer::Printer;
+use crate::{is_whitespace, parser, Json, SeqError};
+use std::io::{self, BufRead, Write};
+
+const RS: u8 = 0x1E;
+
+/// An iterator over the values of a JSON text sequence. See `Json::parse_seq`.
+#[derive(Debug)]
+pub struct SeqReader<R> {
+    reader: R,
+    buffer: Vec<u8>,
+    record: usize,
+    // Set after an I/O error, which would most likely just repeat.
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for SeqReader<R> {
+    type Item = Result<Json, SeqError>;
+
+    fn next(&mut self) -> Option<Result<Json, SeqError>> {
+        while !self.done {
+            self.buffer.clear();
+
+            match self.reader.read_until(RS, &mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) => {
+                    if self.buffer.last() == Some(&RS) {
+                        self.buffer.pop();
+                    }
+
+                    if let Some(result) = self.parse_record() {
+                        return Some(result);
+                    }
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => {
+                    self.done = true;
+
+                    return Some(Err(SeqError::Io(error)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<R> SeqReader<R> {
+    // Parses the record in `buffer`, if it isn't empty.
+    fn parse_record(&mut self) -> Option<Result<Json, SeqError>> {
+        let start = self.buffer.iter().position(|c| !is_whitespace(*c))?;
+        let end = self.buffer.iter().rposition(|c| !is_whitespace(*c))? + 1;
+        let text = &self.buffer[start..end];
+
+        let record = self.record;
+        self.record += 1;
+
+        let error = |position, message| SeqError::Parse {
+            record,
+            position,
+            message,
+        };
+
+        // A number, `true`, `false` or `null` can only be told complete by the whitespace after it
+        // (RFC 7464, section 2.4).
+        if end == self.buffer.len() && !matches!(text[0], b'{' | b'[' | b'\"') {
+            return Some(Err(error(text.len(), "Possibly truncated value.")));
+        }
+
+        Some(
+            parser::parse(text, &mut 0).map_err(|(position, kind)| error(position, kind.message())),
+        )
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Writes values as a JSON text sequence, i.e. each one preceded by a record separator (`0x1E`) and
+/// followed by a line feed. Unlike `Json::print`, names and strings are escaped, so that
+/// `Json::parse_seq` reads back the values written.
+/// ## Example
+/// ```
+/// use json_minimal::*;
+///
+/// let mut writer = SeqWriter::new(Vec::new());
+///
+/// writer.write(&Json::parse(b"{\"level\":\"info\"}").unwrap()).unwrap();
+/// writer.write(&Json::NUMBER(36.0)).unwrap();
+///
+/// let output = writer.into_inner();
+///
+/// assert_eq!(output, b"\x1e{\"level\":\"info\"}\n\x1e36\n");
+///
+/// let values: Vec<Json> = Json::parse_seq(&output[..]).map(Result::unwrap).collect();
+///
+/// assert_eq!(values, vec![Json::parse(b"{\"level\":\"info\"}").unwrap(), Json::NUMBER(36.0)]);
+/// ```
+#[derive(Debug)]
+pub struct SeqWriter<W> {
+    writer: W,
+}
+
+impl<W: Write> SeqWriter<W> {
+    pub fn new(writer: W) -> SeqWriter<W> {
+        SeqWriter { writer }
+    }
+
+    /// Writes `json` as the next record.
+    pub fn write(&mut self, json: &Json) -> io::Result<()> {
+        let mut record = String::new();
+
+        let mut printer = Printer::new(json);
+
+        record.push(RS as char);
+
+        while printer.step(&mut record) {}
+
+        record.push('\n');
+
+        self.writer.write_all(record.as_bytes())
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// Returns the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl Json {
+    /// Reads a JSON text sequence (RFC 7464) from `reader`, yielding one value per record. Empty
+    /// records are skipped. A record that can't be parsed yields a `SeqError::Parse` and reading
+    /// continues with the next one, as does a number, `true`, `false` or `null` without whitespace
+    /// after it (which is possibly truncated). Reading stops after a `SeqError::Io`.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"\x1e{\"id\":1}\n\x1e[1,2]\n\x1e{\"id\":}\n\x1e\"done\"\n\x1e42";
+    ///
+    /// let mut values = Json::parse_seq(&input[..]);
+    ///
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"{\"id\":1}").unwrap());
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"[1,2]").unwrap());
+    /// assert!(matches!(values.next(), Some(Err(SeqError::Parse { record: 2, .. }))));
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::string("done"));
+    /// assert!(matches!(values.next(), Some(Err(SeqError::Parse { record: 4, .. }))));
+    /// assert!(values.next().is_none());
+    /// ```
+    pub fn parse_seq<R: BufRead>(reader: R) -> SeqReader<R> {
+        SeqReader {
+            reader,
+            buffer: Vec::new(),
+            record: 0,
+            done: false,
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! `Serialize` and `Deserialize` for `Json` (requires the `serde` feature).
+
+use crate::Json;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{self, Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::fmt;
+
+// The most elements or members reserved up front, whatever length the input claims, like serde's
+// own `size_hint::cautious`. A longer sequence or map still grows as it is read.
+pub(crate) const MAX_PREALLOCATED: usize = 4096;
+
+/// A `Json::JSON` is serialized as a map and a `Json::ARRAY` as a sequence. A standalone
+/// `Json::OBJECT` becomes a map with a single entry. A `Json::JSON` holding unnamed values can't be
+/// represented as a map and results in an error.
+impl Serialize for Json {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Json::OBJECT { name, value } => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry(name, value.unbox())?;
+                map.end()
+            }
+            Json::JSON(values) => {
+                let mut map = serializer.serialize_map(Some(values.len()))?;
+
+                for json in values {
+                    match json {
+                        Json::OBJECT { name, value } => {
+                            map.serialize_entry(name, value.unbox())?;
+                        }
+                        json => {
+                            return Err(ser::Error::custom(format!(
+                                "A `Json::JSON` holding an unnamed value ({}) can't be serialized as a map.",
+                                json.variant_name()
+                            )));
+                        }
+                    }
+                }
+
+                map.end()
+            }
+            Json::ARRAY(values) => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+
+                for json in values {
+                    seq.serialize_element(json)?;
+                }
+
+                seq.end()
+            }
+            Json::STRING(val) => serializer.serialize_str(val),
+            Json::NUMBER(val) => serializer.serialize_f64(*val),
+            Json::BOOL(val) => serializer.serialize_bool(*val),
+            Json::NULL => serializer.serialize_unit(),
+        }
+    }
+}
+
+/// Maps become a `Json::JSON` of `Json::OBJECT`s, sequences a `Json::ARRAY`, all numbers a
+/// `Json::NUMBER` and unit or `None` `Json::NULL`.
+impl<'de> Deserialize<'de> for Json {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Json, D::Error> {
+        deserializer.deserialize_any(JsonVisitor)
+    }
+}
+
+struct JsonVisitor;
+
+impl<'de> Visitor<'de> for JsonVisitor {
+    type Value = Json;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("any json value")
+    }
+
+    fn visit_bool<E: de::Error>(self, val: bool) -> Result<Json, E> {
+        Ok(Json::BOOL(val))
+    }
+
+    fn visit_i64<E: de::Error>(self, val: i64) -> Result<Json, E> {
+        Ok(Json::NUMBER(val as f64))
+    }
+
+    fn visit_u64<E: de::Error>(self, val: u64) -> Result<Json, E> {
+        Ok(Json::NUMBER(val as f64))
+    }
+
+    fn visit_f64<E: de::Error>(self, val: f64) -> Result<Json, E> {
+        Ok(Json::NUMBER(val))
+    }
+
+    fn visit_str<E: de::Error>(self, val: &str) -> Result<Json, E> {
+        Ok(Json::STRING(String::from(val)))
+    }
+
+    fn visit_string<E: de::Error>(self, val: String) -> Result<Json, E> {
+        Ok(Json::STRING(val))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Json, E> {
+        Ok(Json::NULL)
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Json, E> {
+        Ok(Json::NULL)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Json, D::Error> {
+        Json::deserialize(deserializer)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Json, A::Error> {
+        let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+
+        while let Some(json) = seq.next_element()? {
+            values.push(json);
+        }
+
+        Ok(Json::ARRAY(values))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Json, A::Error> {
+        let mut values = Vec::with_capacity(map.size_hint().unwrap_or(0).min(MAX_PREALLOCATED));
+
+        while let Some((name, value)) = map.next_entry::<String, Json>()? {
+            values.push(Json::member(name, value));
+        }
+
+        Ok(Json::JSON(values))
+    }
+}
@@ -0,0 +1,61 @@
+//! Conversions between `Json` and `serde_json::Value` (requires the `serde_json` feature).
+
+use crate::Json;
+use serde_json::{Map, Number, Value};
+
+/// Objects become a `Json::JSON` of `Json::OBJECT`s, arrays a `Json::ARRAY` and every number a
+/// `Json::NUMBER`.
+impl From<Value> for Json {
+    fn from(value: Value) -> Json {
+        match value {
+            Value::Null => Json::NULL,
+            Value::Bool(val) => Json::BOOL(val),
+            Value::Number(val) => Json::NUMBER(val.as_f64().unwrap_or(f64::NAN)),
+            Value::String(val) => Json::STRING(val),
+            Value::Array(values) => Json::ARRAY(values.into_iter().map(Json::from).collect()),
+            Value::Object(values) => Json::JSON(
+                values
+                    .into_iter()
+                    .map(|(name, value)| Json::member(name, Json::from(value)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// A standalone `Json::OBJECT` becomes an object with a single entry. Unnamed values within a
+/// `Json::JSON` have no counterpart in a `serde_json::Map` and are left out. Whole numbers are
+/// stored as integers and numbers which aren't finite become `Value::Null`.
+impl From<Json> for Value {
+    fn from(json: Json) -> Value {
+        match json {
+            Json::OBJECT { name, value } => {
+                let mut map = Map::new();
+                map.insert(name, Value::from(*value));
+                Value::Object(map)
+            }
+            Json::JSON(values) => Value::Object(
+                values
+                    .into_iter()
+                    .filter_map(|json| match json {
+                        Json::OBJECT { name, value } => Some((name, Value::from(*value))),
+                        _ => None,
+                    })
+                    .collect(),
+            ),
+            Json::ARRAY(values) => Value::Array(values.into_iter().map(Value::from).collect()),
+            Json::STRING(val) => Value::String(val),
+            Json::NUMBER(val) => number(val).map_or(Value::Null, Value::Number),
+            Json::BOOL(val) => Value::Bool(val),
+            Json::NULL => Value::Null,
+        }
+    }
+}
+
+fn number(val: f64) -> Option<Number> {
+    if val.fract() == 0.0 && (-9007199254740992.0..=9007199254740992.0).contains(&val) {
+        Some(Number::from(val as i64))
+    } else {
+        Number::from_f64(val)
+    }
+}
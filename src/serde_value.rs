@@ -0,0 +1,874 @@
+//! A serde data format over the `Json` tree (requires the `serde` feature).
+
+use crate::serde_impl::MAX_PREALLOCATED;
+use crate::{Json, SerdeError};
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess,
+    VariantAccess, Visitor,
+};
+use serde::ser::{self, Impossible, Serialize};
+use std::fmt;
+use std::vec;
+
+/// Serializes `value` into a `Json`.
+///
+/// Structs and maps become a `Json::JSON`, sequences and tuples a `Json::ARRAY` and every number a
+/// `Json::NUMBER`. Enum variants are written like serde_json does: unit variants as their name, all
+/// others as a `Json::JSON` with a single member named after the variant.
+///
+/// ## Example
+/// ```
+/// use json_minimal::*;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Point {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// let json = to_value(&Point { x: 1, y: 2 }).unwrap();
+///
+/// assert_eq!(json.print(), r#"{"x":1,"y":2}"#);
+/// ```
+/// ## Errors
+/// Fails if a map key isn't a string, number, bool or char, or if `value`'s `Serialize`
+/// implementation fails itself.
+pub fn to_value<T: Serialize + ?Sized>(value: &T) -> Result<Json, SerdeError> {
+    value.serialize(ValueSerializer)
+}
+
+/// Deserializes a `T` from `json`, the counterpart of `to_value`.
+///
+/// ## Example
+/// ```
+/// use json_minimal::*;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize, Debug, PartialEq)]
+/// struct Point {
+///     x: u32,
+///     y: u32,
+/// }
+///
+/// let json = Json::parse(br#"{"x":1,"y":2}"#).unwrap();
+///
+/// assert_eq!(from_value::<Point>(json).unwrap(), Point { x: 1, y: 2 });
+/// ```
+/// ## Errors
+/// Fails if `json` doesn't have the shape `T` expects.
+pub fn from_value<T: DeserializeOwned>(json: Json) -> Result<T, SerdeError> {
+    T::deserialize(json)
+}
+
+impl ser::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> SerdeError {
+        SerdeError {
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl de::Error for SerdeError {
+    fn custom<T: fmt::Display>(msg: T) -> SerdeError {
+        SerdeError {
+            message: msg.to_string(),
+        }
+    }
+}
+
+fn variant(name: &str, value: Json) -> Json {
+    Json::JSON(vec![Json::member(name, value)])
+}
+
+// The range of whole numbers `deserialize_any` hands out as `u64` and `i64` respectively.
+const U64_END: f64 = 18446744073709551616.0;
+const I64_START: f64 = -9223372036854775808.0;
+
+struct ValueSerializer;
+
+impl ser::Serializer for ValueSerializer {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    type SerializeSeq = SerializeArray;
+    type SerializeTuple = SerializeArray;
+    type SerializeTupleStruct = SerializeArray;
+    type SerializeTupleVariant = SerializeArray;
+    type SerializeMap = SerializeObject;
+    type SerializeStruct = SerializeObject;
+    type SerializeStructVariant = SerializeObject;
+
+    fn serialize_bool(self, v: bool) -> Result<Json, SerdeError> {
+        Ok(Json::BOOL(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v as f64))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Json, SerdeError> {
+        Ok(Json::NUMBER(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<Json, SerdeError> {
+        Ok(Json::STRING(v.to_string()))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Json, SerdeError> {
+        Ok(Json::string(v))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<Json, SerdeError> {
+        Ok(Json::ARRAY(
+            v.iter().map(|byte| Json::NUMBER(*byte as f64)).collect(),
+        ))
+    }
+
+    fn serialize_none(self) -> Result<Json, SerdeError> {
+        Ok(Json::NULL)
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<Json, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Json, SerdeError> {
+        Ok(Json::NULL)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Json, SerdeError> {
+        Ok(Json::NULL)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<Json, SerdeError> {
+        Ok(Json::string(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Json, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Json, SerdeError> {
+        Ok(variant(name, value.serialize(self)?))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeArray, SerdeError> {
+        Ok(SerializeArray {
+            variant: None,
+            values: Vec::with_capacity(len.unwrap_or(0).min(MAX_PREALLOCATED)),
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeArray, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, SerdeError> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeArray, SerdeError> {
+        Ok(SerializeArray {
+            variant: Some(variant),
+            values: Vec::with_capacity(len.min(MAX_PREALLOCATED)),
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeObject, SerdeError> {
+        Ok(SerializeObject {
+            variant: None,
+            values: Vec::with_capacity(len.unwrap_or(0).min(MAX_PREALLOCATED)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, SerdeError> {
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeObject, SerdeError> {
+        Ok(SerializeObject {
+            variant: Some(variant),
+            values: Vec::with_capacity(len.min(MAX_PREALLOCATED)),
+            key: None,
+        })
+    }
+}
+
+struct SerializeArray {
+    variant: Option<&'static str>,
+    values: Vec<Json>,
+}
+
+impl SerializeArray {
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.values.push(value.serialize(ValueSerializer)?);
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Json, SerdeError> {
+        let array = Json::ARRAY(self.values);
+
+        match self.variant {
+            Some(name) => Ok(variant(name, array)),
+            None => Ok(array),
+        }
+    }
+}
+
+impl ser::SerializeSeq for SerializeArray {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SerializeArray {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SerializeArray {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleVariant for SerializeArray {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+struct SerializeObject {
+    variant: Option<&'static str>,
+    values: Vec<Json>,
+    key: Option<String>,
+}
+
+impl SerializeObject {
+    fn insert<T: Serialize + ?Sized>(&mut self, name: String, value: &T) -> Result<(), SerdeError> {
+        self.values
+            .push(Json::member(name, value.serialize(ValueSerializer)?));
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Json, SerdeError> {
+        let json = Json::JSON(self.values);
+
+        match self.variant {
+            Some(name) => Ok(variant(name, json)),
+            None => Ok(json),
+        }
+    }
+}
+
+impl ser::SerializeMap for SerializeObject {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), SerdeError> {
+        self.key = Some(key.serialize(KeySerializer)?);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), SerdeError> {
+        match self.key.take() {
+            Some(name) => self.insert(name, value),
+            None => Err(ser::Error::custom(
+                "serialize_value was called before serialize_key",
+            )),
+        }
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStruct for SerializeObject {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.insert(String::from(key), value)
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeStructVariant for SerializeObject {
+    type Ok = Json;
+    type Error = SerdeError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), SerdeError> {
+        self.insert(String::from(key), value)
+    }
+
+    fn end(self) -> Result<Json, SerdeError> {
+        self.finish()
+    }
+}
+
+// Turns map keys into member names. Like serde_json, numbers, bools and chars are accepted and
+// written as strings.
+struct KeySerializer;
+
+impl KeySerializer {
+    fn unsupported(found: &str) -> SerdeError {
+        ser::Error::custom(format!("a map key must be a string but found {}", found))
+    }
+}
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = SerdeError;
+
+    type SerializeSeq = Impossible<String, SerdeError>;
+    type SerializeTuple = Impossible<String, SerdeError>;
+    type SerializeTupleStruct = Impossible<String, SerdeError>;
+    type SerializeTupleVariant = Impossible<String, SerdeError>;
+    type SerializeMap = Impossible<String, SerdeError>;
+    type SerializeStruct = Impossible<String, SerdeError>;
+    type SerializeStructVariant = Impossible<String, SerdeError>;
+
+    fn serialize_bool(self, v: bool) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String, SerdeError> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<String, SerdeError> {
+        Ok(String::from(v))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<String, SerdeError> {
+        Err(KeySerializer::unsupported("bytes"))
+    }
+
+    fn serialize_none(self) -> Result<String, SerdeError> {
+        Err(KeySerializer::unsupported("none"))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<String, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<String, SerdeError> {
+        Err(KeySerializer::unsupported("unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<String, SerdeError> {
+        Err(KeySerializer::unsupported(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+    ) -> Result<String, SerdeError> {
+        Ok(String::from(variant))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String, SerdeError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<String, SerdeError> {
+        Err(KeySerializer::unsupported(variant))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, SerdeError> {
+        Err(KeySerializer::unsupported("a sequence"))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, SerdeError> {
+        Err(KeySerializer::unsupported("a tuple"))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, SerdeError> {
+        Err(KeySerializer::unsupported(name))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, SerdeError> {
+        Err(KeySerializer::unsupported(variant))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, SerdeError> {
+        Err(KeySerializer::unsupported("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, SerdeError> {
+        Err(KeySerializer::unsupported(name))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, SerdeError> {
+        Err(KeySerializer::unsupported(variant))
+    }
+}
+
+impl<'de> IntoDeserializer<'de, SerdeError> for Json {
+    type Deserializer = Json;
+
+    fn into_deserializer(self) -> Json {
+        self
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Json {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Json::OBJECT { name, value } => visitor.visit_map(Members {
+                values: vec![Json::OBJECT { name, value }].into_iter(),
+                value: None,
+            }),
+            Json::JSON(values) => visitor.visit_map(Members {
+                values: values.into_iter(),
+                value: None,
+            }),
+            Json::ARRAY(values) => visitor.visit_seq(Elements {
+                values: values.into_iter(),
+            }),
+            Json::STRING(val) => visitor.visit_string(val),
+            // Whole numbers are handed out as integers so that integer fields can be read back.
+            Json::NUMBER(val) if val.fract() == 0.0 && (0.0..U64_END).contains(&val) => {
+                visitor.visit_u64(val as u64)
+            }
+            Json::NUMBER(val) if val.fract() == 0.0 && (I64_START..0.0).contains(&val) => {
+                visitor.visit_i64(val as i64)
+            }
+            Json::NUMBER(val) => visitor.visit_f64(val),
+            Json::BOOL(val) => visitor.visit_bool(val),
+            Json::NULL => visitor.visit_unit(),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        match self {
+            Json::NULL => visitor.visit_none(),
+            json => visitor.visit_some(json),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self {
+            Json::STRING(name) => visitor.visit_enum(Variant { name, value: None }),
+            Json::OBJECT { name, value } => visitor.visit_enum(Variant {
+                name,
+                value: Some(*value),
+            }),
+            Json::JSON(mut values) if values.len() == 1 => match values.pop() {
+                Some(Json::OBJECT { name, value }) => visitor.visit_enum(Variant {
+                    name,
+                    value: Some(*value),
+                }),
+                _ => Err(de::Error::custom(
+                    "expected a string or a Json::JSON with a single member for an enum",
+                )),
+            },
+            json => Err(de::Error::custom(format!(
+                "expected a string or a Json::JSON with a single member for an enum but found a {}",
+                json.variant_name()
+            ))),
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct Elements {
+    values: vec::IntoIter<Json>,
+}
+
+impl<'de> SeqAccess<'de> for Elements {
+    type Error = SerdeError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, SerdeError> {
+        match self.values.next() {
+            Some(json) => seed.deserialize(json).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct Members {
+    values: vec::IntoIter<Json>,
+    value: Option<Json>,
+}
+
+impl<'de> MapAccess<'de> for Members {
+    type Error = SerdeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, SerdeError> {
+        match self.values.next() {
+            Some(Json::OBJECT { name, value }) => {
+                self.value = Some(*value);
+                seed.deserialize(KeyDeserializer { name }).map(Some)
+            }
+            Some(json) => Err(de::Error::custom(format!(
+                "expected a named member but found an unnamed {}",
+                json.variant_name()
+            ))),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, SerdeError> {
+        match self.value.take() {
+            Some(json) => seed.deserialize(json),
+            None => Err(de::Error::custom(
+                "next_value_seed was called before next_key_seed",
+            )),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+// Reads member names back as map keys, parsing them for keys `KeySerializer` wrote as strings.
+struct KeyDeserializer {
+    name: String,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+                match self.name.parse() {
+                    Ok(val) => visitor.$visit(val),
+                    Err(_) => visitor.visit_string(self.name),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for KeyDeserializer {
+    type Error = SerdeError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, SerdeError> {
+        visitor.visit_string(self.name)
+    }
+
+    deserialize_parsed_key! {
+        deserialize_bool => visit_bool,
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+        deserialize_f32 => visit_f32,
+        deserialize_f64 => visit_f64,
+        deserialize_char => visit_char,
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        visitor.visit_enum(self.name.into_deserializer())
+    }
+
+    serde::forward_to_deserialize_any! {
+        i128 u128 str string bytes byte_buf option unit unit_struct seq tuple
+        tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct Variant {
+    name: String,
+    value: Option<Json>,
+}
+
+impl<'de> EnumAccess<'de> for Variant {
+    type Error = SerdeError;
+    type Variant = VariantValue;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantValue), SerdeError> {
+        let name = seed.deserialize(self.name.into_deserializer())?;
+
+        Ok((name, VariantValue { value: self.value }))
+    }
+}
+
+struct VariantValue {
+    value: Option<Json>,
+}
+
+impl VariantValue {
+    fn expected(self, expected: &str) -> Result<Json, SerdeError> {
+        match self.value {
+            Some(json) => Ok(json),
+            None => Err(de::Error::invalid_type(
+                de::Unexpected::UnitVariant,
+                &expected,
+            )),
+        }
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantValue {
+    type Error = SerdeError;
+
+    fn unit_variant(self) -> Result<(), SerdeError> {
+        match self.value {
+            None | Some(Json::NULL) => Ok(()),
+            Some(json) => Err(de::Error::custom(format!(
+                "expected a unit variant but found a {}",
+                json.variant_name()
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(
+        self,
+        seed: T,
+    ) -> Result<T::Value, SerdeError> {
+        seed.deserialize(self.expected("newtype variant")?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_seq(self.expected("tuple variant")?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, SerdeError> {
+        de::Deserializer::deserialize_map(self.expected("struct variant")?, visitor)
+    }
+}
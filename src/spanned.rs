@@ -0,0 +1,187 @@
+//! Parsing that keeps track of where each value came from, so that later errors can point at it.
+
+use crate::pointer::push;
+use crate::{Json, JsonEvent, JsonLexer, ParseError};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+
+/// Where the values returned by `Json::parse_spanned` were found in the input, by JSON Pointer (see
+/// `Json::pointer`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spans {
+    spans: HashMap<String, Range<usize>>,
+    // Where the members start, i.e. their names.
+    members: HashMap<String, usize>,
+    // Where each line starts.
+    lines: Vec<usize>,
+    pub(crate) comments: Vec<Range<usize>>,
+}
+
+impl Spans {
+    /// The bytes of the input the value `pointer` refers to was parsed from. A member's value doesn't
+    /// include its name, a `Json::OBJECT` does.
+    pub fn get(&self, pointer: &str) -> Option<Range<usize>> {
+        self.spans.get(pointer).cloned()
+    }
+
+    /// The line (counting from `1`) and the offset into it of the start of the value `pointer` refers
+    /// to, as given by `ParseError`.
+    pub fn location(&self, pointer: &str) -> Option<(usize, usize)> {
+        let start = self.spans.get(pointer)?.start;
+        let line = self.lines.partition_point(|n| *n <= start);
+
+        Some((line, start - self.lines[line - 1]))
+    }
+
+    // Where the value `pointer` refers to starts, including the name if it is a member.
+    pub(crate) fn member(&self, pointer: &str) -> Option<Range<usize>> {
+        let span = self.spans.get(pointer)?;
+
+        Some(self.members.get(pointer).copied().unwrap_or(span.start)..span.end)
+    }
+}
+
+impl Json {
+    /// Parses `input` like `parse`, also returning where each value was found. Of several members of
+    /// the same name only the first has a span, as only the first is found by `pointer`. Unlike
+    /// `parse`, nothing but whitespace may follow the value.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\n  \"host\": \"localhost\",\n  \"port\": \"80\"\n}";
+    ///
+    /// let (json, spans) = Json::parse_spanned(input).unwrap();
+    ///
+    /// // `port` should have been a number, so point at it.
+    /// assert!(json.pointer("/port").and_then(Json::as_f64).is_none());
+    /// assert_eq!(spans.location("/port"), Some((3, 10)));
+    ///
+    /// assert_eq!(&input[spans.get("/host").unwrap()], b"\"localhost\"");
+    /// assert_eq!(spans.get(""), Some(0..input.len()));
+    /// ```
+    /// ## Errors
+    /// Fails like the `JsonLexer` does.
+    pub fn parse_spanned(input: &[u8]) -> Result<(Json, Spans), ParseError> {
+        parse(input, JsonLexer::new(input))
+    }
+}
+
+// Parses `input` with `lexer`, which may be set up to allow comments.
+pub(crate) fn parse(input: &[u8], mut lexer: JsonLexer<'_>) -> Result<(Json, Spans), ParseError> {
+    let mut spans = Spans {
+        spans: HashMap::new(),
+        members: HashMap::new(),
+        comments: Vec::new(),
+        lines: std::iter::once(0)
+            .chain(
+                (0..input.len())
+                    .filter(|n| input[*n] == b'\n')
+                    .map(|n| n + 1),
+            )
+            .collect(),
+    };
+
+    let first = next(&mut lexer)?;
+    let json = value(&mut lexer, first, Some(String::new()), &mut spans)?;
+
+    // Checks that nothing follows the value.
+    if let Some(Err(error)) = lexer.next() {
+        return Err(error);
+    }
+
+    spans.comments = lexer.comments().to_vec();
+
+    Ok((json, spans))
+}
+
+fn next(lexer: &mut JsonLexer<'_>) -> Result<JsonEvent, ParseError> {
+    lexer
+        .next()
+        .expect("the lexer yields events until the value is complete")
+}
+
+// Builds the value starting with `event`, recording the spans of it and everything in it under
+// `path`. Values which `pointer` can't reach have no `path`.
+fn value(
+    lexer: &mut JsonLexer<'_>,
+    event: JsonEvent,
+    path: Option<String>,
+    spans: &mut Spans,
+) -> Result<Json, ParseError> {
+    let start = lexer.span().start;
+
+    let json = match event {
+        JsonEvent::ObjectStart => {
+            let mut values = Vec::new();
+            let mut names = HashSet::new();
+
+            loop {
+                match next(lexer)? {
+                    JsonEvent::ObjectEnd => break,
+                    JsonEvent::Key(name) => {
+                        let path = match &path {
+                            Some(path) if names.insert(name.clone()) => Some(push(path, &name)),
+                            _ => None,
+                        };
+
+                        if let Some(path) = &path {
+                            spans.members.insert(path.clone(), lexer.span().start);
+                        }
+
+                        let first = next(lexer)?;
+                        let value = value(lexer, first, path, spans)?;
+
+                        values.push(Json::OBJECT {
+                            name,
+                            value: Box::new(value),
+                        });
+                    }
+                    event => values.push(value(lexer, event, None, spans)?),
+                }
+            }
+
+            Json::JSON(values)
+        }
+        JsonEvent::ArrayStart => {
+            let mut values = Vec::new();
+
+            loop {
+                match next(lexer)? {
+                    JsonEvent::ArrayEnd => break,
+                    event => {
+                        let path = path
+                            .as_ref()
+                            .map(|path| format!("{}/{}", path, values.len()));
+
+                        values.push(value(lexer, event, path, spans)?);
+                    }
+                }
+            }
+
+            Json::ARRAY(values)
+        }
+        // A standalone object, which `pointer` sees through.
+        JsonEvent::Key(name) => {
+            let first = next(lexer)?;
+
+            Json::OBJECT {
+                name,
+                value: Box::new(value(lexer, first, path.clone(), spans)?),
+            }
+        }
+        JsonEvent::String(string) => Json::STRING(string),
+        JsonEvent::Number(number) => Json::NUMBER(number),
+        JsonEvent::Bool(bool) => Json::BOOL(bool),
+        JsonEvent::Null => Json::NULL,
+        // The lexer doesn't yield an end without a start.
+        JsonEvent::ObjectEnd | JsonEvent::ArrayEnd => unreachable!(),
+    };
+
+    // A standalone object overwrites the span of its value, which it shares the path with.
+    if let Some(path) = path {
+        spans.spans.insert(path, start..lexer.span().end);
+    }
+
+    Ok(json)
+}
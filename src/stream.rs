@@ -0,0 +1,399 @@
+//! Concatenated JSON, i.e. values following each other without any framing (`{"a":1} {"b":2}`).
+
+use crate::scan;
+use crate::{is_whitespace, ErrorKind, Json, ParseError};
+use std::io::{self, BufRead};
+
+/// An iterator over the values of concatenated JSON. See `Json::parse_stream`.
+#[derive(Debug)]
+pub struct StreamReader<R> {
+    reader: R,
+    // The input read so far, without the values already yielded.
+    buffer: Vec<u8>,
+    // Where `buffer` starts in the input.
+    line: usize,
+    position: usize,
+    eof: bool,
+    // Set after the first error, as there is no telling where the next value starts.
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for StreamReader<R> {
+    type Item = Result<Json, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Json, ParseError>> {
+        if self.done {
+            return None;
+        }
+
+        match self.find_start(false) {
+            Ok(Some(start)) => Some(self.value(start)),
+            Ok(None) => None,
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+impl<R: BufRead> StreamReader<R> {
+    // Finds the next byte in `buffer` that isn't whitespace (or a comma, if `commas` is set),
+    // reading on as needed. `None` means that the input ended.
+    fn find_start(&mut self, commas: bool) -> Result<Option<usize>, ParseError> {
+        loop {
+            let start = self
+                .buffer
+                .iter()
+                .position(|c| !(is_whitespace(*c) || (commas && *c == b',')));
+
+            if start.is_some() || self.eof {
+                return Ok(start);
+            }
+
+            self.fill_or_stop()?;
+        }
+    }
+
+    // Parses the value starting at `buffer[start]`, reading on until its end.
+    fn value(&mut self, start: usize) -> Result<Json, ParseError> {
+        loop {
+            match scan_value(&self.buffer, start, self.eof) {
+                Some(end) => return self.parse(start, end),
+                None if self.eof => {
+                    return Err(self.stop(self.buffer.len(), ErrorKind::UnexpectedEof))
+                }
+                None => self.fill_or_stop()?,
+            }
+        }
+    }
+
+    fn fill_or_stop(&mut self) -> Result<(), ParseError> {
+        self.fill().map_err(|error| {
+            self.done = true;
+
+            ParseError::io(self.line, error)
+        })
+    }
+
+    // Reads on until `buffer` has (at least) doubled in size, so that a large value isn't scanned
+    // again for every chunk of it.
+    fn fill(&mut self) -> io::Result<()> {
+        let target = self.buffer.len() * 2;
+
+        loop {
+            match self.reader.fill_buf() {
+                Ok(chunk) => {
+                    let len = chunk.len();
+
+                    self.eof = len == 0;
+                    self.buffer.extend_from_slice(chunk);
+                    self.reader.consume(len);
+
+                    if self.eof || self.buffer.len() >= target {
+                        return Ok(());
+                    }
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl<R> StreamReader<R> {
+    // Parses the value at `buffer[start..end]` and drops everything up to its end from `buffer`.
+    fn parse(&mut self, start: usize, end: usize) -> Result<Json, ParseError> {
+        let mut incr = start;
+
+        match Json::parse_value(&self.buffer[..end], &mut incr) {
+            Ok(json) => {
+                self.advance(end);
+
+                Ok(json)
+            }
+            Err((position, message)) => Err(self.stop(position, message)),
+        }
+    }
+
+    // Drops the first `len` bytes from `buffer`, keeping track of where it starts.
+    fn advance(&mut self, len: usize) {
+        let (line, position) = self.locate(len);
+
+        self.line = line;
+        self.position = position;
+        self.buffer.drain(..len);
+    }
+
+    // The line and the offset into it of `buffer[index]`.
+    fn locate(&self, index: usize) -> (usize, usize) {
+        self.buffer[..index.min(self.buffer.len())].iter().fold(
+            (self.line, self.position),
+            |(line, position), c| {
+                if *c == b'\n' {
+                    (line + 1, 0)
+                } else {
+                    (line, position + 1)
+                }
+            },
+        )
+    }
+
+    fn error(&self, index: usize, message: ErrorKind) -> ParseError {
+        let (line, position) = self.locate(index);
+
+        ParseError::new(line, position, message)
+    }
+
+    // The error at `buffer[index]`, after which there is no telling how to go on.
+    fn stop(&mut self, index: usize, message: ErrorKind) -> ParseError {
+        self.done = true;
+        self.error(index, message)
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl Json {
+    /// Reads values from `input` one after the other, as emitted by streaming APIs without any
+    /// framing. They may be separated by whitespace, which numbers, `true`, `false` and `null` need
+    /// to be. Anything implementing `BufRead` will do, including a `&[u8]`. Errors carry the line
+    /// (counting from `1`) and the offset into it, iteration stops after the first one.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"{\"a\":1} {\"b\":2}[3]\n\"four\" 5\n{\"c\":";
+    ///
+    /// let mut values = Json::parse_stream(&input[..]);
+    ///
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"{\"a\":1}").unwrap());
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"{\"b\":2}").unwrap());
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::parse(b"[3]").unwrap());
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::string("four"));
+    /// assert_eq!(values.next().unwrap().unwrap(), Json::NUMBER(5.0));
+    ///
+    /// let error = values.next().unwrap().unwrap_err();
+    ///
+    /// assert_eq!((error.line, error.position, error.message), (3, 5, "Unexpected end of input."));
+    /// assert!(values.next().is_none());
+    /// ```
+    pub fn parse_stream<R: BufRead>(input: R) -> StreamReader<R> {
+        StreamReader {
+            reader: input,
+            buffer: Vec::new(),
+            line: 1,
+            position: 0,
+            eof: false,
+            done: false,
+        }
+    }
+
+    /// Parses the bytes `input` yields, e.g. a decompressor or a decoding adapter producing an
+    /// iterator rather than a slice. The bytes are consumed as they are needed, so that only the
+    /// value being built and a small buffer are held. Whitespace may surround the value, but nothing
+    /// else may follow it. Errors carry the line (counting from `1`) and the offset into it.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// // E.g. the output of a decoder.
+    /// let input = "{\"a\":[1,2]}".chars().map(|c| c as u8);
+    ///
+    /// assert_eq!(Json::parse_iter(input).unwrap(), Json::parse(b"{\"a\":[1,2]}").unwrap());
+    ///
+    /// let error = Json::parse_iter(b"[1]\n]".iter().copied()).unwrap_err();
+    ///
+    /// assert_eq!((error.line, error.position), (2, 0));
+    /// ```
+    pub fn parse_iter<I: IntoIterator<Item = u8>>(input: I) -> Result<Json, ParseError> {
+        let mut values = Json::parse_stream(io::BufReader::new(IterReader(input.into_iter())));
+
+        let json = match values.next() {
+            Some(result) => result?,
+            None => return Err(values.error(values.buffer.len(), ErrorKind::UnexpectedEof)),
+        };
+
+        match values.find_start(false)? {
+            Some(start) => Err(values.error(start, ErrorKind::TrailingData)),
+            None => Ok(json),
+        }
+    }
+}
+
+// Reads the bytes an iterator yields.
+struct IterReader<I>(I);
+
+impl<I: Iterator<Item = u8>> io::Read for IterReader<I> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        Ok(buffer
+            .iter_mut()
+            .zip(&mut self.0)
+            .map(|(c, byte)| *c = byte)
+            .count())
+    }
+}
+
+/// An iterator over the elements of an array, read one at a time. See `Json::parse_array_stream`.
+#[derive(Debug)]
+pub struct ArrayReader<R> {
+    stream: StreamReader<R>,
+    started: bool,
+}
+
+impl<R: BufRead> Iterator for ArrayReader<R> {
+    type Item = Result<Json, ParseError>;
+
+    fn next(&mut self) -> Option<Result<Json, ParseError>> {
+        if self.stream.done {
+            return None;
+        }
+
+        match self.element() {
+            Ok(Some(start)) => Some(self.stream.value(start)),
+            Ok(None) => None,
+            Err(error) => Some(Err(error)),
+        }
+    }
+}
+
+impl<R: BufRead> ArrayReader<R> {
+    // Finds the start of the next element, `None` meaning that the array ended.
+    fn element(&mut self) -> Result<Option<usize>, ParseError> {
+        let stream = &mut self.stream;
+
+        if !self.started {
+            match stream.find_start(false)? {
+                Some(start) if stream.buffer[start] == b'[' => stream.advance(start + 1),
+                Some(start) => return Err(stream.stop(start, ErrorKind::ExpectedArray)),
+                None => return Err(stream.stop(stream.buffer.len(), ErrorKind::UnexpectedEof)),
+            }
+
+            self.started = true;
+        }
+
+        // Commas are skipped like whitespace, as they are by the parser.
+        match stream.find_start(true)? {
+            Some(start) if stream.buffer[start] == b']' => stream.advance(start + 1),
+            Some(start) => return Ok(Some(start)),
+            None => return Err(stream.stop(stream.buffer.len(), ErrorKind::UnexpectedEof)),
+        }
+
+        stream.done = true;
+
+        match stream.find_start(false)? {
+            Some(start) => Err(stream.error(start, ErrorKind::TrailingData)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl<R> ArrayReader<R> {
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.stream.reader
+    }
+}
+
+impl Json {
+    /// Reads the array in `input` one element at a time, so that arrays far too large to be held in
+    /// memory can be processed, e.g. the export of a database. Only the element being read and what
+    /// `input` buffers are held. Errors carry the line (counting from `1`) and the offset into it,
+    /// iteration stops after the first one.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let input = b"[\n  {\"id\":1},\n  {\"id\":2},\n  3\n]\n";
+    ///
+    /// let elements: Vec<Json> = Json::parse_array_stream(&input[..]).map(Result::unwrap).collect();
+    ///
+    /// assert_eq!(elements.len(), 3);
+    /// assert_eq!(elements[1], Json::parse(b"{\"id\":2}").unwrap());
+    ///
+    /// let error = Json::parse_array_stream(&b"[1,2"[..]).nth(2).unwrap().unwrap_err();
+    ///
+    /// assert_eq!((error.line, error.position), (1, 4));
+    /// ```
+    pub fn parse_array_stream<R: BufRead>(input: R) -> ArrayReader<R> {
+        ArrayReader {
+            stream: Json::parse_stream(input),
+            started: false,
+        }
+    }
+}
+
+// Finds the end of the value starting at `buffer[start]` without parsing it, `None` meaning that it
+// continues beyond `buffer`. Unless `eof` is set, a value which might go on is incomplete too.
+pub(crate) fn scan_value(buffer: &[u8], start: usize, eof: bool) -> Option<usize> {
+    match buffer[start] {
+        b'{' | b'[' => scan_container(buffer, start),
+        b'\"' => {
+            let end = scan_string(buffer, start)?;
+
+            // `"name":value` is a (standalone) object.
+            match buffer.get(end) {
+                Some(b':') => {
+                    let value = (end + 1..buffer.len()).find(|n| !is_whitespace(buffer[*n]))?;
+
+                    scan_value(buffer, value, eof)
+                }
+                Some(_) => Some(end),
+                None if eof => Some(end),
+                None => None,
+            }
+        }
+        _ => match buffer[start + 1..]
+            .iter()
+            .position(|c| is_whitespace(*c) || matches!(c, b',' | b']' | b'}'))
+        {
+            Some(len) => Some(start + 1 + len),
+            None if eof => Some(buffer.len()),
+            None => None,
+        },
+    }
+}
+
+pub(crate) fn scan_string(buffer: &[u8], start: usize) -> Option<usize> {
+    let mut n = start + 1;
+
+    loop {
+        n += scan::find(buffer.get(n..)?, b"\"\\")?;
+
+        if buffer[n] == b'\"' {
+            return Some(n + 1);
+        }
+
+        n += 2;
+    }
+}
+
+fn scan_container(buffer: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut n = start;
+
+    while n < buffer.len() {
+        n += scan::find(&buffer[n..], b"{}[]\"")?;
+
+        match buffer[n] {
+            b'{' | b'[' => depth += 1,
+            b'}' | b']' => {
+                depth -= 1;
+
+                if depth == 0 {
+                    return Some(n + 1);
+                }
+            }
+            b'\"' => {
+                n = scan_string(buffer, n)?;
+
+                continue;
+            }
+            _ => {}
+        }
+
+        n += 1;
+    }
+
+    None
+}
@@ -73,38 +73,4721 @@ fn test_make_json() {
         )
 }
 
+#[test]
+fn test_try_add() {
+    let mut json = Json::new();
+
+    match json.try_add(Json::JSON(Vec::new())) {
+        Err(AddError::UnnamedJson(value)) => {
+            assert_eq!(value, Json::JSON(Vec::new()));
+        }
+        result => {
+            panic!("Expected AddError::UnnamedJson but found {:?}", result);
+        }
+    }
+
+    let mut greeting = Json::OBJECT {
+        name: String::from("Greeting"),
+
+        value: Box::new(Json::STRING(String::from("Hello, world!"))),
+    };
+
+    match greeting.try_add(Json::NULL) {
+        Err(AddError::NotAContainer(value)) => {
+            assert_eq!(value, Json::NULL);
+        }
+        result => {
+            panic!("Expected AddError::NotAContainer but found {:?}", result);
+        }
+    }
+
+    let mut days = Json::ARRAY(Vec::new());
+
+    match days
+        .try_add(Json::STRING(String::from("Monday")))
+        .and_then(|days| days.try_add(Json::STRING(String::from("Tuesday"))))
+    {
+        Ok(days) => {
+            assert_eq!("[\"Monday\",\"Tuesday\"]", days.print());
+        }
+        Err(e) => {
+            panic!("Unexpected error: {}", e);
+        }
+    }
+
+    assert!(json.try_add(greeting).is_ok());
+    assert_eq!("{\"Greeting\":\"Hello, world!\"}", json.print());
+}
+
+#[test]
+#[should_panic]
+fn test_add_panics() {
+    Json::NULL.add(Json::NULL);
+}
+
+#[test]
+fn test_try_get() {
+    let mut json = match Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Days\":[\"Monday\"]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        json.try_value_of("Greeting"),
+        Ok(Some(&Json::STRING(String::from("Hello, world!"))))
+    );
+    assert_eq!(json.try_get("Farewell"), Ok(None));
+
+    match json.try_get_mut("Days") {
+        Ok(Some(days)) => {
+            assert_eq!(
+                days.try_get("Monday"),
+                Err(VariantError {
+                    function: "get",
+                    found: "Json::ARRAY"
+                })
+            );
+            assert!(days.try_retain(|_, _| false).is_ok());
+        }
+        result => {
+            panic!("Expected Days but found {:?}", result);
+        }
+    }
+
+    match Json::NULL.try_retain(|_, _| true) {
+        Err(error) => {
+            assert_eq!(
+                error.to_string(),
+                "The function `retain` may not be called on a `Json::NULL`."
+            );
+        }
+        Ok(()) => {
+            panic!("Expected an error!!!");
+        }
+    }
+
+    assert_eq!(
+        json.try_value_of_mut("Days"),
+        Ok(Some(&mut Json::ARRAY(Vec::new())))
+    );
+}
+
+#[test]
+#[should_panic(expected = "The function `get` may not be called on a `Json::STRING`.")]
+fn test_get_panics() {
+    Json::STRING(String::from("Hello, world!")).get("Greeting");
+}
+
+#[test]
+fn test_builder() {
+    let json = JsonBuilder::new()
+        .string("Greeting", "Hello, world!")
+        .array("Days", |days| {
+            days.push(Json::STRING(String::from("Monday")))
+                .push_object(|day| day.bool("Holiday", true))
+                .push_array(|numbers| numbers.push(Json::NUMBER(7.0)))
+        })
+        .object("Conclusion", |conclusion| conclusion.null("Comment"))
+        .build();
+
+    match json {
+        Ok(json) => {
+            assert_eq!(
+                "{\"Greeting\":\"Hello, world!\",\"Days\":[\"Monday\",{\"Holiday\":true},[7]],\"Conclusion\":{\"Comment\":null}}",
+                json.print()
+            );
+        }
+        Err(e) => {
+            panic!("Unexpected error: {}", e);
+        }
+    }
+
+    let json = JsonBuilder::new()
+        .number("First", 1.0)
+        .object("Broken", |broken| broken.push_object(|nested| nested))
+        .number("Ignored", 2.0)
+        .build();
+
+    assert_eq!(json, Err(AddError::UnnamedJson(Json::JSON(Vec::new()))));
+}
+
+#[test]
+fn test_constructors() {
+    assert_eq!(Json::object(), Json::new());
+    assert_eq!(Json::array(), Json::ARRAY(Vec::new()));
+
+    let mut json = Json::object();
+
+    let mut days = Json::array();
+
+    days.add(Json::string("Monday"))
+        .add(Json::string(String::from("Tuesday")));
+
+    json.add(Json::member("Days", days))
+        .add(Json::member(String::from("Total"), Json::number(2.0)));
+
+    assert_eq!(
+        "{\"Days\":[\"Monday\",\"Tuesday\"],\"Total\":2}",
+        json.print()
+    );
+}
+
+#[test]
+fn test_typed_getters() {
+    let json = match Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Total\":7,\"Minimal\":true,\"Days\":[\"Monday\",\"Tuesday\"]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(json.get_str("Greeting"), Some("Hello, world!"));
+    assert_eq!(json.get_f64("Total"), Some(7.0));
+    assert_eq!(json.get_bool("Minimal"), Some(true));
+
+    match json.get_array("Days") {
+        Some(days) => {
+            assert_eq!(days.len(), 2);
+            assert_eq!(days[1].as_str(), Some("Tuesday"));
+        }
+        None => {
+            panic!("Days not found!!!");
+        }
+    }
+
+    assert_eq!(json.get_bool("Greeting"), None);
+    assert_eq!(json.get_str("Farewell"), None);
+    assert_eq!(Json::array().get_str("Greeting"), None);
+
+    match json.get("Total") {
+        Some(total) => {
+            assert_eq!(total.as_f64(), Some(7.0));
+        }
+        None => {
+            panic!("Total not found!!!");
+        }
+    }
+}
+
 #[test]
 fn test_get_mut() {
     let mut json = Json::new();
 
-    json.add(Json::OBJECT {
-        name: String::from("Greeting"),
+    json.add(Json::OBJECT {
+        name: String::from("Greeting"),
+
+        value: Box::new(Json::STRING(String::from("Hello, world!"))),
+    });
+
+    match json.get_mut("Greeting") {
+        Some(json) => match json {
+            Json::OBJECT { name: _, value } => match value.unbox_mut() {
+                Json::STRING(val) => {
+                    assert_eq!("Hello, world!", val);
+
+                    val.push_str(" How are you?");
+
+                    assert_eq!("Hello, world! How are you?", val);
+                }
+                _ => {
+                    panic!("Expected `Json::STRING`!!!");
+                }
+            },
+            _ => {
+                panic!("Expected `Json::OBJECT`!!!");
+            }
+        },
+        None => {
+            panic!("Not found!!!");
+        }
+    }
+}
+
+#[test]
+fn test_take() {
+    let mut json = Json::new();
+
+    json.add(Json::OBJECT {
+        name: String::from("Greeting"),
+
+        value: Box::new(Json::STRING(String::from("Hello, world!"))),
+    });
+
+    match json.get_mut("Greeting") {
+        Some(Json::OBJECT { name: _, value }) => match value.take() {
+            Json::STRING(val) => {
+                assert_eq!("Hello, world!", val);
+            }
+            json => {
+                panic!("Expected Json::STRING but found {:?}", json);
+            }
+        },
+        json => {
+            panic!("Expected Json::OBJECT but found {:?}", json);
+        }
+    }
+
+    assert_eq!("{\"Greeting\":null}", json.print());
+}
+
+#[test]
+fn test_retain() {
+    let mut json = match Json::parse(b"{\"Keep\":1,\"Drop\":null,\"Numbers\":[1,2,3,4]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    json.retain(|name, _| name != Some("Drop"));
+
+    match json.get_mut("Numbers") {
+        Some(numbers) => {
+            numbers.retain(|_, value| match value {
+                Json::NUMBER(n) => *n > 2.0,
+                _ => false,
+            });
+        }
+        None => {
+            panic!("Numbers not found!!!");
+        }
+    }
+
+    assert_eq!("{\"Keep\":1,\"Numbers\":[3,4]}", json.print());
+}
+
+#[test]
+fn test_contains() {
+    let json = match Json::parse(b"{\"Days\":[\"Monday\",{\"Holiday\":true}]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert!(json.contains_key("Days"));
+    assert!(!json.contains_key("Monday"));
+
+    match json.get("Days") {
+        Some(days) => {
+            assert!(days.contains(&Json::STRING(String::from("Monday"))));
+            assert!(!days.contains(&Json::STRING(String::from("Tuesday"))));
+
+            let mut holiday = Json::new();
+
+            holiday.add(Json::OBJECT {
+                name: String::from("Holiday"),
+
+                value: Box::new(Json::BOOL(true)),
+            });
+
+            assert!(days.contains(&holiday));
+            assert!(!days.contains_key("Holiday"));
+        }
+        None => {
+            panic!("Days not found!!!");
+        }
+    }
+}
+
+#[test]
+fn test_keys_and_values() {
+    let mut json =
+        match Json::parse(b"{\"Name\":\"  Ferris  \",\"Language\":\" Rust\",\"Legs\":10}") {
+            Ok(json) => json,
+            Err(e) => {
+                return parse_error(e);
+            }
+        };
+
+    assert_eq!(
+        json.keys().collect::<Vec<&str>>(),
+        vec!["Name", "Language", "Legs"]
+    );
+
+    for value in json.values_mut() {
+        if let Json::STRING(val) = value {
+            *val = val.trim().to_string();
+        }
+    }
+
+    assert_eq!(
+        json.values().collect::<Vec<&Json>>(),
+        vec![
+            &Json::STRING(String::from("Ferris")),
+            &Json::STRING(String::from("Rust")),
+            &Json::NUMBER(10.0)
+        ]
+    );
+
+    assert_eq!(Json::ARRAY(vec![Json::NULL]).keys().count(), 0);
+}
+
+#[test]
+fn test_iter() {
+    let mut json = match Json::parse(b"{\"Numbers\":[1,2,3],\"Total\":0}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let mut total = 0.0;
+
+    if let Some(numbers) = json.get("Numbers") {
+        for (name, value) in numbers {
+            assert_eq!(name, None);
+
+            if let Json::NUMBER(n) = value {
+                total += n;
+            }
+        }
+    }
+
+    for (name, value) in json.iter_mut() {
+        if name == Some("Total") {
+            *value = Json::NUMBER(total);
+        }
+    }
+
+    assert_eq!("{\"Numbers\":[1,2,3],\"Total\":6}", json.print());
+    assert_eq!(json.iter().len(), 2);
+    assert_eq!(Json::NULL.iter().next(), None);
+}
+
+#[test]
+fn test_into_iter() {
+    let json = match Json::parse(b"{\"Days\":[\"Monday\",\"Tuesday\"],\"Weekend\":[\"Saturday\"]}")
+    {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let mut days = Vec::new();
+
+    for (name, value) in json {
+        assert!(name.is_some());
+
+        for (name, day) in value {
+            assert_eq!(name, None);
+
+            match day {
+                Json::STRING(day) => days.push(day),
+                json => {
+                    panic!("Expected Json::STRING but found {:?}", json);
+                }
+            }
+        }
+    }
+
+    assert_eq!(days, vec!["Monday", "Tuesday", "Saturday"]);
+    assert_eq!(Json::STRING(String::from("Monday")).into_iter().count(), 0);
+}
+
+#[test]
+fn test_value_of() {
+    let mut json = match Json::parse(b"{\"Greeting\":\"Hello, world!\",\"Nested\":{\"Answer\":42}}")
+    {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        json.value_of("Greeting"),
+        Some(&Json::STRING(String::from("Hello, world!")))
+    );
+    assert_eq!(json.value_of("Farewell"), None);
+
+    match json.value_of_mut("Nested") {
+        Some(nested) => {
+            assert_eq!(nested.value_of("Answer"), Some(&Json::NUMBER(42.0)));
+
+            if let Some(answer) = nested.value_of_mut("Answer") {
+                *answer = Json::NUMBER(43.0);
+            }
+        }
+        None => {
+            panic!("Nested not found!!!");
+        }
+    }
+
+    assert_eq!(
+        "{\"Greeting\":\"Hello, world!\",\"Nested\":{\"Answer\":43}}",
+        json.print()
+    );
+}
+
+#[test]
+fn test_diff_patch() {
+    let a =
+        match Json::parse(b"{\"Config\":{\"a/b\":1,\"Retries\":3},\"List\":[1,2,3],\"Same\":[]}") {
+            Ok(json) => json,
+            Err(e) => {
+                return parse_error(e);
+            }
+        };
+
+    let b = match Json::parse(b"{\"Config\":{\"a/b\":2,\"Retries\":3},\"List\":[1],\"Same\":[]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!("[]", Json::diff_patch(&a, &a).print());
+    assert_eq!(
+        "[{\"op\":\"replace\",\"path\":\"/Config/a~1b\",\"value\":2},{\"op\":\"remove\",\"path\":\"/List/2\"},{\"op\":\"remove\",\"path\":\"/List/1\"}]",
+        Json::diff_patch(&a, &b).print()
+    );
+    assert_eq!(
+        "[{\"op\":\"replace\",\"path\":\"\",\"value\":true}]",
+        Json::diff_patch(&a, &Json::BOOL(true)).print()
+    );
+}
+
+#[test]
+fn test_diff() {
+    let expected =
+        match Json::parse(b"{\"Name\":\"Ferris\",\"Tags\":[1,2,3],\"Nested\":{\"a/b\":true}}") {
+            Ok(json) => json,
+            Err(e) => {
+                return parse_error(e);
+            }
+        };
+
+    let actual =
+        match Json::parse(b"{\"Tags\":[1,5],\"Nested\":{\"a/b\":false},\"Name\":\"Ferris\"}") {
+            Ok(json) => json,
+            Err(e) => {
+                return parse_error(e);
+            }
+        };
+
+    assert!(Json::diff(&expected, &expected).is_empty());
+
+    let differences = Json::diff(&expected, &actual);
+
+    assert_eq!(
+        differences,
+        vec![
+            Difference {
+                path: String::from("/Tags/1"),
+                expected: Some(&Json::NUMBER(2.0)),
+                actual: Some(&Json::NUMBER(5.0)),
+            },
+            Difference {
+                path: String::from("/Tags/2"),
+                expected: Some(&Json::NUMBER(3.0)),
+                actual: None,
+            },
+            Difference {
+                path: String::from("/Nested/a~1b"),
+                expected: Some(&Json::BOOL(true)),
+                actual: Some(&Json::BOOL(false)),
+            },
+        ]
+    );
+    assert_eq!(
+        differences[1].to_string(),
+        "`/Tags/2`: expected 3 but found nothing"
+    );
+}
+
+#[test]
+fn test_merge() {
+    let defaults = match Json::parse(
+        b"{\"Name\":\"default\",\"Tags\":[\"a\",\"b\"],\"Nested\":{\"Level\":1,\"Keep\":true}}",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let user = match Json::parse(
+        b"{\"Name\":\"user\",\"Tags\":[\"b\",\"c\"],\"Nested\":{\"Level\":2},\"Extra\":null}",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let mut json = defaults.clone();
+    json.merge(user.clone(), MergeStrategy::default());
+    assert_eq!(
+        "{\"Name\":\"user\",\"Tags\":[\"b\",\"c\"],\"Nested\":{\"Level\":2,\"Keep\":true},\"Extra\":null}",
+        json.print()
+    );
+
+    let mut json = defaults.clone();
+    json.merge(
+        user.clone(),
+        MergeStrategy {
+            arrays: ArrayMerge::Concat,
+            conflicts: Conflict::Keep,
+        },
+    );
+    assert_eq!(
+        "{\"Name\":\"default\",\"Tags\":[\"a\",\"b\",\"b\",\"c\"],\"Nested\":{\"Level\":1,\"Keep\":true},\"Extra\":null}",
+        json.print()
+    );
+
+    let mut json = defaults;
+    json.merge(
+        user,
+        MergeStrategy {
+            arrays: ArrayMerge::Union,
+            conflicts: Conflict::Overwrite,
+        },
+    );
+    assert_eq!(json.get_array("Tags").map(|tags| tags.len()), Some(3));
+}
+
+#[test]
+fn test_walk_mut() {
+    let mut json = match Json::parse(b"{\"Keep\":\" a \",\"Raw\":[\" b \"],\"Nested\":{\"Deep\":[\" c \",\" d \"]},\"Last\":\" e \"}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let mut visited = 0;
+
+    json.walk_mut(|json| {
+        visited += 1;
+
+        match json {
+            Json::OBJECT { name, value: _ } if name == "Raw" => Walk::Skip,
+            Json::STRING(val) if val == " d " => Walk::Stop,
+            Json::STRING(val) => {
+                *val = val.trim().to_string();
+
+                Walk::Continue
+            }
+            _ => Walk::Continue,
+        }
+    });
+
+    assert_eq!(visited, 10);
+    assert_eq!(
+        "{\"Keep\":\"a\",\"Raw\":[\" b \"],\"Nested\":{\"Deep\":[\"c\",\" d \"]},\"Last\":\" e \"}",
+        json.print()
+    );
+}
+
+#[test]
+fn test_map_values() {
+    let json = match Json::parse(b"[{\"a\":1,\"b\":[true,null]},\"x\",2]") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let mut leaves = 0;
+
+    let json = json.map_values(|json| {
+        leaves += 1;
+
+        match json {
+            Json::NUMBER(n) => Json::NUMBER(n * 10.0),
+            Json::NULL => Json::BOOL(false),
+            json => json,
+        }
+    });
+
+    assert_eq!(leaves, 5);
+    assert_eq!("[{\"a\":10,\"b\":[true,false]},\"x\",20]", json.print());
+}
+
+#[test]
+fn test_flatten() {
+    let json = match Json::parse(b"{\"a/b\":{\"c\":[1,[2,{}],[]]},\"d\":null}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let flat = json.flatten(KeyStyle::Pointer);
+
+    assert_eq!(
+        "{\"/a~1b/c/0\":1,\"/a~1b/c/1/0\":2,\"/a~1b/c/1/1\":{},\"/a~1b/c/2\":[],\"/d\":null}",
+        flat.print()
+    );
+    assert_eq!(flat.unflatten(KeyStyle::Pointer), json);
+
+    let flat = json.flatten(KeyStyle::Dotted);
+
+    assert_eq!(
+        "{\"a/b.c[0]\":1,\"a/b.c[1][0]\":2,\"a/b.c[1][1]\":{},\"a/b.c[2]\":[],\"d\":null}",
+        flat.print()
+    );
+    assert_eq!(flat.unflatten(KeyStyle::Dotted), json);
+
+    let array = match Json::parse(b"{\"[0]\":1,\"[2].x\":true}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        "[1,null,{\"x\":true}]",
+        array.unflatten(KeyStyle::Dotted).print()
+    );
+    assert_eq!(
+        Json::NUMBER(1.0)
+            .flatten(KeyStyle::Dotted)
+            .unflatten(KeyStyle::Dotted),
+        Json::NUMBER(1.0)
+    );
+
+    let far = match Json::parse(b"{\"/a/0\":1,\"/a/4000000000\":2,\"/a/1\":3}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        "{\"a\":{\"0\":1,\"4000000000\":2,\"1\":3}}",
+        far.unflatten(KeyStyle::Pointer).print()
+    );
+    assert_eq!(
+        "[{\"300\":true}]",
+        Json::JSON(vec![Json::member("[0][300]", Json::BOOL(true))])
+            .unflatten(KeyStyle::Dotted)
+            .print()
+    );
+
+    for text in [
+        "{\"0\":1,\"a\":{\"1\":2}}",
+        "{\"a\":{\"0\":\"x\",\"b\":\"y\"},\"c\":[1,{\"2\":true}],\"1\":[]}",
+        "[{\"x\":1},[{\"0\":null,\"y\":2}]]",
+    ] {
+        let json = match Json::parse(text.as_bytes()) {
+            Ok(json) => json,
+            Err(e) => {
+                return parse_error(e);
+            }
+        };
+
+        assert_eq!(
+            json.flatten(KeyStyle::Pointer).unflatten(KeyStyle::Pointer),
+            json
+        );
+    }
+
+    assert_eq!(
+        "{\"x\":{\"0\":1,\"y\":2}}",
+        Json::JSON(vec![
+            Json::member("x[0]", Json::NUMBER(1.0)),
+            Json::member("x.y", Json::NUMBER(2.0))
+        ])
+        .unflatten(KeyStyle::Dotted)
+        .print()
+    );
+}
+
+#[test]
+fn test_redact() {
+    let mut json = match Json::parse(
+        b"[{\"token\":\"a\",\"nested\":{\"password\":[1,2]}},\"token\",{\"other\":\"token\"}]",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    json.redact(&["token", "password"], Json::NULL);
+
+    assert_eq!(
+        "[{\"token\":null,\"nested\":{\"password\":null}},\"token\",{\"other\":\"token\"}]",
+        json.print()
+    );
+}
+
+#[test]
+fn test_sort_array() {
+    let mut json = match Json::parse(
+        b"[{\"id\":\"b\"},{\"id\":2},{\"x\":0},{\"id\":null},{\"id\":\"a\"},{\"id\":1}]",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    json.sort_array_by_key("id");
+
+    assert_eq!(
+        "[{\"id\":null},{\"id\":1},{\"id\":2},{\"id\":\"a\"},{\"id\":\"b\"},{\"x\":0}]",
+        json.print()
+    );
+
+    json.sort_array_by(|a, b| Json::compare(b, a));
+
+    assert_eq!(
+        "[{\"x\":0},{\"id\":\"b\"},{\"id\":\"a\"},{\"id\":2},{\"id\":1},{\"id\":null}]",
+        json.print()
+    );
+
+    let mut numbers = Json::ARRAY(
+        [2.0, f64::NAN, -1.0, 0.0, f64::NAN, -0.0, 1.0]
+            .iter()
+            .map(|n| Json::NUMBER(*n))
+            .collect(),
+    );
+
+    numbers.sort_array_by(Json::compare);
+
+    assert_eq!("[-1,-0,0,1,2,NaN,NaN]", numbers.print());
+    assert_eq!(
+        Json::compare(&Json::NUMBER(f64::NAN), &Json::NUMBER(1.0)),
+        std::cmp::Ordering::Greater
+    );
+
+    let mut string = Json::string("unchanged");
+    string.sort_array_by_key("id");
+    assert_eq!(string, Json::string("unchanged"));
+}
+
+#[test]
+fn test_sort_keys_recursive() {
+    let mut json = match Json::parse(b"{\"c\":{\"b\":1,\"a\":2},\"unnamed\",\"a\":[{\"y\":[{\"k\":0,\"j\":0}],\"x\":null}],\"B\":true}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    json.sort_keys_recursive();
+
+    assert_eq!(
+        "{\"B\":true,\"a\":[{\"x\":null,\"y\":[{\"j\":0,\"k\":0}]}],\"c\":{\"a\":2,\"b\":1},\"unnamed\"}",
+        json.print()
+    );
+}
+
+#[test]
+fn test_dedup() {
+    let mut json = match Json::parse(
+        b"[{\"id\":1,\"n\":\"a\"},{\"n\":\"b\"},{\"id\":1,\"n\":\"c\"},{\"n\":\"b\"},{\"id\":[1]}]",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let mut by_key = json.clone();
+
+    json.dedup();
+
+    assert_eq!(
+        "[{\"id\":1,\"n\":\"a\"},{\"n\":\"b\"},{\"id\":1,\"n\":\"c\"},{\"id\":[1]}]",
+        json.print()
+    );
+
+    by_key.dedup_by_key("id");
+
+    assert_eq!(
+        "[{\"id\":1,\"n\":\"a\"},{\"n\":\"b\"},{\"n\":\"b\"},{\"id\":[1]}]",
+        by_key.print()
+    );
+}
+
+#[test]
+fn test_filter_array() {
+    let json = match Json::parse(b"{\"Numbers\":[1,\"two\",3,4]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    match json.get("Numbers") {
+        Some(numbers) => {
+            let odd = numbers.filter_array(|json| match json {
+                Json::NUMBER(n) => n % 2.0 == 1.0,
+                _ => false,
+            });
+
+            assert_eq!("[1,3]", odd.print());
+            assert_eq!(numbers.as_array().map(|numbers| numbers.len()), Some(4));
+        }
+        None => {
+            panic!("Numbers not found!!!");
+        }
+    }
+
+    assert_eq!(json.filter_array(|_| true), Json::array());
+}
+
+#[test]
+fn test_find() {
+    let mut json =
+        match Json::parse(b"[{\"id\":\"a\",\"Done\":false},{\"id\":\"b\",\"Done\":false}]") {
+            Ok(json) => json,
+            Err(e) => {
+                return parse_error(e);
+            }
+        };
+
+    assert_eq!(
+        json.position(|task| task.get_str("id") == Some("b")),
+        Some(1)
+    );
+    assert_eq!(json.position(|task| task.get_str("id") == Some("c")), None);
+
+    match json.find_mut(|task| task.get_str("id") == Some("b")) {
+        Some(task) => {
+            if let Some(done) = task.value_of_mut("Done") {
+                *done = Json::BOOL(true);
+            }
+        }
+        None => {
+            panic!("Task b not found!!!");
+        }
+    }
+
+    assert_eq!(
+        json.find(|task| task.get_bool("Done") == Some(true))
+            .and_then(|task| task.get_str("id")),
+        Some("b")
+    );
+    assert_eq!(Json::NULL.find(|_| true), None);
+}
+
+#[test]
+fn test_pluck() {
+    let json = match Json::parse(b"{\"Users\":[{\"Name\":\"a\",\"Tags\":[1]},{\"Name\":\"b\",\"Tags\":[]},\"not an object\",{\"Tags\":null}]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    match json.get("Users") {
+        Some(users) => {
+            assert_eq!("[\"a\",\"b\"]", users.pluck("Name").print());
+            assert_eq!("[[1],[],null]", users.pluck("Tags").print());
+            assert_eq!(users.pluck("Age"), Json::array());
+        }
+        None => {
+            panic!("Users not found!!!");
+        }
+    }
+}
+
+#[test]
+fn test_group_by() {
+    let json = match Json::parse(
+        b"[{\"n\":1,\"ok\":true},{\"n\":2},{\"n\":3,\"ok\":false},{\"n\":4,\"ok\":true}]",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let groups = json.group_by("ok");
+
+    assert_eq!(groups.keys().collect::<Vec<&str>>(), vec!["true", "false"]);
+    assert_eq!(
+        groups
+            .value_of("true")
+            .map(|group| group.pluck("n").print()),
+        Some(String::from("[1,4]"))
+    );
+    assert_eq!(Json::NULL.group_by("ok"), Json::object());
+}
+
+#[test]
+fn test_stats() {
+    let json = Json::ARRAY(vec![
+        Json::number(3.0),
+        Json::string("x"),
+        Json::number(-1.0),
+        Json::NULL,
+        Json::number(4.0),
+    ]);
+
+    assert_eq!(json.sum(), Some(6.0));
+    assert_eq!(json.avg(), Some(2.0));
+    assert_eq!(json.min(), Some(-1.0));
+    assert_eq!(json.max(), Some(4.0));
+
+    assert_eq!(
+        json.stats(NonNumeric::Fail),
+        Err(NotANumber {
+            index: 1,
+            found: "Json::STRING"
+        })
+    );
+    assert_eq!(Json::array().stats(NonNumeric::Fail), Ok(None));
+    assert_eq!(Json::array().sum(), None);
+    assert_eq!(Json::string("3").max(), None);
+}
+
+#[test]
+fn test_columns() {
+    let rows = match Json::parse(b"[{\"a\":1,\"b\":2},{\"b\":4,\"c\":[5]},\"not a row\"]") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let columns = rows.to_columns();
+
+    assert_eq!(
+        "{\"a\":[1,null,null],\"b\":[2,4,null],\"c\":[null,[5],null]}",
+        columns.print()
+    );
+    assert_eq!(
+        "[{\"a\":1,\"b\":2,\"c\":null},{\"a\":null,\"b\":4,\"c\":[5]},{\"a\":null,\"b\":null,\"c\":null}]",
+        columns.from_columns().print()
+    );
+
+    let uneven = match Json::parse(b"{\"a\":[1,2],\"b\":true}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        "[{\"a\":1,\"b\":true},{\"a\":2}]",
+        uneven.from_columns().print()
+    );
+}
+
+#[test]
+fn test_entries() {
+    let entries = match Json::parse(
+        b"[[\"a\",{\"b\":null}],[1,true],[\"too\",\"many\",\"values\"],\"nope\"]",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let json = entries.from_entries();
+
+    assert_eq!("{\"a\":{\"b\":null},\"1\":true}", json.print());
+    assert_eq!(
+        "[[\"a\",{\"b\":null}],[\"1\",true]]",
+        json.to_entries().print()
+    );
+    assert_eq!(Json::array().to_entries(), Json::array());
+}
+
+#[test]
+fn test_paths() {
+    let json = match Json::parse(b"[{\"a\":[],\"b\":{\"c\":\"x\"}},2,{}]") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let paths: Vec<(String, &Json)> = json.paths().collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            (String::from("/0/a"), &Json::array()),
+            (String::from("/0/b/c"), &Json::string("x")),
+            (String::from("/1"), &Json::number(2.0)),
+            (String::from("/2"), &Json::object()),
+        ]
+    );
+    assert_eq!(
+        Json::BOOL(true).paths().collect::<Vec<(String, &Json)>>(),
+        vec![(String::new(), &Json::BOOL(true))]
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    use serde_test::{assert_de_tokens, assert_ser_tokens_error, assert_tokens, Token};
+
+    let json = match Json::parse(b"{\"a\":[1,\"x\",null],\"b\":true}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_tokens(
+        &json,
+        &[
+            Token::Map { len: Some(2) },
+            Token::Str("a"),
+            Token::Seq { len: Some(3) },
+            Token::F64(1.0),
+            Token::Str("x"),
+            Token::Unit,
+            Token::SeqEnd,
+            Token::Str("b"),
+            Token::Bool(true),
+            Token::MapEnd,
+        ],
+    );
+
+    assert_ser_tokens_error(
+        &Json::JSON(vec![Json::NULL]),
+        &[Token::Map { len: Some(1) }],
+        "A `Json::JSON` holding an unnamed value (Json::NULL) can't be serialized as a map.",
+    );
+
+    // The lengths claimed aren't reserved up front.
+    assert_de_tokens(
+        &Json::ARRAY(vec![Json::JSON(vec![Json::member("a", Json::NULL)])]),
+        &[
+            Token::Seq {
+                len: Some(usize::MAX),
+            },
+            Token::Map {
+                len: Some(usize::MAX),
+            },
+            Token::Str("a"),
+            Token::Unit,
+            Token::MapEnd,
+            Token::SeqEnd,
+        ],
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_to_value_from_value() {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(u8, u8),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Drawing {
+        name: String,
+        id: i64,
+        tags: Vec<String>,
+        parent: Option<u32>,
+        shapes: Vec<Shape>,
+        counts: BTreeMap<u8, bool>,
+    }
+
+    let mut counts = BTreeMap::new();
+    counts.insert(1, true);
+
+    let drawing = Drawing {
+        name: String::from("d"),
+        id: -3,
+        tags: vec![String::from("a")],
+        parent: None,
+        shapes: vec![
+            Shape::Empty,
+            Shape::Circle(0.5),
+            Shape::Line(1, 2),
+            Shape::Rect { w: 3, h: 4 },
+        ],
+        counts,
+    };
+
+    let json = to_value(&drawing).unwrap();
+
+    assert_eq!(
+        json.print(),
+        r#"{"name":"d","id":-3,"tags":["a"],"parent":null,"shapes":["Empty",{"Circle":0.5},{"Line":[1,2]},{"Rect":{"w":3,"h":4}}],"counts":{"1":true}}"#
+    );
+    assert_eq!(from_value::<Drawing>(json).unwrap(), drawing);
+
+    assert!(from_value::<u32>(Json::string("x")).is_err());
+    assert!(from_value::<u8>(Json::NUMBER(1.5)).is_err());
+    assert!(from_value::<BTreeMap<String, bool>>(Json::JSON(vec![Json::BOOL(true)])).is_err());
+    assert!(to_value(
+        &[(vec![1], 2)]
+            .iter()
+            .cloned()
+            .collect::<BTreeMap<Vec<u8>, u8>>()
+    )
+    .is_err());
+
+    // A length which is claimed but never reached isn't reserved up front.
+    struct Claiming;
+
+    impl Serialize for Claiming {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            use serde::ser::SerializeSeq;
+
+            let mut seq = serializer.serialize_seq(Some(usize::MAX))?;
+            seq.serialize_element(&1)?;
+            seq.end()
+        }
+    }
+
+    assert_eq!(to_value(&Claiming).unwrap().print(), "[1]");
+}
+
+#[cfg(feature = "serde_json")]
+#[test]
+fn test_serde_json_value() {
+    let json = match Json::parse(b"{\"a\":[1,2.5,\"x\",null],\"b\":{\"c\":false}}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let value = serde_json::Value::from(json.clone());
+
+    assert_eq!(value.to_string(), json.print());
+    assert_eq!(Json::from(value), json);
+
+    let value = serde_json::Value::from(Json::JSON(vec![
+        Json::NULL,
+        Json::member("n", Json::NUMBER(f64::NAN)),
+    ]));
+
+    assert_eq!(value.to_string(), r#"{"n":null}"#);
+}
+
+#[test]
+fn test_to_json_from_json() {
+    use std::collections::BTreeMap;
+
+    let mut map = BTreeMap::new();
+    map.insert(String::from("a"), vec![Some(1u8), None]);
+    map.insert(String::from("b"), vec![]);
+
+    let json = map.to_json();
+
+    assert_eq!(json.print(), r#"{"a":[1,null],"b":[]}"#);
+    assert_eq!(BTreeMap::from_json(&json), Ok(map));
+
+    assert_eq!("x".to_json(), Json::string("x"));
+    assert_eq!(char::from_json(&'y'.to_json()), Ok('y'));
+    assert_eq!(i64::from_json(&(-2i64).to_json()), Ok(-2));
+    assert_eq!(f32::from_json(&Json::NUMBER(0.5)), Ok(0.5));
+    assert_eq!(
+        String::from_json(&Json::member("name", Json::string("value"))),
+        Ok(String::from("value"))
+    );
+
+    assert_eq!(
+        u8::from_json(&Json::NUMBER(300.0)),
+        Err(FromJsonError::InvalidType {
+            target: "u8",
+            found: "Json::NUMBER"
+        })
+    );
+    assert!(u8::from_json(&Json::NUMBER(1.5)).is_err());
+    assert!(u64::from_json(&Json::NUMBER(18446744073709551616.0)).is_err());
+    assert!(i64::from_json(&Json::NUMBER(9223372036854775808.0)).is_err());
+    assert_eq!(
+        i64::from_json(&Json::NUMBER(-9223372036854775808.0)),
+        Ok(i64::MIN)
+    );
+    assert_eq!(
+        u64::from_json(&Json::NUMBER(18446744073709549568.0)),
+        Ok(18446744073709549568)
+    );
+    assert_eq!(u8::from_json(&Json::NUMBER(255.0)), Ok(255));
+    assert!(u8::from_json(&Json::NUMBER(256.0)).is_err());
+    assert!(char::from_json(&Json::string("xy")).is_err());
+    assert!(Vec::<bool>::from_json(&Json::ARRAY(vec![Json::NULL])).is_err());
+    assert!(BTreeMap::<String, bool>::from_json(&Json::JSON(vec![Json::BOOL(true)])).is_err());
+    assert_eq!(
+        bool::from_json(&Json::NULL).unwrap_err().to_string(),
+        "A `Json::NULL` can't be converted to a `bool`."
+    );
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive() {
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    struct Point<T> {
+        x: T,
+        r#y: T,
+        label: Option<String>,
+    }
+
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    struct Id(u32);
+
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    struct Pair(Id, bool);
+
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    struct Marker;
+
+    let point = Point {
+        x: 1.5,
+        y: -2.0,
+        label: None,
+    };
+
+    assert_eq!(point.to_json().print(), r#"{"x":1.5,"y":-2,"label":null}"#);
+    assert_eq!(Point::from_json(&point.to_json()), Ok(point));
+
+    let json = match Json::parse(b"{\"y\":2,\"x\":1}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        Point::from_json(&json),
+        Ok(Point {
+            x: 1,
+            y: 2,
+            label: None
+        })
+    );
+
+    let pair = Pair(Id(7), true);
+
+    assert_eq!(pair.to_json().print(), "[7,true]");
+    assert_eq!(Pair::from_json(&pair.to_json()), Ok(pair));
+    assert_eq!(Marker::from_json(&Marker.to_json()), Ok(Marker));
+
+    assert_eq!(
+        Point::<u8>::from_json(&Json::JSON(vec![Json::member("x", Json::NUMBER(1.0))])),
+        Err(FromJsonError::MissingField {
+            target: "Point",
+            field: "y"
+        })
+    );
+    assert_eq!(
+        Pair::from_json(&Json::ARRAY(vec![])),
+        Err(FromJsonError::InvalidLength {
+            target: "Pair",
+            expected: 2,
+            found: 0
+        })
+    );
+    assert_eq!(
+        Point::<u8>::from_json(&Json::NULL),
+        Err(FromJsonError::InvalidType {
+            target: "Point",
+            found: "Json::NULL"
+        })
+    );
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_attributes() {
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    struct User {
+        #[json(rename = "userName")]
+        user_name: String,
+        #[json(skip)]
+        session: Option<u32>,
+        #[json(default, rename = "isAdmin")]
+        is_admin: bool,
+    }
+
+    let user = User {
+        user_name: String::from("ann"),
+        session: Some(3),
+        is_admin: true,
+    };
+
+    assert_eq!(
+        user.to_json().print(),
+        r#"{"userName":"ann","isAdmin":true}"#
+    );
+
+    let json = match Json::parse(b"{\"userName\":\"bob\",\"session\":9}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        User::from_json(&json),
+        Ok(User {
+            user_name: String::from("bob"),
+            session: None,
+            is_admin: false
+        })
+    );
+    assert_eq!(
+        User::from_json(&Json::JSON(vec![])),
+        Err(FromJsonError::MissingField {
+            target: "User",
+            field: "userName"
+        })
+    );
+}
+
+#[cfg(feature = "derive")]
+#[test]
+fn test_derive_enum() {
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    enum Color {
+        Red,
+        #[json(rename = "dark-blue")]
+        DarkBlue,
+    }
+
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    enum Shape {
+        Empty,
+        Circle(f64),
+        Line(u8, u8),
+        Rect { w: u32, h: u32 },
+    }
+
+    #[derive(ToJson, FromJson, Debug, PartialEq)]
+    #[json(tag = "type")]
+    enum Event {
+        Ping,
+        Click {
+            x: i32,
+            #[json(rename = "Y")]
+            y: i32,
+        },
+    }
+
+    assert_eq!(Color::DarkBlue.to_json(), Json::string("dark-blue"));
+    assert_eq!(Color::from_json(&Json::string("Red")), Ok(Color::Red));
+    assert_eq!(
+        Color::from_json(&Json::string("Green")),
+        Err(FromJsonError::UnknownVariant {
+            target: "Color",
+            variant: String::from("Green")
+        })
+    );
+
+    let shapes = vec![
+        Shape::Empty,
+        Shape::Circle(0.5),
+        Shape::Line(1, 2),
+        Shape::Rect { w: 3, h: 4 },
+    ];
+
+    assert_eq!(
+        shapes.to_json().print(),
+        r#"["Empty",{"Circle":0.5},{"Line":[1,2]},{"Rect":{"w":3,"h":4}}]"#
+    );
+    assert_eq!(Vec::from_json(&shapes.to_json()), Ok(shapes));
+    assert!(Shape::from_json(&Json::string("Circle")).is_err());
+
+    let events = vec![Event::Ping, Event::Click { x: 1, y: -1 }];
+
+    assert_eq!(
+        events.to_json().print(),
+        r#"[{"type":"Ping"},{"type":"Click","x":1,"Y":-1}]"#
+    );
+    assert_eq!(Vec::from_json(&events.to_json()), Ok(events));
+    assert_eq!(
+        Event::from_json(&Json::JSON(vec![])),
+        Err(FromJsonError::MissingField {
+            target: "Event",
+            field: "type"
+        })
+    );
+}
+
+#[cfg(feature = "cbor")]
+#[test]
+fn test_cbor() {
+    let json = match Json::parse(b"{\"a\":[0,23,24,1000,1.1,\"\\u00fc\",null],\"b\":{\"c\":false}}")
+    {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(Json::from_cbor(&json.to_cbor()), Ok(json));
+
+    assert_eq!(Json::NUMBER(-1000.0).to_cbor(), vec![0x39, 0x03, 0xe7]);
+    assert_eq!(
+        Json::NUMBER(1.1).to_cbor(),
+        vec![0xfb, 0x3f, 0xf1, 0x99, 0x99, 0x99, 0x99, 0x99, 0x9a]
+    );
+    assert_eq!(
+        Json::from_cbor(&[0x39, 0x03, 0xe7]),
+        Ok(Json::NUMBER(-1000.0))
+    );
+    assert_eq!(Json::from_cbor(&[0xf9, 0x3c, 0x00]), Ok(Json::NUMBER(1.0)));
+    assert_eq!(
+        Json::from_cbor(&[0xf9, 0x7b, 0xff]),
+        Ok(Json::NUMBER(65504.0))
+    );
+    assert_eq!(
+        Json::from_cbor(&[0x9f, 0x01, 0x82, 0x02, 0x03, 0x9f, 0x04, 0x05, 0xff, 0xff])
+            .map(|json| json.print()),
+        Ok(String::from("[1,[2,3],[4,5]]"))
+    );
+    assert_eq!(
+        Json::from_cbor(b"\x7f\x65strea\x65mingX\xff").map(|json| json.print()),
+        Ok(String::from("\"streamingX\""))
+    );
+    assert_eq!(
+        Json::from_cbor(&[0xa1, 0x01, 0xc1, 0x02]).map(|json| json.print()),
+        Ok(String::from("{\"1\":2}"))
+    );
+
+    assert_eq!(
+        Json::from_cbor(&[0x19, 0x01]),
+        Err(CborError {
+            position: 1,
+            message: "Unexpected end of input."
+        })
+    );
+    assert!(Json::from_cbor(&[0x01, 0x02]).is_err());
+    assert!(Json::from_cbor(&[0x82, 0x01]).is_err());
+    assert!(Json::from_cbor(&[0xa1, 0x80, 0x01]).is_err());
+    assert!(Json::from_cbor(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).is_err());
+
+    let mut nested = vec![0x81; 127];
+    nested.push(0xf6);
+
+    assert!(Json::from_cbor(&nested).is_ok());
+
+    let mut nested = vec![0x81; 200_000];
+    nested.push(0xf6);
+
+    assert_eq!(
+        Json::from_cbor(&nested),
+        Err(CborError {
+            position: 128,
+            message: "Too deeply nested."
+        })
+    );
+    assert!(Json::from_cbor(&[0xc1; 200_000]).is_err());
+}
+
+#[cfg(feature = "msgpack")]
+#[test]
+fn test_msgpack() {
+    let json = match Json::parse(b"{\"a\":[0,127,128,65536,1.5,\"x\",null],\"b\":{\"c\":false}}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(Json::from_msgpack(&json.to_msgpack()), Ok(json));
+
+    assert_eq!(Json::NUMBER(-1.0).to_msgpack(), vec![0xff]);
+    assert_eq!(Json::NUMBER(-33.0).to_msgpack(), vec![0xd0, 0xdf]);
+    assert_eq!(Json::NUMBER(256.0).to_msgpack(), vec![0xcd, 0x01, 0x00]);
+    assert_eq!(Json::string("a".repeat(32)).to_msgpack()[..2], [0xd9, 32]);
+    assert_eq!(
+        Json::ARRAY(vec![Json::NULL; 16]).to_msgpack()[..3],
+        [0xdc, 0x00, 0x10]
+    );
+
+    for number in &[
+        -1.0,
+        -33.0,
+        -40000.0,
+        -3000000000.0,
+        256.0,
+        70000.0,
+        5000000000.0,
+    ] {
+        assert_eq!(
+            Json::from_msgpack(&Json::NUMBER(*number).to_msgpack()),
+            Ok(Json::NUMBER(*number))
+        );
+    }
+
+    assert_eq!(
+        Json::from_msgpack(&[0x81, 0x01, 0xc4, 0x02, 0x07, 0x08]).map(|json| json.print()),
+        Ok(String::from("{\"1\":[7,8]}"))
+    );
+    assert_eq!(
+        Json::from_msgpack(&[0xcd, 0x01]),
+        Err(MsgPackError {
+            position: 1,
+            message: "Unexpected end of input."
+        })
+    );
+    assert!(Json::from_msgpack(&[0xc1]).is_err());
+    assert!(Json::from_msgpack(&[0xd4, 0x00, 0x00]).is_err());
+    assert!(Json::from_msgpack(&[0x01, 0x02]).is_err());
+    assert!(Json::from_msgpack(&[0x81, 0xc0, 0xc0]).is_err());
+    assert!(Json::from_msgpack(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+
+    let mut nested = vec![0x91; 127];
+    nested.push(0xc0);
+
+    assert!(Json::from_msgpack(&nested).is_ok());
+
+    let mut nested = vec![0x91; 200_000];
+    nested.push(0xc0);
+
+    assert_eq!(
+        Json::from_msgpack(&nested),
+        Err(MsgPackError {
+            position: 128,
+            message: "Too deeply nested."
+        })
+    );
+}
+
+#[test]
+fn test_query() {
+    let json = match Json::parse(
+        b"{\"a\":{\"b\":[{\"c\":\"x&y=z\"},true]},\"d\":null,\"e\":[],\"f\":1.5}",
+    ) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let query = json.to_query(QueryStyle::Brackets);
+
+    assert_eq!(query, "a[b][0][c]=x%26y%3Dz&a[b][1]=true&d=&f=1.5");
+    assert_eq!(
+        Json::from_query(&query, QueryStyle::Brackets).print(),
+        r#"{"a":{"b":[{"c":"x&y=z"},"true"]},"d":"","f":"1.5"}"#
+    );
+
+    assert_eq!(
+        json.to_query(QueryStyle::Flat),
+        "a=%7B%22b%22%3A%5B%7B%22c%22%3A%22x%26y%3Dz%22%7D%2Ctrue%5D%7D&d=&f=1.5"
+    );
+    assert_eq!(
+        Json::from_query("a&b=%zz&a=1&a=2&&c=%C3%BC", QueryStyle::Flat).print(),
+        r#"{"a":["","1","2"],"b":"%zz","c":"ü"}"#
+    );
+    assert_eq!(
+        Json::from_query("x[][n]=1&x[][n]=2&y[1]=b&y[0]=a&z[=1", QueryStyle::Brackets).print(),
+        r#"{"x":[{"n":"1"},{"n":"2"}],"y":["a","b"],"z[":"1"}"#
+    );
+
+    assert_eq!(
+        Json::from_query("a[1]=x&a[50000000]=y", QueryStyle::Brackets).print(),
+        r#"{"a":{"0":null,"1":"x","50000000":"y"}}"#
+    );
+    assert_eq!(
+        Json::from_query("a[0]=x&a[b]=y&c[d]=1&c[1]=2", QueryStyle::Brackets).print(),
+        r#"{"a":{"0":"x","b":"y"},"c":{"d":"1","1":"2"}}"#
+    );
+
+    assert_eq!(Json::ARRAY(vec![]).to_query(QueryStyle::Flat), "");
+    assert_eq!(Json::from_query("", QueryStyle::Flat), Json::object());
+}
+
+#[test]
+fn test_map_conversions() {
+    use std::collections::{BTreeMap, HashMap};
+    use std::convert::TryFrom;
+
+    let json = match Json::parse(b"{\"b\":[1],\"a\":{\"c\":null}}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let map = BTreeMap::try_from(json.clone()).unwrap();
+
+    assert_eq!(map.keys().collect::<Vec<_>>(), vec!["a", "b"]);
+    assert_eq!(map["b"], Json::ARRAY(vec![Json::NUMBER(1.0)]));
+    assert_eq!(Json::from(map).print(), r#"{"a":{"c":null},"b":[1]}"#);
+
+    let map: HashMap<String, Json> = HashMap::try_from(json.clone()).unwrap();
+    let mut json_from_map = Json::from(map);
+
+    json_from_map.sort_keys_recursive();
+
+    assert_eq!(json_from_map.print(), r#"{"a":{"c":null},"b":[1]}"#);
+
+    assert_eq!(
+        BTreeMap::try_from(Json::member("x", json)).map(|map| map.len()),
+        Ok(2)
+    );
+    assert!(HashMap::<String, Json>::try_from(Json::ARRAY(vec![])).is_err());
+    assert!(BTreeMap::try_from(Json::JSON(vec![Json::NULL])).is_err());
+}
+
+#[test]
+fn test_vec_conversions() {
+    use std::convert::TryFrom;
+
+    let json = match Json::parse(b"[[\"a\",\"b\"],[1,2.5],[true],[\"c\",3]]") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(json.to_vec_of::<Json>().map(|values| values.len()), Ok(4));
+    assert_eq!(
+        Vec::<Vec<String>>::try_from(json.clone()),
+        Err(FromJsonError::InvalidElement {
+            index: 1,
+            error: Box::new(FromJsonError::InvalidElement {
+                index: 0,
+                error: Box::new(FromJsonError::InvalidType {
+                    target: "alloc::string::String",
+                    found: "Json::NUMBER"
+                })
+            })
+        })
+    );
+
+    let values = json.to_vec_of::<Json>().unwrap();
+
+    assert_eq!(
+        Vec::<String>::try_from(values[0].clone()),
+        Ok(vec![String::from("a"), String::from("b")])
+    );
+    assert_eq!(Vec::<f64>::try_from(values[1].clone()), Ok(vec![1.0, 2.5]));
+    assert_eq!(
+        Json::member("x", values[2].clone()).to_vec_of::<bool>(),
+        Ok(vec![true])
+    );
+    assert_eq!(
+        values[3].to_vec_of::<String>().unwrap_err().to_string(),
+        "Element 1: A `Json::NUMBER` can't be converted to a `alloc::string::String`."
+    );
+    assert!(Json::NULL.to_vec_of::<bool>().is_err());
+}
+
+#[cfg(feature = "datetime")]
+#[test]
+fn test_datetime() {
+    let datetime = DateTime::parse("1999-12-31t23:59:60.123456789123-05:30").unwrap();
+
+    assert_eq!(
+        datetime,
+        DateTime {
+            year: 1999,
+            month: 12,
+            day: 31,
+            hour: 23,
+            minute: 59,
+            second: 60,
+            nanosecond: 123456789,
+            offset_minutes: -330
+        }
+    );
+    assert_eq!(datetime.to_string(), "1999-12-31T23:59:60.123456789-05:30");
+    assert_eq!(
+        Json::from_datetime(&DateTime::parse("2000-01-01 00:00:00.5z").unwrap()),
+        Json::string("2000-01-01T00:00:00.5Z")
+    );
+
+    for (input, position, message) in &[
+        ("2021-02-29T00:00:00Z", 8, "Value out of range."),
+        ("2021-13-01T00:00:00Z", 5, "Value out of range."),
+        ("2021-01-01T24:00:00Z", 11, "Value out of range."),
+        ("2021-01-01", 10, "Expected `T`."),
+        ("2021-01-01T00:00:00.Z", 20, "Expected a digit."),
+        (
+            "2021-01-01T00:00:00",
+            19,
+            "Expected `Z` or an offset like `+01:00`.",
+        ),
+        ("2021-01-01T00:00:00+0100", 22, "Expected `:`."),
+        (
+            "2021-01-01T00:00:00Z ",
+            20,
+            "Unexpected characters after the timestamp.",
+        ),
+    ] {
+        assert_eq!(
+            Json::string(*input).as_datetime(),
+            Err(DateTimeError {
+                value: String::from(*input),
+                position: *position,
+                message
+            })
+        );
+    }
+
+    assert_eq!(
+        Json::NUMBER(1.0).as_datetime().unwrap_err().to_string(),
+        "Expected a `Json::STRING`. (at position `0` of `1`)"
+    );
+}
+
+#[cfg(all(feature = "chrono", feature = "time"))]
+#[test]
+fn test_datetime_conversions() {
+    use std::convert::TryFrom;
+
+    let datetime = DateTime::parse("2020-02-29T12:30:00.25+01:00").unwrap();
+
+    let chrono = chrono::DateTime::<chrono::FixedOffset>::try_from(datetime).unwrap();
+
+    assert_eq!(chrono.to_rfc3339(), "2020-02-29T12:30:00.250+01:00");
+    assert_eq!(DateTime::try_from(chrono), Ok(datetime));
+
+    let time = time::OffsetDateTime::try_from(datetime).unwrap();
+
+    assert_eq!(time.unix_timestamp(), chrono.timestamp());
+    assert_eq!(DateTime::try_from(time), Ok(datetime));
+
+    let leap = DateTime::parse("2016-12-31T23:59:60Z").unwrap();
+
+    assert_eq!(
+        DateTime::try_from(chrono::DateTime::<chrono::FixedOffset>::try_from(leap).unwrap()),
+        Ok(leap)
+    );
+    assert!(time::OffsetDateTime::try_from(leap).is_err());
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_uuid() {
+    let id = Json::string("67E55044-10B1-426F-9247-BB680E5FE0C8")
+        .as_uuid()
+        .unwrap();
+
+    assert_eq!(
+        Json::from_uuid(&id),
+        Json::string("67e55044-10b1-426f-9247-bb680e5fe0c8")
+    );
+    assert_eq!(Json::member("id", Json::from_uuid(&id)).as_uuid(), Ok(id));
+
+    for input in &[
+        "{67e55044-10b1-426f-9247-bb680e5fe0c8}",
+        "urn:uuid:67e55044-10b1-426f-9247-bb680e5fe0c8",
+        "67e55044-10b1-426f-9247-bb680e5fe0c",
+        "67e5504410b1-426f-9247-bb680e5fe0c8-",
+        "67e55044-10b1-426f-9247-bb680e5fe0cg",
+    ] {
+        assert_eq!(
+            Json::string(*input).as_uuid().map_err(|error| error.value),
+            Err(String::from(*input))
+        );
+    }
+
+    assert_eq!(
+        Json::NULL.as_uuid().unwrap_err().to_string(),
+        "Expected a `Json::STRING`. (found `null`)"
+    );
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_parse_bytes() {
+    let input = "{\"name\": \"Ferris\", \"tags\":[\"\\u00fcber\",\"cr\u{e9}me\",true,null,36.5],\"nested\":{\"key\":\"value\"}}";
+    let buffer = bytes::Bytes::from(input);
+
+    let json = match Json::parse_bytes(buffer.clone()) {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let name = json.get("name").unwrap().as_str().unwrap().clone();
+
+    assert_eq!(name, "Ferris");
+    assert_eq!(name.as_ptr(), buffer[10..].as_ptr());
+    assert_eq!(name.into_bytes(), buffer.slice(10..16));
+    assert_eq!(
+        json.get("nested")
+            .unwrap()
+            .get("key")
+            .and_then(BytesJson::as_str)
+            .map(|key| key.as_ptr()),
+        Some(buffer[81..].as_ptr())
+    );
+
+    match json.get("tags") {
+        Some(BytesJson::OBJECT { name: _, value }) => match &**value {
+            BytesJson::ARRAY(values) => {
+                assert_eq!(values[0].as_str().unwrap(), "\u{fc}ber");
+                assert_eq!(values[1].as_str().unwrap(), "cr\u{e9}me");
+                assert_eq!(
+                    &values[2..],
+                    &[
+                        BytesJson::BOOL(true),
+                        BytesJson::NULL,
+                        BytesJson::NUMBER(36.5)
+                    ]
+                );
+            }
+            json => panic!("Expected BytesJson::ARRAY but found {:?}", json),
+        },
+        json => panic!("Expected BytesJson::OBJECT but found {:?}", json),
+    }
+
+    assert_eq!(Json::from(json), Json::parse(input.as_bytes()).unwrap());
+
+    for input in &[
+        &b""[..],
+        b"{",
+        b"[1,",
+        b"{\"a\":",
+        b"\"abc",
+        b"\"\xff\"",
+        b"{\"a\"\\x}",
+    ] {
+        assert!(Json::parse_bytes(bytes::Bytes::from_static(input)).is_err());
+    }
+}
+
+#[test]
+fn test_to_rust_structs() {
+    let json = match Json::parse(b"{\"id\":1,\"items\":[{\"sku\":\"a\",\"price\":2},{\"sku\":\"b\",\"price\":2.5,\"note\":null}]}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(
+        json.to_rust_structs("order"),
+        "use json_minimal::{FromJson, FromJsonError, Json};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Order {
+    pub id: i64,
+    pub items: Vec<ItemsItem>,
+}
+
+impl FromJson for Order {
+    fn from_json(json: &Json) -> Result<Order, FromJsonError> {
+        let member = |name: &str| match json.try_value_of(name) {
+            Ok(value) => Ok(value.unwrap_or(&Json::NULL)),
+            Err(error) => Err(FromJsonError::InvalidType {
+                target: \"Order\",
+                found: error.found,
+            }),
+        };
+
+        Ok(Order {
+            id: FromJson::from_json(member(\"id\")?)?,
+            items: FromJson::from_json(member(\"items\")?)?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ItemsItem {
+    pub sku: String,
+    pub price: f64,
+    pub note: Json,
+}
+
+impl FromJson for ItemsItem {
+    fn from_json(json: &Json) -> Result<ItemsItem, FromJsonError> {
+        let member = |name: &str| match json.try_value_of(name) {
+            Ok(value) => Ok(value.unwrap_or(&Json::NULL)),
+            Err(error) => Err(FromJsonError::InvalidType {
+                target: \"ItemsItem\",
+                found: error.found,
+            }),
+        };
+
+        Ok(ItemsItem {
+            sku: FromJson::from_json(member(\"sku\")?)?,
+            price: FromJson::from_json(member(\"price\")?)?,
+            note: FromJson::from_json(member(\"note\")?)?,
+        })
+    }
+}
+"
+    );
+
+    let json = match Json::parse(b"[{\"type\":1,\"Type\":\"a\",\"2fa\":true,\"HTTPStatus\":[],\"tag\":\"a\"},{\"type\":null,\"Type\":\"b\",\"2fa\":false,\"HTTPStatus\":[[1]]}]") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    let code = json.to_rust_structs("Vec");
+
+    assert!(code.contains("\npub type Vec_ = Vec<VecItem>;\n"));
+    assert!(code.contains(
+        "pub struct VecItem {
+    pub r#type: Option<i64>,
+    pub type2: String,
+    pub _2fa: bool,
+    pub http_status: Vec<Vec<i64>>,
+    pub tag: Option<String>,
+}"
+    ));
+    assert!(code.contains("r#type: FromJson::from_json(member(\"type\")?)?,"));
+    assert!(code.contains("type2: FromJson::from_json(member(\"Type\")?)?,"));
+}
+
+#[cfg(feature = "proptest")]
+proptest::proptest! {
+    #[test]
+    fn test_proptest_print_parse(json in proptest::arbitrary::any::<Json>()) {
+        proptest::prop_assert_eq!(parse_kind(json.print().as_bytes()), Ok(json));
+    }
+
+    #[test]
+    fn test_proptest_bounds(json in proptest::arbitrary::any_with::<Json>(ArbitraryBounds { depth: 1, size: 8, width: 2 })) {
+        for value in json.members() {
+            proptest::prop_assert!(value.members().is_empty());
+        }
+
+        proptest::prop_assert!(json.members().len() <= 2);
+    }
+}
+
+#[cfg(feature = "quickcheck")]
+#[test]
+fn test_quickcheck_print_parse() {
+    fn print_parse(json: Json) -> bool {
+        parse_kind(json.print().as_bytes()) == Ok(json)
+    }
+
+    quickcheck::quickcheck(print_parse as fn(Json) -> bool);
+
+    let json = Json::ARRAY(vec![Json::NUMBER(2.5), Json::member("a", Json::BOOL(true))]);
+    let shrunk: Vec<Json> = quickcheck::Arbitrary::shrink(&json).collect();
+
+    assert_eq!(shrunk[0], Json::NULL);
+    assert!(shrunk.contains(&Json::NUMBER(2.5)));
+    assert!(shrunk.contains(&Json::BOOL(true)));
+    assert!(shrunk.contains(&Json::ARRAY(vec![Json::NUMBER(2.5)])));
+}
+
+#[test]
+fn test_pointer() {
+    let json = match Json::parse(b"{\"a\":{\"b\":[1,{\"~c/\":true}]},\"\":null}") {
+        Ok(json) => json,
+        Err(e) => {
+            return parse_error(e);
+        }
+    };
+
+    assert_eq!(json.pointer(""), Some(&json));
+    assert_eq!(json.pointer("/"), Some(&Json::NULL));
+    assert_eq!(json.pointer("/a/b/0"), Some(&Json::NUMBER(1.0)));
+    assert_eq!(json.pointer("/a/b/1/~0c~1"), Some(&Json::BOOL(true)));
+    assert_eq!(json.pointer("/a/b/01"), None);
+    assert_eq!(json.pointer("/a/b/+1"), None);
+    assert_eq!(json.pointer("/a/b/2"), None);
+    assert_eq!(json.pointer("/a/c"), None);
+    assert_eq!(json.pointer("a"), None);
+    assert_eq!(
+        Json::member("x", Json::ARRAY(vec![Json::NULL])).pointer("/0"),
+        Some(&Json::NULL)
+    );
+}
+
+#[cfg(feature = "ffi")]
+#[test]
+fn test_ffi() {
+    use std::ffi::{CStr, CString};
+
+    unsafe {
+        let input = b"{\"users\":[{\"name\":\"Ada\"}]}";
+        let json = ffi::json_minimal_parse(input.as_ptr(), input.len(), std::ptr::null_mut());
+
+        assert!(!json.is_null());
+
+        let pointer = CString::new("/users/0").unwrap();
+        let user = ffi::json_minimal_pointer(json, pointer.as_ptr());
+        let printed = ffi::json_minimal_print(user);
+
+        assert_eq!(CStr::from_ptr(printed).to_str(), Ok("{\"name\":\"Ada\"}"));
+
+        ffi::json_minimal_string_free(printed);
+
+        let pointer = CString::new("/users/1").unwrap();
+
+        assert!(ffi::json_minimal_pointer(json, pointer.as_ptr()).is_null());
+        assert!(ffi::json_minimal_print(std::ptr::null()).is_null());
+
+        ffi::json_minimal_free(json);
+
+        for input in &[&b""[..], b"[1,", b"{\"a\":}"] {
+            let mut error = std::ptr::null_mut();
+            let json = ffi::json_minimal_parse(input.as_ptr(), input.len(), &mut error);
+
+            assert!(json.is_null());
+            assert!(CStr::from_ptr(error)
+                .to_str()
+                .unwrap()
+                .contains("at position"));
+
+            ffi::json_minimal_string_free(error);
+        }
+
+        assert!(ffi::json_minimal_parse(std::ptr::null(), 0, std::ptr::null_mut()).is_null());
+    }
+}
+
+#[test]
+fn test_parse_seq() {
+    let input = b"\x1e\x1e \n\x1e  {\"a\":[true,null]}\r\n\x1etrue\n\x1e[1,}\n\x1enull";
+    let values: Vec<Result<Json, SeqError>> = Json::parse_seq(&input[..]).collect();
+
+    assert_eq!(values.len(), 4);
+    assert_eq!(
+        values[0].as_ref().unwrap(),
+        &Json::parse(b"{\"a\":[true,null]}").unwrap()
+    );
+    assert_eq!(values[1].as_ref().unwrap(), &Json::BOOL(true));
+    assert_eq!(
+        values[2].as_ref().unwrap_err().to_string(),
+        "`Error parsing array.` at position `3` of record `2`."
+    );
+    assert!(matches!(
+        values[3],
+        Err(SeqError::Parse {
+            record: 3,
+            position: 4,
+            message: "Possibly truncated value."
+        })
+    ));
+
+    struct Failing;
+
+    impl std::io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken pipe"))
+        }
+    }
+
+    let mut values = Json::parse_seq(std::io::BufReader::new(Failing));
+
+    assert!(matches!(values.next(), Some(Err(SeqError::Io(_)))));
+    assert!(values.next().is_none());
+
+    let mut writer = SeqWriter::new(Vec::new());
+
+    for value in &[Json::string("a"), Json::ARRAY(vec![]), Json::NULL] {
+        writer.write(value).unwrap();
+    }
+
+    let output = writer.into_inner();
+
+    assert_eq!(output, b"\x1e\"a\"\n\x1e[]\n\x1enull\n");
+    assert_eq!(
+        Json::parse_seq(&output[..])
+            .map(Result::unwrap)
+            .collect::<Vec<Json>>(),
+        vec![Json::string("a"), Json::ARRAY(vec![]), Json::NULL]
+    );
+
+    let value = Json::JSON(vec![Json::member(
+        "say \"hi\"",
+        Json::ARRAY(vec![Json::string("a\"b\nc\\d\u{1e}")]),
+    )]);
+    let mut writer = SeqWriter::new(Vec::new());
+
+    writer.write(&value).unwrap();
+
+    let output = writer.into_inner();
+
+    assert_eq!(
+        output,
+        b"\x1e{\"say \\\"hi\\\"\":[\"a\\\"b\\nc\\\\d\\u001e\"]}\n"
+    );
+    assert_eq!(
+        Json::parse_seq(&output[..])
+            .map(Result::unwrap)
+            .collect::<Vec<Json>>(),
+        vec![value]
+    );
+}
+
+#[test]
+fn test_parse_ndjson() {
+    let input = b"{\"a\":1}\n   \n  [true,\"x\"]  \r\n12 13\nnull\n{\"b\":[}\n\"last\"";
+    let values: Vec<Result<Json, ParseError>> = Json::parse_ndjson(&input[..]).collect();
+
+    assert_eq!(values.len(), 6);
+    assert_eq!(
+        values[0].as_ref().unwrap(),
+        &Json::parse(b"{\"a\":1}").unwrap()
+    );
+    assert_eq!(
+        values[1].as_ref().unwrap(),
+        &Json::ARRAY(vec![Json::BOOL(true), Json::string("x")])
+    );
+
+    let error = values[2].as_ref().unwrap_err();
+
+    assert_eq!(
+        (error.line, error.position, error.message),
+        (4, 3, "Unexpected data after the value.")
+    );
+    assert!(error.io_error().is_none());
+    assert_eq!(values[3].as_ref().unwrap(), &Json::NULL);
+
+    let error = values[4].as_ref().unwrap_err();
+
+    assert_eq!((error.line, error.position), (6, 6));
+    assert_eq!(
+        error.to_string(),
+        "`Error parsing array.` at line `6`, position `6`."
+    );
+    assert_eq!(values[5].as_ref().unwrap(), &Json::string("last"));
+
+    struct Failing;
+
+    impl std::io::Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("broken pipe"))
+        }
+    }
+
+    let mut values = Json::parse_ndjson(std::io::BufReader::new(Failing));
+    let error = values.next().unwrap().unwrap_err();
+
+    assert_eq!(error.line, 1);
+    assert_eq!(error.io_error().unwrap().to_string(), "broken pipe");
+    assert!(std::error::Error::source(&error).is_some());
+    assert!(values.next().is_none());
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_par_parse_ndjson() {
+    let mut input = b"{\"a\":1}\n   \n  [true,\"x\"]  \r\n12 13\nnull\n{\"b\":[}\n".to_vec();
+
+    // Enough lines for several batches.
+    for n in 0..50_000 {
+        input.extend(format!("{{\"id\":{},\"text\":\"{:100}\"}}\n", n, n).bytes());
+    }
+
+    input.extend(b"\"last\"");
+
+    let values: Vec<String> = Json::par_parse_ndjson(&input[..])
+        .map(|value| format!("{:?}", value))
+        .collect();
+    let expected: Vec<String> = Json::parse_ndjson(&input[..])
+        .map(|value| format!("{:?}", value))
+        .collect();
+
+    assert_eq!(values.len(), 50_006);
+    assert_eq!(values, expected);
+
+    struct Failing(usize);
+
+    impl std::io::Read for Failing {
+        fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+            if self.0 == 0 {
+                return Err(std::io::Error::other("broken pipe"));
+            }
+
+            self.0 -= 1;
+            buffer[..2].copy_from_slice(b"1\n");
+
+            Ok(2)
+        }
+    }
+
+    let values: Vec<Result<Json, ParseError>> =
+        Json::par_parse_ndjson(std::io::BufReader::new(Failing(2))).collect();
+
+    assert_eq!(values.len(), 3);
+    assert_eq!(values[1].as_ref().unwrap(), &Json::NUMBER(1.0));
+    assert_eq!(values[2].as_ref().unwrap_err().line, 3);
+    assert_eq!(
+        values[2]
+            .as_ref()
+            .unwrap_err()
+            .io_error()
+            .unwrap()
+            .to_string(),
+        "broken pipe"
+    );
+}
+
+#[test]
+fn test_parse_stream() {
+    // Feeds the input a few bytes at a time, so that values are split across reads.
+    let input: &[u8] = b" {\"a\":[1,\"]}\\\"\"]}{\"b\":{}}\n\"k\": true  [null]\"s\"7\n\n";
+    let values: Vec<Json> = Json::parse_stream(std::io::BufReader::with_capacity(3, input))
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(
+        values,
+        vec![
+            Json::parse(b"{\"a\":[1,\"]}\\\"\"]}").unwrap(),
+            Json::parse(b"{\"b\":{}}").unwrap(),
+            Json::member("k", Json::BOOL(true)),
+            Json::ARRAY(vec![Json::NULL]),
+            Json::string("s"),
+            Json::NUMBER(7.0),
+        ]
+    );
+
+    assert!(Json::parse_stream(&b" \n "[..]).next().is_none());
+
+    let mut values = Json::parse_stream(&b"1 2,3"[..]);
+
+    assert_eq!(values.next().unwrap().unwrap(), Json::NUMBER(1.0));
+    assert_eq!(values.next().unwrap().unwrap(), Json::NUMBER(2.0));
+
+    let error = values.next().unwrap().unwrap_err();
+
+    assert_eq!((error.line, error.position), (1, 3));
+    assert!(values.next().is_none());
+
+    let mut values = Json::parse_stream(&b"[1]\n  {\"a\" 1:}"[..]);
+
+    assert!(values.next().unwrap().is_ok());
+
+    let error = values.next().unwrap().unwrap_err();
+
+    assert_eq!((error.line, error.position), (2, 9));
+}
+
+#[test]
+fn test_parse_array_stream() {
+    // Feeds the input a few bytes at a time, so that elements are split across reads.
+    let input: &[u8] = b" \n[ {\"a\":[1,\"]\"]},,\"k\":true,\n12.5 ,[],\"s\"] \n";
+    let elements: Vec<Json> = Json::parse_array_stream(std::io::BufReader::with_capacity(3, input))
+        .map(Result::unwrap)
+        .collect();
+
+    assert_eq!(
+        elements,
+        vec![
+            Json::parse(b"{\"a\":[1,\"]\"]}").unwrap(),
+            Json::member("k", Json::BOOL(true)),
+            Json::NUMBER(12.5),
+            Json::array(),
+            Json::string("s"),
+        ]
+    );
+
+    assert!(Json::parse_array_stream(&b"[ ]"[..]).next().is_none());
+
+    for (input, count, line, position) in &[
+        (&b"{}"[..], 0, 1, 0),
+        (b"", 0, 1, 0),
+        (b"[1,\n2", 2, 2, 1),
+        (b"[1] [2]", 1, 1, 4),
+        (b"[1,{\"a\":}]", 1, 1, 8),
+    ] {
+        let mut elements = Json::parse_array_stream(&input[..]);
+
+        for _ in 0..*count {
+            assert!(elements.next().unwrap().is_ok());
+        }
+
+        let error = elements.next().unwrap().unwrap_err();
+
+        assert_eq!((error.line, error.position), (*line, *position));
+        assert!(elements.next().is_none());
+    }
+}
+
+#[test]
+fn test_parse_iter() {
+    let input = b"\n {\"a\":[1,{\"b\":\"c\"}],\"d\":null} \n";
+    let json = match Json::parse_iter(input.iter().copied()) {
+        Ok(json) => json,
+        Err(e) => panic!("{}", e),
+    };
+
+    assert_eq!(json, Json::parse(&input[2..input.len() - 2]).unwrap());
+
+    // A large value, which is read in several chunks.
+    let large = format!("[{}1]", "\"abcdefgh\",".repeat(10_000));
+
+    assert_eq!(
+        Json::parse_iter(large.bytes())
+            .unwrap()
+            .as_array()
+            .unwrap()
+            .len(),
+        10_001
+    );
+    assert_eq!(Json::parse_iter(b"7".to_vec()).unwrap(), Json::NUMBER(7.0));
+
+    for (input, line, position) in &[(&b" "[..], 1, 1), (b"[1,", 1, 3), (b"1\n 2", 2, 1)] {
+        let error = Json::parse_iter(input.iter().copied()).unwrap_err();
+
+        assert_eq!((error.line, error.position), (*line, *position));
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn test_print_to_async() {
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let mut json = Json::new();
+
+    for n in 0..2000 {
+        json.add(Json::member(
+            format!("key {}", n),
+            Json::ARRAY(vec![
+                Json::NUMBER(n as f64 / 8.0),
+                Json::JSON(vec![]),
+                Json::ARRAY(vec![Json::NULL, Json::BOOL(n % 2 == 0)]),
+                Json::string("value"),
+            ]),
+        ));
+    }
+
+    for json in &[
+        json,
+        Json::member("a", Json::string("b")),
+        Json::ARRAY(vec![]),
+    ] {
+        let mut output = Vec::new();
+
+        runtime.block_on(json.print_to_async(&mut output)).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), json.print());
+    }
+
+    let json = Json::member("say \"hi\"", Json::string("a\\b\n\u{1}"));
+    let mut output = Vec::new();
+
+    runtime.block_on(json.print_to_async(&mut output)).unwrap();
+
+    assert_eq!(output, br#""say \"hi\"":"a\\b\n\u0001""#);
+    assert_eq!(Json::parse(&output).unwrap(), json);
+
+    struct Failing;
+
+    impl tokio::io::AsyncWrite for Failing {
+        fn poll_write(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+            _: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            std::task::Poll::Ready(Err(std::io::Error::other("broken pipe")))
+        }
+
+        fn poll_flush(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(
+            self: std::pin::Pin<&mut Self>,
+            _: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::task::Poll::Ready(Ok(()))
+        }
+    }
+
+    let error = runtime
+        .block_on(Json::NULL.print_to_async(Failing))
+        .unwrap_err();
+
+    assert_eq!(error.to_string(), "broken pipe");
+}
+
+#[test]
+fn test_parse_lazy() {
+    let input = b"  {\"a\\u0062\": [1, \"x\":{\"y\":2}, \"z\"],\n\"n\":{\"bad\":[1,}, \"ok\":true},7,\"s\":\"t\"}  ";
+
+    let lazy = match Json::parse_lazy(input) {
+        Ok(lazy) => lazy,
+        Err(e) => panic!("{}", e),
+    };
+
+    let array = lazy.get("ab").unwrap();
+
+    assert_eq!(array.raw(), b"[1, \"x\":{\"y\":2}, \"z\"]");
+    assert_eq!(array.index(0).unwrap().value().unwrap(), &Json::NUMBER(1.0));
+    assert_eq!(
+        array.index(1).unwrap().get("y").unwrap().value().unwrap(),
+        &Json::NUMBER(2.0)
+    );
+    assert_eq!(array.index(2).unwrap().value().unwrap(), &Json::string("z"));
+    assert!(array.index(3).is_none());
+    assert!(array.get("x").is_none());
+    assert!(lazy.index(0).is_none());
+    assert_eq!(lazy.get("s").unwrap().value().unwrap(), &Json::string("t"));
+
+    let nested = lazy.get("n").unwrap();
+
+    assert_eq!(nested.get("bad").unwrap().raw(), b"[1,}");
+    assert_eq!(
+        nested.get("ok").unwrap().value().unwrap(),
+        &Json::BOOL(true)
+    );
+
+    let error = nested.value().unwrap_err();
+
+    assert_eq!((error.line, error.position), (2, 14));
+    assert_eq!(lazy.value().unwrap_err().position, 14);
+    assert_eq!(
+        array.value().unwrap(),
+        &Json::parse(b"[1,\"x\":{\"y\":2},\"z\"]").unwrap()
+    );
+
+    for (input, position) in &[(&b" "[..], 1), (b"[1,2", 4), (b"{} x", 3), (b"1 2", 2)] {
+        assert_eq!(Json::parse_lazy(input).unwrap_err().position, *position);
+    }
+}
+
+#[test]
+fn test_json_lexer() {
+    let input = b" {\"a\":[1,\"x\",{\"b\":null}], \"c\":\"d\":false,\n\"e\\u0066\":{}} ";
+    let key = |name: &str| JsonEvent::Key(String::from(name));
+
+    let events: Vec<JsonEvent> = JsonLexer::new(input).map(Result::unwrap).collect();
+
+    assert_eq!(
+        events,
+        vec![
+            JsonEvent::ObjectStart,
+            key("a"),
+            JsonEvent::ArrayStart,
+            JsonEvent::Number(1.0),
+            JsonEvent::String(String::from("x")),
+            JsonEvent::ObjectStart,
+            key("b"),
+            JsonEvent::Null,
+            JsonEvent::ObjectEnd,
+            JsonEvent::ArrayEnd,
+            key("c"),
+            key("d"),
+            JsonEvent::Bool(false),
+            key("ef"),
+            JsonEvent::ObjectStart,
+            JsonEvent::ObjectEnd,
+            JsonEvent::ObjectEnd,
+        ]
+    );
+
+    let events: Vec<JsonEvent> = JsonLexer::new(b"\"a\": 7").map(Result::unwrap).collect();
+
+    assert_eq!(events, vec![key("a"), JsonEvent::Number(7.0)]);
+
+    for (input, events, line, position) in &[
+        (&b"[1}"[..], 2, 1, 2),
+        (b"{\"a\":}", 2, 1, 5),
+        (b"[1,\n[2]", 5, 2, 3),
+        (b"\"ab", 0, 1, 3),
+        (b"1 2", 1, 1, 2),
+        (b"[tru]", 1, 1, 4),
+        (b"  ", 0, 1, 2),
+    ] {
+        let mut lexer = JsonLexer::new(input);
+
+        for _ in 0..*events {
+            assert!(lexer.next().unwrap().is_ok());
+        }
+
+        let error = lexer.next().unwrap().unwrap_err();
+
+        assert_eq!((error.line, error.position), (*line, *position));
+        assert!(lexer.next().is_none());
+    }
+}
+
+#[test]
+fn test_json_lexer_span() {
+    let input = b" [ \"a\\\"b\" ,\n{\"k\":1},\"n\":null, 2.5e3 ] ";
+
+    let mut lexer = JsonLexer::new(input);
+    let mut spans = Vec::new();
+
+    assert_eq!(lexer.span(), 0..0);
+
+    while let Some(event) = lexer.next() {
+        if event.is_ok() {
+            spans.push(lexer.span());
+        }
+    }
+
+    assert_eq!(
+        spans,
+        vec![
+            1..2,
+            3..9,
+            12..13,
+            13..16,
+            17..18,
+            18..19,
+            20..23,
+            24..28,
+            30..35,
+            36..37
+        ]
+    );
+}
+
+#[test]
+fn test_parse_spanned() {
+    let input = b"{\"a\":[1, {\"b\":\"c\":true}],\r\n\"a\":2, \"x/y\" 3,\n\"s\":\"t\":[null]}";
+
+    let (json, spans) = match Json::parse_spanned(input) {
+        Ok(result) => result,
+        Err(e) => panic!("{}", e),
+    };
+
+    assert_eq!(json, Json::parse(input).unwrap());
+
+    let text = |pointer: &str| spans.get(pointer).map(|span| &input[span]);
+
+    assert_eq!(text(""), Some(&input[..]));
+    assert_eq!(text("/a"), Some(&b"[1, {\"b\":\"c\":true}]"[..]));
+    assert_eq!(text("/a/0"), Some(&b"1"[..]));
+    assert_eq!(text("/a/1/b"), Some(&b"\"c\":true"[..]));
+    assert_eq!(text("/a/1/b/c"), None);
+    assert_eq!(text("/s"), Some(&b"\"t\":[null]"[..]));
+    assert_eq!(text("/s/0"), Some(&b"null"[..]));
+    assert_eq!(json.pointer("/s/0"), Some(&Json::NULL));
+    assert_eq!(text("/x~1y"), None);
+    assert_eq!(spans.location("/a/0"), Some((1, 6)));
+    assert_eq!(spans.location("/s/0"), Some((3, 9)));
+    assert_eq!(spans.location("/b"), None);
+
+    let (json, spans) = Json::parse_spanned(b" \"a\":{\"b\":1} ").unwrap();
+
+    assert_eq!(json, Json::member("a", Json::parse(b"{\"b\":1}").unwrap()));
+    assert_eq!(spans.get(""), Some(1..12));
+    assert_eq!(spans.get("/b"), Some(10..11));
+
+    for input in &[&b"[1,2"[..], b"{} {}", b"{\"a\":}"] {
+        assert!(Json::parse_spanned(input).is_err());
+    }
+}
+
+#[test]
+fn test_json_document() {
+    let input = b"{ \"a\":\t[1,  2],\r\n  \"b\": {},\"c\":{\n}, \"d\":\"e\":{\"f\":1}}  ";
+
+    let mut document = match JsonDocument::parse(input) {
+        Ok(document) => document,
+        Err(e) => panic!("{}", e),
+    };
+
+    assert_eq!(document.as_bytes(), &input[..]);
+    assert!(document.set("/a/1", &Json::string("two")));
+    assert!(document.set("/b/x", &Json::NULL));
+    assert!(document.set("/c/y", &Json::array()));
+    assert!(document.set("/d/g", &Json::BOOL(false)));
+
+    assert_eq!(
+        document.to_string(),
+        "{ \"a\":\t[1,  \"two\"],\r\n  \"b\": {\"x\":null},\"c\":{\"y\":[]\n}, \"d\":\"e\":{\"f\":1,\"g\":false}}  "
+    );
+    assert_eq!(document.json(), &Json::parse(document.as_bytes()).unwrap());
+    assert_eq!(document.spans().get("/d/g"), Some(73..78));
+
+    assert!(document.set("", &Json::NUMBER(1.0)));
+    assert_eq!(document.into_bytes(), b"1  ");
+
+    let mut document = JsonDocument::parse(b"{\"a\":[1],\"b\":2}").unwrap();
+
+    assert!(!document.set("/a/1", &Json::NULL));
+    assert!(!document.set("/b/c", &Json::NULL));
+    assert!(!document.set("/x/y", &Json::NULL));
+    assert!(!document.set("/b", &Json::string("\"")));
+    assert_eq!(document.as_bytes(), b"{\"a\":[1],\"b\":2}");
+}
+
+#[test]
+fn test_json_document_comments() {
+    let input = b"/* header */\n[\n  // first\n  \"http://x\", /*a*/ /*b*/\n  {\"k\": /* v */ 1 // one\n  }\n] // end\n";
+
+    let mut document = match JsonDocument::parse_with_comments(input) {
+        Ok(document) => document,
+        Err(e) => panic!("{}", e),
+    };
+
+    assert_eq!(
+        document.json(),
+        &Json::parse(b"[\"http://x\",{\"k\":1}]").unwrap()
+    );
+    assert_eq!(document.comments(""), vec!["/* header */", "// end"]);
+    assert_eq!(document.comments("/0"), vec!["// first", "/*a*/"]);
+    assert_eq!(document.comments("/1"), Vec::<String>::new());
+    assert_eq!(document.comments("/1/k"), vec!["// one"]);
+    assert!(document.comments("/2").is_empty());
+
+    assert!(document.set("/1/k", &Json::NUMBER(2.0)));
+    assert!(document.set("/1/l", &Json::NULL));
+    assert!(!document.set("/1/m", &Json::string("\"")));
+
+    assert_eq!(
+        document.to_string(),
+        "/* header */\n[\n  // first\n  \"http://x\", /*a*/ /*b*/\n  {\"k\": /* v */ 2, // one\n\"l\": null\n  }\n] // end\n"
+    );
+    assert_eq!(document.comments("/1/l"), Vec::<String>::new());
+    assert!(document.set("/1/k", &Json::NUMBER(3.0)));
+    assert_eq!(document.comments("/1/k"), vec!["// one"]);
+
+    assert!(JsonDocument::parse(b"[1 // no\n]").is_err());
+
+    for (input, position) in &[(&b"[1 /* open"[..], 3), (b"[1 / 2]", 3), (b"[1] /", 4)] {
+        let error = JsonDocument::parse_with_comments(input).unwrap_err();
+
+        assert_eq!(error.position, *position);
+    }
+}
+
+#[test]
+fn test_validate() {
+    let valid: &[&[u8]] = &[
+        b"{\"a\":[1, 2.5e3,,true],\"b\":{\"c\":null},\"d\":\"\\u00e9\\n\"}",
+        b"\"name\":\"value\"",
+        b"\"a\":\"b\":[]",
+        b"[\"x\":1, {}]",
+        b"{\"a\" 1}",
+        "\"\u{e9}\"".as_bytes(),
+        b"36",
+        b"[[[[]]]]",
+    ];
+
+    for input in valid {
+        if let Err(e) = Json::validate(input) {
+            panic!("{} for {}", e, String::from_utf8_lossy(input));
+        }
+
+        assert!(Json::parse(input).is_ok());
+        assert!(Json::validate(&[&b"\n "[..], input, b"\t"].concat()).is_ok());
+    }
+
+    let invalid: &[(&[u8], &str, usize)] = &[
+        (b"", "Unexpected end of input.", 0),
+        (b"[1,2", "Unexpected end of input.", 4),
+        (b"[1}", "Unexpected end of a container.", 2),
+        (b"{\"a\":}", "Unexpected end of a container.", 5),
+        (b"{\"a\":,1}", "Not a valid json format", 5),
+        (b"{} x", "Unexpected data after the value.", 3),
+        (b"-1", "Not a valid json format", 0),
+        (b"[1.2.3]", "Error parsing number.", 6),
+        (b"nul", "Unexpected end of input.", 3),
+        (
+            b"\"a\\x\"",
+            "Error parsing invalid string escape sequence.",
+            3,
+        ),
+        (
+            b"\"\\ud800\"",
+            "Error parsing unicode string escape sequence.",
+            2,
+        ),
+        (b"\"\xff\"", "Error parsing non-utf8 string.", 3),
+        (b"\"abc", "Unexpected end of input.", 4),
+        (b"]", "Unexpected end of a container.", 0),
+    ];
+
+    for (input, message, position) in invalid {
+        let error = Json::validate(input).unwrap_err();
+
+        assert_eq!((error.message, error.position), (*message, *position));
+    }
+
+    let deep = [vec![b'['; 1024], vec![b']'; 1024]].concat();
+    let deeper = [&b"["[..], &deep, b"]"].concat();
+
+    assert!(Json::validate(&deep).is_ok());
+    assert!(Json::parse(&deep).is_ok());
+    assert_eq!(
+        Json::validate(&deeper).unwrap_err().kind,
+        ErrorKind::TooDeep
+    );
+    assert_eq!(Json::parse(&deeper).unwrap_err().kind, ErrorKind::TooDeep);
+
+    // Both accept and reject the same, but for what's around the value.
+    let inputs: &[&[u8]] = &[
+        b"{}",
+        b"[[[]]]",
+        b"1.5e3",
+        b"01",
+        b"1.",
+        b"ture",
+        b"nul",
+        b"\"a\":1",
+        b"[1 2]",
+        b"[1,,2]",
+        b"[,1]",
+        b"[1,]",
+        b"{1}",
+        b"{\"a\" 1}",
+        b"{\"a\"}",
+        b"[\"a\":1]",
+        b"{]",
+        b"[",
+        b"\"\\u12\"",
+        b"[\t1\r\n]",
+        b"{ \"a\" : 1 }",
+        b"[+1]",
+        b"NaN",
+        b"[1,2,3",
+    ];
+
+    for input in inputs {
+        assert_eq!(
+            Json::validate(input).is_ok(),
+            Json::parse(input).is_ok(),
+            "{}",
+            String::from_utf8_lossy(input)
+        );
+    }
+
+    assert!(Json::validate(b" 1").is_ok() && Json::parse(b" 1").is_err());
+    assert!(Json::validate(b"[1]]").is_err() && Json::parse(b"[1]]").is_ok());
+}
+
+#[test]
+fn test_parse_pointer() {
+    let input =
+        b" {\"a\":[1,{\"b~/c\":\"d\"},[true]],\"0\":\"zero\",\"a\":2,\"e\":\"f\":{\"g\":null}} ";
+    let json = Json::parse(&input[1..]).unwrap();
+
+    for pointer in &[
+        "",
+        "/a",
+        "/a/0",
+        "/a/1/b~0~1c",
+        "/a/2/0",
+        "/a/3",
+        "/a/01",
+        "/0",
+        "/e",
+        "/e/g",
+        "/x",
+        "a",
+        "/a/-",
+    ] {
+        match Json::parse_pointer(input, pointer) {
+            Ok(found) => assert_eq!(found.as_ref(), json.pointer(pointer), "{}", pointer),
+            Err(e) => panic!("{} for {}", e, pointer),
+        }
+    }
+
+    let input = b"{\"skipped\":[1,,x:],\"broken\":[tru],\"ok\":[36]}";
+
+    assert_eq!(
+        Json::parse_pointer(input, "/ok/0").unwrap(),
+        Some(Json::NUMBER(36.0))
+    );
+
+    let error = Json::parse_pointer(input, "/broken").unwrap_err();
+
+    assert_eq!((error.message, error.position), ("Error parsing bool.", 32));
+    assert!(Json::parse_pointer(b"{\"a\":1", "/a").is_err());
+}
+
+#[test]
+fn test_parse_only() {
+    let input = b" {\"a\":[1,{\"b\":2,\"c\":3},[4,5],\"x\":{\"y\":6}], \"d\\u0065\":\"e\",\"f\":\"g\":{\"h\":7,\"i\":8},\"a\":[9],\"j\":[bad,,]} ";
+
+    let cases: &[(&[&str], &[u8])] = &[
+        (&[], b"{}"),
+        (&["/de"], b"{\"de\":\"e\"}"),
+        (
+            &["/a/1/c", "/a/2/1", "/zz"],
+            b"{\"a\":[{\"c\":3},[5]],\"a\":[]}",
+        ),
+        (
+            &["/a/0", "/a/3/y"],
+            b"{\"a\":[1,\"x\":{\"y\":6}],\"a\":[9]}",
+        ),
+        (
+            &["/f/i", "a", "/a/01"],
+            b"{\"a\":[],\"f\":\"g\":{\"i\":8},\"a\":[]}",
+        ),
+    ];
+
+    for (paths, expected) in cases {
+        match Json::parse_only(input, paths) {
+            Ok(json) => assert_eq!(json, Json::parse(expected).unwrap(), "{:?}", paths),
+            Err(e) => panic!("{} for {:?}", e, paths),
+        }
+    }
+
+    assert_eq!(Json::parse_only(b"7", &["/a"]).unwrap(), Json::NUMBER(7.0));
+    assert_eq!(
+        Json::parse_only(b" [1,{}] ", &["/0", ""]).unwrap(),
+        Json::parse(b"[1,{}]").unwrap()
+    );
+
+    let error = Json::parse_only(input, &["/j"]).unwrap_err();
+
+    assert_eq!(
+        (error.message, error.position),
+        ("Error parsing array.", 92)
+    );
+    assert!(Json::parse_only(b"{\"a\":1} 2", &[]).is_err());
+}
+
+#[test]
+fn test_filter_ndjson() {
+    let input = b"{\"n\":1,\"big\":[1,2,3]}\n  {\"n\":2}  \r\n\n[3]\n{\"n\":4}";
+    let mut output = Vec::new();
+    let mut seen = 0;
+
+    let count = Json::filter_ndjson(&input[..], &mut output, |record| {
+        seen += 1;
+
+        match record.get("n").map(|n| n.value()) {
+            Some(Ok(n)) => n.as_f64().unwrap() % 2.0 == 0.0,
+            _ => true,
+        }
+    });
+
+    assert_eq!(count.unwrap(), 3);
+    assert_eq!(seen, 4);
+    assert_eq!(output, b"{\"n\":2}\n[3]\n{\"n\":4}\n");
+
+    let error =
+        Json::filter_ndjson(&b"1 \n\n{\"a\":1} x\n"[..], std::io::sink(), |_| true).unwrap_err();
+
+    assert_eq!((error.line, error.position), (3, 8));
+
+    struct Failing;
+
+    impl std::io::Write for Failing {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::Error::other("disk full"))
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let error = Json::filter_ndjson(&b"\n1\n2"[..], Failing, |_| true).unwrap_err();
+
+    assert_eq!(error.line, 2);
+    assert_eq!(error.io_error().unwrap().to_string(), "disk full");
+    assert_eq!(
+        Json::filter_ndjson(&b"1\n2"[..], Failing, |_| false).unwrap(),
+        0
+    );
+}
+
+#[test]
+fn test_parse_nested() {
+    let depth = 1024;
+    let input = [vec![b'['; depth], vec![b']'; depth]].concat();
+
+    let mut json = Json::parse(&input).unwrap();
+
+    for _ in 1..depth {
+        json = match json {
+            Json::ARRAY(mut values) if values.len() == 1 => values.remove(0),
+            json => panic!("Expected a Json::ARRAY of one but found {:?}", json),
+        };
+    }
+
+    assert_eq!(json, Json::ARRAY(Vec::new()));
+
+    let input = [vec![b'['; depth + 1], vec![b']'; depth + 1]].concat();
+
+    assert_eq!(parse_kind(&input), Err((depth, ErrorKind::TooDeep)));
+    assert_eq!(
+        parse_kind(&[b'['; 200_000]),
+        Err((depth, ErrorKind::TooDeep))
+    );
+
+    // Printing, cloning, comparing and dropping recurse, but fit on the stack of a test thread
+    // at the deepest nesting parsed, even with members, which take two levels each.
+    let input = [
+        "{\"a\":".repeat(depth).into_bytes(),
+        b"1".to_vec(),
+        vec![b'}'; depth],
+    ]
+    .concat();
+    let json = Json::parse(&input).unwrap();
+
+    assert_eq!(json.print().as_bytes(), &input[..]);
+    assert_eq!(json.clone(), json);
+
+    // Members within members, and the input ending within containers.
+    assert_eq!(
+        parse_kind(b"[\"a\":\"b\": 1,2]"),
+        Ok(Json::ARRAY(vec![
+            Json::member("a", Json::member("b", Json::NUMBER(1.0))),
+            Json::NUMBER(2.0)
+        ]))
+    );
+    assert_eq!(parse_kind(b"{\"a\":[1"), Err((7, ErrorKind::UnexpectedEof)));
+    assert_eq!(parse_kind(b"{\"a\":1,"), Err((7, ErrorKind::UnexpectedEof)));
+    assert_eq!(parse_kind(b"\"a\": "), Err((5, ErrorKind::UnexpectedEof)));
+    assert_eq!(parse_kind(b""), Err((0, ErrorKind::UnexpectedEof)));
+}
+
+#[test]
+fn test_parse_string_runs() {
+    let mut incr = 0;
+
+    assert_eq!(
+        Json::parse_string(
+            "\"caf\u{e9} \\\"au\\u004Ait\\\"\\n\", 1".as_bytes(),
+            &mut incr
+        ),
+        Ok(String::from("caf\u{e9} \"auJit\"\n"))
+    );
+    assert_eq!(incr, 24);
+
+    incr = 0;
+
+    assert_eq!(
+        Json::parse_string(b"\"abc\\ndef", &mut incr),
+        Err((9, ErrorKind::UnexpectedEof))
+    );
+
+    incr = 0;
+
+    assert_eq!(
+        Json::parse_string(b"\"ab\xffc\" ", &mut incr),
+        Err((6, ErrorKind::InvalidUtf8))
+    );
+}
+
+#[test]
+fn test_scan() {
+    let input = b"        \t\r\n    , \"0123456789abcdef\\\"";
+
+    assert_eq!(scan::skip(input, b" \t\r\n"), Some(15));
+    assert_eq!(scan::skip(input, b" \t\r\n,"), Some(17));
+    assert_eq!(scan::find(input, b"\\"), Some(34));
+    assert_eq!(scan::find(&input[18..], b"\"\\"), Some(16));
+    assert_eq!(scan::find(input, b"{"), None);
+    assert_eq!(scan::skip(&input[..8], b" "), None);
+
+    // Every byte in every position of a word.
+    for n in 0..24 {
+        let mut input = vec![b'a'; 24];
+
+        input[n] = 0xff;
+
+        assert_eq!(scan::find(&input, &[0xff]), Some(n));
+        assert_eq!(scan::skip(&input, b"a"), Some(n));
+        assert_eq!(scan::find(&input[..n], &[0xff]), None);
+    }
+}
+
+#[test]
+fn test_parse_with_report() {
+    let input = b"[{\"a\":\"b\":\"\\u00e9\"}, [[]], 1, true]";
+
+    let (json, report) = Json::parse_with_report(input);
+
+    assert_eq!(json.unwrap(), Json::parse(input).unwrap());
+    assert_eq!(report.bytes, input.len());
+    // `é`, `b:é`, `a:b:é`, the object, the two arrays, `1`, `true` and the array around them.
+    assert_eq!(report.nodes, 9);
+    assert_eq!(report.depth, 3);
+    assert_eq!(report.string_bytes, 4);
+
+    let (json, report) = Json::parse_with_report(b"7");
+
+    assert_eq!(json.unwrap(), Json::NUMBER(7.0));
+    assert_eq!((report.bytes, report.nodes, report.depth), (1, 1, 0));
+
+    let (json, report) = Json::parse_with_report(b"{\"a\":[tru]}");
+
+    assert_eq!(json.unwrap_err().message, "Error parsing bool.");
+    assert_eq!((report.bytes, report.nodes, report.depth), (9, 0, 2));
+    assert_eq!(report.string_bytes, 1);
+}
+
+#[test]
+fn test_parse_with() {
+    let input = b"{\"name\":\"abc\",\"tags\":[\"de\",\"f\"]}";
+
+    assert_eq!(
+        Json::parse_with(input, &ParseOptions::default()).unwrap(),
+        Json::parse(input).unwrap()
+    );
+
+    // Exactly at the limits.
+    let options = ParseOptions {
+        max_nodes: Some(7),
+        max_string_bytes: Some(14),
+        ..ParseOptions::default()
+    };
+
+    assert!(Json::parse_with(input, &options).is_ok());
+
+    let error = Json::parse_with(
+        input,
+        &ParseOptions {
+            max_nodes: Some(6),
+            ..options
+        },
+    )
+    .unwrap_err();
+
+    assert_eq!((error.position, error.message), (32, "Too many values."));
+
+    let error = Json::parse_with(
+        input,
+        &ParseOptions {
+            max_string_bytes: Some(13),
+            ..options
+        },
+    )
+    .unwrap_err();
+
+    assert_eq!(
+        (error.position, error.message),
+        (27, "Too many string bytes.")
+    );
+
+    // The longest string is `name`, counted like the rest with escape sequences decoded.
+    let input = b"{\"name\":\"\\u0061\\u0062\",\"tags\":[\"de\"]}";
+    let max_string_len = |max| ParseOptions {
+        max_string_len: Some(max),
+        ..ParseOptions::default()
+    };
+
+    assert!(Json::parse_with(input, &max_string_len(4)).is_ok());
+
+    let error = Json::parse_with(input, &max_string_len(3)).unwrap_err();
+
+    assert_eq!((error.position, error.kind), (1, ErrorKind::StringTooLong));
+
+    let error = Json::parse_with(input, &max_string_len(1)).unwrap_err();
+
+    assert_eq!((error.position, error.kind), (1, ErrorKind::StringTooLong));
+
+    // A huge string fails without being copied, before its end is even looked for.
+    let huge = format!("[\"{}", "a".repeat(1 << 20));
+    let error = Json::parse_with(huge.as_bytes(), &max_string_len(1024)).unwrap_err();
+
+    assert_eq!((error.position, error.kind), (1, ErrorKind::StringTooLong));
+}
+
+#[test]
+fn test_error_kind() {
+    let kind = |input: &[u8]| {
+        Json::parse_with(input, &ParseOptions::default())
+            .unwrap_err()
+            .kind
+    };
+
+    assert_eq!(kind(b""), ErrorKind::UnexpectedEof);
+    assert_eq!(kind(b"{\"a\":1,]"), ErrorKind::InvalidObject);
+    assert_eq!(kind(b"[1,}"), ErrorKind::InvalidArray);
+    assert_eq!(kind(b"\"a\":,"), ErrorKind::InvalidMember);
+    assert_eq!(kind(b"\"abc"), ErrorKind::UnexpectedEof);
+    assert_eq!(kind(b"\"\xff\""), ErrorKind::InvalidUtf8);
+    assert_eq!(kind(b"\"\\"), ErrorKind::UnexpectedEof);
+    assert_eq!(kind(b"\"\\x\""), ErrorKind::InvalidEscape);
+    assert_eq!(kind(b"\"\\u12\""), ErrorKind::InvalidUnicodeEscape);
+    assert_eq!(kind(b"1.2.3"), ErrorKind::InvalidNumber);
+    assert_eq!(kind(b"[tru]"), ErrorKind::InvalidBool);
+    assert_eq!(kind(b"[nul]"), ErrorKind::InvalidNull);
+
+    let error = Json::validate(b"[1} ").unwrap_err();
+
+    assert_eq!(
+        (error.kind, error.message),
+        (ErrorKind::UnexpectedClose, "Unexpected end of a container.")
+    );
+    assert_eq!(
+        Json::validate(b"1 2").unwrap_err().kind,
+        ErrorKind::TrailingData
+    );
+    assert_eq!(
+        Json::validate(&[b'['; 5000]).unwrap_err().kind,
+        ErrorKind::TooDeep
+    );
+
+    let mut lexer = JsonLexer::new(b"[/* 1").allow_comments();
+
+    lexer.next();
+
+    assert_eq!(
+        lexer.next().unwrap().unwrap_err().kind,
+        ErrorKind::UnterminatedComment
+    );
+
+    let error = Json::parse_array_stream(&b"{}"[..])
+        .next()
+        .unwrap()
+        .unwrap_err();
+
+    assert_eq!(error.kind, ErrorKind::ExpectedArray);
+    assert_eq!(ErrorKind::TooManyValues.to_string(), "Too many values.");
+    assert_eq!(parse_kind(b"[1,}"), Err((3, ErrorKind::InvalidArray)));
+}
+
+#[test]
+fn test_parse_recovering() {
+    fn recover(input: &str) -> (String, Vec<(usize, usize, ErrorKind)>) {
+        let (json, errors) = Json::parse_recovering(input.as_bytes());
+
+        let errors = errors
+            .iter()
+            .map(|error| (error.line, error.position, error.kind))
+            .collect();
+
+        (json.print(), errors)
+    }
+
+    let input = "{\"a\":[1,2,{\"b\":null}],\"c\":\"d\"}";
+
+    assert_eq!(recover(input), (input.to_string(), vec![]));
+
+    assert_eq!(
+        recover("[1,2"),
+        ("[1,2]".to_string(), vec![(1, 4, ErrorKind::UnexpectedEof)])
+    );
+    assert_eq!(
+        recover("{\"a\":[1}"),
+        (
+            "{\"a\":[1]}".to_string(),
+            vec![(1, 7, ErrorKind::InvalidArray)]
+        )
+    );
+    assert_eq!(
+        recover("[1}]"),
+        ("[1]".to_string(), vec![(1, 2, ErrorKind::InvalidArray)])
+    );
+    assert_eq!(
+        recover("{\"a\":{\"b\":},\"c\":1}"),
+        (
+            "{\"a\":{},\"c\":1}".to_string(),
+            vec![(1, 10, ErrorKind::InvalidMember)]
+        )
+    );
+    assert_eq!(
+        recover("[1,\"a\\qb\",\n[2,x],4]"),
+        (
+            "[1,[2],4]".to_string(),
+            vec![
+                (1, 6, ErrorKind::InvalidEscape),
+                (2, 3, ErrorKind::InvalidArray)
+            ]
+        )
+    );
+    assert_eq!(
+        recover("[\"abc"),
+        ("[]".to_string(), vec![(1, 5, ErrorKind::UnexpectedEof)])
+    );
+    assert_eq!(
+        recover("[1]] "),
+        ("[1]".to_string(), vec![(1, 3, ErrorKind::TrailingData)])
+    );
+    assert_eq!(
+        recover("tru"),
+        ("null".to_string(), vec![(1, 3, ErrorKind::UnexpectedEof)])
+    );
+}
+
+#[test]
+fn test_parse_with_warnings() {
+    fn warnings(input: &str, options: &ParseOptions) -> Vec<(usize, WarningKind)> {
+        let (_, warnings) = Json::parse_with_warnings(input.as_bytes(), options).unwrap();
+
+        warnings
+            .iter()
+            .map(|warning| (warning.position, warning.kind))
+            .collect()
+    }
+
+    let strict = ParseOptions::default();
+    let comments = ParseOptions {
+        allow_comments: true,
+        ..ParseOptions::default()
+    };
+
+    assert_eq!(
+        warnings("{\"a\":[1, 2],\"b\":{\"a\":null}}", &strict),
+        vec![]
+    );
+    assert_eq!(warnings("[[],{}]", &strict), vec![]);
+
+    assert_eq!(
+        warnings("[1,2,]", &strict),
+        vec![(4, WarningKind::TrailingComma)]
+    );
+    assert_eq!(
+        warnings("[,1,,2 3,,]", &strict),
+        vec![
+            (1, WarningKind::ExtraComma),
+            (4, WarningKind::ExtraComma),
+            (7, WarningKind::MissingComma),
+            (8, WarningKind::TrailingComma)
+        ]
+    );
+    assert_eq!(warnings("[,]", &strict), vec![(1, WarningKind::ExtraComma)]);
+    assert_eq!(
+        warnings("{\"a\":1,\"b\":\"c\":2,\"a\":{\"a\":3}}", &strict),
+        vec![(17, WarningKind::DuplicateKey)]
+    );
+    assert_eq!(
+        warnings("[/* one */1,// two\n2]", &comments),
+        vec![(1, WarningKind::Comment), (12, WarningKind::Comment)]
+    );
+
+    // Comments are only allowed if asked for, with or without warnings.
+    let error = Json::parse_with(b"[1,/* one */2]", &strict).unwrap_err();
+
+    assert_eq!((error.position, error.kind), (3, ErrorKind::InvalidArray));
+    assert_eq!(
+        Json::parse_with(b"{\"a\": /* one */ [1]}", &comments).unwrap(),
+        Json::parse(b"{\"a\":[1]}").unwrap()
+    );
+
+    let error = Json::parse_with_warnings(b"[1, /* one", &comments).unwrap_err();
+
+    assert_eq!(
+        (error.position, error.kind),
+        (4, ErrorKind::UnterminatedComment)
+    );
+}
+
+#[test]
+fn test_parse_repair() {
+    fn repair(input: &str) -> (String, Vec<(usize, RepairKind)>) {
+        let (json, repairs) = Json::parse_repair(input.as_bytes()).unwrap();
+
+        let repairs = repairs
+            .iter()
+            .map(|repair| (repair.position, repair.kind))
+            .collect();
+
+        (json.print(), repairs)
+    }
+
+    let input = "{\"a\":[1,true,null,\"it's\"]}";
+
+    assert_eq!(repair(input), (input.to_string(), vec![]));
+
+    let (json, repairs) = Json::parse_repair(b"['a\\'b\"c\\n']").unwrap();
+
+    assert_eq!(
+        json,
+        Json::ARRAY(vec![Json::STRING("a'b\"c\n".to_string())])
+    );
+    assert_eq!(
+        (repairs[0].position, repairs[0].kind),
+        (1, RepairKind::SingleQuotes)
+    );
+    assert_eq!(
+        repair("{_id$2:{null:None},true:False}"),
+        (
+            "{\"_id$2\":{\"null\":null},\"true\":false}".to_string(),
+            vec![
+                (1, RepairKind::UnquotedKey),
+                (8, RepairKind::UnquotedKey),
+                (13, RepairKind::PythonLiteral),
+                (19, RepairKind::UnquotedKey),
+                (24, RepairKind::PythonLiteral)
+            ]
+        )
+    );
+
+    // Only what is asked for is repaired.
+    assert!(Json::parse(b"['a']").is_err());
+
+    let error = Json::parse_repair(b"[1,Nothing]").unwrap_err();
+
+    assert_eq!((error.position, error.kind), (3, ErrorKind::InvalidArray));
+
+    let error = Json::parse_repair(b"{a :1}").unwrap_err();
+
+    assert_eq!((error.position, error.kind), (1, ErrorKind::InvalidObject));
+
+    let error = Json::parse_repair(b"['abc]").unwrap_err();
+
+    assert_eq!((error.position, error.kind), (6, ErrorKind::UnexpectedEof));
+}
+
+#[test]
+fn test_parse_error_path() {
+    fn path(input: &str) -> Option<String> {
+        Json::parse_with(input.as_bytes(), &ParseOptions::default())
+            .unwrap_err()
+            .path
+    }
+
+    assert_eq!(path("tru"), None);
+    assert_eq!(path("{\"a\":1 x}"), None);
+    assert_eq!(path("{\"a\":x}").as_deref(), Some("a"));
+    assert_eq!(path("[1,2,x]").as_deref(), Some("[2]"));
+    assert_eq!(
+        path("{\"servers\":[{\"port\":80},{\"port\":\"8\\x\"}]}").as_deref(),
+        Some("servers[1].port")
+    );
+    assert_eq!(
+        path("{\"a\":\"b\":[[1,nul]]}").as_deref(),
+        Some("a.b[0][1]")
+    );
+
+    // Siblings parsed before don't count.
+    assert_eq!(
+        path("{\"a\":{\"b\":1},\"c\":{\"d\":[]},\"e\":-}").as_deref(),
+        Some("e")
+    );
+
+    // The errors collected while recovering name theirs too.
+    let (_, errors) = Json::parse_recovering(b"{\"a\":[tru],\"b\":nul}");
+    let paths: Vec<Option<&str>> = errors.iter().map(|error| error.path.as_deref()).collect();
+
+    assert_eq!(paths, vec![Some("a[0]"), Some("b")]);
+
+    let error = Json::parse_with_report(b"[{\"a\":}]").0.unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "`Error parsing object.` at line `1`, position `6`, while parsing the value of `[0].a`."
+    );
+
+    // Without a path, the message is the same as before.
+    assert_eq!(
+        Json::validate(b"[1,").unwrap_err().to_string(),
+        "`Unexpected end of input.` at line `1`, position `3`."
+    );
+}
+
+#[test]
+fn test_cancellable() {
+    let input = format!("{{\"a\":[{}]}}", "[1,{\"b\":null}],".repeat(10_000));
+    let json = Json::parse(input.as_bytes()).unwrap();
+
+    // Asked every so often, and not at all for small values.
+    let mut calls = 0;
+
+    assert_eq!(
+        Json::parse_cancellable(input.as_bytes(), &ParseOptions::default(), &mut || {
+            calls += 1;
+            false
+        })
+        .unwrap(),
+        json
+    );
+    assert!(calls > 10 && calls < 100);
+
+    let mut calls = 0;
+
+    assert_eq!(
+        json.print_cancellable(&mut || {
+            calls += 1;
+            false
+        }),
+        Ok(json.print())
+    );
+    assert!(calls > 10 && calls < 100);
+
+    assert_eq!(
+        Json::parse_cancellable(b"[1,2]", &ParseOptions::default(), &mut || true).unwrap(),
+        Json::parse(b"[1,2]").unwrap()
+    );
+    assert_eq!(
+        Json::parse(b"[1,2]")
+            .unwrap()
+            .print_cancellable(&mut || true),
+        Ok("[1,2]".to_string())
+    );
+
+    let escaped = Json::ARRAY(vec![Json::member("\"", Json::string("\\\t"))]);
+
+    assert_eq!(
+        escaped.print_cancellable(&mut || false),
+        Ok(r#"["\"":"\\\t"]"#.to_string())
+    );
+
+    // Given up on at the first check which says so.
+    let mut calls = 0;
+    let error = Json::parse_cancellable(input.as_bytes(), &ParseOptions::default(), &mut || {
+        calls += 1;
+        calls == 3
+    })
+    .unwrap_err();
+
+    assert_eq!(error.kind, ErrorKind::Cancelled);
+    assert_eq!(error.path.as_deref(), Some("a[1754][2]"));
+
+    let mut calls = 0;
+
+    assert_eq!(
+        json.print_cancellable(&mut || {
+            calls += 1;
+            calls == 3
+        }),
+        Err(Cancelled)
+    );
+    assert_eq!(calls, 3);
+}
+
+#[test]
+fn test_parse_truncated() {
+    let documents: &[&[u8]] = &[
+        b"{\"a\":[1,2.5e+3,{\"b\":null}],\"c\":\"d\\n\\u0041\"}",
+        b"[true,false,null,\"x\":\"y\":0,[[]],{}]",
+        b"[\"caf\xc3\xa9\",1E5,\"\\\"\\\\\"]",
+    ];
+
+    for document in documents {
+        assert!(Json::parse(document).is_ok());
+
+        // Every input cut short fails as such, wherever it was cut.
+        for end in 0..document.len() {
+            let input = &document[..end];
+
+            assert_eq!(
+                Json::parse(input).map_err(|error| error.kind),
+                Err(ErrorKind::UnexpectedEof),
+                "{:?}",
+                String::from_utf8_lossy(input)
+            );
+            assert_eq!(
+                Json::parse_with(input, &ParseOptions::default())
+                    .unwrap_err()
+                    .kind,
+                ErrorKind::UnexpectedEof
+            );
+            assert_eq!(
+                Json::validate(input).unwrap_err().kind,
+                ErrorKind::UnexpectedEof
+            );
+            assert!(Json::parse_lazy(input).is_err());
+            assert!(Json::parse_recovering(input)
+                .1
+                .iter()
+                .any(|error| error.kind == ErrorKind::UnexpectedEof));
+
+            #[cfg(feature = "bytes")]
+            assert_eq!(
+                Json::parse_bytes(bytes::Bytes::copy_from_slice(input))
+                    .map(|_| ())
+                    .map_err(|error| error.kind),
+                Err(ErrorKind::UnexpectedEof)
+            );
+        }
+    }
+
+    // Found by fuzzing: these used to index past the end of the input.
+    let kind = |input: &[u8]| {
+        Json::parse_with(input, &ParseOptions::default())
+            .unwrap_err()
+            .kind
+    };
+
+    for input in [
+        &b"{"[..],
+        b"{\"a\":",
+        b"\"\\u00",
+        b"\"ab\\",
+        b"tr",
+        b"nul",
+        b"1e",
+        b"[1,2e+",
+    ] {
+        assert_eq!(kind(input), ErrorKind::UnexpectedEof);
+    }
+
+    assert_eq!(kind(b"\"\\u00x"), ErrorKind::InvalidUnicodeEscape);
+    assert_eq!(kind(b"[trux"), ErrorKind::InvalidBool);
+}
+
+#[test]
+fn test_strict_numbers() {
+    let options = ParseOptions {
+        strict_numbers: true,
+        ..ParseOptions::default()
+    };
+    let parse = |input: &str| {
+        Json::parse_with(input.as_bytes(), &options).map_err(|error| (error.position, error.kind))
+    };
+
+    assert_eq!(
+        parse("[0,-0,10,1.5,-2.25e-1,3E+2,4e0]"),
+        Ok(Json::ARRAY(vec![
+            Json::NUMBER(0.0),
+            Json::NUMBER(-0.0),
+            Json::NUMBER(10.0),
+            Json::NUMBER(1.5),
+            Json::NUMBER(-0.225),
+            Json::NUMBER(300.0),
+            Json::NUMBER(4.0),
+        ]))
+    );
+
+    // `f64` takes these, the grammar of JSON doesn't.
+    assert_eq!(
+        parse_kind(b"[007]"),
+        Ok(Json::ARRAY(vec![Json::NUMBER(7.0)]))
+    );
+    assert_eq!(parse("[007]"), Err((1, ErrorKind::LeadingZero)));
+    assert_eq!(parse("[-01]"), Err((2, ErrorKind::LeadingZero)));
+    assert_eq!(parse("[1.]"), Err((3, ErrorKind::MissingDigit)));
+    assert_eq!(parse("[1.e5]"), Err((3, ErrorKind::MissingDigit)));
+    assert_eq!(parse("[1e+]"), Err((4, ErrorKind::MissingDigit)));
+    assert_eq!(parse("[-]"), Err((2, ErrorKind::MissingDigit)));
+    assert_eq!(parse("[- 1]"), Err((2, ErrorKind::MissingDigit)));
+    assert_eq!(parse("[1.2.3]"), Err((4, ErrorKind::InvalidNumber)));
+    assert_eq!(parse("[1x]"), Err((2, ErrorKind::InvalidNumber)));
+    assert_eq!(parse("[1e5.0]"), Err((4, ErrorKind::InvalidNumber)));
+
+    // Cut short, the number isn't wrong yet.
+    assert_eq!(parse("[1."), Err((3, ErrorKind::UnexpectedEof)));
+    assert_eq!(parse("-"), Err((1, ErrorKind::UnexpectedEof)));
+    assert_eq!(parse("1e-"), Err((3, ErrorKind::UnexpectedEof)));
+
+    let error = Json::parse_with(b"{\"a\":[1,00]}", &options).unwrap_err();
+
+    assert_eq!(error.message, "Leading zero in number.");
+    assert_eq!(error.path.as_deref(), Some("a[1]"));
+}
+
+#[test]
+fn test_strict_syntax() {
+    let options = ParseOptions {
+        strict_syntax: true,
+        ..ParseOptions::default()
+    };
+    let parse = |input: &str| {
+        Json::parse_with(input.as_bytes(), &options).map_err(|error| (error.position, error.kind))
+    };
+
+    assert_eq!(
+        parse("{\"a\" : [1, {}], \"b\":\"c\"}"),
+        Ok(Json::parse(b"{\"a\":[1,{}],\"b\":\"c\"}").unwrap())
+    );
+
+    // `parse` takes these, the grammar of JSON doesn't.
+    assert!(Json::parse(b"{\"a\" 1}").is_ok());
+    assert!(Json::parse(b"[1 2]").is_ok());
+    assert_eq!(parse("{\"a\" 1}"), Err((5, ErrorKind::ExpectedColon)));
+    assert_eq!(parse("{\"a\"1}"), Err((4, ErrorKind::ExpectedColon)));
+    assert_eq!(
+        parse("{\"a\":1,\"b\"}"),
+        Err((10, ErrorKind::ExpectedColon))
+    );
+    assert_eq!(parse("{1}"), Err((1, ErrorKind::InvalidObject)));
+    assert_eq!(parse("[1,]"), Err((2, ErrorKind::TrailingComma)));
+    assert_eq!(parse("{\"a\":1,}"), Err((6, ErrorKind::TrailingComma)));
+    assert_eq!(parse("[,1]"), Err((1, ErrorKind::ExtraComma)));
+    assert_eq!(parse("[1,,2]"), Err((3, ErrorKind::ExtraComma)));
+    assert_eq!(parse("[1 2]"), Err((3, ErrorKind::MissingComma)));
+    assert_eq!(
+        parse("{\"a\":1 \"b\":2}"),
+        Err((7, ErrorKind::MissingComma))
+    );
+
+    // A string in an array or on its own needs no `:`.
+    assert_eq!(parse("[\"a\" ]"), Ok(Json::ARRAY(vec![Json::string("a")])));
+
+    let error = Json::parse_with(b"{\"a\":[1 2]}", &options).unwrap_err();
+
+    assert_eq!(error.message, "Missing comma.");
+    assert_eq!(error.path.as_deref(), Some("a[1]"));
+}
+
+#[test]
+fn test_get_ignore_case() {
+    let json = Json::parse(b"{\"Content-Type\":\"text/plain\",\"X-Id\":1,\"x-id\":2}").unwrap();
+
+    assert_eq!(
+        json.get_ignore_case("content-type"),
+        Some(&Json::member(
+            "Content-Type",
+            Json::STRING("text/plain".to_string())
+        ))
+    );
+    assert_eq!(
+        json.get_ignore_case("X-ID"),
+        Some(&Json::member("X-Id", Json::NUMBER(1.0)))
+    );
+    assert_eq!(
+        json.get_ignore_case("x-id"),
+        Some(&Json::member("x-id", Json::NUMBER(2.0)))
+    );
+    assert_eq!(json.get_ignore_case("content_type"), None);
+
+    // Only ASCII letters are matched up to case.
+    let json = Json::parse("{\"\u{c4}\":1}".as_bytes()).unwrap();
+
+    assert_eq!(json.get_ignore_case("\u{e4}"), None);
+    assert!(Json::ARRAY(vec![]).try_get_ignore_case("a").is_err());
+}
+
+#[test]
+fn test_approx_eq() {
+    let json = Json::parse(b"{\"a\":[1.0,{\"b\":2.5}],\"c\":\"d\",\"e\":null}").unwrap();
+    let close =
+        Json::parse(b"{\"a\":[1.0000001,{\"b\":2.4999999}],\"c\":\"d\",\"e\":null}").unwrap();
+
+    assert!(json.approx_eq(&json, 0.0));
+    assert!(json.approx_eq(&close, 1e-6));
+    assert!(close.approx_eq(&json, 1e-6));
+    assert!(!json.approx_eq(&close, 1e-8));
+
+    // Everything but numbers still has to be the same, in the same order.
+    for other in [
+        &b"{\"a\":[1.0,{\"b\":2.5}],\"c\":\"D\",\"e\":null}"[..],
+        b"{\"a\":[1.0,{\"x\":2.5}],\"c\":\"d\",\"e\":null}",
+        b"{\"c\":\"d\",\"a\":[1.0,{\"b\":2.5}],\"e\":null}",
+        b"{\"a\":[1.0,{\"b\":2.5},3],\"c\":\"d\",\"e\":null}",
+        b"{\"a\":[1.0,{\"b\":2.5}],\"c\":\"d\",\"e\":0}",
+    ] {
+        assert!(!json.approx_eq(&Json::parse(other).unwrap(), 1.0));
+    }
+
+    assert!(Json::NUMBER(f64::INFINITY).approx_eq(&Json::NUMBER(f64::INFINITY), 0.0));
+    assert!(!Json::NUMBER(f64::NAN).approx_eq(&Json::NUMBER(f64::NAN), 1.0));
+}
+
+#[test]
+fn test_eq_with() {
+    let eq = |a: &str, b: &str, options: &EqOptions| {
+        Json::eq_with(
+            &Json::parse(a.as_bytes()).unwrap(),
+            &Json::parse(b.as_bytes()).unwrap(),
+            options,
+        )
+    };
+    let keys = EqOptions {
+        ignore_key_order: true,
+        ..EqOptions::default()
+    };
+    let arrays = EqOptions {
+        ignore_array_order: true,
+        ..EqOptions::default()
+    };
+
+    let a = "{\"a\":1,\"b\":{\"c\":[1,2],\"d\":null}}";
+    let b = "{\"b\":{\"d\":null,\"c\":[1,2]},\"a\":1}";
+
+    assert!(eq(a, a, &EqOptions::default()));
+    assert!(!eq(a, b, &EqOptions::default()));
+    assert!(eq(a, b, &keys));
+    assert!(!eq(a, b, &arrays));
+    assert!(!eq(a, "{\"b\":{\"d\":null,\"c\":[2,1]},\"a\":1}", &keys));
+
+    // Duplicates are counted, not just looked for.
+    assert!(eq("[1,2,2]", "[2,1,2]", &arrays));
+    assert!(!eq("[1,1,2]", "[1,2,2]", &arrays));
+    assert!(!eq("[1,2]", "[2,1,1]", &arrays));
+    assert!(!eq("{\"a\":1,\"a\":1}", "{\"a\":1,\"b\":1}", &keys));
+    assert!(eq("[[1,2],[3]]", "[[3],[2,1]]", &arrays));
+
+    // The first match found for 1.0 is the only one for 1.6.
+    let options = EqOptions {
+        epsilon: 0.45,
+        ..arrays
+    };
+
+    assert!(eq("[1.0,1.6]", "[1.4,0.9]", &options));
+    assert!(!eq(
+        "[1.0,1.6]",
+        "[1.4,1.3]",
+        &EqOptions {
+            epsilon: 0.1,
+            ..options
+        }
+    ));
+
+    // Many equal elements, and a chain where every element on the left takes the one on the right
+    // the next one needs, until the last one.
+    let ones = Json::ARRAY(vec![Json::NUMBER(1.0); 1500]);
+
+    assert!(Json::eq_with(&ones, &ones.clone(), &arrays));
+
+    let a = Json::ARRAY((0..2000).rev().map(|n| Json::NUMBER(n as f64)).collect());
+    let b = Json::ARRAY((0..2000).map(|n| Json::NUMBER(n as f64 + 0.5)).collect());
+
+    assert!(Json::eq_with(
+        &a,
+        &b,
+        &EqOptions {
+            epsilon: 0.6,
+            ..arrays
+        }
+    ));
+    assert!(!Json::eq_with(
+        &a,
+        &b,
+        &EqOptions {
+            epsilon: 0.4,
+            ..arrays
+        }
+    ));
+}
+
+#[test]
+fn test_contains_subset() {
+    let json =
+        Json::parse(b"{\"a\":1,\"b\":{\"c\":[1,{\"d\":true,\"e\":\"f\"}],\"g\":null},\"h\":[]}")
+            .unwrap();
+    let contains =
+        |expected: &str| json.contains_subset(&Json::parse(expected.as_bytes()).unwrap());
+
+    assert!(json.contains_subset(&json));
+    assert!(contains("{}"));
+    assert!(contains("{\"h\":[],\"a\":1}"));
+    assert!(contains("{\"b\":{\"g\":null}}"));
+    assert!(contains("{\"b\":{\"c\":[1,{\"e\":\"f\"}]}}"));
+    assert!(contains("{\"b\":{\"c\":[1,{}]}}"));
+
+    assert!(!contains("{\"a\":2}"));
+    assert!(!contains("{\"x\":null}"));
+    assert!(!contains("{\"b\":{\"g\":{}}}"));
+    assert!(!contains("{\"b\":{\"c\":[1]}}"));
+    assert!(!contains("{\"b\":{\"c\":[{\"e\":\"f\"},1]}}"));
+    assert!(!contains("{\"b\":{\"c\":[1,{\"e\":\"f\",\"z\":0}]}}"));
+    assert!(!contains("[]"));
+
+    // A name which is there more than once needs just one of them to match.
+    let json = Json::parse(b"{\"a\":1,\"a\":{\"b\":2,\"c\":3}}").unwrap();
+
+    assert!(json.contains_subset(&Json::parse(b"{\"a\":{\"c\":3}}").unwrap()));
+    assert!(Json::NUMBER(1.0).contains_subset(&Json::NUMBER(1.0)));
+    assert!(!Json::NUMBER(1.0).contains_subset(&Json::NULL));
+}
+
+#[test]
+fn test_matches_pattern() {
+    let json = Json::parse(
+        b"{\"id\":\"a1\",\"n\":2.5,\"ok\":true,\"o\":{\"x\":1},\"l\":[1,\"2\"],\"z\":null}",
+    )
+    .unwrap();
+    let matches = |pattern: &str| json.matches_pattern(&Json::parse(pattern.as_bytes()).unwrap());
 
-        value: Box::new(Json::STRING(String::from("Hello, world!"))),
-    });
+    assert!(json.matches_pattern(&json));
+    assert!(matches("\"<<any>>\""));
+    assert!(matches("\"<<any_object>>\""));
+    assert!(matches(
+        "{\"id\":\"<<any_string>>\",\"n\":\"<<any_number>>\",\"ok\":\"<<any_bool>>\",\
+         \"o\":\"<<any_object>>\",\"l\":\"<<any_array>>\",\"z\":\"<<any>>\"}"
+    ));
+    assert!(matches(
+        "{\"id\":\"a1\",\"n\":2.5,\"ok\":true,\"o\":{\"x\":\"<<any>>\"},\
+         \"l\":[\"<<any_number>>\",\"<<any_string>>\"],\"z\":null}"
+    ));
 
-    match json.get_mut("Greeting") {
-        Some(json) => match json {
-            Json::OBJECT { name: _, value } => match value.unbox_mut() {
-                Json::STRING(val) => {
-                    assert_eq!("Hello, world!", val);
+    for kind in ["string", "number", "bool", "object", "array"] {
+        assert!(!matches(&format!(
+            "{{\"id\":\"a1\",\"n\":2.5,\"ok\":true,\"o\":{{\"x\":1}},\"l\":[1,\"2\"],\
+             \"z\":\"<<any_{}>>\"}}",
+            kind
+        )));
+    }
 
-                    val.push_str(" How are you?");
+    // Everything else still has to be there, as it is.
+    assert!(!matches("{\"id\":\"<<any>>\"}"));
+    assert!(!matches("\"<<any_array>>\""));
+    assert!(!Json::STRING("x".to_string()).matches_pattern(&Json::STRING("<<other>>".to_string())));
+    assert!(Json::STRING("<<other>>".to_string())
+        .matches_pattern(&Json::STRING("<<other>>".to_string())));
+}
 
-                    assert_eq!("Hello, world! How are you?", val);
-                }
-                _ => {
-                    panic!("Expected `Json::STRING`!!!");
-                }
-            },
-            _ => {
-                panic!("Expected `Json::OBJECT`!!!");
-            }
-        },
-        None => {
-            panic!("Not found!!!");
+#[test]
+fn test_hash_canonical() {
+    let hash = |input: &str| Json::parse(input.as_bytes()).unwrap().hash_canonical();
+
+    // The same value, written differently.
+    assert_eq!(
+        hash("{\"a\":1,\"b\":[{\"c\":null,\"d\":\"x\"}],\"e\":0}"),
+        hash("{\"e\":0,\"b\":[{\"d\":\"x\",\"c\":null}],\"a\":1.00}")
+    );
+    assert_eq!(hash("[1e2]"), hash("[100]"));
+    assert_eq!(
+        Json::NUMBER(-0.0).hash_canonical(),
+        Json::NUMBER(0.0).hash_canonical()
+    );
+
+    let distinct = [
+        "null",
+        "true",
+        "false",
+        "0",
+        "1",
+        "\"\"",
+        "\"0\"",
+        "[]",
+        "{}",
+        "[[]]",
+        "[null]",
+        "[0,1]",
+        "[1,0]",
+        "[\"ab\",\"c\"]",
+        "[\"a\",\"bc\"]",
+        "{\"a\":0}",
+        "{\"b\":0}",
+        "{\"a\":\"0\"}",
+        "{\"a\":1,\"a\":2}",
+        "{\"a\":2,\"a\":1}",
+        "\"a\":0",
+    ];
+
+    for (n, a) in distinct.iter().enumerate() {
+        for b in &distinct[n + 1..] {
+            assert_ne!(hash(a), hash(b), "{} {}", a, b);
         }
     }
+
+    // Unlike `DefaultHasher`, it doesn't change between versions.
+    assert_eq!(hash("null"), 0xd228_cb69_661a_8caf_7891_2b70_4e4a_7e51);
+}
+
+#[test]
+fn test_hash_tree() {
+    let json = Json::parse(b"{\"a\":[1,{\"b\":null}],\"c\":\"d\"}").unwrap();
+    let tree = json.hash_tree();
+
+    assert_eq!(tree.hash, json.hash_canonical());
+    assert_eq!(tree.children.len(), 2);
+    assert_eq!(
+        tree.children[0].children[0].children[1].hash,
+        Json::parse(b"{\"b\":null}").unwrap().hash_canonical()
+    );
+    assert_eq!(
+        tree.children[1],
+        HashTree {
+            hash: Json::member("c", Json::STRING("d".to_string())).hash_canonical(),
+            children: vec![HashTree {
+                hash: Json::STRING("d".to_string()).hash_canonical(),
+                children: vec![],
+            }],
+        }
+    );
+}
+
+#[test]
+fn test_diff_hashed() {
+    let pairs = [
+        (
+            "{\"a\":1,\"b\":[1,2,{\"c\":3}]}",
+            "{\"a\":1,\"b\":[1,2,{\"c\":4}]}",
+        ),
+        ("{\"a\":1,\"b\":[1,2]}", "{\"b\":[1,2,3],\"x\":true}"),
+        (
+            "[{\"a\":[]},{\"b\":{}}]",
+            "[{\"a\":[]},{\"b\":{\"c\":null}}]",
+        ),
+        ("\"a\":{\"b\":1}", "\"a\":{\"b\":2}"),
+        ("[1,[2,[3]]]", "[1,[2,[3]]]"),
+        ("{\"a\":1,\"a\":2}", "{\"a\":1,\"a\":3}"),
+    ];
+
+    for (a, b) in pairs.iter() {
+        let a = Json::parse(a.as_bytes()).unwrap();
+        let b = Json::parse(b.as_bytes()).unwrap();
+
+        assert_eq!(
+            Json::diff_hashed(&a, &a.hash_tree(), &b, &b.hash_tree()),
+            Json::diff(&a, &b)
+        );
+    }
+
+    // Values with the same digests aren't looked at, so with the same ones on both sides nothing
+    // is found.
+    let a = Json::parse(b"{\"a\":[1,2]}").unwrap();
+    let b = Json::parse(b"{\"a\":[1,3]}").unwrap();
+    let tree = a.hash_tree();
+
+    assert_eq!(Json::diff_hashed(&a, &tree, &b, &tree), vec![]);
+    assert_eq!(Json::diff_hashed(&a, &tree, &b, &b.hash_tree()).len(), 1);
+}
+
+#[test]
+fn test_normalize() {
+    let normalize = |input: &str, options: NormalizeOptions| {
+        let mut json = Json::parse(input.as_bytes()).unwrap();
+
+        json.normalize(options);
+        json.print()
+    };
+    let input = "{\"b\":1,\"a\":[{\"y\":0,\"x\":1}],\"b\":2,\"c\":3,\"b\":4}";
+
+    assert_eq!(
+        normalize(input, NormalizeOptions::default()),
+        "{\"a\":[{\"x\":1,\"y\":0}],\"b\":4,\"c\":3}"
+    );
+    assert_eq!(
+        normalize(
+            input,
+            NormalizeOptions {
+                duplicate_keys: DuplicateKeys::First,
+                ..NormalizeOptions::default()
+            }
+        ),
+        "{\"a\":[{\"x\":1,\"y\":0}],\"b\":1,\"c\":3}"
+    );
+    assert_eq!(
+        normalize(
+            input,
+            NormalizeOptions {
+                sort_keys: false,
+                ..NormalizeOptions::default()
+            }
+        ),
+        "{\"a\":[{\"y\":0,\"x\":1}],\"c\":3,\"b\":4}"
+    );
+    assert_eq!(
+        normalize(
+            input,
+            NormalizeOptions {
+                duplicate_keys: DuplicateKeys::Keep,
+                ..NormalizeOptions::default()
+            }
+        ),
+        "{\"a\":[{\"x\":1,\"y\":0}],\"b\":1,\"b\":2,\"b\":4,\"c\":3}"
+    );
+
+    let mut json = Json::ARRAY(vec![
+        Json::NUMBER(-0.0),
+        Json::member("a", Json::NUMBER(-0.0)),
+    ]);
+
+    json.normalize(NormalizeOptions::default());
+
+    assert_eq!(json.print(), "[0,\"a\":0]");
+}
+
+#[cfg(feature = "unicode-normalization")]
+#[test]
+fn test_normalize_nfc() {
+    // "é" once precomposed and once as "e" with a combining acute accent.
+    let mut json =
+        Json::parse("{\"caf\u{e9}\":1,\"cafe\u{301}\":[\"cafe\u{301}\"]}".as_bytes()).unwrap();
+
+    json.normalize(NormalizeOptions::default());
+
+    assert_eq!(
+        json,
+        Json::JSON(vec![Json::member(
+            "caf\u{e9}",
+            Json::ARRAY(vec![Json::STRING("caf\u{e9}".to_string())])
+        )])
+    );
+
+    let mut json = Json::STRING("cafe\u{301}".to_string());
+
+    json.normalize(NormalizeOptions {
+        nfc: false,
+        ..NormalizeOptions::default()
+    });
+
+    assert_eq!(json, Json::STRING("cafe\u{301}".to_string()));
+}
+
+#[cfg(not(feature = "unicode-normalization"))]
+#[test]
+fn test_normalize_nfc() {
+    let mut json = Json::STRING("cafe\u{301}".to_string());
+
+    json.normalize(NormalizeOptions {
+        nfc: true,
+        ..NormalizeOptions::default()
+    });
+
+    assert_eq!(json, Json::STRING("cafe\u{301}".to_string()));
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn test_schema() {
+    let schema = Json::parse(
+        b"{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"type\":\"object\",\
+          \"required\":[\"id\",\"name\"],\"properties\":{\
+          \"id\":{\"type\":\"integer\",\"minimum\":1},\
+          \"name\":{\"type\":\"string\",\"minLength\":2,\"maxLength\":4,\"pattern\":\"^[a-z]+$\"},\
+          \"score\":{\"type\":[\"number\",\"null\"],\"exclusiveMinimum\":0,\"exclusiveMaximum\":1},\
+          \"tags\":{\"type\":\"array\",\"minItems\":1,\"maxItems\":2,\
+          \"items\":{\"enum\":[\"a\",{\"b\":1,\"c\":2}]}},\
+          \"extra\":false,\"any\":true}}",
+    )
+    .unwrap();
+    let schema = Schema::compile(&schema).unwrap();
+    let violations = |input: &str| -> Vec<(String, &'static str)> {
+        schema
+            .validate(&Json::parse(input.as_bytes()).unwrap())
+            .into_iter()
+            .map(|violation| (violation.path, violation.keyword))
+            .collect()
+    };
+
+    assert!(violations(
+        "{\"id\":1,\"name\":\"abc\",\"score\":0.5,\"tags\":[\"a\",{\"c\":2,\"b\":1}],\"any\":[]}"
+    )
+    .is_empty());
+    assert!(violations("{\"id\":2,\"name\":\"ab\",\"score\":null,\"other\":1}").is_empty());
+
+    let expected = |violations: &[(&str, &'static str)]| -> Vec<(String, &'static str)> {
+        violations
+            .iter()
+            .map(|(path, keyword)| (path.to_string(), *keyword))
+            .collect()
+    };
+
+    assert_eq!(violations("[]"), expected(&[("", "type")]));
+    assert_eq!(
+        violations("{\"name\":\"ab\"}"),
+        expected(&[("", "required")])
+    );
+    assert_eq!(
+        violations("{\"id\":1.5,\"name\":\"ABCDE\",\"score\":1,\"tags\":[],\"extra\":null}"),
+        expected(&[
+            ("/id", "type"),
+            ("/name", "maxLength"),
+            ("/name", "pattern"),
+            ("/score", "exclusiveMaximum"),
+            ("/tags", "minItems"),
+            ("/extra", "false"),
+        ])
+    );
+    assert_eq!(
+        violations("{\"id\":0,\"name\":\"a\",\"score\":0,\"tags\":[\"b\",{\"b\":1},\"a\"]}"),
+        expected(&[
+            ("/id", "minimum"),
+            ("/name", "minLength"),
+            ("/score", "exclusiveMinimum"),
+            ("/tags", "maxItems"),
+            ("/tags/0", "enum"),
+            ("/tags/1", "enum"),
+        ])
+    );
+
+    let json = Json::parse("{\"id\":0,\"name\":\"\u{e9}\u{e9}\"}".as_bytes()).unwrap();
+    let violation = schema.validate(&json);
+
+    assert_eq!(
+        violation[0].to_string(),
+        "`/id`: expected at least 1 but found 0"
+    );
+    assert_eq!(
+        violation[1].to_string(),
+        "`/name`: expected to match `^[a-z]+$` but found \"\u{e9}\u{e9}\""
+    );
+    assert_eq!(
+        Schema::compile(&Json::BOOL(false))
+            .unwrap()
+            .validate(&Json::NULL)[0]
+            .to_string(),
+        "``: expected nothing"
+    );
+
+    // Schemas which don't make sense.
+    let error = |input: &str| {
+        let error = Schema::compile(&Json::parse(input.as_bytes()).unwrap()).unwrap_err();
+
+        (error.path, error.message)
+    };
+
+    assert_eq!(
+        error("{\"properties\":{\"a/b\":{\"type\":\"text\"}}}"),
+        (
+            "/properties/a~1b/type".to_string(),
+            "expected the name of a type".to_string()
+        )
+    );
+    assert_eq!(
+        error("{\"items\":{\"pattern\":\"(\"}}"),
+        (
+            "/items/pattern".to_string(),
+            "expected a regular expression".to_string()
+        )
+    );
+    assert_eq!(
+        error("{\"minItems\":1.5}"),
+        (
+            "/minItems".to_string(),
+            "expected a non-negative integer".to_string()
+        )
+    );
+    assert_eq!(
+        error("{\"required\":[\"a\",1]}"),
+        (
+            "/required".to_string(),
+            "expected an array of strings".to_string()
+        )
+    );
+    assert_eq!(
+        error("[]"),
+        (String::new(), "expected an object or a bool".to_string())
+    );
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn test_parse_with_schema() {
+    let schema = Json::parse(
+        b"{\"type\":\"object\",\"required\":[\"id\"],\"properties\":{\
+          \"id\":{\"type\":\"integer\",\"minimum\":1},\
+          \"tags\":{\"type\":\"array\",\"items\":{\"type\":\"string\"}},\
+          \"extra\":false}}",
+    )
+    .unwrap();
+    let schema = Schema::compile(&schema).unwrap();
+    let options = ParseOptions::default();
+    let parse = |input: &str| {
+        Json::parse_with_schema(input.as_bytes(), &options, &schema)
+            .map_err(|error| (error.kind, error.position, error.path))
+    };
+
+    let input = "{\"id\":1,\"tags\":[\"a\",\"b\"],\"other\":{\"x\":[1]}}";
+
+    assert_eq!(parse(input), Ok(Json::parse(input.as_bytes()).unwrap()));
+
+    // At the value which doesn't conform, before the rest is parsed.
+    let violation = |position, path: Option<&str>| {
+        Err((
+            ErrorKind::SchemaViolation,
+            position,
+            path.map(str::to_string),
+        ))
+    };
+
+    assert_eq!(parse("{\"id\":0,\"tags\":[nul"), violation(6, Some("id")));
+    assert_eq!(
+        parse("{\"id\":1,\"tags\":[\"a\",2,"),
+        violation(20, Some("tags[1]"))
+    );
+    assert_eq!(
+        parse("{\"id\":1,\"extra\":null}"),
+        violation(16, Some("extra"))
+    );
+    // Objects and arrays of the wrong type as soon as they start.
+    assert_eq!(
+        parse("{\"id\":1,\"tags\":{\"a\":[nul"),
+        violation(15, Some("tags"))
+    );
+    assert_eq!(parse("[1,2,3"), violation(0, None));
+    // Objects missing a member at their closing bracket.
+    assert_eq!(parse("{\"tags\":[]}"), violation(10, None));
+    // Errors which aren't about the schema still come first.
+    assert_eq!(
+        parse("{\"id\":1,\"tags\":[\"a\",").unwrap_err().0,
+        ErrorKind::UnexpectedEof
+    );
+    // As `Schema::validate` would have it.
+    let json = Json::parse(b"{\"id\":1.5}").unwrap();
+
+    assert_eq!(schema.is_valid(&json), parse("{\"id\":1.5}").is_ok());
+}
+
+#[cfg(feature = "schema")]
+#[test]
+fn test_validate_events() {
+    let schema = Json::parse(
+        b"{\"type\":\"object\",\"required\":[\"id\",\"name\"],\"properties\":{\
+          \"id\":{\"type\":\"integer\",\"minimum\":1},\
+          \"name\":{\"type\":\"string\",\"minLength\":2,\"pattern\":\"^[a-z]+$\"},\
+          \"a/b\":{\"type\":\"null\"},\
+          \"tags\":{\"type\":\"array\",\"minItems\":1,\"maxItems\":2,\
+          \"items\":{\"enum\":[\"a\",{\"b\":1,\"c\":[2,{\"d\":3}]}]}},\
+          \"nested\":{\"properties\":{\"deep\":{\"items\":{\"required\":[\"x\"]}}}},\
+          \"extra\":false}}",
+    )
+    .unwrap();
+    let schema = Schema::compile(&schema).unwrap();
+
+    // The same violations as `validate` finds, those of objects and arrays at their end.
+    for input in &[
+        "{\"id\":1,\"name\":\"ab\",\"tags\":[\"a\",{\"c\":[2,{\"d\":3}],\"b\":1}]}",
+        "{\"id\":0,\"name\":\"A\",\"tags\":[],\"extra\":{\"a\":[1]},\"a/b\":1}",
+        "{\"id\":1.5,\"tags\":[\"b\",{\"b\":1,\"c\":[2,{\"d\":4}]},{\"b\":1}]}",
+        "{\"name\":[1],\"tags\":{\"a\":1},\"nested\":{\"deep\":[{\"x\":1},{\"y\":{\"x\":1}},2]}}",
+        "{\"id\":2,\"name\":\"xy\",\"tags\":[\"a\",\"c\":{\"b\":1}],\"other\":{\"id\":0}}",
+        "[{\"id\":0}]",
+        "\"id\":{\"id\":0}",
+    ] {
+        let mut expected = schema.validate(&Json::parse(input.as_bytes()).unwrap());
+        let mut violations = schema
+            .validate_events(JsonLexer::new(input.as_bytes()))
+            .unwrap();
+
+        expected.sort_by(|a, b| (&a.path, a.keyword).cmp(&(&b.path, b.keyword)));
+        violations.sort_by(|a, b| (&a.path, a.keyword).cmp(&(&b.path, b.keyword)));
+
+        assert_eq!(violations, expected, "{}", input);
+    }
+
+    let input = b"{\"tags\":[\"a\",\"b\"],\"id\":\"1\"}";
+    let violations: Vec<(String, &'static str)> = schema
+        .validate_events(JsonLexer::new(input))
+        .unwrap()
+        .into_iter()
+        .map(|violation| (violation.path, violation.keyword))
+        .collect();
+
+    assert_eq!(
+        violations,
+        vec![
+            ("/tags/1".to_string(), "enum"),
+            ("/id".to_string(), "type"),
+            ("".to_string(), "required"),
+        ]
+    );
+    assert_eq!(
+        schema
+            .validate_events(JsonLexer::new(b"{\"id\":0,\"tags\":["))
+            .unwrap_err()
+            .kind,
+        ErrorKind::UnexpectedEof
+    );
+
+    // Events which don't follow those before are ignored.
+    let mut validator = schema.validator();
+
+    assert!(validator.feed(&JsonEvent::ArrayEnd).is_empty());
+    assert_eq!(validator.feed(&JsonEvent::Null)[0].keyword, "type");
+}
+
+#[test]
+fn test_get_index() {
+    let mut json = Json::parse(b"{\"name\":\"app\",1,\"version\":2,\"debug\":true}").unwrap();
+
+    // Unnamed values don't count.
+    assert_eq!(
+        json.get_index(0),
+        Some(("name", &Json::STRING(String::from("app"))))
+    );
+    assert_eq!(json.get_index(1), Some(("version", &Json::NUMBER(2.0))));
+    assert_eq!(json.get_index(2), Some(("debug", &Json::BOOL(true))));
+    assert_eq!(json.get_index(3), None);
+    assert_eq!(json.first(), json.get_index(0));
+    assert_eq!(json.last(), json.get_index(2));
+
+    if let Some((name, value)) = json.get_index_mut(1) {
+        assert_eq!(name, "version");
+        *value = Json::NUMBER(3.0);
+    }
+
+    assert_eq!(json.value_of("version"), Some(&Json::NUMBER(3.0)));
+
+    // Through a `Json::OBJECT`, and nothing for other variants.
+    let member = Json::member("config", json);
+
+    assert_eq!(member.first().map(|(name, _)| name), Some("name"));
+    assert_eq!(Json::JSON(Vec::new()).first(), None);
+    assert_eq!(Json::ARRAY(vec![Json::NULL]).last(), None);
+    assert_eq!(Json::NULL.get_index(0), None);
+}
+
+#[test]
+fn test_insert_at() {
+    let mut json = Json::parse(b"{\"b\":2,3,\"d\":4}").unwrap();
+
+    assert_eq!(json.insert_at(0, "a", Json::number(1.0)), None);
+    assert_eq!(json.insert_at(2, "c", Json::number(3.0)), None);
+    assert_eq!(json.insert_at(4, "e", Json::number(5.0)), None);
+    // Unnamed values are skipped over when counting.
+    assert_eq!(json.print(), "{\"a\":1,\"b\":2,3,\"c\":3,\"d\":4,\"e\":5}");
+
+    // An existing member moves.
+    assert_eq!(json.insert_at(0, "e", Json::NULL), Some(Json::number(5.0)));
+    assert_eq!(
+        json.keys().collect::<Vec<&str>>(),
+        vec!["e", "a", "b", "c", "d"]
+    );
+    assert_eq!(json.insert_at(4, "e", Json::BOOL(true)), Some(Json::NULL));
+    assert_eq!(json.last(), Some(("e", &Json::BOOL(true))));
+
+    // Through a `Json::OBJECT`.
+    let mut member = Json::member("config", Json::new());
+
+    member.insert_at(0, "name", Json::string("app"));
+    assert_eq!(member.print(), "\"config\":{\"name\":\"app\"}");
+
+    assert_eq!(
+        Json::array()
+            .try_insert_at(0, "a", Json::NULL)
+            .unwrap_err()
+            .found,
+        "Json::ARRAY"
+    );
+
+    // Past the end, without changing anything before.
+    let mut json = Json::parse(b"{\"a\":1,\"b\":2}").unwrap();
+
+    assert_eq!(
+        json.try_insert_at(5, "a", Json::NULL),
+        Ok(Some(Json::number(1.0)))
+    );
+    assert_eq!(json.print(), "{\"b\":2,\"a\":null}");
+
+    let caught = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        json.insert_at(2, "b", Json::NULL);
+    }));
+
+    assert!(caught.is_err());
+    assert_eq!(json.print(), "{\"b\":2,\"a\":null}");
+}
+
+#[test]
+#[should_panic]
+fn test_insert_at_panics() {
+    let mut json = Json::parse(b"{\"a\":1}").unwrap();
+
+    json.insert_at(2, "b", Json::NULL);
+}
+
+#[test]
+fn test_move_key() {
+    let mut json = Json::parse(b"{\"a\":1,2,\"b\":3,\"c\":4,\"a\":5}").unwrap();
+
+    assert!(json.move_key("c", 0));
+    assert_eq!(json.print(), "{\"c\":4,\"a\":1,2,\"b\":3,\"a\":5}");
+    // The first of the same name, counting only members.
+    assert!(json.move_key("a", 3));
+    assert_eq!(json.print(), "{\"c\":4,2,\"b\":3,\"a\":5,\"a\":1}");
+    assert!(json.move_key("b", 1));
+    assert_eq!(json.print(), "{\"c\":4,2,\"b\":3,\"a\":5,\"a\":1}");
+    assert!(!json.move_key("d", 0));
+
+    assert_eq!(
+        Json::NULL.try_move_key("a", 0).unwrap_err().found,
+        "Json::NULL"
+    );
+
+    // Out of range, leaving everything where it is.
+    assert_eq!(json.try_move_key("c", 4), Ok(false));
+    assert_eq!(json.try_move_key("d", 4), Ok(false));
+    assert_eq!(json.print(), "{\"c\":4,2,\"b\":3,\"a\":5,\"a\":1}");
+    assert_eq!(json.try_move_key("c", 3), Ok(true));
+    assert_eq!(json.print(), "{2,\"b\":3,\"a\":5,\"a\":1,\"c\":4}");
+}
+
+#[test]
+#[should_panic]
+fn test_move_key_panics() {
+    let mut json = Json::parse(b"{\"a\":1,\"b\":2}").unwrap();
+
+    json.move_key("a", 2);
+}
+
+#[test]
+fn test_sort_keys_by() {
+    let mut json = Json::parse(b"{\"b\":1,3,\"C\":{\"z\":1,\"y\":2},\"a\":2,\"B\":4}").unwrap();
+
+    // Stable, unnamed values behind, and objects inside left as they are.
+    json.sort_keys_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+    assert_eq!(
+        json.print(),
+        "{\"a\":2,\"b\":1,\"B\":4,\"C\":{\"z\":1,\"y\":2},3}"
+    );
+
+    json.sort_keys_by(|a, b| b.cmp(a));
+    assert_eq!(json.keys().collect::<Vec<&str>>(), vec!["b", "a", "C", "B"]);
+
+    let mut member = Json::member("m", Json::parse(b"{\"b\":1,\"a\":2}").unwrap());
+
+    member.sort_keys_by(|a, b| a.cmp(b));
+    assert_eq!(member.first().map(|(name, _)| name), Some("a"));
+    assert!(Json::array().try_sort_keys_by(|a, b| a.cmp(b)).is_err());
+}
+
+#[test]
+fn test_pointer_buf() {
+    use crate::pointer::{self, PointerBuf};
+
+    for token in &["", "a", "a/b", "~", "~1", "/~0/", "x~01"] {
+        assert_eq!(&pointer::unescape(&pointer::escape(token)), token);
+    }
+
+    assert_eq!(pointer::escape("~1"), "~01");
+    assert_eq!(pointer::unescape("~01"), "~1");
+
+    let json = Json::parse(b"{\"a/b\":{\"~\":[0,{\"\":true}]}}").unwrap();
+    let mut path = PointerBuf::new();
+
+    assert_eq!(json.pointer(path.as_str()), Some(&json));
+    assert_eq!(path.pop(), None);
+
+    path.push("a/b");
+    path.push("~");
+    path.push_index(1);
+    path.push("");
+
+    assert_eq!(path.to_string(), "/a~1b/~0/1/");
+    assert_eq!(json.pointer(path.as_ref()), Some(&Json::BOOL(true)));
+    assert_eq!(path.tokens(), vec!["a/b", "~", "1", ""]);
+    assert_eq!(path.pop(), Some(String::new()));
+    assert_eq!(path.pop(), Some(String::from("1")));
+    assert_eq!(path.pop(), Some(String::from("~")));
+    assert_eq!(String::from(path.clone()), "/a~1b");
+    assert_eq!(path.pop(), Some(String::from("a/b")));
+    assert_eq!(path, PointerBuf::new());
+}
+
+#[test]
+fn test_get_glob() {
+    let json = Json::parse(
+        b"{\"a\":{\"b\":1,\"bc\":2,\"x.y\":3,\"*\":4},\
+          \"list\":[{\"b\":5},6,{\"c\":{\"b\":7}}],\"b\":8}",
+    )
+    .unwrap();
+    let glob = |pattern| -> Vec<String> {
+        json.get_glob(pattern)
+            .into_iter()
+            .map(Json::print)
+            .collect()
+    };
+
+    assert_eq!(glob(""), vec![json.print()]);
+    assert_eq!(glob("a.b"), vec!["1"]);
+    assert_eq!(glob("a.b*"), vec!["1", "2"]);
+    assert_eq!(glob("a.?c"), vec!["2"]);
+    assert_eq!(glob("a.*"), vec!["1", "2", "3", "4"]);
+    assert_eq!(glob("a.x\\.y"), vec!["3"]);
+    assert_eq!(glob("a.\\*"), vec!["4"]);
+    assert_eq!(glob("a.*.b"), Vec::<String>::new());
+    // Indexes, with or without brackets.
+    assert_eq!(glob("list.1"), vec!["6"]);
+    assert_eq!(glob("list[*].b"), vec!["5"]);
+    assert_eq!(glob("list[1?]"), Vec::<String>::new());
+    // Any number of levels, each value once.
+    assert_eq!(glob("**.b"), vec!["1", "5", "7", "8"]);
+    assert_eq!(glob("**.**.b"), vec!["1", "5", "7", "8"]);
+    assert_eq!(glob("**.c.**.b"), vec!["7"]);
+    assert_eq!(glob("list.**"), glob("list.**.**"));
+    // `list` itself, and the six values inside it.
+    assert_eq!(glob("list.**").len(), 7);
+
+    // Through a `Json::OBJECT`, and nothing below other variants.
+    let member = Json::member("m", json.clone());
+
+    assert_eq!(member.get_glob("a.bc"), vec![&Json::NUMBER(2.0)]);
+    assert!(Json::NULL.get_glob("*").is_empty());
+}
+
+#[test]
+fn test_select_all_mut() {
+    let mut json = Json::parse(
+        b"{\"a\":{\"enabled\":true,\"b\":{\"enabled\":true}},\
+          \"list\":[{\"enabled\":1},2],\"enabled\":null}",
+    )
+    .unwrap();
+    let mut seen = Vec::new();
+
+    let count = json.select_all_mut("**.enabled", |enabled| {
+        seen.push(enabled.print());
+        *enabled = Json::BOOL(false);
+    });
+
+    assert_eq!(count, 4);
+    assert_eq!(seen, vec!["true", "true", "1", "null"]);
+    assert_eq!(
+        json.print(),
+        "{\"a\":{\"enabled\":false,\"b\":{\"enabled\":false}},\"list\":[{\"enabled\":false},2],\
+         \"enabled\":false}"
+    );
+
+    // What is inside the changed value is matched.
+    let count = json.select_all_mut("list.**", |value| {
+        if let Json::NUMBER(_) = value {
+            *value = Json::parse(b"{\"x\":\"1\"}").unwrap();
+        }
+    });
+
+    assert_eq!(count, 5);
+    assert_eq!(json.get_glob("list[1].x"), vec![&Json::string("1")]);
+    assert_eq!(json.select_all_mut("missing.*", |_| unreachable!()), 0);
+}
+
+#[test]
+fn test_json_cursor() {
+    let json = Json::parse(b"{\"a\":[1,{\"b/c\":true}],2,\"d\":{}}").unwrap();
+    let mut cursor = json.cursor();
+
+    assert_eq!(cursor.current(), &json);
+    assert_eq!(
+        (cursor.name(), cursor.index(), cursor.depth()),
+        (None, None, 0)
+    );
+    assert_eq!(cursor.pointer().as_deref(), Some(""));
+    assert!(!cursor.up());
+    assert!(!cursor.next_sibling());
+
+    assert!(cursor.down());
+    assert_eq!(
+        (cursor.name(), cursor.index(), cursor.depth()),
+        (Some("a"), Some(0), 1)
+    );
+    assert!(cursor.down());
+    assert!(!cursor.down());
+    assert_eq!(cursor.current(), &Json::NUMBER(1.0));
+    assert_eq!(cursor.name(), None);
+    assert!(cursor.next_sibling());
+    assert!(cursor.down());
+    assert_eq!(cursor.name(), Some("b/c"));
+    assert_eq!(cursor.pointer().as_deref(), Some("/a/1/b~1c"));
+    assert_eq!(
+        json.pointer(&cursor.pointer().unwrap()),
+        Some(cursor.current())
+    );
+    assert!(!cursor.next_sibling());
+
+    // Back up, and on to an unnamed value, which can't be pointed to.
+    assert!(cursor.up());
+    assert!(cursor.up());
+    assert!(cursor.next_sibling());
+    assert_eq!(cursor.current(), &Json::NUMBER(2.0));
+    assert_eq!(cursor.pointer(), None);
+    assert!(cursor.next_sibling());
+    assert_eq!(cursor.name(), Some("d"));
+    // Empty objects can't be entered.
+    assert!(!cursor.down());
+    assert!(!cursor.next_sibling());
+    assert!(cursor.prev_sibling());
+    assert!(cursor.prev_sibling());
+    assert!(!cursor.prev_sibling());
+    assert_eq!(cursor.index(), Some(0));
+
+    // A `Json::OBJECT` is resolved to its value.
+    let member = Json::member("m", Json::ARRAY(vec![Json::NULL]));
+    let mut cursor = JsonCursor::new(&member);
+
+    assert!(cursor.down());
+    assert_eq!(cursor.pointer().as_deref(), Some("/0"));
+}
+
+#[test]
+fn test_editable_json() {
+    let original = Json::parse(b"{\"a\":1,\"b\":[1,2,3],\"c\":{\"d\":true}}").unwrap();
+    let mut json = EditableJson::new(original.clone());
+
+    assert!(!json.can_undo() && !json.can_redo());
+    assert!(!json.undo());
+
+    // Removing and undoing keeps the order of members.
+    assert_eq!(json.remove("/a"), Ok(Json::NUMBER(1.0)));
+    assert!(json.undo());
+    assert_eq!(json.json(), &original);
+
+    // Adding to an existing member replaces its value in place.
+    json.add("/a", Json::string("x")).unwrap();
+    json.add("/b/0", Json::NULL).unwrap();
+    json.add("/b/-", Json::BOOL(false)).unwrap();
+    json.add("/c/e", Json::array()).unwrap();
+    assert_eq!(json.remove("/b/2"), Ok(Json::NUMBER(2.0)));
+    assert_eq!(json.replace("/c/d", Json::NULL), Ok(Json::BOOL(true)));
+
+    let edited = "{\"a\":\"x\",\"b\":[null,1,3,false],\"c\":{\"d\":null,\"e\":[]}}";
+
+    assert_eq!(json.json().print(), edited);
+
+    // The patch turns the original into the same.
+    let mut replayed = EditableJson::new(original.clone());
+
+    replayed.apply_patch(&json.to_patch()).unwrap();
+    assert_eq!(replayed.json().print(), edited);
+    assert_eq!(json.to_patch().as_array().map(<[Json]>::len), Some(6));
+
+    // One undo for all of a patch.
+    assert!(replayed.undo());
+    assert_eq!(replayed.json(), &original);
+    assert!(!replayed.undo());
+    assert!(replayed.redo());
+    assert_eq!(replayed.json().print(), edited);
+
+    while json.undo() {}
+    assert_eq!(json.json(), &original);
+    assert!(json.redo() && json.redo());
+    assert_eq!(
+        json.json().print(),
+        "{\"a\":\"x\",\"b\":[null,1,2,3],\"c\":{\"d\":true}}"
+    );
+
+    // A new change can't be followed by what was undone.
+    json.replace("", Json::NULL).unwrap();
+    assert!(!json.can_redo());
+    assert_eq!(json.json(), &Json::NULL);
+    assert!(json.undo());
+
+    // Errors, and nothing changed by a patch which fails.
+    let error = |json: &mut EditableJson, patch: &str| {
+        let before = json.json().clone();
+        let error = json
+            .apply_patch(&Json::parse(patch.as_bytes()).unwrap())
+            .unwrap_err();
+
+        assert_eq!(json.json(), &before);
+
+        (error.path, error.message)
+    };
+    let expected = |path: &str, message: &str| (path.to_string(), message.to_string());
+
+    assert_eq!(
+        error(
+            &mut json,
+            "[{\"op\":\"add\",\"path\":\"/b/5\",\"value\":1}]"
+        ),
+        expected("/b/5", "no such index to add at")
+    );
+    assert_eq!(
+        error(
+            &mut json,
+            "[{\"op\":\"remove\",\"path\":\"/a\"},{\"op\":\"add\",\"path\":\"/x/y\",\"value\":1}]"
+        ),
+        expected("/x/y", "no object or array to add to")
+    );
+    assert_eq!(
+        error(
+            &mut json,
+            "[{\"op\":\"move\",\"from\":\"/c\",\"path\":\"/c/f\"}]"
+        ),
+        expected("/c/f", "can't move a value into itself")
+    );
+    assert_eq!(
+        error(
+            &mut json,
+            "[{\"op\":\"replace\",\"path\":\"/b/01\",\"value\":1}]"
+        ),
+        expected("/b/01", "nothing to replace")
+    );
+    assert_eq!(
+        error(&mut json, "[{\"op\":\"copy\",\"path\":\"/z\"}]"),
+        expected("/z", "expected a `from`")
+    );
+    assert_eq!(
+        error(&mut json, "[{\"op\":\"append\",\"path\":\"/z\"}]"),
+        expected("/z", "expected an `op` of a JSON Patch")
+    );
+    assert_eq!(
+        error(&mut json, "[1]"),
+        expected("", "expected an operation with a `path`")
+    );
+    assert_eq!(
+        error(&mut json, "{}"),
+        expected("", "expected an array of operations")
+    );
+    assert_eq!(
+        json.remove("").unwrap_err().message,
+        "can't remove the whole value"
+    );
+
+    // Moving within an array counts indexes after the removal.
+    json.apply_patch(
+        &Json::parse(
+            b"[{\"op\":\"move\",\"from\":\"/b/0\",\"path\":\"/b/3\"},\
+              {\"op\":\"test\",\"path\":\"/b\",\"value\":[1,2,3,null]}]",
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert!(json.undo());
+    assert_eq!(
+        json.into_inner().print(),
+        "{\"a\":\"x\",\"b\":[null,1,2,3],\"c\":{\"d\":true}}"
+    );
 }
 
 #[test]
@@ -133,7 +4816,7 @@ fn test_parse_bool() {
     match Json::parse_bool(b"true", &mut incr) {
         Ok(json) => match json {
             Json::BOOL(val) => {
-                assert_eq!(val, true);
+                assert!(val);
             }
             json => {
                 panic!("Expected Json::BOOL but found {:?}", json);
@@ -149,7 +4832,7 @@ fn test_parse_bool() {
     match Json::parse_bool(b"false", &mut incr) {
         Ok(json) => match json {
             Json::BOOL(val) => {
-                assert_eq!(val, false);
+                assert!(!val);
             }
             json => {
                 panic!("Expected Json::BOOL but found {:?}", json);
@@ -182,7 +4865,7 @@ fn test_parse_null() {
 fn test_parse_array() {
     let mut incr: usize = 0;
 
-    match Json::parse_array(
+    match Json::parse_value(
         b"[1,\"two\",true,[\"array\",[\"another one\",[\"another one\",1.5]]]]",
         &mut incr,
     ) {
@@ -204,7 +4887,7 @@ fn test_parse_array() {
 fn test_parse_json() {
     let mut incr: usize = 0;
 
-    match Json::parse_json(b"{\"on\",\"off\"}", &mut incr) {
+    match Json::parse_value(b"{\"on\",\"off\"}", &mut incr) {
         Ok(json) => match json {
             Json::JSON(vals) => {
                 assert_eq!(vals.len(), 2);
@@ -223,7 +4906,7 @@ fn test_parse_json() {
 fn test_parse_json_2() {
     let mut incr: usize = 0;
 
-    match Json::parse_json(
+    match Json::parse_value(
         b"{\"on\",\"off\",\"OBJECT\":{\"ARRAY\":[\"on\",\"off\"]},\"on or off?\"}",
         &mut incr,
     ) {
@@ -245,7 +4928,7 @@ fn test_parse_json_2() {
 fn test_parse_object() {
     let mut incr: usize = 0;
 
-    match Json::parse_string(b"\"String\":\"Value\"", &mut incr) {
+    match Json::parse_value(b"\"String\":\"Value\"", &mut incr) {
         Ok(json) => match json {
             Json::OBJECT { name, value } => {
                 assert_eq!(name, "String");
@@ -329,8 +5012,8 @@ fn test_parse() {
                                                     Json::ARRAY(vals) => {
                                                         assert_eq!(vals.len(),7);
 
-                                                        for n in 0..7 {
-                                                            match &vals[n] {
+                                                        for json in vals {
+                                                            match json {
                                                                 Json::STRING(val) => {
                                                                     match val.as_bytes() {
                                                                         b"Monday" => {
@@ -404,8 +5087,8 @@ fn test_parse_2() {
             Ok(json) => {
                 json
             },
-            Err( (position,message) ) => {
-                panic!("`{}` at position `{}`!!!",message,position);
+            Err(error) => {
+                panic!("{}!!!",error);
             }
         };
 
@@ -469,8 +5152,8 @@ fn test_parse_2() {
 fn parse_strange() {
     let json = match Json::parse(b"[0,{\"hello\":\"world\",\"what's\":\"up?\"}]") {
         Ok(json) => json,
-        Err((pos, msg)) => {
-            panic!("`{}` at position {}", msg, pos);
+        Err(error) => {
+            panic!("{}", error);
         }
     };
 
@@ -524,8 +5207,8 @@ fn parse_strange() {
 fn parse_escape_sequence() {
     let json = match Json::parse(br#""a \" \/ \b \f \n \r \t \u2764 z""#) {
         Ok(json) => json,
-        Err((pos, msg)) => {
-            panic!("`{}` at position {}", msg, pos);
+        Err(error) => {
+            panic!("{}", error);
         }
     };
 
@@ -543,8 +5226,8 @@ fn parse_escape_sequence() {
 fn parse_escape_sequence_in_array() {
     let json = match Json::parse(br#"["\"foo"]"#) {
         Ok(json) => json,
-        Err((pos, msg)) => {
-            panic!("`{}` at position {}", msg, pos);
+        Err(error) => {
+            panic!("{}", error);
         }
     };
 
@@ -571,8 +5254,8 @@ fn parse_escape_sequence_in_array() {
 fn parse_non_ascii() {
     let json = match Json::parse(r#""a ❤ z""#.as_bytes()) {
         Ok(json) => json,
-        Err((pos, msg)) => {
-            panic!("`{}` at position {}", msg, pos);
+        Err(error) => {
+            panic!("{}", error);
         }
     };
 
@@ -590,8 +5273,8 @@ fn parse_non_ascii() {
 fn parse_pretty() {
     let json = match Json::parse(b"{\r\n\t\"Array\": [\r\n\t\t\"First\" ,\r\n\r\n\t\t2 ,\r\n\r\n\t\t[\"Three\"] ,\r\n\r\n\t\t3.6\r\n\t],\r\n\t{\r\n\r\n\t\t\"Sub-Object\": \"Hello, world!\"\r\n\t}\r\n}") {
         Ok(json) => json,
-        Err((pos, msg)) => {
-            panic!("`{}` at position {}", msg, pos);
+        Err(error) => {
+            panic!("{}", error);
         } 
     };
 
@@ -649,6 +5332,11 @@ fn parse_pretty() {
     }
 }
 
-fn parse_error((pos, msg): (usize, &str)) {
-    panic!("`{}` at position `{}`!!!", msg, pos);
+fn parse_error<E: std::fmt::Debug>(error: E) {
+    panic!("{:?}!!!", error);
+}
+
+// The result of `Json::parse`, with an error as where it happened and its kind.
+fn parse_kind(input: &[u8]) -> Result<Json, (usize, ErrorKind)> {
+    Json::parse(input).map_err(|error| (error.position, error.kind))
 }
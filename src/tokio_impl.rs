@@ -0,0 +1,69 @@
+//! Printing to a `tokio::io::AsyncWrite` (requires the `tokio` feature).
+
+use crate::printer::Printer;
+use crate::Json;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+// The output is written in chunks of about this size, yielding to the executor after each.
+const CHUNK: usize = 8 * 1024;
+
+impl Json {
+    /// Writes the same text as `print` to `writer`, without building it up in memory first, but with
+    /// quotes, backslashes and control characters in names and strings escaped. The output
+    /// is written in chunks of about 8 KiB and the task yields to the executor after each, so that
+    /// printing a huge value doesn't hold up other tasks. The writer is flushed at the end.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"items\":[1,2,3],\"done\":true}").unwrap();
+    ///
+    /// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    /// let mut output = Vec::new();
+    ///
+    /// runtime.block_on(json.print_to_async(&mut output)).unwrap();
+    ///
+    /// assert_eq!(output, json.print().into_bytes());
+    /// ```
+    /// ## Errors
+    /// Fails with the first error returned by `writer`.
+    pub async fn print_to_async<W: AsyncWrite + Unpin>(&self, mut writer: W) -> io::Result<()> {
+        let mut buffer = String::with_capacity(CHUNK);
+        let mut printer = Printer::new(self);
+
+        while printer.step(&mut buffer) {
+            if buffer.len() >= CHUNK {
+                writer.write_all(buffer.as_bytes()).await?;
+                buffer.clear();
+
+                YieldNow(false).await;
+            }
+        }
+
+        writer.write_all(buffer.as_bytes()).await?;
+        writer.flush().await
+    }
+}
+
+// Returns `Poll::Pending` once (after waking the task right away), which lets the executor run
+// other tasks. This doesn't depend on a particular runtime.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            return Poll::Ready(());
+        }
+
+        self.0 = true;
+        cx.waker().wake_by_ref();
+
+        Poll::Pending
+    }
+}
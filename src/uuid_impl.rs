@@ -0,0 +1,61 @@
+//! UUID helpers (requires the `uuid` feature).
+
+use crate::{Json, UuidError};
+use uuid::Uuid;
+
+impl Json {
+    /// Parses the string held by a `Json::STRING` (or a `Json::OBJECT` holding one) as a UUID in its
+    /// hyphenated form, like `67e55044-10b1-426f-9247-bb680e5fe0c8` (in upper or lower case).
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Id\":\"67e55044-10b1-426f-9247-bb680e5fe0c8\"}").unwrap();
+    ///
+    /// let id = json.get("Id").unwrap().as_uuid().unwrap();
+    ///
+    /// assert_eq!(id.as_u128(), 0x67e5504410b1426f9247bb680e5fe0c8);
+    /// assert_eq!(Json::from_uuid(&id), Json::string("67e55044-10b1-426f-9247-bb680e5fe0c8"));
+    ///
+    /// assert!(Json::string("67e5504410b1426f9247bb680e5fe0c8").as_uuid().is_err());
+    /// ```
+    /// ## Errors
+    /// Fails if `self` isn't a string (with the printed value) or if the string isn't a hyphenated
+    /// UUID.
+    pub fn as_uuid(&self) -> Result<Uuid, UuidError> {
+        let val = match self.inner() {
+            Json::STRING(val) => val,
+            json => {
+                return Err(UuidError {
+                    value: json.print(),
+                    message: String::from("Expected a `Json::STRING`."),
+                })
+            }
+        };
+
+        let hyphens = val
+            .char_indices()
+            .filter(|(_, c)| *c == '-')
+            .map(|(n, _)| n)
+            .eq([8, 13, 18, 23].iter().copied());
+
+        if val.len() != 36 || !hyphens {
+            return Err(UuidError {
+                value: val.clone(),
+                message: String::from(
+                    "Expected the hyphenated form `xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx`.",
+                ),
+            });
+        }
+
+        Uuid::try_parse(val).map_err(|error| UuidError {
+            value: val.clone(),
+            message: error.to_string(),
+        })
+    }
+
+    /// Creates a `Json::STRING` holding `uuid` in its lower case hyphenated form.
+    pub fn from_uuid(uuid: &Uuid) -> Json {
+        Json::STRING(uuid.hyphenated().to_string())
+    }
+}
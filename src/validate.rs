@@ -0,0 +1,175 @@
+//! Checking input without parsing it into a `Json`.
+
+use crate::parser::MAX_DEPTH;
+use crate::{is_whitespace, truncated, ErrorKind, Json, ParseError};
+
+impl Json {
+    /// Checks that `input` holds a value which `parse` accepts, in a single pass without allocating
+    /// anything, e.g. to turn away a malformed upload before parsing it. Objects and arrays may be
+    /// nested 1024 deep, as with `parse`. Unlike `parse`, which takes the value at the very start of
+    /// `input` and ignores whatever follows it, this allows whitespace before the value and fails
+    /// with `ErrorKind::TrailingData` at anything but whitespace after it.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// assert!(Json::validate(b" {\"a\":[1,true,\"\\u00e9\"]}\n").is_ok());
+    ///
+    /// let error = Json::validate(b"{\"a\":[1,tru]}").unwrap_err();
+    ///
+    /// assert_eq!((error.line, error.position, error.message), (1, 11, "Error parsing bool."));
+    /// ```
+    pub fn validate(input: &[u8]) -> Result<(), ParseError> {
+        let error = |index, message| Err(ParseError::at(input, index, message));
+
+        // Which of the containers entered are objects, one bit each.
+        let mut objects = [0u64; MAX_DEPTH / 64];
+        let mut depth = 0;
+        // Set after the name of a member, until its value.
+        let mut named = false;
+        let mut n = 0;
+
+        loop {
+            while n < input.len()
+                && (is_whitespace(input[n]) || (input[n] == b',' && depth > 0 && !named))
+            {
+                n += 1;
+            }
+
+            if n == input.len() {
+                return error(n, ErrorKind::UnexpectedEof);
+            }
+
+            match input[n] {
+                c @ (b'{' | b'[') => {
+                    if depth == MAX_DEPTH {
+                        return error(n, ErrorKind::TooDeep);
+                    }
+
+                    if c == b'{' {
+                        objects[depth / 64] |= 1 << (depth % 64);
+                    } else {
+                        objects[depth / 64] &= !(1 << (depth % 64));
+                    }
+
+                    depth += 1;
+                    named = false;
+                    n += 1;
+
+                    continue;
+                }
+                c @ (b'}' | b']') => {
+                    let object =
+                        depth > 0 && objects[(depth - 1) / 64] & 1 << ((depth - 1) % 64) != 0;
+
+                    if depth == 0 || named || object != (c == b'}') {
+                        return error(n, ErrorKind::UnexpectedClose);
+                    }
+
+                    depth -= 1;
+                    n += 1;
+                }
+                b'\"' => {
+                    n = validate_string(input, n)?;
+
+                    if input.get(n) == Some(&b':') {
+                        named = true;
+                        n += 1;
+
+                        continue;
+                    }
+                }
+                b't' | b'f' | b'n' | b'0'..=b'9' => n = validate_scalar(input, n)?,
+                _ => return error(n, ErrorKind::InvalidValue),
+            }
+
+            named = false;
+
+            if depth == 0 {
+                break;
+            }
+        }
+
+        match (n..input.len()).find(|n| !is_whitespace(input[*n])) {
+            Some(n) => error(n, ErrorKind::TrailingData),
+            None => Ok(()),
+        }
+    }
+}
+
+// Checks the string starting at `input[start]` like `Json::parse_string`, returning where it ends.
+fn validate_string(input: &[u8], start: usize) -> Result<usize, ParseError> {
+    let error = |index, message| Err(ParseError::at(input, index, message));
+    let mut n = start + 1;
+
+    loop {
+        match input.get(n) {
+            None => return error(n, ErrorKind::UnexpectedEof),
+            Some(b'\"') => break,
+            Some(b'\\') => match input.get(n + 1) {
+                Some(b'\"' | b'\\' | b'/' | b'b' | b'f' | b'n' | b'r' | b't') => n += 2,
+                Some(b'u') => {
+                    let valid = input
+                        .get(n + 2..n + 6)
+                        .and_then(|hex| std::str::from_utf8(hex).ok())
+                        .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+                        .and_then(|value| std::char::from_u32(value as u32))
+                        .is_some();
+
+                    let digits = &input[(n + 2).min(input.len())..];
+
+                    if !valid && digits.len() < 4 && digits.iter().all(u8::is_ascii_hexdigit) {
+                        return error(input.len(), ErrorKind::UnexpectedEof);
+                    }
+
+                    if !valid {
+                        return error(n + 1, ErrorKind::InvalidUnicodeEscape);
+                    }
+
+                    n += 6;
+                }
+                Some(_) => return error(n + 1, ErrorKind::InvalidEscape),
+                None => return error(n + 1, ErrorKind::UnexpectedEof),
+            },
+            Some(_) => n += 1,
+        }
+    }
+
+    if std::str::from_utf8(&input[start + 1..n]).is_err() {
+        return error(n + 1, ErrorKind::InvalidUtf8);
+    }
+
+    Ok(n + 1)
+}
+
+// Checks the number, `true`, `false` or `null` starting at `input[start]` like the parser, returning
+// where it ends.
+fn validate_scalar(input: &[u8], start: usize) -> Result<usize, ParseError> {
+    let end = (start..input.len())
+        .find(|n| is_whitespace(input[*n]) || matches!(input[*n], b',' | b']' | b'}'))
+        .unwrap_or(input.len());
+
+    let token = &input[start..end];
+
+    let (valid, message) = match token[0] {
+        b't' | b'f' => (
+            token == b"true" || token == b"false",
+            ErrorKind::InvalidBool,
+        ),
+        b'n' => (token == b"null", ErrorKind::InvalidNull),
+        _ => (
+            std::str::from_utf8(token).is_ok_and(|token| token.parse::<f64>().is_ok()),
+            ErrorKind::InvalidNumber,
+        ),
+    };
+
+    if !valid && truncated(input, end, token) {
+        return Err(ParseError::at(input, end, ErrorKind::UnexpectedEof));
+    }
+
+    if !valid {
+        return Err(ParseError::at(input, end, message));
+    }
+
+    Ok(end)
+}
@@ -0,0 +1,185 @@
+//! Visiting every node of a `Json`.
+
+use crate::Json;
+
+/// Returned by the closure passed to `Json::walk` and `Json::walk_mut` to control the traversal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    /// Go on, including the children of the current node.
+    Continue,
+    /// Go on, but don't visit the children of the current node.
+    Skip,
+    /// Stop walking altogether.
+    Stop,
+}
+
+impl Json {
+    /// Calls `visit` for `self` and every node below it, parents before their children and in the
+    /// order in which they appear. A member of an object is visited as the `Json::OBJECT` itself (so
+    /// its name can be inspected) followed by its value.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Skipped\":[1,2,3],\"Counted\":[4,5]}").unwrap();
+    ///
+    /// let mut numbers = 0;
+    ///
+    /// json.walk(|json| match json {
+    ///     Json::OBJECT { name, value: _ } if name == "Skipped" => Walk::Skip,
+    ///     Json::NUMBER(_) => {
+    ///         numbers += 1;
+    ///
+    ///         Walk::Continue
+    ///     }
+    ///     _ => Walk::Continue,
+    /// });
+    ///
+    /// assert_eq!(numbers, 2);
+    /// ```
+    pub fn walk<F>(&self, mut visit: F)
+    where
+        F: FnMut(&Json) -> Walk,
+    {
+        walk(self, &mut visit);
+    }
+
+    /// Same as `walk` above, but the nodes may be changed (or replaced) by `visit`. The children of a
+    /// node are visited after `visit` returned, i.e. the children of the changed node are visited.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"Name\":\"  Ferris \",\"Friends\":[\" Corro\"]}").unwrap();
+    ///
+    /// json.walk_mut(|json| {
+    ///     if let Json::STRING(val) = json {
+    ///         *val = val.trim().to_string();
+    ///     }
+    ///
+    ///     Walk::Continue
+    /// });
+    ///
+    /// assert_eq!(json.print(), "{\"Name\":\"Ferris\",\"Friends\":[\"Corro\"]}");
+    /// ```
+    pub fn walk_mut<F>(&mut self, mut visit: F)
+    where
+        F: FnMut(&mut Json) -> Walk,
+    {
+        walk_mut(self, &mut visit);
+    }
+
+    /// Consumes `self` and returns the same structure with every leaf (i.e. every `Json::STRING`,
+    /// `Json::NUMBER`, `Json::BOOL` and `Json::NULL`) replaced by what `f` returns for it. Names of
+    /// members, objects and arrays are kept as they are.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let json = Json::parse(b"{\"Id\":42,\"Scores\":[1.5,2],\"Name\":\"Ferris\"}").unwrap();
+    ///
+    /// let json = json.map_values(|json| match json {
+    ///     Json::NUMBER(n) => Json::STRING(n.to_string()),
+    ///     json => json,
+    /// });
+    ///
+    /// assert_eq!(json.print(), "{\"Id\":\"42\",\"Scores\":[\"1.5\",\"2\"],\"Name\":\"Ferris\"}");
+    /// ```
+    pub fn map_values<F>(self, mut f: F) -> Json
+    where
+        F: FnMut(Json) -> Json,
+    {
+        map_values(self, &mut f)
+    }
+
+    /// Replaces the value of every member (at any depth, including objects inside arrays) whose name
+    /// is one of `keys` with a copy of `replacement`. Handy before logging documents holding secrets.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let mut json = Json::parse(b"{\"User\":\"ferris\",\"Password\":\"hunter2\",\"Sessions\":[{\"Token\":{\"Value\":\"abc\"}}]}").unwrap();
+    ///
+    /// json.redact(&["Password", "Token"], Json::string("***"));
+    ///
+    /// assert_eq!(
+    ///     json.print(),
+    ///     "{\"User\":\"ferris\",\"Password\":\"***\",\"Sessions\":[{\"Token\":\"***\"}]}"
+    /// );
+    /// ```
+    pub fn redact(&mut self, keys: &[&str], replacement: Json) {
+        self.walk_mut(|json| match json {
+            Json::OBJECT { name, value } if keys.contains(&name.as_str()) => {
+                **value = replacement.clone();
+
+                Walk::Skip
+            }
+            _ => Walk::Continue,
+        });
+    }
+}
+
+fn map_values<F>(json: Json, f: &mut F) -> Json
+where
+    F: FnMut(Json) -> Json,
+{
+    match json {
+        Json::OBJECT { name, value } => Json::OBJECT {
+            name,
+
+            value: Box::new(map_values(*value, f)),
+        },
+        Json::JSON(values) => {
+            Json::JSON(values.into_iter().map(|json| map_values(json, f)).collect())
+        }
+        Json::ARRAY(values) => {
+            Json::ARRAY(values.into_iter().map(|json| map_values(json, f)).collect())
+        }
+        leaf => f(leaf),
+    }
+}
+
+// Both return `false` if the walk was stopped.
+fn walk<F>(json: &Json, visit: &mut F) -> bool
+where
+    F: FnMut(&Json) -> Walk,
+{
+    match visit(json) {
+        Walk::Continue => {}
+        Walk::Skip => {
+            return true;
+        }
+        Walk::Stop => {
+            return false;
+        }
+    }
+
+    match json {
+        Json::OBJECT { name: _, value } => walk(value, visit),
+        Json::JSON(values) | Json::ARRAY(values) => values.iter().all(|json| walk(json, visit)),
+        _ => true,
+    }
+}
+
+fn walk_mut<F>(json: &mut Json, visit: &mut F) -> bool
+where
+    F: FnMut(&mut Json) -> Walk,
+{
+    match visit(json) {
+        Walk::Continue => {}
+        Walk::Skip => {
+            return true;
+        }
+        Walk::Stop => {
+            return false;
+        }
+    }
+
+    match json {
+        Json::OBJECT { name: _, value } => walk_mut(value, visit),
+        Json::JSON(values) | Json::ARRAY(values) => {
+            values.iter_mut().all(|json| walk_mut(json, visit))
+        }
+        _ => true,
+    }
+}
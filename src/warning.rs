@@ -0,0 +1,127 @@
+//! Warnings about what parsing accepts although it isn't standard JSON, e.g. for a linter.
+
+use crate::error::locate;
+use crate::parser::{self, Context};
+use crate::{Json, ParseError, ParseOptions};
+use std::fmt;
+
+/// Something `Json::parse_with_warnings` accepted although it isn't standard JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Warning {
+    /// The line it is on, counting from `1`.
+    pub line: usize,
+    /// The byte offset into that line.
+    pub position: usize,
+    /// What was accepted.
+    pub kind: WarningKind,
+}
+
+impl Warning {
+    // The warning at `input[index]`, with the line and the offset into it worked out.
+    pub(crate) fn at(input: &[u8], index: usize, kind: WarningKind) -> Warning {
+        let (line, position) = locate(input, index);
+
+        Warning {
+            line,
+            position,
+            kind,
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "`{}` at line `{}`, position `{}`.",
+            self.kind, self.line, self.position
+        )
+    }
+}
+
+/// What kind of non-standard JSON a `Warning` is about. More kinds may be added.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum WarningKind {
+    /// A comma before the closing bracket of an object or array, at the (first) comma.
+    TrailingComma,
+    /// A comma before the first value of an object or array, or one more between two values, at
+    /// the comma.
+    ExtraComma,
+    /// Two values of an object or array without a comma between them, at the second value.
+    MissingComma,
+    /// A comment, as allowed by `ParseOptions::allow_comments`, at its start.
+    Comment,
+    /// A member with the same name as one before it in the same object, at its name.
+    DuplicateKey,
+}
+
+impl WarningKind {
+    /// What a warning of this kind says, e.g. `"Trailing comma."`.
+    pub fn message(self) -> &'static str {
+        match self {
+            WarningKind::TrailingComma => "Trailing comma.",
+            WarningKind::ExtraComma => "Extra comma.",
+            WarningKind::MissingComma => "Missing comma.",
+            WarningKind::Comment => "Comment.",
+            WarningKind::DuplicateKey => "Duplicate key.",
+        }
+    }
+}
+
+impl fmt::Display for WarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl Json {
+    /// Parses `input` like `parse_with`, also returning a warning, in order, for everything
+    /// accepted although it isn't standard JSON: commas where there should be none or one is
+    /// missing, comments (if `options` allows them) and duplicate keys. Checking for duplicate keys
+    /// takes time quadratic in the number of members of an object.
+    /// ## Example
+    /// ```
+    /// use json_minimal::*;
+    ///
+    /// let options = ParseOptions {
+    ///     allow_comments: true,
+    ///     ..ParseOptions::default()
+    /// };
+    ///
+    /// let input = b"{\"a\":1, // one\n\"b\":[2,],\"a\":3}";
+    /// let (json, warnings) = Json::parse_with_warnings(input, &options).unwrap();
+    ///
+    /// assert_eq!(json.print(), "{\"a\":1,\"b\":[2],\"a\":3}");
+    ///
+    /// let warnings: Vec<(usize, usize, WarningKind)> = warnings
+    ///     .iter()
+    ///     .map(|warning| (warning.line, warning.position, warning.kind))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     warnings,
+    ///     vec![
+    ///         (1, 8, WarningKind::Comment),
+    ///         (2, 6, WarningKind::TrailingComma),
+    ///         (2, 9, WarningKind::DuplicateKey),
+    ///     ]
+    /// );
+    /// ```
+    pub fn parse_with_warnings(
+        input: &[u8],
+        options: &ParseOptions,
+    ) -> Result<(Json, Vec<Warning>), ParseError> {
+        let mut context = Context {
+            options: *options,
+            warnings: Some(Vec::new()),
+            ..Context::default()
+        };
+        let mut incr = 0;
+
+        let json = parser::parse_with(input, &mut incr, &mut context)
+            .map_err(|error| context.error(input, error))?;
+
+        Ok((json, context.warnings.unwrap_or_default()))
+    }
+}